@@ -4,21 +4,32 @@
 // @author Dennis Kuhnert <dennis.kuhnert@campus.tu-berlin.de>
 // @date 2017
 
+mod project;
+mod test_runner;
+
+use crate::project::{Dependency, Fingerprint, Fingerprints, Manifest};
+use crate::test_runner::{find_test_files, Tests};
 use bincode::{deserialize_from, serialize_into, Infinite};
-use clap::{App, AppSettings, Arg, SubCommand};
+use clap::{App, AppSettings, Arg, ArgMatches, SubCommand};
 use serde_json::Value;
-use std::fs::File;
+#[cfg(feature = "github")]
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::env;
+use std::fs::{self, File};
 use std::io::{stdin, BufReader, BufWriter, Read, Write};
 use std::path::{Path, PathBuf};
 use std::string::String;
-use std::{env, io};
-use zokrates_core::compile::compile;
+use zokrates_core::abi::Abi;
+use zokrates_core::compile::{compile, profile, CompilationArtifacts};
+use zokrates_core::imports::{self, Resolver};
 use zokrates_core::ir;
 use zokrates_core::proof_system::*;
-use zokrates_field::field::{Field, FieldPrime};
-use zokrates_fs_resolver::resolve as fs_resolve;
+use zokrates_core::types::Type;
+use zokrates_field::field::{Bls12Field, Field, FieldPrime};
+use zokrates_fs_resolver::FileSystemResolver;
 #[cfg(feature = "github")]
-use zokrates_github_resolver::{is_github_import, resolve as github_resolve};
+use zokrates_github_resolver::{is_github_import, Cache, GithubResolver, Lockfile, LOCKFILE};
 
 fn main() {
     cli().unwrap_or_else(|e| {
@@ -27,26 +38,169 @@ fn main() {
     })
 }
 
-fn resolve(
-    location: &Option<String>,
-    source: &String,
-) -> Result<(BufReader<File>, String, String), io::Error> {
+/// Resolves GitHub imports if the `github` feature is enabled, and other imports from the file
+/// system
+#[derive(Default)]
+struct ImportResolver {
+    fs: FileSystemResolver,
+    /// The GitHub dependencies, as the `github.com/{owner}/{repo}/{rev}` prefix of their imports
     #[cfg(feature = "github")]
-    {
-        if is_github_import(source) {
-            return github_resolve(location, source);
-        };
+    libraries: BTreeMap<String, String>,
+    /// The lockfile pinning GitHub imports, and whether they are resolved offline
+    #[cfg(feature = "github")]
+    lockfile: Option<(PathBuf, bool)>,
+    /// Created on the first GitHub import, so that programs without GitHub imports need neither a
+    /// cache nor a lockfile
+    #[cfg(feature = "github")]
+    github: RefCell<Option<GithubResolver>>,
+}
+
+impl ImportResolver {
+    /// Adds a dependency, so that `name/path/to/file.code` is read from
+    /// `directory/path/to/file.code`
+    fn library(mut self, name: String, directory: PathBuf) -> ImportResolver {
+        self.fs = self.fs.library(name, directory);
+        self
+    }
+
+    /// Adds a GitHub dependency, so that `name/path/to/file.code` is imported from
+    /// `path/to/file.code` in `repository` at `rev`, or at `master` if it is not set
+    #[cfg(feature = "github")]
+    fn github_library(
+        mut self,
+        name: String,
+        repository: &str,
+        rev: Option<&String>,
+    ) -> Result<ImportResolver, String> {
+        if repository.split('/').count() != 2 {
+            return Err(format!(
+                "invalid repository {} for dependency {}, expected owner/repo",
+                repository, name
+            ));
+        }
+
+        let rev = rev.map(String::as_str).unwrap_or("master");
+        self.libraries
+            .insert(name, format!("github.com/{}/{}", repository, rev));
+        Ok(self)
+    }
+
+    #[cfg(not(feature = "github"))]
+    fn github_library(
+        self,
+        name: String,
+        _: &str,
+        _: Option<&String>,
+    ) -> Result<ImportResolver, String> {
+        Err(format!(
+            "dependency {} is on GitHub, which is not supported as ZoKrates was built without the github feature",
+            name
+        ))
+    }
+
+    /// Pins GitHub imports to the commits recorded in `lockfile`, or in the lockfile in
+    /// `directory` if it is not set, and keeps the imported files in the cache, resolving them
+    /// from the cache only if `offline` is set
+    #[cfg(feature = "github")]
+    fn locked(
+        self,
+        directory: &Path,
+        lockfile: Option<&Path>,
+        offline: bool,
+    ) -> Result<ImportResolver, String> {
+        let lockfile = lockfile
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|| directory.join(LOCKFILE));
+
+        Ok(ImportResolver {
+            lockfile: Some((lockfile, offline)),
+            ..self
+        })
+    }
+
+    #[cfg(not(feature = "github"))]
+    fn locked(self, _: &Path, _: Option<&Path>, offline: bool) -> Result<ImportResolver, String> {
+        match offline {
+            true => Err(String::from(
+                "--offline is not supported, as ZoKrates was built without the github feature",
+            )),
+            false => Ok(self),
+        }
+    }
+
+    /// Returns the GitHub import `import` refers to, if it is a GitHub import or an import from a
+    /// GitHub dependency
+    #[cfg(feature = "github")]
+    fn github_import(&self, import: &str) -> Option<String> {
+        if is_github_import(import) {
+            return Some(import.to_string());
+        }
+
+        let mut parts = import.splitn(2, '/');
+        let (name, path) = (parts.next()?, parts.next()?);
+        self.libraries
+            .get(name)
+            .map(|prefix| format!("{}/{}", prefix, path))
+    }
+
+    #[cfg(feature = "github")]
+    fn github_resolver(&self) -> Result<GithubResolver, String> {
+        match self.lockfile {
+            Some((ref lockfile, offline)) => {
+                let lockfile = Lockfile::read(lockfile)
+                    .map_err(|why| format!("couldn't read {}: {}", lockfile.display(), why))?;
+                let cache = Cache::default_directory().ok_or_else(|| {
+                    String::from("couldn't find the cache directory, please set $ZOKRATES_CACHE")
+                })?;
+
+                Ok(GithubResolver::new()
+                    .cache(Cache::new(cache))
+                    .lockfile(lockfile)
+                    .offline(offline))
+            }
+            None => Ok(GithubResolver::new()),
+        }
+    }
+}
+
+impl Resolver for ImportResolver {
+    fn resolve(
+        &self,
+        current_location: &Option<String>,
+        import_location: &String,
+    ) -> Result<(String, String, String), imports::Error> {
+        #[cfg(feature = "github")]
+        {
+            if let Some(import) = self.github_import(import_location) {
+                if self.github.borrow().is_none() {
+                    let github = self.github_resolver().map_err(imports::Error::new)?;
+                    *self.github.borrow_mut() = Some(github);
+                }
+
+                return self
+                    .github
+                    .borrow()
+                    .as_ref()
+                    .unwrap()
+                    .resolve(current_location, &import);
+            };
+        }
+        self.fs.resolve(current_location, import_location)
     }
-    fs_resolve(location, source)
 }
 
 fn cli() -> Result<(), String> {
     const FLATTENED_CODE_DEFAULT_PATH: &str = "out";
+    const ABI_SPEC_DEFAULT_PATH: &str = "abi.json";
     const VERIFICATION_KEY_DEFAULT_PATH: &str = "verification.key";
     const PROVING_KEY_DEFAULT_PATH: &str = "proving.key";
     const VERIFICATION_CONTRACT_DEFAULT_PATH: &str = "verifier.sol";
     const WITNESS_DEFAULT_PATH: &str = "witness";
     const JSON_PROOF_PATH: &str = "proof.json";
+    const MPC_DEFAULT_PATH: &str = "mpc.params";
+    const BN128: &str = "bn128";
+    const BLS12_381: &str = "bls12_381";
+    const CURVES: &[&str] = &[BN128, BLS12_381];
     let default_scheme = env::var("ZOKRATES_PROVING_SCHEME").unwrap_or(String::from("g16"));
     let default_solidity_abi = "v1";
 
@@ -56,8 +210,32 @@ fn cli() -> Result<(), String> {
     .version(env!("CARGO_PKG_VERSION"))
     .author("Jacob Eberhardt, Thibaut Schaeffer, Stefan Deml")
     .about("Supports generation of zkSNARKs from high level language code including Smart Contracts for proof verification on the Ethereum Blockchain.\n'I know that I show nothing!'")
+    .subcommand(SubCommand::with_name("new")
+        .about("Creates a project with a zokrates.toml manifest, a program and a test file")
+        .arg(Arg::with_name("path")
+            .help("Path of the project directory, the last component of which is the name of the project")
+            .value_name("PATH")
+            .required(true)
+        )
+    )
+    .subcommand(SubCommand::with_name("build")
+        .about("Compiles the circuits of a project, runs their setup and exports their verifiers, skipping the steps whose inputs did not change")
+        .arg(Arg::with_name("path")
+            .short("p")
+            .long("path")
+            .help("Path of the project directory, containing zokrates.toml")
+            .value_name("DIR")
+            .takes_value(true)
+            .required(false)
+            .default_value(".")
+        ).arg(Arg::with_name("offline")
+            .long("offline")
+            .help("Resolve GitHub imports from the cache only, as pinned in zokrates.lock")
+            .required(false)
+        )
+    )
     .subcommand(SubCommand::with_name("compile")
-        .about("Compiles into flattened conditions. Produces two files: human-readable '.code' file for debugging and binary file")
+        .about("Compiles into flattened conditions. Produces three files: human-readable '.code' file for debugging, binary file and JSON ABI of the main function")
         .arg(Arg::with_name("input")
             .short("i")
             .long("input")
@@ -73,10 +251,37 @@ fn cli() -> Result<(), String> {
             .takes_value(true)
             .required(false)
             .default_value(FLATTENED_CODE_DEFAULT_PATH)
+        ).arg(Arg::with_name("abi-spec")
+            .short("s")
+            .long("abi-spec")
+            .help("Path of the ABI specification")
+            .value_name("FILE")
+            .takes_value(true)
+            .required(false)
+            .default_value(ABI_SPEC_DEFAULT_PATH)
         ).arg(Arg::with_name("light")
             .long("light")
             .help("Skip logs and human readable output")
             .required(false)
+        ).arg(Arg::with_name("curve")
+            .short("c")
+            .long("curve")
+            .help("Curve to be used in the compilation")
+            .value_name("CURVE")
+            .takes_value(true)
+            .required(false)
+            .possible_values(CURVES)
+            .default_value(BN128)
+        ).arg(Arg::with_name("lockfile")
+            .long("lockfile")
+            .help("Path of the lockfile pinning GitHub imports, zokrates.lock next to the source code by default")
+            .value_name("FILE")
+            .takes_value(true)
+            .required(false)
+        ).arg(Arg::with_name("offline")
+            .long("offline")
+            .help("Resolve GitHub imports from the cache only, as pinned in the lockfile")
+            .required(false)
         )
      )
     .subcommand(SubCommand::with_name("setup")
@@ -120,6 +325,162 @@ fn cli() -> Result<(), String> {
             .long("light")
             .help("Skip logs and human readable output")
             .required(false)
+        ).arg(Arg::with_name("curve")
+            .short("c")
+            .long("curve")
+            .help("Curve to be used in the setup")
+            .value_name("CURVE")
+            .takes_value(true)
+            .required(false)
+            .possible_values(CURVES)
+            .default_value(BN128)
+        )
+    )
+    .subcommand(SubCommand::with_name("mpc")
+        .about("Runs the program-specific phase of a multi-party trusted setup for G16")
+        .setting(AppSettings::SubcommandRequiredElseHelp)
+        .subcommand(SubCommand::with_name("init")
+            .about("Initializes the ceremony from the result of a powers of tau ceremony")
+            .arg(Arg::with_name("input")
+                .short("i")
+                .long("input")
+                .help("Path of compiled code")
+                .value_name("FILE")
+                .takes_value(true)
+                .required(false)
+                .default_value(FLATTENED_CODE_DEFAULT_PATH)
+            ).arg(Arg::with_name("radix-path")
+                .short("r")
+                .long("radix-path")
+                .help("Path of the phase 1 file for the size of the program, `phase1radix2m{n}`")
+                .value_name("FILE")
+                .takes_value(true)
+                .required(true)
+            ).arg(Arg::with_name("output")
+                .short("o")
+                .long("output")
+                .help("Path of the initial parameters")
+                .value_name("FILE")
+                .takes_value(true)
+                .required(false)
+                .default_value(MPC_DEFAULT_PATH)
+            ).arg(Arg::with_name("curve")
+                .short("c")
+                .long("curve")
+                .help("Curve the program was compiled for")
+                .value_name("CURVE")
+                .takes_value(true)
+                .required(false)
+                .possible_values(CURVES)
+                .default_value(BN128)
+            )
+        )
+        .subcommand(SubCommand::with_name("contribute")
+            .about("Contributes randomness to the parameters")
+            .arg(Arg::with_name("input")
+                .short("i")
+                .long("input")
+                .help("Path of the parameters to contribute to")
+                .value_name("FILE")
+                .takes_value(true)
+                .required(false)
+                .default_value(MPC_DEFAULT_PATH)
+            ).arg(Arg::with_name("output")
+                .short("o")
+                .long("output")
+                .help("Path of the parameters after the contribution")
+                .value_name("FILE")
+                .takes_value(true)
+                .required(true)
+            ).arg(Arg::with_name("entropy")
+                .short("e")
+                .long("entropy")
+                .help("Random text mixed with randomness from the operating system")
+                .value_name("ENTROPY")
+                .takes_value(true)
+                .required(true)
+            ).arg(Arg::with_name("curve")
+                .short("c")
+                .long("curve")
+                .help("Curve the program was compiled for")
+                .value_name("CURVE")
+                .takes_value(true)
+                .required(false)
+                .possible_values(CURVES)
+                .default_value(BN128)
+            )
+        )
+        .subcommand(SubCommand::with_name("verify")
+            .about("Verifies that the parameters result from contributions to the initial parameters of a program")
+            .arg(Arg::with_name("input")
+                .short("i")
+                .long("input")
+                .help("Path of the parameters")
+                .value_name("FILE")
+                .takes_value(true)
+                .required(false)
+                .default_value(MPC_DEFAULT_PATH)
+            ).arg(Arg::with_name("program")
+                .long("program")
+                .help("Path of compiled code")
+                .value_name("FILE")
+                .takes_value(true)
+                .required(false)
+                .default_value(FLATTENED_CODE_DEFAULT_PATH)
+            ).arg(Arg::with_name("radix-path")
+                .short("r")
+                .long("radix-path")
+                .help("Path of the phase 1 file the ceremony was initialized with")
+                .value_name("FILE")
+                .takes_value(true)
+                .required(true)
+            ).arg(Arg::with_name("curve")
+                .short("c")
+                .long("curve")
+                .help("Curve the program was compiled for")
+                .value_name("CURVE")
+                .takes_value(true)
+                .required(false)
+                .possible_values(CURVES)
+                .default_value(BN128)
+            )
+        )
+        .subcommand(SubCommand::with_name("export")
+            .about("Exports the G16 keys from the parameters")
+            .arg(Arg::with_name("input")
+                .short("i")
+                .long("input")
+                .help("Path of the parameters")
+                .value_name("FILE")
+                .takes_value(true)
+                .required(false)
+                .default_value(MPC_DEFAULT_PATH)
+            ).arg(Arg::with_name("proving-key-path")
+                .short("p")
+                .long("proving-key-path")
+                .help("Path of the generated proving key file")
+                .value_name("FILE")
+                .takes_value(true)
+                .required(false)
+                .default_value(PROVING_KEY_DEFAULT_PATH)
+            ).arg(Arg::with_name("verification-key-path")
+                .short("v")
+                .long("verification-key-path")
+                .help("Path of the generated verification key file")
+                .value_name("FILE")
+                .takes_value(true)
+                .required(false)
+                .default_value(VERIFICATION_KEY_DEFAULT_PATH)
+            ).arg(Arg::with_name("curve")
+                .short("c")
+                .long("curve")
+                .help("Curve the program was compiled for")
+                .value_name("CURVE")
+                .takes_value(true)
+                .required(false)
+                .possible_values(CURVES)
+                .default_value(BN128)
+            )
         )
     )
     .subcommand(SubCommand::with_name("export-verifier")
@@ -184,10 +545,43 @@ fn cli() -> Result<(), String> {
             .takes_value(true)
             .multiple(true) // allows multiple values
             .required(false)
+            .conflicts_with("abi-input")
+        ).arg(Arg::with_name("abi-input")
+            .long("abi-input")
+            .help("Path of a JSON file containing the arguments for the program's main method, keyed by parameter name")
+            .value_name("FILE")
+            .takes_value(true)
+            .required(false)
+        ).arg(Arg::with_name("abi-spec")
+            .short("s")
+            .long("abi-spec")
+            .help("Path of the ABI specification used to interpret the JSON arguments")
+            .value_name("FILE")
+            .takes_value(true)
+            .required(false)
+            .default_value(ABI_SPEC_DEFAULT_PATH)
+        ).arg(Arg::with_name("format")
+            .short("f")
+            .long("format")
+            .help("Format of the witness: space-delimited `variable value` lines, the iden3 `.wtns` binary format ordered like `export-r1cs`, or JSON")
+            .value_name("FORMAT")
+            .takes_value(true)
+            .required(false)
+            .possible_values(&["csv", "wtns", "json"])
+            .default_value("csv")
         ).arg(Arg::with_name("light")
             .long("light")
             .help("Skip logs and human readable output")
             .required(false)
+        ).arg(Arg::with_name("curve")
+            .short("c")
+            .long("curve")
+            .help("Curve to be used in the witness computation")
+            .value_name("CURVE")
+            .takes_value(true)
+            .required(false)
+            .possible_values(CURVES)
+            .default_value(BN128)
         )
     )
     .subcommand(SubCommand::with_name("generate-proof")
@@ -232,6 +626,169 @@ fn cli() -> Result<(), String> {
             .takes_value(true)
             .required(false)
             .default_value(&default_scheme)
+        ).arg(Arg::with_name("threads")
+            .short("t")
+            .long("threads")
+            .help("Number of threads used to generate the proof, all available CPUs by default. Only supported on Linux")
+            .value_name("THREADS")
+            .takes_value(true)
+            .required(false)
+        ).arg(Arg::with_name("curve")
+            .short("c")
+            .long("curve")
+            .help("Curve to be used in the proof generation")
+            .value_name("CURVE")
+            .takes_value(true)
+            .required(false)
+            .possible_values(CURVES)
+            .default_value(BN128)
+        )
+    )
+    .subcommand(SubCommand::with_name("verify")
+        .about("Verifies a given proof with the given verification key")
+        .arg(Arg::with_name("proofpath")
+            .short("j")
+            .long("proofpath")
+            .help("Path of the JSON proof file")
+            .value_name("FILE")
+            .takes_value(true)
+            .required(false)
+            .default_value(JSON_PROOF_PATH)
+        ).arg(Arg::with_name("verification-key-path")
+            .short("v")
+            .long("verification-key-path")
+            .help("Path of the verification key file")
+            .value_name("FILE")
+            .takes_value(true)
+            .required(false)
+            .default_value(VERIFICATION_KEY_DEFAULT_PATH)
+        ).arg(Arg::with_name("proving-scheme")
+            .short("s")
+            .long("proving-scheme")
+            .help("Proving scheme the proof was generated with. Available options are G16 (default), PGHR13 and GM17")
+            .value_name("FILE")
+            .takes_value(true)
+            .required(false)
+            .default_value(&default_scheme)
+        ).arg(Arg::with_name("curve")
+            .short("c")
+            .long("curve")
+            .help("Curve to be used in the verification")
+            .value_name("CURVE")
+            .takes_value(true)
+            .required(false)
+            .possible_values(CURVES)
+            .default_value(BN128)
+        )
+    )
+    .subcommand(SubCommand::with_name("check-soundness")
+        .about("Reports the variables of a compiled program which are not determined by its arguments")
+        .arg(Arg::with_name("input")
+            .short("i")
+            .long("input")
+            .help("Path of compiled code")
+            .value_name("FILE")
+            .takes_value(true)
+            .required(false)
+            .default_value(FLATTENED_CODE_DEFAULT_PATH)
+        ).arg(Arg::with_name("verbose")
+            .long("verbose")
+            .help("Also report the variables which are not determined but cannot influence other variables")
+            .required(false)
+        ).arg(Arg::with_name("curve")
+            .short("c")
+            .long("curve")
+            .help("Curve the program was compiled for")
+            .value_name("CURVE")
+            .takes_value(true)
+            .required(false)
+            .possible_values(CURVES)
+            .default_value(BN128)
+        )
+    )
+    .subcommand(SubCommand::with_name("export-r1cs")
+        .about("Exports the constraint system of a compiled program in the iden3 binary format or as JSON")
+        .arg(Arg::with_name("input")
+            .short("i")
+            .long("input")
+            .help("Path of compiled code")
+            .value_name("FILE")
+            .takes_value(true)
+            .required(false)
+            .default_value(FLATTENED_CODE_DEFAULT_PATH)
+        ).arg(Arg::with_name("output")
+            .short("o")
+            .long("output")
+            .help("Path of the output file, `out.r1cs` or `out.r1cs.json` by default")
+            .value_name("FILE")
+            .takes_value(true)
+            .required(false)
+        ).arg(Arg::with_name("format")
+            .short("f")
+            .long("format")
+            .help("Format of the constraint system")
+            .value_name("FORMAT")
+            .takes_value(true)
+            .required(false)
+            .possible_values(&["iden3", "json"])
+            .default_value("iden3")
+        ).arg(Arg::with_name("curve")
+            .short("c")
+            .long("curve")
+            .help("Curve the program was compiled for")
+            .value_name("CURVE")
+            .takes_value(true)
+            .required(false)
+            .possible_values(CURVES)
+            .default_value(BN128)
+        )
+    )
+    .subcommand(SubCommand::with_name("profile")
+        .about("Reports the constraints and directives of a program by originating function and source line")
+        .arg(Arg::with_name("input")
+            .short("i")
+            .long("input")
+            .help("Path of the source code")
+            .value_name("FILE")
+            .takes_value(true)
+            .required(true)
+        ).arg(Arg::with_name("json")
+            .short("j")
+            .long("json")
+            .help("Path of a JSON file to write the profile to")
+            .value_name("FILE")
+            .takes_value(true)
+            .required(false)
+        ).arg(Arg::with_name("curve")
+            .short("c")
+            .long("curve")
+            .help("Curve to be used in the compilation")
+            .value_name("CURVE")
+            .takes_value(true)
+            .required(false)
+            .possible_values(CURVES)
+            .default_value(BN128)
+        )
+    )
+    .subcommand(SubCommand::with_name("test")
+        .about("Runs the JSON test files found in a directory, each listing the expected results of a program on some inputs")
+        .arg(Arg::with_name("dir")
+            .short("d")
+            .long("dir")
+            .help("Directory to search for test files, entry points being relative to the current directory")
+            .value_name("DIR")
+            .takes_value(true)
+            .required(false)
+            .default_value(".")
+        ).arg(Arg::with_name("curve")
+            .short("c")
+            .long("curve")
+            .help("Curve to be used in the compilation")
+            .value_name("CURVE")
+            .takes_value(true)
+            .required(false)
+            .possible_values(CURVES)
+            .default_value(BN128)
         )
     )
      .subcommand(SubCommand::with_name("print-proof")
@@ -257,99 +814,501 @@ fn cli() -> Result<(), String> {
     .get_matches();
 
     match matches.subcommand() {
-        ("compile", Some(sub_matches)) => {
-            println!("Compiling {}\n", sub_matches.value_of("input").unwrap());
+        ("new", Some(sub_matches)) => cli_new(sub_matches)?,
+        ("build", Some(sub_matches)) => cli_build(sub_matches)?,
+        ("compile", Some(sub_matches)) => match sub_matches.value_of("curve").unwrap() {
+            BN128 => cli_compile::<FieldPrime>(sub_matches)?,
+            BLS12_381 => cli_compile::<Bls12Field>(sub_matches)?,
+            _ => unreachable!(),
+        },
+        ("compute-witness", Some(sub_matches)) => match sub_matches.value_of("curve").unwrap() {
+            BN128 => cli_compute::<FieldPrime>(sub_matches)?,
+            BLS12_381 => cli_compute::<Bls12Field>(sub_matches)?,
+            _ => unreachable!(),
+        },
+        ("setup", Some(sub_matches)) => match sub_matches.value_of("curve").unwrap() {
+            BN128 => cli_setup::<FieldPrime>(sub_matches)?,
+            BLS12_381 => cli_setup::<Bls12Field>(sub_matches)?,
+            _ => unreachable!(),
+        },
+        ("mpc", Some(sub_matches)) => match sub_matches.subcommand() {
+            (command, Some(sub_matches)) => match sub_matches.value_of("curve").unwrap() {
+                BN128 => cli_mpc::<FieldPrime>(command, sub_matches)?,
+                BLS12_381 => cli_mpc::<Bls12Field>(command, sub_matches)?,
+                _ => unreachable!(),
+            },
+            _ => unreachable!(),
+        },
+        ("export-verifier", Some(sub_matches)) => {
+            {
+                let scheme =
+                    FieldPrime::get_scheme(sub_matches.value_of("proving-scheme").unwrap())?;
+                let is_abiv2 = sub_matches.value_of("abi").unwrap() == "v2";
+                println!("Exporting verifier...");
 
-            let path = PathBuf::from(sub_matches.value_of("input").unwrap());
+                // read vk file
+                let input_path = Path::new(sub_matches.value_of("input").unwrap());
+                let input_file = File::open(&input_path)
+                    .map_err(|why| format!("couldn't open {}: {}", input_path.display(), why))?;
+                let reader = BufReader::new(input_file);
 
-            let location = path
-                .parent()
-                .unwrap()
-                .to_path_buf()
-                .into_os_string()
-                .into_string()
-                .unwrap();
+                let verifier = scheme.export_solidity_verifier(reader, is_abiv2);
 
-            let light = sub_matches.occurrences_of("light") > 0;
+                //write output file
+                let output_path = Path::new(sub_matches.value_of("output").unwrap());
+                let output_file = File::create(&output_path)
+                    .map_err(|why| format!("couldn't create {}: {}", output_path.display(), why))?;
 
-            let bin_output_path = Path::new(sub_matches.value_of("output").unwrap());
+                let mut writer = BufWriter::new(output_file);
 
-            let hr_output_path = bin_output_path.to_path_buf().with_extension("code");
+                writer
+                    .write_all(&verifier.as_bytes())
+                    .map_err(|_| "Failed writing output to file.".to_string())?;
+                println!("Finished exporting verifier.");
+            }
+        }
+        ("generate-proof", Some(sub_matches)) => match sub_matches.value_of("curve").unwrap() {
+            BN128 => cli_generate_proof::<FieldPrime>(sub_matches)?,
+            BLS12_381 => cli_generate_proof::<Bls12Field>(sub_matches)?,
+            _ => unreachable!(),
+        },
+        ("verify", Some(sub_matches)) => match sub_matches.value_of("curve").unwrap() {
+            BN128 => cli_verify::<FieldPrime>(sub_matches)?,
+            BLS12_381 => cli_verify::<Bls12Field>(sub_matches)?,
+            _ => unreachable!(),
+        },
+        ("check-soundness", Some(sub_matches)) => match sub_matches.value_of("curve").unwrap() {
+            BN128 => cli_check_soundness::<FieldPrime>(sub_matches)?,
+            BLS12_381 => cli_check_soundness::<Bls12Field>(sub_matches)?,
+            _ => unreachable!(),
+        },
+        ("export-r1cs", Some(sub_matches)) => match sub_matches.value_of("curve").unwrap() {
+            BN128 => cli_export_r1cs::<FieldPrime>(sub_matches)?,
+            BLS12_381 => cli_export_r1cs::<Bls12Field>(sub_matches)?,
+            _ => unreachable!(),
+        },
+        ("profile", Some(sub_matches)) => match sub_matches.value_of("curve").unwrap() {
+            BN128 => cli_profile::<FieldPrime>(sub_matches)?,
+            BLS12_381 => cli_profile::<Bls12Field>(sub_matches)?,
+            _ => unreachable!(),
+        },
+        ("test", Some(sub_matches)) => match sub_matches.value_of("curve").unwrap() {
+            BN128 => cli_test::<FieldPrime>(sub_matches)?,
+            BLS12_381 => cli_test::<Bls12Field>(sub_matches)?,
+            _ => unreachable!(),
+        },
+        ("print-proof", Some(sub_matches)) => {
+            let format = sub_matches.value_of("format").unwrap();
 
-            let file = File::open(path.clone()).unwrap();
+            let path = Path::new(sub_matches.value_of("proofpath").unwrap());
 
-            let mut reader = BufReader::new(file);
+            let file = File::open(&path)
+                .map_err(|why| format!("couldn't open {}: {}", path.display(), why))?;
 
-            let program_flattened: ir::Prog<FieldPrime> =
-                compile(&mut reader, Some(location), Some(resolve))
-                    .map_err(|e| format!("Compilation failed:\n\n {}", e))?;
+            let proof_object: Value =
+                serde_json::from_reader(file).map_err(|why| format!("{:?}", why))?;
+
+            match format {
+                "json" => {
+                    println!("~~~~~~~~ Copy the output below for valid ABIv2 format ~~~~~~~~");
+                    println!();
+                    print!("{}", proof_object["proof"]);
+                    print!(",");
+                    println!("{}", proof_object["inputs"]);
+                    println!();
+                    println!("~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~");
+                }
+                "remix" => {
+                    println!("~~~~~~~~ Copy the output below for valid ABIv1 format ~~~~~~~~");
+                    println!();
 
-            // number of constraints the flattened program will translate to.
-            let num_constraints = program_flattened.constraint_count();
+                    for (_, value) in proof_object["proof"].as_object().unwrap().iter() {
+                        print!("{}", value);
+                        print!(",");
+                    }
 
-            // serialize flattened program and write to binary file
-            let bin_output_file = File::create(&bin_output_path)
-                .map_err(|why| format!("couldn't create {}: {}", bin_output_path.display(), why))?;
+                    println!("{}", proof_object["inputs"]);
+                    println!();
+                    println!("~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~");
+                }
+                _ => unreachable!(),
+            }
+        }
+        _ => unreachable!(),
+    }
+    Ok(())
+}
 
-            let mut writer = BufWriter::new(bin_output_file);
+fn cli_new(sub_matches: &ArgMatches) -> Result<(), String> {
+    let path = Path::new(sub_matches.value_of("path").unwrap());
+    let name = path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .ok_or_else(|| format!("invalid project path {}", path.display()))?;
 
-            serialize_into(&mut writer, &program_flattened, Infinite)
-                .map_err(|_| "Unable to write data to file.".to_string())?;
+    project::create(path, name)?;
 
-            if !light {
-                // write human-readable output file
-                let hr_output_file = File::create(&hr_output_path).map_err(|why| {
-                    format!("couldn't create {}: {}", hr_output_path.display(), why)
-                })?;
+    println!("Created project {} at '{}'", name, path.display());
+    Ok(())
+}
 
-                let mut hrofb = BufWriter::new(hr_output_file);
-                write!(&mut hrofb, "{}\n", program_flattened)
-                    .map_err(|_| "Unable to write data to file.".to_string())?;
-                hrofb
-                    .flush()
-                    .map_err(|_| "Unable to flush buffer.".to_string())?;
-            }
+fn cli_build(sub_matches: &ArgMatches) -> Result<(), String> {
+    build_project(
+        Path::new(sub_matches.value_of("path").unwrap()),
+        sub_matches.is_present("offline"),
+    )
+}
 
-            if !light {
-                // debugging output
-                println!("Compiled program:\n{}", program_flattened);
+fn build_project(root: &Path, offline: bool) -> Result<(), String> {
+    let manifest = Manifest::read(root)?;
+
+    println!(
+        "Building {} {}",
+        manifest.project.name, manifest.project.version
+    );
+
+    // imports starting with the name of a dependency are resolved in its directory or repository
+    let resolver = manifest.dependencies.iter().try_fold(
+        ImportResolver::default().locked(root, None, offline)?,
+        |resolver, (name, dependency)| match dependency {
+            Dependency::Path { path } => Ok(resolver.library(name.clone(), root.join(path))),
+            Dependency::Github { github, rev } => {
+                resolver.github_library(name.clone(), github, rev.as_ref())
             }
+        },
+    )?;
+
+    for circuit in &manifest.circuits {
+        let config = manifest.config(circuit)?;
+        let entry_point = root.join(&circuit.entry_point);
+        let output = root.join(&config.output);
+
+        let sources = || fingerprint_sources(root, &manifest, &config, &entry_point);
+
+        match config.curve.as_str() {
+            "bn128" => build_circuit::<FieldPrime>(
+                &circuit.name,
+                &entry_point,
+                &output,
+                &config,
+                &sources,
+                &resolver,
+            )?,
+            "bls12_381" => build_circuit::<Bls12Field>(
+                &circuit.name,
+                &entry_point,
+                &output,
+                &config,
+                &sources,
+                &resolver,
+            )?,
+            _ => unreachable!(),
+        }
+    }
 
-            println!("Compiled code written to '{}'", bin_output_path.display());
+    Ok(())
+}
 
-            if !light {
-                println!("Human readable code to '{}'", hr_output_path.display());
-            }
+/// Fingerprints the inputs of the compilation of a circuit. The imports of a program are not
+/// known before compiling it, so any source of the project or of its dependencies may be one of
+/// them.
+fn fingerprint_sources(
+    root: &Path,
+    manifest: &Manifest,
+    config: &project::Config,
+    entry_point: &Path,
+) -> Result<String, String> {
+    let exclude = root.join(&config.output).parent().unwrap().to_path_buf();
+    let add_sources = |fingerprint: Fingerprint, directory: &Path| {
+        fingerprint
+            .add(directory.to_string_lossy().as_bytes())
+            .add_sources(directory, &exclude)
+            .map_err(|why| {
+                format!(
+                    "couldn't read the sources of {}: {}",
+                    directory.display(),
+                    why
+                )
+            })
+    };
+
+    let mut fingerprint = Fingerprint::new()
+        .add(config.curve.as_bytes())
+        .add(entry_point.to_string_lossy().as_bytes());
+    fingerprint = add_sources(fingerprint, root)?;
+
+    for (name, dependency) in &manifest.dependencies {
+        fingerprint = fingerprint.add(name.as_bytes());
+        fingerprint = match dependency {
+            Dependency::Path { path } => add_sources(fingerprint, &root.join(path))?,
+            Dependency::Github { github, rev } => fingerprint
+                .add(github.as_bytes())
+                .add(rev.as_ref().map_or("master", String::as_str).as_bytes()),
+        };
+    }
 
-            println!("Number of constraints: {}", num_constraints);
+    // GitHub imports are pinned to the commits recorded in the lockfile
+    #[cfg(feature = "github")]
+    {
+        let lockfile = root.join(LOCKFILE);
+        if lockfile.exists() {
+            fingerprint = fingerprint
+                .add_file(&lockfile)
+                .map_err(|why| format!("couldn't read {}: {}", lockfile.display(), why))?;
         }
-        ("compute-witness", Some(sub_matches)) => {
-            println!("Computing witness...");
+    }
 
-            // read compiled program
-            let path = Path::new(sub_matches.value_of("input").unwrap());
-            let file = File::open(&path)
-                .map_err(|why| format!("couldn't open {}: {}", path.display(), why))?;
+    Ok(fingerprint.finish())
+}
 
-            let mut reader = BufReader::new(file);
+/// Runs the steps of the build of a circuit whose inputs changed since the last build
+fn build_circuit<T: Curve>(
+    name: &str,
+    entry_point: &Path,
+    output: &Path,
+    config: &project::Config,
+    sources: &dyn Fn() -> Result<String, String>,
+    resolver: &dyn Resolver,
+) -> Result<(), String> {
+    fs::create_dir_all(output)
+        .map_err(|why| format!("couldn't create {}: {}", output.display(), why))?;
+
+    let mut fingerprints = Fingerprints::read(output);
+
+    let program_path = output.join("out");
+    let abi_spec_path = output.join("abi.json");
+    let pk_path = output.join("proving.key");
+    let vk_path = output.join("verification.key");
+    let verifier_path = output.join("verifier.sol");
+
+    let fingerprint = sources()?;
+    if fingerprints.is_fresh("compile", &fingerprint, &[&program_path, &abi_spec_path]) {
+        println!("{}: compilation is up to date", name);
+    } else {
+        println!("{}: compiling {}", name, entry_point.display());
+
+        let artifacts = compile_entry_point::<T>(entry_point, resolver)?;
+
+        let program_file = File::create(&program_path)
+            .map_err(|why| format!("couldn't create {}: {}", program_path.display(), why))?;
+        serialize_into(
+            &mut BufWriter::new(program_file),
+            artifacts.prog(),
+            Infinite,
+        )
+        .map_err(|_| "Unable to write data to file.".to_string())?;
+
+        let abi_spec_file = File::create(&abi_spec_path)
+            .map_err(|why| format!("couldn't create {}: {}", abi_spec_path.display(), why))?;
+        serde_json::to_writer_pretty(BufWriter::new(abi_spec_file), artifacts.abi())
+            .map_err(|_| "Unable to write data to file.".to_string())?;
+
+        println!(
+            "{}: {} constraints",
+            name,
+            artifacts.prog().constraint_count()
+        );
+
+        // compiling may pin new GitHub imports in the lockfile, so the sources are fingerprinted
+        // again to record the state they were compiled in
+        fingerprints.update("compile", sources()?)?;
+    }
+
+    let fingerprint = Fingerprint::new()
+        .add(config.proving_scheme.to_lowercase().as_bytes())
+        .add_file(&program_path)
+        .map_err(|why| format!("couldn't read {}: {}", program_path.display(), why))?
+        .finish();
+    if fingerprints.is_fresh("setup", &fingerprint, &[&pk_path, &vk_path]) {
+        println!("{}: setup is up to date", name);
+    } else {
+        println!("{}: running the {} setup", name, config.proving_scheme);
+
+        let scheme = T::get_scheme(&config.proving_scheme)?;
+
+        let program_file = File::open(&program_path)
+            .map_err(|why| format!("couldn't open {}: {}", program_path.display(), why))?;
+        let program: ir::Prog<T> = deserialize_from(&mut BufReader::new(program_file), Infinite)
+            .map_err(|why| format!("{:?}", why))?;
+
+        scheme.setup(
+            program,
+            pk_path.to_str().unwrap(),
+            vk_path.to_str().unwrap(),
+        )?;
+
+        fingerprints.update("setup", fingerprint)?;
+    }
+
+    // solidity verifiers are only supported on bn128
+    if config.curve != "bn128" {
+        return Ok(());
+    }
+
+    let fingerprint = Fingerprint::new()
+        .add(config.proving_scheme.to_lowercase().as_bytes())
+        .add(config.solidity_abi.as_bytes())
+        .add_file(&vk_path)
+        .map_err(|why| format!("couldn't read {}: {}", vk_path.display(), why))?
+        .finish();
+    if fingerprints.is_fresh("export-verifier", &fingerprint, &[&verifier_path]) {
+        println!("{}: verifier is up to date", name);
+    } else {
+        println!("{}: exporting the verifier", name);
+
+        let scheme = T::get_scheme(&config.proving_scheme)?;
+
+        let vk_file = File::open(&vk_path)
+            .map_err(|why| format!("couldn't open {}: {}", vk_path.display(), why))?;
+        let verifier =
+            scheme.export_solidity_verifier(BufReader::new(vk_file), config.solidity_abi == "v2");
+
+        fs::write(&verifier_path, verifier)
+            .map_err(|why| format!("couldn't write {}: {}", verifier_path.display(), why))?;
+
+        fingerprints.update("export-verifier", fingerprint)?;
+    }
+
+    println!("{}: artifacts written to '{}'", name, output.display());
+    Ok(())
+}
+
+fn cli_compile<T: Curve>(sub_matches: &ArgMatches) -> Result<(), String> {
+    println!("Compiling {}\n", sub_matches.value_of("input").unwrap());
+
+    let path = PathBuf::from(sub_matches.value_of("input").unwrap());
+
+    let location = path
+        .parent()
+        .unwrap()
+        .to_path_buf()
+        .into_os_string()
+        .into_string()
+        .unwrap();
+
+    let light = sub_matches.occurrences_of("light") > 0;
+
+    let bin_output_path = Path::new(sub_matches.value_of("output").unwrap());
+
+    let abi_spec_path = Path::new(sub_matches.value_of("abi-spec").unwrap());
+
+    let hr_output_path = bin_output_path.to_path_buf().with_extension("code");
+
+    let resolver = ImportResolver::default().locked(
+        path.parent().unwrap(),
+        sub_matches.value_of("lockfile").map(Path::new),
+        sub_matches.is_present("offline"),
+    )?;
+
+    let file = File::open(path.clone()).unwrap();
+
+    let mut reader = BufReader::new(file);
+
+    let artifacts: CompilationArtifacts<T> = compile(&mut reader, Some(location), Some(&resolver))
+        .map_err(|e| format!("Compilation failed:\n\n {}", e))?;
+
+    let program_flattened = artifacts.prog();
+
+    // number of constraints the flattened program will translate to.
+    let num_constraints = program_flattened.constraint_count();
 
-            let program_ast: ir::Prog<FieldPrime> =
-                deserialize_from(&mut reader, Infinite).map_err(|why| why.to_string())?;
+    // serialize flattened program and write to binary file
+    let bin_output_file = File::create(&bin_output_path)
+        .map_err(|why| format!("couldn't create {}: {}", bin_output_path.display(), why))?;
 
-            // print deserialized flattened program
-            if !sub_matches.is_present("light") {
-                println!("{}", program_ast);
+    let mut writer = BufWriter::new(bin_output_file);
+
+    serialize_into(&mut writer, program_flattened, Infinite)
+        .map_err(|_| "Unable to write data to file.".to_string())?;
+
+    // serialize ABI spec and write to JSON file
+    let abi_spec_file = File::create(&abi_spec_path)
+        .map_err(|why| format!("couldn't create {}: {}", abi_spec_path.display(), why))?;
+
+    serde_json::to_writer_pretty(BufWriter::new(abi_spec_file), artifacts.abi())
+        .map_err(|_| "Unable to write data to file.".to_string())?;
+
+    if !light {
+        // write human-readable output file
+        let hr_output_file = File::create(&hr_output_path)
+            .map_err(|why| format!("couldn't create {}: {}", hr_output_path.display(), why))?;
+
+        let mut hrofb = BufWriter::new(hr_output_file);
+        write!(&mut hrofb, "{}\n", program_flattened)
+            .map_err(|_| "Unable to write data to file.".to_string())?;
+        hrofb
+            .flush()
+            .map_err(|_| "Unable to flush buffer.".to_string())?;
+    }
+
+    if !light {
+        // debugging output
+        println!("Compiled program:\n{}", program_flattened);
+    }
+
+    println!("Compiled code written to '{}'", bin_output_path.display());
+    println!("ABI specification written to '{}'", abi_spec_path.display());
+
+    if !light {
+        println!("Human readable code to '{}'", hr_output_path.display());
+    }
+
+    println!("Number of constraints: {}", num_constraints);
+    Ok(())
+}
+
+fn cli_compute<T: Curve>(sub_matches: &ArgMatches) -> Result<(), String> {
+    println!("Computing witness...");
+
+    // read compiled program
+    let path = Path::new(sub_matches.value_of("input").unwrap());
+    let file =
+        File::open(&path).map_err(|why| format!("couldn't open {}: {}", path.display(), why))?;
+
+    let mut reader = BufReader::new(file);
+
+    let program_ast: ir::Prog<T> =
+        deserialize_from(&mut reader, Infinite).map_err(|why| why.to_string())?;
+
+    // print deserialized flattened program
+    if !sub_matches.is_present("light") {
+        println!("{}", program_ast);
+    }
+
+    let arguments: Vec<T> = match sub_matches.value_of("abi-input") {
+        Some(input_path) => {
+            // read the ABI specification to interpret the arguments
+            let abi_spec_path = Path::new(sub_matches.value_of("abi-spec").unwrap());
+            let abi_spec_file = File::open(&abi_spec_path)
+                .map_err(|why| format!("couldn't open {}: {}", abi_spec_path.display(), why))?;
+            let abi: Abi = serde_json::from_reader(BufReader::new(abi_spec_file))
+                .map_err(|why| format!("couldn't parse {}: {}", abi_spec_path.display(), why))?;
+
+            if abi.signature() != program_ast.signature {
+                Err(format!(
+                    "ABI specification {} does not match the program's signature {}",
+                    abi_spec_path.display(),
+                    program_ast.signature
+                ))?
             }
 
+            let input_path = Path::new(input_path);
+            let input_file = File::open(&input_path)
+                .map_err(|why| format!("couldn't open {}: {}", input_path.display(), why))?;
+            let inputs: Value = serde_json::from_reader(BufReader::new(input_file))
+                .map_err(|why| format!("couldn't parse {}: {}", input_path.display(), why))?;
+
+            abi.encode_inputs(&inputs)
+                .map_err(|e| format!("Could not parse argument: {}", e))?
+        }
+        None => {
             let expected_cli_args_count =
                 program_ast.public_arguments_count() + program_ast.private_arguments_count();
 
             // get arguments
-            let arguments: Vec<_> = match sub_matches.values_of("arguments") {
+            let arguments: Vec<String> = match sub_matches.values_of("arguments") {
                 // take inline arguments
-                Some(p) => p
-                    .map(|x| FieldPrime::try_from_dec_str(x).map_err(|_| x.to_string()))
-                    .collect(),
+                Some(p) => Ok(p.map(|x| x.to_string()).collect()),
                 // take stdin arguments
                 None => {
                     if expected_cli_args_count > 0 {
@@ -358,12 +1317,7 @@ fn cli() -> Result<(), String> {
                         match stdin.read_to_string(&mut input) {
                             Ok(_) => {
                                 input.retain(|x| x != '\n');
-                                input
-                                    .split(" ")
-                                    .map(|x| {
-                                        FieldPrime::try_from_dec_str(x).map_err(|_| x.to_string())
-                                    })
-                                    .collect()
+                                Ok(input.split(" ").map(|x| x.to_string()).collect())
                             }
                             Err(_) => Err(String::from("???")),
                         }
@@ -382,158 +1336,536 @@ fn cli() -> Result<(), String> {
                 ))?
             }
 
-            let witness = program_ast
-                .execute(&arguments)
-                .map_err(|e| format!("Execution failed: {}", e))?;
+            // interpret each argument based on the type of the main input it belongs to
+            let argument_types = program_ast
+                .signature
+                .inputs
+                .iter()
+                .flat_map(primitive_types);
+
+            arguments
+                .iter()
+                .zip(argument_types)
+                .map(|(x, t)| parse_argument(x, &t))
+                .collect::<Result<_, _>>()
+                .map_err(|e| format!("Could not parse argument: {}", e))?
+        }
+    };
+
+    let witness = program_ast
+        .execute(&arguments)
+        .map_err(|e| format!("Execution failed: {}", e))?;
+
+    println!("\nWitness: \n\n{}", witness.format_outputs());
+
+    // write witness to file
+    let output_path = Path::new(sub_matches.value_of("output").unwrap());
+    let output_file = File::create(&output_path)
+        .map_err(|why| format!("couldn't create {}: {}", output_path.display(), why))?;
+
+    let writer = BufWriter::new(output_file);
+
+    match sub_matches.value_of("format").unwrap() {
+        "csv" => witness
+            .write(writer)
+            .map_err(|why| format!("could not save witness: {:?}", why))?,
+        "wtns" => witness
+            .write_wtns(&program_ast.r1cs().wires, writer)
+            .map_err(|why| format!("could not save witness: {:?}", why))?,
+        "json" => witness
+            .write_json(writer)
+            .map_err(|why| format!("could not save witness: {:?}", why))?,
+        _ => unreachable!(),
+    }
+    Ok(())
+}
+
+fn cli_setup<T: Curve>(sub_matches: &ArgMatches) -> Result<(), String> {
+    let scheme = T::get_scheme(sub_matches.value_of("proving-scheme").unwrap())?;
 
-            println!("\nWitness: \n\n{}", witness.format_outputs());
+    println!("Performing setup...");
 
-            // write witness to file
-            let output_path = Path::new(sub_matches.value_of("output").unwrap());
-            let output_file = File::create(&output_path)
-                .map_err(|why| format!("couldn't create {}: {}", output_path.display(), why))?;
+    let path = Path::new(sub_matches.value_of("input").unwrap());
+    let file =
+        File::open(&path).map_err(|why| format!("couldn't open {}: {}", path.display(), why))?;
 
-            let writer = BufWriter::new(output_file);
+    let mut reader = BufReader::new(file);
 
-            witness
-                .write(writer)
-                .map_err(|why| format!("could not save witness: {:?}", why))?;
-        }
-        ("setup", Some(sub_matches)) => {
-            let scheme = get_scheme(sub_matches.value_of("proving-scheme").unwrap())?;
+    let program: ir::Prog<T> =
+        deserialize_from(&mut reader, Infinite).map_err(|why| format!("{:?}", why))?;
 
-            println!("Performing setup...");
+    // print deserialized flattened program
+    if !sub_matches.is_present("light") {
+        println!("{}", program);
+    }
 
-            let path = Path::new(sub_matches.value_of("input").unwrap());
-            let file = File::open(&path)
-                .map_err(|why| format!("couldn't open {}: {}", path.display(), why))?;
+    // get paths for proving and verification keys
+    let pk_path = sub_matches.value_of("proving-key-path").unwrap();
+    let vk_path = sub_matches.value_of("verification-key-path").unwrap();
 
-            let mut reader = BufReader::new(file);
+    // run setup phase
+    scheme.setup(program, pk_path, vk_path)
+}
 
-            let program: ir::Prog<FieldPrime> =
-                deserialize_from(&mut reader, Infinite).map_err(|why| format!("{:?}", why))?;
+fn cli_generate_proof<T: Curve>(sub_matches: &ArgMatches) -> Result<(), String> {
+    println!("Generating proof...");
 
-            // print deserialized flattened program
-            if !sub_matches.is_present("light") {
-                println!("{}", program);
-            }
+    let scheme = T::get_scheme(sub_matches.value_of("proving-scheme").unwrap())?;
 
-            // get paths for proving and verification keys
-            let pk_path = sub_matches.value_of("proving-key-path").unwrap();
-            let vk_path = sub_matches.value_of("verification-key-path").unwrap();
+    let program_path = Path::new(sub_matches.value_of("input").unwrap());
+    let program_file = File::open(&program_path)
+        .map_err(|why| format!("couldn't open {}: {}", program_path.display(), why))?;
 
-            // run setup phase
-            scheme.setup(program, pk_path, vk_path);
-        }
-        ("export-verifier", Some(sub_matches)) => {
-            {
-                let scheme = get_scheme(sub_matches.value_of("proving-scheme").unwrap())?;
-                let is_abiv2 = sub_matches.value_of("abi").unwrap() == "v2";
-                println!("Exporting verifier...");
+    let mut reader = BufReader::new(program_file);
 
-                // read vk file
-                let input_path = Path::new(sub_matches.value_of("input").unwrap());
-                let input_file = File::open(&input_path)
-                    .map_err(|why| format!("couldn't open {}: {}", input_path.display(), why))?;
-                let reader = BufReader::new(input_file);
+    let program: ir::Prog<T> =
+        deserialize_from(&mut reader, Infinite).map_err(|why| format!("{:?}", why))?;
 
-                let verifier = scheme.export_solidity_verifier(reader, is_abiv2);
+    // deserialize witness, the values of a `.wtns` witness being those of the program's wires
+    let witness_path = Path::new(sub_matches.value_of("witness").unwrap());
+    let witness_file = match File::open(&witness_path) {
+        Ok(file) => file,
+        Err(why) => panic!("couldn't open {}: {}", witness_path.display(), why),
+    };
 
-                //write output file
-                let output_path = Path::new(sub_matches.value_of("output").unwrap());
-                let output_file = File::create(&output_path)
-                    .map_err(|why| format!("couldn't create {}: {}", output_path.display(), why))?;
+    let witness = ir::Witness::read(BufReader::new(witness_file), &program.r1cs().wires)
+        .map_err(|why| format!("could not load witness: {:?}", why))?;
 
-                let mut writer = BufWriter::new(output_file);
+    let pk_path = sub_matches.value_of("provingkey").unwrap();
+    let proof_path = sub_matches.value_of("proofpath").unwrap();
 
-                writer
-                    .write_all(&verifier.as_bytes())
-                    .map_err(|_| "Failed writing output to file.".to_string())?;
-                println!("Finished exporting verifier.");
+    if let Some(threads) = sub_matches.value_of("threads") {
+        let threads = threads
+            .parse()
+            .map_err(|_| format!("Invalid number of threads: {}", threads))?;
+        set_threads(threads)?;
+    }
+
+    scheme.generate_proof(program, witness, pk_path, proof_path)?;
+
+    println!("generate-proof successful: {}", proof_path);
+    Ok(())
+}
+
+fn cli_verify<T: Curve>(sub_matches: &ArgMatches) -> Result<(), String> {
+    let scheme = T::get_scheme(sub_matches.value_of("proving-scheme").unwrap())?;
+
+    println!("Verifying proof...");
+
+    let vk_path = sub_matches.value_of("verification-key-path").unwrap();
+    let proof_path = sub_matches.value_of("proofpath").unwrap();
+
+    match scheme.verify(vk_path, proof_path)? {
+        true => println!("PASSED"),
+        false => Err("FAILED")?,
+    }
+    Ok(())
+}
+
+fn cli_check_soundness<T: Curve>(sub_matches: &ArgMatches) -> Result<(), String> {
+    println!("Checking soundness...");
+
+    let path = Path::new(sub_matches.value_of("input").unwrap());
+    let file =
+        File::open(&path).map_err(|why| format!("couldn't open {}: {}", path.display(), why))?;
+
+    let mut reader = BufReader::new(file);
+
+    let program: ir::Prog<T> =
+        deserialize_from(&mut reader, Infinite).map_err(|why| format!("{:?}", why))?;
+
+    let (harmless, underconstrained): (Vec<_>, Vec<_>) = program
+        .underconstrained_variables()
+        .into_iter()
+        .partition(|v| v.harmless);
+
+    if sub_matches.is_present("verbose") && !harmless.is_empty() {
+        println!(
+            "{} variable(s) are not determined but cannot influence other variables:",
+            harmless.len()
+        );
+        for v in &harmless {
+            println!("\t{}", v);
+        }
+    }
+
+    match underconstrained.len() {
+        0 => {
+            println!("No underconstrained variables found");
+            Ok(())
+        }
+        count => {
+            println!("Found {} underconstrained variable(s):", count);
+            for v in &underconstrained {
+                println!("\t{}", v);
             }
+            Err(String::from("Soundness check failed"))
         }
-        ("generate-proof", Some(sub_matches)) => {
-            println!("Generating proof...");
+    }
+}
 
-            let scheme = get_scheme(sub_matches.value_of("proving-scheme").unwrap())?;
+fn cli_export_r1cs<T: Curve>(sub_matches: &ArgMatches) -> Result<(), String> {
+    println!("Exporting constraint system...");
 
-            // deserialize witness
-            let witness_path = Path::new(sub_matches.value_of("witness").unwrap());
-            let witness_file = match File::open(&witness_path) {
-                Ok(file) => file,
-                Err(why) => panic!("couldn't open {}: {}", witness_path.display(), why),
-            };
+    let path = Path::new(sub_matches.value_of("input").unwrap());
+    let file =
+        File::open(&path).map_err(|why| format!("couldn't open {}: {}", path.display(), why))?;
+
+    let mut reader = BufReader::new(file);
 
-            let witness = ir::Witness::read(witness_file)
-                .map_err(|why| format!("could not load witness: {:?}", why))?;
+    let program: ir::Prog<T> =
+        deserialize_from(&mut reader, Infinite).map_err(|why| format!("{:?}", why))?;
 
-            let pk_path = sub_matches.value_of("provingkey").unwrap();
-            let proof_path = sub_matches.value_of("proofpath").unwrap();
+    let format = sub_matches.value_of("format").unwrap();
+    let output_path = Path::new(sub_matches.value_of("output").unwrap_or(match format {
+        "json" => "out.r1cs.json",
+        _ => "out.r1cs",
+    }));
+    let output_file = File::create(&output_path)
+        .map_err(|why| format!("couldn't create {}: {}", output_path.display(), why))?;
+    let writer = BufWriter::new(output_file);
 
-            let program_path = Path::new(sub_matches.value_of("input").unwrap());
-            let program_file = File::open(&program_path)
-                .map_err(|why| format!("couldn't open {}: {}", program_path.display(), why))?;
+    let r1cs = program.r1cs();
+
+    match format {
+        "json" => r1cs
+            .write_json(writer)
+            .map_err(|why| format!("Unable to write data to file: {}", why))?,
+        _ => r1cs
+            .write_iden3(writer)
+            .map_err(|why| format!("Unable to write data to file: {}", why))?,
+    }
+
+    println!(
+        "Constraint system with {} wires and {} constraints written to '{}'",
+        r1cs.wires.len(),
+        r1cs.constraints.len(),
+        output_path.display()
+    );
+    Ok(())
+}
+
+fn cli_mpc<T: Curve>(command: &str, sub_matches: &ArgMatches) -> Result<(), String> {
+    let read_program = |path: &str| -> Result<ir::Prog<T>, String> {
+        let path = Path::new(path);
+        let file = File::open(&path)
+            .map_err(|why| format!("couldn't open {}: {}", path.display(), why))?;
+        deserialize_from(&mut BufReader::new(file), Infinite).map_err(|why| format!("{:?}", why))
+    };
+
+    let open = |path: &str| {
+        File::open(path)
+            .map(BufReader::new)
+            .map_err(|why| format!("couldn't open {}: {}", path, why))
+    };
+
+    let read_parameters = |path: &str| -> Result<MpcParameters<T::BellmanEngine>, String> {
+        MpcParameters::<T::BellmanEngine>::read(open(path)?, true)
+            .map_err(|why| format!("could not load parameters from {}: {}", path, why))
+    };
+
+    let write_parameters = |parameters: &MpcParameters<T::BellmanEngine>, path: &str| {
+        File::create(path)
+            .map(BufWriter::new)
+            .and_then(|writer| parameters.write(writer))
+            .map_err(|why| format!("couldn't write {}: {}", path, why))
+    };
+
+    let to_hex = |hash: &[u8]| {
+        hash.iter()
+            .map(|b| format!("{:02x}", b))
+            .collect::<String>()
+    };
+
+    match command {
+        "init" => {
+            println!("Initializing MPC...");
+
+            let program = read_program(sub_matches.value_of("input").unwrap())?;
+            let parameters =
+                MpcParameters::new(program, open(sub_matches.value_of("radix-path").unwrap())?)?;
+
+            let output_path = sub_matches.value_of("output").unwrap();
+            write_parameters(&parameters, output_path)?;
+
+            println!("Initial parameters written to '{}'", output_path);
+        }
+        "contribute" => {
+            println!("Contributing to MPC...");
 
-            let mut reader = BufReader::new(program_file);
+            let mut parameters = read_parameters(sub_matches.value_of("input").unwrap())?;
+            let hash = parameters
+                .contribute(sub_matches.value_of("entropy").unwrap().as_bytes())
+                .map_err(|why| format!("could not contribute: {}", why))?;
 
-            let program: ir::Prog<FieldPrime> =
-                deserialize_from(&mut reader, Infinite).map_err(|why| format!("{:?}", why))?;
+            let output_path = sub_matches.value_of("output").unwrap();
+            write_parameters(&parameters, output_path)?;
 
+            println!("Contribution written to '{}'", output_path);
             println!(
-                "generate-proof successful: {:?}",
-                scheme.generate_proof(program, witness, pk_path, proof_path)
+                "Publish the hash of your contribution:\n{}",
+                to_hex(&hash[..])
             );
         }
-        ("print-proof", Some(sub_matches)) => {
-            let format = sub_matches.value_of("format").unwrap();
+        "verify" => {
+            println!("Verifying MPC...");
 
-            let path = Path::new(sub_matches.value_of("proofpath").unwrap());
+            let parameters = read_parameters(sub_matches.value_of("input").unwrap())?;
+            let program = read_program(sub_matches.value_of("program").unwrap())?;
+            let hashes =
+                parameters.verify(program, open(sub_matches.value_of("radix-path").unwrap())?)?;
 
-            let file = File::open(&path)
-                .map_err(|why| format!("couldn't open {}: {}", path.display(), why))?;
+            println!("Verification succeeded, contributions:");
+            for (index, hash) in hashes.iter().enumerate() {
+                println!("{}: {}", index + 1, to_hex(&hash[..]));
+            }
+        }
+        "export" => {
+            println!("Exporting keys from MPC...");
 
-            let proof_object: Value =
-                serde_json::from_reader(file).map_err(|why| format!("{:?}", why))?;
+            let parameters = read_parameters(sub_matches.value_of("input").unwrap())?;
 
-            match format {
-                "json" => {
-                    println!("~~~~~~~~ Copy the output below for valid ABIv2 format ~~~~~~~~");
-                    println!();
-                    print!("{}", proof_object["proof"]);
-                    print!(",");
-                    println!("{}", proof_object["inputs"]);
-                    println!();
-                    println!("~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~");
-                }
-                "remix" => {
-                    println!("~~~~~~~~ Copy the output below for valid ABIv1 format ~~~~~~~~");
-                    println!();
+            let pk_path = sub_matches.value_of("proving-key-path").unwrap();
+            let vk_path = sub_matches.value_of("verification-key-path").unwrap();
 
-                    for (_, value) in proof_object["proof"].as_object().unwrap().iter() {
-                        print!("{}", value);
-                        print!(",");
-                    }
+            G16::write_keys(parameters.parameters(), pk_path, vk_path)
+                .map_err(|why| format!("could not write keys: {}", why))?;
 
-                    println!("{}", proof_object["inputs"]);
-                    println!();
-                    println!("~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~~");
+            println!(
+                "Proving key written to '{}', verification key written to '{}'",
+                pk_path, vk_path
+            );
+        }
+        _ => unreachable!(),
+    }
+    Ok(())
+}
+
+fn cli_test<T: Curve>(sub_matches: &ArgMatches) -> Result<(), String> {
+    let directory = Path::new(sub_matches.value_of("dir").unwrap());
+
+    let (passed, failed) = run_tests::<T>(directory)?;
+
+    match failed {
+        0 => println!("\ntest result: ok. {} passed; 0 failed", passed),
+        _ => {
+            println!(
+                "\ntest result: FAILED. {} passed; {} failed",
+                passed, failed
+            );
+            Err(String::from("Some tests failed"))?
+        }
+    }
+    Ok(())
+}
+
+/// Runs the tests found under `directory`, compiling each entry point once, and returns the
+/// number of passed and failed tests
+fn run_tests<T: Field>(directory: &Path) -> Result<(usize, usize), String> {
+    // the outputs of a project, such as its ABI specifications and proofs, are not searched
+    let exclude = if directory.join(project::MANIFEST).exists() {
+        let manifest = Manifest::read(directory)?;
+        manifest
+            .circuits
+            .iter()
+            .map(|circuit| {
+                manifest.config(circuit).map(|config| {
+                    directory
+                        .join(config.output)
+                        .parent()
+                        .unwrap()
+                        .to_path_buf()
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?
+    } else {
+        vec![]
+    };
+
+    let paths = find_test_files(directory, &exclude)
+        .map_err(|why| format!("couldn't read {}: {}", directory.display(), why))?;
+
+    let mut programs = BTreeMap::new();
+    let (mut passed, mut failed) = (0, 0);
+
+    for path in paths {
+        let tests = match Tests::read(&path) {
+            Ok(Some(tests)) => tests,
+            Ok(None) => continue,
+            Err(e) => {
+                println!("test {} ... FAILED\n\t{}", path.display(), e);
+                failed += 1;
+                continue;
+            }
+        };
+
+        let program = programs
+            .entry(tests.entry_point.clone())
+            .or_insert_with(|| {
+                compile_entry_point::<T>(&tests.entry_point, &ImportResolver::default())
+                    .map(|artifacts| artifacts.prog().clone())
+            });
+
+        let program = match program {
+            Ok(program) => program,
+            Err(e) => {
+                println!("test {} ... FAILED\n\t{}", path.display(), e);
+                failed += tests.tests.len();
+                continue;
+            }
+        };
+
+        for (index, test) in tests.tests.iter().enumerate() {
+            let result = test
+                .inputs::<T>()
+                .and_then(|inputs| test.check(program.execute(&inputs)));
+
+            match result {
+                Ok(()) => {
+                    println!("test {} #{} ... ok", path.display(), index + 1);
+                    passed += 1;
+                }
+                Err(e) => {
+                    println!(
+                        "test {} #{} ... FAILED\n\tinput: [{}]\n\t{}",
+                        path.display(),
+                        index + 1,
+                        test.input.values.join(", "),
+                        e.replace("\n", "\n\t")
+                    );
+                    failed += 1;
                 }
-                _ => unreachable!(),
             }
         }
-        _ => unreachable!(),
+    }
+
+    Ok((passed, failed))
+}
+
+fn compile_entry_point<T: Field>(
+    path: &Path,
+    resolver: &dyn Resolver,
+) -> Result<CompilationArtifacts<T>, String> {
+    let location = path
+        .parent()
+        .unwrap()
+        .to_path_buf()
+        .into_os_string()
+        .into_string()
+        .unwrap();
+
+    let file =
+        File::open(path).map_err(|why| format!("couldn't open {}: {}", path.display(), why))?;
+
+    let mut reader = BufReader::new(file);
+
+    compile(&mut reader, Some(location), Some(resolver))
+        .map_err(|e| format!("Compilation of {} failed:\n\n {}", path.display(), e))
+}
+
+fn cli_profile<T: Curve>(sub_matches: &ArgMatches) -> Result<(), String> {
+    println!("Profiling {}\n", sub_matches.value_of("input").unwrap());
+
+    let path = PathBuf::from(sub_matches.value_of("input").unwrap());
+
+    let location = path
+        .parent()
+        .unwrap()
+        .to_path_buf()
+        .into_os_string()
+        .into_string()
+        .unwrap();
+
+    let file = File::open(path.clone())
+        .map_err(|why| format!("couldn't open {}: {}", path.display(), why))?;
+
+    let mut reader = BufReader::new(file);
+
+    let profile = profile::<T, _>(
+        &mut reader,
+        Some(location),
+        Some(&ImportResolver::default()),
+    )
+    .map_err(|e| format!("Compilation failed:\n\n {}", e))?;
+
+    println!("{}", profile);
+
+    if let Some(json_path) = sub_matches.value_of("json") {
+        let json_path = Path::new(json_path);
+        let json_file = File::create(&json_path)
+            .map_err(|why| format!("couldn't create {}: {}", json_path.display(), why))?;
+
+        serde_json::to_writer_pretty(BufWriter::new(json_file), &profile)
+            .map_err(|_| "Unable to write data to file.".to_string())?;
+
+        println!("\nProfile written to '{}'", json_path.display());
     }
     Ok(())
 }
 
-fn get_scheme(scheme_str: &str) -> Result<&'static dyn ProofSystem, String> {
-    match scheme_str.to_lowercase().as_ref() {
-        #[cfg(feature = "libsnark")]
-        "pghr13" => Ok(&PGHR13 {}),
-        #[cfg(feature = "libsnark")]
-        "gm17" => Ok(&GM17 {}),
-        "g16" => Ok(&G16 {}),
-        s => Err(format!("Backend \"{}\" not supported", s)),
+// the primitive types `ty` is flattened to, in the order its values are passed on the command line
+fn primitive_types(ty: &Type) -> Vec<Type> {
+    match ty {
+        Type::Array(array_type) => (0..array_type.size)
+            .flat_map(|_| primitive_types(&array_type.ty))
+            .collect(),
+        Type::Struct(struct_type) => struct_type
+            .iter()
+            .flat_map(|m| primitive_types(&m.ty))
+            .collect(),
+        t => vec![t.clone()],
+    }
+}
+
+fn parse_argument<T: Field>(value: &str, ty: &Type) -> Result<T, String> {
+    match ty {
+        Type::Boolean => match value {
+            "true" | "1" => Ok(T::from(1)),
+            "false" | "0" => Ok(T::from(0)),
+            _ => Err(format!("{} is not a valid {}", value, ty)),
+        },
+        Type::Uint(bitwidth) => {
+            let parsed = match value.starts_with("0x") {
+                true => u128::from_str_radix(&value[2..], 16),
+                false => u128::from_str_radix(value, 10),
+            };
+            match parsed {
+                Ok(v) if v >> bitwidth == 0 => Ok(T::try_from_dec_str(&v.to_string()).unwrap()),
+                _ => Err(format!("{} is not a valid {}", value, ty)),
+            }
+        }
+        _ => T::try_from_dec_str(value).map_err(|_| value.to_string()),
+    }
+}
+
+// a curve supported by the command line, identified by its scalar field, along with the
+// proving schemes available on it
+trait Curve: Field + serde::Serialize + serde::de::DeserializeOwned + 'static {
+    fn get_scheme(scheme_str: &str) -> Result<&'static dyn ProofSystem<Self>, String>;
+}
+
+impl Curve for FieldPrime {
+    fn get_scheme(scheme_str: &str) -> Result<&'static dyn ProofSystem<Self>, String> {
+        match scheme_str.to_lowercase().as_ref() {
+            #[cfg(feature = "libsnark")]
+            "pghr13" => Ok(&PGHR13 {}),
+            "gm17" => Ok(&GM17 {}),
+            "g16" => Ok(&G16 {}),
+            s => Err(format!("Backend \"{}\" not supported", s)),
+        }
+    }
+}
+
+impl Curve for Bls12Field {
+    fn get_scheme(scheme_str: &str) -> Result<&'static dyn ProofSystem<Self>, String> {
+        match scheme_str.to_lowercase().as_ref() {
+            "gm17" => Ok(&GM17 {}),
+            "g16" => Ok(&G16 {}),
+            s => Err(format!(
+                "Backend \"{}\" not supported on curve bls12_381",
+                s
+            )),
+        }
     }
 }
 
@@ -543,6 +1875,184 @@ mod tests {
     use self::glob::glob;
     use super::*;
 
+    #[test]
+    fn parse_typed_arguments() {
+        assert_eq!(
+            parse_argument("42", &Type::FieldElement),
+            Ok(FieldPrime::from(42))
+        );
+        assert_eq!(
+            parse_argument("true", &Type::Boolean),
+            Ok(FieldPrime::from(1))
+        );
+        assert_eq!(
+            parse_argument("0xff", &Type::Uint(8)),
+            Ok(FieldPrime::from(255))
+        );
+        assert_eq!(
+            parse_argument("65535", &Type::Uint(16)),
+            Ok(FieldPrime::from(65535))
+        );
+        assert!(parse_argument::<FieldPrime>("256", &Type::Uint(8)).is_err());
+        assert!(parse_argument::<FieldPrime>("2", &Type::Boolean).is_err());
+    }
+
+    #[test]
+    fn run_test_files() {
+        let dir = tempdir::TempDir::new("tests").unwrap();
+
+        std::fs::write(
+            dir.path().join("add.json"),
+            r#"{
+                "entry_point": "./examples/add.code",
+                "tests": [
+                    { "input": { "values": ["1"] }, "output": { "Ok": { "values": ["38"] } } },
+                    { "input": { "values": ["0"] }, "output": { "Ok": { "values": ["0"] } } },
+                    {
+                        "input": { "values": [] },
+                        "output": { "Err": { "WrongInputCount": { "expected": 1, "received": 0 } } }
+                    }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        // other JSON files are ignored
+        std::fs::write(dir.path().join("abi.json"), "{}").unwrap();
+        std::fs::write(
+            dir.path().join("add.inputs.json"),
+            r#"{ "entry_point": "./examples/add.code" }"#,
+        )
+        .unwrap();
+
+        // and so are the outputs of a project
+        std::fs::write(
+            dir.path().join(project::MANIFEST),
+            r#"
+                [project]
+                name = "tests"
+                version = "0.1.0"
+
+                [[circuit]]
+                name = "main"
+                entry_point = "main.code"
+            "#,
+        )
+        .unwrap();
+        let output = dir.path().join("target").join("main");
+        std::fs::create_dir_all(&output).unwrap();
+        std::fs::write(output.join("proof.json"), "").unwrap();
+
+        assert_eq!(run_tests::<FieldPrime>(dir.path()), Ok((2, 1)));
+    }
+
+    #[test]
+    fn build_new_project() {
+        let dir = tempdir::TempDir::new("project").unwrap();
+        let root = dir.path().join("example");
+
+        project::create(&root, "example").unwrap();
+        // projects are not created over existing files
+        assert!(project::create(&root, "example").is_err());
+
+        build_project(&root, false).unwrap();
+
+        let output = root.join("target").join("main");
+        let proving_key = std::fs::read(output.join("proving.key")).unwrap();
+        assert!(output.join("verifier.sol").exists());
+
+        // the setup is skipped when the program did not change
+        build_project(&root, false).unwrap();
+        assert_eq!(
+            std::fs::read(output.join("proving.key")).unwrap(),
+            proving_key
+        );
+
+        std::fs::write(
+            root.join("src").join("main.code"),
+            "def main(private field a, field b) -> (field):\n  return a * b\n",
+        )
+        .unwrap();
+
+        build_project(&root, false).unwrap();
+        assert_ne!(
+            std::fs::read(output.join("proving.key")).unwrap(),
+            proving_key
+        );
+    }
+
+    #[test]
+    fn build_project_with_path_dependency() {
+        let dir = tempdir::TempDir::new("project").unwrap();
+        let root = dir.path().join("example");
+        let utils = dir.path().join("utils");
+
+        project::create(&root, "example").unwrap();
+        std::fs::create_dir(&utils).unwrap();
+        std::fs::write(
+            utils.join("square.code"),
+            "def main(field a) -> (field):\n  return a * a\n",
+        )
+        .unwrap();
+
+        let manifest = std::fs::read_to_string(root.join(project::MANIFEST)).unwrap();
+        std::fs::write(
+            root.join(project::MANIFEST),
+            format!("{}utils = {{ path = \"../utils\" }}\n", manifest),
+        )
+        .unwrap();
+        std::fs::write(
+            root.join("src").join("main.code"),
+            "import \"utils/square.code\" as square\n\ndef main(private field a, field b) -> (field):\n  return square(a) * b\n",
+        )
+        .unwrap();
+
+        build_project(&root, false).unwrap();
+
+        let output = root.join("target").join("main");
+        let program = std::fs::read(output.join("out")).unwrap();
+
+        // a change to a dependency outside of the project is picked up
+        std::fs::write(
+            utils.join("square.code"),
+            "def main(field a) -> (field):\n  return a * a * a\n",
+        )
+        .unwrap();
+
+        build_project(&root, false).unwrap();
+        assert_ne!(std::fs::read(output.join("out")).unwrap(), program);
+    }
+
+    #[cfg(feature = "github")]
+    #[test]
+    fn github_dependencies() {
+        let resolver = ImportResolver::default()
+            .github_library(
+                String::from("ecc"),
+                "owner/repo",
+                Some(&String::from("v1.0")),
+            )
+            .unwrap()
+            .github_library(String::from("utils"), "owner/utils", None)
+            .unwrap();
+
+        assert_eq!(
+            resolver.github_import("ecc/edwards.code"),
+            Some(String::from("github.com/owner/repo/v1.0/edwards.code"))
+        );
+        assert_eq!(
+            resolver.github_import("utils/hashes/sha256.code"),
+            Some(String::from(
+                "github.com/owner/utils/master/hashes/sha256.code"
+            ))
+        );
+        assert_eq!(resolver.github_import("hashes/sha256.code"), None);
+
+        assert!(ImportResolver::default()
+            .github_library(String::from("ecc"), "repo", None)
+            .is_err());
+    }
+
     #[test]
     fn examples() {
         for p in glob("./examples/**/*.code").expect("Failed to read glob pattern") {
@@ -568,8 +2078,12 @@ mod tests {
                 .into_string()
                 .unwrap();
 
-            let _: ir::Prog<FieldPrime> =
-                compile(&mut reader, Some(location), Some(resolve)).unwrap();
+            let _: CompilationArtifacts<FieldPrime> = compile(
+                &mut reader,
+                Some(location),
+                Some(&ImportResolver::default()),
+            )
+            .unwrap();
         }
     }
 
@@ -595,10 +2109,15 @@ mod tests {
 
             let mut reader = BufReader::new(file);
 
-            let program_flattened: ir::Prog<FieldPrime> =
-                compile(&mut reader, Some(location), Some(resolve)).unwrap();
+            let artifacts: CompilationArtifacts<FieldPrime> = compile(
+                &mut reader,
+                Some(location),
+                Some(&ImportResolver::default()),
+            )
+            .unwrap();
 
-            let _ = program_flattened
+            let _ = artifacts
+                .prog()
                 .execute(&vec![FieldPrime::from(0)])
                 .unwrap();
         }
@@ -627,10 +2146,15 @@ mod tests {
 
             let mut reader = BufReader::new(file);
 
-            let program_flattened: ir::Prog<FieldPrime> =
-                compile(&mut reader, Some(location), Some(resolve)).unwrap();
+            let artifacts: CompilationArtifacts<FieldPrime> = compile(
+                &mut reader,
+                Some(location),
+                Some(&ImportResolver::default()),
+            )
+            .unwrap();
 
-            let _ = program_flattened
+            let _ = artifacts
+                .prog()
                 .execute(&vec![FieldPrime::from(0)])
                 .unwrap();
         }
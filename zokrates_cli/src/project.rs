@@ -0,0 +1,503 @@
+//! Projects described by a `zokrates.toml` manifest, which declares the programs to build and the
+//! options of each step, so that `zokrates build` can compile them, run the setup and export the
+//! verifiers without passing paths around
+//!
+//! ```toml
+//! [project]
+//! name = "example"
+//! version = "0.1.0"
+//!
+//! # defaults for all circuits
+//! [build]
+//! curve = "bn128"
+//! proving_scheme = "g16"
+//! solidity_abi = "v1"
+//! output = "target"
+//!
+//! [[circuit]]
+//! name = "main"
+//! entry_point = "src/main.code"
+//! # any option of [build] can be overridden for a circuit
+//! proving_scheme = "gm17"
+//!
+//! [dependencies]
+//! utils = { path = "../utils" }
+//! ecc = { github = "owner/repo", rev = "master" }
+//! ```
+
+use blake2_rfc_bellman_edition::blake2b::Blake2b;
+use serde_derive::Deserialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::{self, File};
+use std::io::{self, BufReader, Read};
+use std::path::{Path, PathBuf};
+
+pub const MANIFEST: &str = "zokrates.toml";
+
+/// The file holding the fingerprints of the last build of a circuit, in its output directory
+const FINGERPRINTS: &str = "fingerprints.json";
+
+const CURVES: &[&str] = &["bn128", "bls12_381"];
+#[cfg(feature = "libsnark")]
+const PROVING_SCHEMES: &[&str] = &["g16", "gm17", "pghr13"];
+#[cfg(not(feature = "libsnark"))]
+const PROVING_SCHEMES: &[&str] = &["g16", "gm17"];
+const SOLIDITY_ABIS: &[&str] = &["v1", "v2"];
+
+#[derive(Deserialize)]
+pub struct Manifest {
+    pub project: Package,
+    #[serde(default)]
+    pub build: Options,
+    #[serde(default, rename = "circuit")]
+    pub circuits: Vec<Circuit>,
+    #[serde(default)]
+    pub dependencies: BTreeMap<String, Dependency>,
+}
+
+#[derive(Deserialize)]
+pub struct Package {
+    pub name: String,
+    pub version: String,
+}
+
+#[derive(Deserialize, Default)]
+pub struct Options {
+    pub curve: Option<String>,
+    pub proving_scheme: Option<String>,
+    pub solidity_abi: Option<String>,
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Deserialize)]
+pub struct Circuit {
+    pub name: String,
+    pub entry_point: PathBuf,
+    #[serde(flatten)]
+    pub options: Options,
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+#[serde(untagged)]
+pub enum Dependency {
+    Path {
+        path: PathBuf,
+    },
+    Github {
+        github: String,
+        #[serde(default)]
+        rev: Option<String>,
+    },
+}
+
+/// The options of a circuit, after applying the defaults of the project
+#[derive(Debug, PartialEq)]
+pub struct Config {
+    pub curve: String,
+    pub proving_scheme: String,
+    pub solidity_abi: String,
+    /// The directory the artifacts of the circuit are written to
+    pub output: PathBuf,
+}
+
+impl Manifest {
+    /// Reads the manifest of the project at `root`
+    pub fn read(root: &Path) -> Result<Manifest, String> {
+        let path = root.join(MANIFEST);
+        let mut source = String::new();
+        File::open(&path)
+            .and_then(|mut file| file.read_to_string(&mut source))
+            .map_err(|why| format!("couldn't read {}: {}", path.display(), why))?;
+
+        let manifest = Manifest::parse(&source)
+            .map_err(|why| format!("invalid manifest {}: {}", path.display(), why))?;
+
+        for (name, dependency) in &manifest.dependencies {
+            if let Dependency::Path { path } = dependency {
+                if !root.join(path).is_dir() {
+                    return Err(format!(
+                        "dependency {} not found at {}",
+                        name,
+                        root.join(path).display()
+                    ));
+                }
+            }
+        }
+
+        Ok(manifest)
+    }
+
+    pub fn parse(source: &str) -> Result<Manifest, String> {
+        let manifest: Manifest = toml::from_str(source).map_err(|why| why.to_string())?;
+
+        if manifest.circuits.is_empty() {
+            return Err(String::from("no circuit declared"));
+        }
+
+        let mut names = BTreeSet::new();
+        for circuit in &manifest.circuits {
+            if !names.insert(&circuit.name) {
+                return Err(format!("circuit {} is declared twice", circuit.name));
+            }
+            manifest.config(circuit)?;
+        }
+
+        Ok(manifest)
+    }
+
+    pub fn config(&self, circuit: &Circuit) -> Result<Config, String> {
+        let option = |circuit: &Option<String>, project: &Option<String>, default: &str| {
+            circuit
+                .clone()
+                .or_else(|| project.clone())
+                .unwrap_or_else(|| default.to_string())
+        };
+
+        let config = Config {
+            curve: option(&circuit.options.curve, &self.build.curve, "bn128"),
+            proving_scheme: option(
+                &circuit.options.proving_scheme,
+                &self.build.proving_scheme,
+                "g16",
+            ),
+            solidity_abi: option(
+                &circuit.options.solidity_abi,
+                &self.build.solidity_abi,
+                "v1",
+            ),
+            output: circuit
+                .options
+                .output
+                .clone()
+                .or_else(|| self.build.output.clone())
+                .unwrap_or_else(|| PathBuf::from("target"))
+                .join(&circuit.name),
+        };
+
+        if !CURVES.contains(&config.curve.as_str()) {
+            return Err(format!(
+                "unknown curve {} for circuit {}",
+                config.curve, circuit.name
+            ));
+        }
+
+        if !PROVING_SCHEMES.contains(&config.proving_scheme.to_lowercase().as_str()) {
+            return Err(format!(
+                "unknown proving scheme {} for circuit {}",
+                config.proving_scheme, circuit.name
+            ));
+        }
+
+        if !SOLIDITY_ABIS.contains(&config.solidity_abi.as_str()) {
+            return Err(format!(
+                "unknown solidity ABI {} for circuit {}",
+                config.solidity_abi, circuit.name
+            ));
+        }
+
+        Ok(config)
+    }
+}
+
+/// Creates a project called `name` at `path`, with a manifest, a program and a test file
+pub fn create(path: &Path, name: &str) -> Result<(), String> {
+    let occupied = match fs::read_dir(path) {
+        Ok(mut entries) => entries.next().is_some(),
+        Err(_) => path.exists(),
+    };
+
+    if occupied {
+        return Err(format!("{} already exists", path.display()));
+    }
+
+    let write = |file: &str, content: &str| {
+        let file = path.join(file);
+        fs::create_dir_all(file.parent().unwrap())
+            .and_then(|_| fs::write(&file, content))
+            .map_err(|why| format!("couldn't create {}: {}", file.display(), why))
+    };
+
+    write(
+        MANIFEST,
+        &format!(
+            r#"[project]
+name = "{}"
+version = "0.1.0"
+
+[build]
+curve = "bn128"
+proving_scheme = "g16"
+solidity_abi = "v1"
+output = "target"
+
+[[circuit]]
+name = "main"
+entry_point = "src/main.code"
+
+[dependencies]
+"#,
+            name
+        ),
+    )?;
+
+    write(
+        "src/main.code",
+        r#"def main(private field a, field b) -> (field):
+  field result = if a * a == b then 1 else 0 fi
+  return result
+"#,
+    )?;
+
+    write(
+        "tests/main.json",
+        r#"{
+    "entry_point": "./src/main.code",
+    "tests": [
+        { "input": { "values": ["3", "9"] }, "output": { "Ok": { "values": ["1"] } } },
+        { "input": { "values": ["3", "8"] }, "output": { "Ok": { "values": ["0"] } } }
+    ]
+}
+"#,
+    )?;
+
+    write(".gitignore", "target/\n")
+}
+
+/// A fingerprint of the inputs of a build step, to skip it if they did not change. It is stored
+/// across runs, so it uses a hash function whose output does not depend on the Rust release.
+pub struct Fingerprint(Blake2b);
+
+impl Default for Fingerprint {
+    fn default() -> Fingerprint {
+        Fingerprint::new()
+    }
+}
+
+impl Fingerprint {
+    pub fn new() -> Fingerprint {
+        Fingerprint(Blake2b::new(32))
+    }
+
+    pub fn add(mut self, bytes: &[u8]) -> Fingerprint {
+        // include the length so that the concatenation of inputs is unambiguous
+        self.0.update(&(bytes.len() as u64).to_le_bytes());
+        self.0.update(bytes);
+        self
+    }
+
+    pub fn add_file(self, path: &Path) -> io::Result<Fingerprint> {
+        let mut bytes = vec![];
+        BufReader::new(File::open(path)?).read_to_end(&mut bytes)?;
+        Ok(self.add(path.to_string_lossy().as_bytes()).add(&bytes))
+    }
+
+    /// Adds the `.code` files under `directory`, ignoring hidden directories and `exclude`
+    pub fn add_sources(self, directory: &Path, exclude: &Path) -> io::Result<Fingerprint> {
+        let mut entries = fs::read_dir(directory)?
+            .map(|entry| entry.map(|e| e.path()))
+            .collect::<io::Result<Vec<_>>>()?;
+        entries.sort();
+
+        entries.into_iter().try_fold(self, |fingerprint, path| {
+            let hidden = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .map(|name| name.starts_with('.'))
+                .unwrap_or(false);
+
+            if hidden || path == exclude {
+                Ok(fingerprint)
+            } else if path.is_dir() {
+                fingerprint.add_sources(&path, exclude)
+            } else if path.extension().map(|e| e == "code").unwrap_or(false) {
+                fingerprint.add_file(&path)
+            } else {
+                Ok(fingerprint)
+            }
+        })
+    }
+
+    pub fn finish(self) -> String {
+        self.0
+            .finalize()
+            .as_bytes()
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect()
+    }
+}
+
+/// The fingerprints of the steps of the last build of a circuit
+pub struct Fingerprints {
+    path: PathBuf,
+    steps: BTreeMap<String, String>,
+}
+
+impl Fingerprints {
+    /// Reads the fingerprints stored in `directory`, if any
+    pub fn read(directory: &Path) -> Fingerprints {
+        let path = directory.join(FINGERPRINTS);
+        let steps = File::open(&path)
+            .ok()
+            .and_then(|file| serde_json::from_reader(BufReader::new(file)).ok())
+            .unwrap_or_default();
+
+        Fingerprints { path, steps }
+    }
+
+    /// Returns whether `step` was last run with the same inputs and its outputs still exist
+    pub fn is_fresh(&self, step: &str, fingerprint: &str, outputs: &[&Path]) -> bool {
+        self.steps
+            .get(step)
+            .map(|f| f == fingerprint)
+            .unwrap_or(false)
+            && outputs.iter().all(|output| output.exists())
+    }
+
+    pub fn update(&mut self, step: &str, fingerprint: String) -> Result<(), String> {
+        self.steps.insert(step.to_string(), fingerprint);
+        File::create(&self.path)
+            .map_err(|why| why.to_string())
+            .and_then(|file| {
+                serde_json::to_writer_pretty(file, &self.steps).map_err(|why| why.to_string())
+            })
+            .map_err(|why| format!("couldn't write {}: {}", self.path.display(), why))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults() {
+        let manifest = Manifest::parse(
+            r#"
+            [project]
+            name = "example"
+            version = "0.1.0"
+
+            [[circuit]]
+            name = "main"
+            entry_point = "main.code"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            manifest.config(&manifest.circuits[0]),
+            Ok(Config {
+                curve: String::from("bn128"),
+                proving_scheme: String::from("g16"),
+                solidity_abi: String::from("v1"),
+                output: PathBuf::from("target/main"),
+            })
+        );
+    }
+
+    #[test]
+    fn overrides() {
+        let manifest = Manifest::parse(
+            r#"
+            [project]
+            name = "example"
+            version = "0.1.0"
+
+            [build]
+            curve = "bls12_381"
+            proving_scheme = "gm17"
+            output = "build"
+
+            [[circuit]]
+            name = "first"
+            entry_point = "first.code"
+
+            [[circuit]]
+            name = "second"
+            entry_point = "second.code"
+            proving_scheme = "g16"
+
+            [dependencies]
+            local = { path = "../local" }
+            remote = { github = "owner/repo", rev = "v1.0" }
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            manifest
+                .config(&manifest.circuits[0])
+                .unwrap()
+                .proving_scheme,
+            "gm17"
+        );
+        assert_eq!(
+            manifest.config(&manifest.circuits[1]),
+            Ok(Config {
+                curve: String::from("bls12_381"),
+                proving_scheme: String::from("g16"),
+                solidity_abi: String::from("v1"),
+                output: PathBuf::from("build/second"),
+            })
+        );
+        assert_eq!(
+            manifest.dependencies["remote"],
+            Dependency::Github {
+                github: String::from("owner/repo"),
+                rev: Some(String::from("v1.0"))
+            }
+        );
+    }
+
+    #[test]
+    fn invalid() {
+        let package = r#"
+            [project]
+            name = "example"
+            version = "0.1.0"
+            "#;
+
+        // no circuit
+        assert!(Manifest::parse(package).is_err());
+
+        // duplicate circuit
+        assert!(Manifest::parse(&format!(
+            "{}{}{}",
+            package,
+            "[[circuit]]\nname = \"main\"\nentry_point = \"a.code\"\n",
+            "[[circuit]]\nname = \"main\"\nentry_point = \"b.code\"\n"
+        ))
+        .is_err());
+
+        // unknown curve
+        assert!(Manifest::parse(&format!(
+            "{}{}",
+            package, "[[circuit]]\nname = \"main\"\nentry_point = \"a.code\"\ncurve = \"foo\"\n"
+        ))
+        .is_err());
+
+        // unknown proving scheme
+        assert_eq!(
+            Manifest::parse(&format!(
+                "{}{}",
+                package,
+                "[[circuit]]\nname = \"main\"\nentry_point = \"a.code\"\nproving_scheme = \"g61\"\n"
+            ))
+            .err(),
+            Some(String::from("unknown proving scheme g61 for circuit main"))
+        );
+    }
+
+    #[test]
+    fn fingerprint() {
+        // fingerprints are compared across runs, so they must not change between releases
+        assert_eq!(
+            Fingerprint::new().add(b"bn128").add(b"main.code").finish(),
+            "ea4eb5b5469ade3475f06b1ed95f88d5428b7079a963c1c66adf952a1a6e8e2c"
+        );
+        assert_ne!(
+            Fingerprint::new().add(b"bn128").add(b"main.code").finish(),
+            Fingerprint::new().add(b"bn128main").add(b".code").finish()
+        );
+    }
+}
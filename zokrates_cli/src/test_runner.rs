@@ -0,0 +1,138 @@
+//! Test files run by `zokrates test`, in the JSON format of the tests of the standard library:
+//! the path of the program to test, relative to the directory the tests are run from, and the
+//! expected result of the program on a list of inputs
+//!
+//! ```json
+//! {
+//!     "entry_point": "./add.code",
+//!     "tests": [
+//!         { "input": { "values": ["1", "2"] }, "output": { "Ok": { "values": ["3"] } } }
+//!     ]
+//! }
+//! ```
+
+use serde_derive::Deserialize;
+use serde_json::Value;
+use std::fs::{self, File};
+use std::io::{self, BufReader};
+use std::path::{Path, PathBuf};
+use zokrates_core::ir;
+use zokrates_field::field::Field;
+
+#[derive(Deserialize)]
+pub struct Tests {
+    pub entry_point: PathBuf,
+    pub tests: Vec<Test>,
+}
+
+#[derive(Deserialize)]
+pub struct Test {
+    pub input: Values,
+    pub output: Result<Values, ir::Error>,
+}
+
+#[derive(Deserialize)]
+pub struct Values {
+    pub values: Vec<String>,
+}
+
+impl Tests {
+    /// Reads the tests at `path`, returning `None` if it is a JSON file of another kind, such as
+    /// an ABI specification, a proof or the inputs of a program
+    pub fn read(path: &Path) -> Result<Option<Tests>, String> {
+        let file =
+            File::open(path).map_err(|why| format!("couldn't open {}: {}", path.display(), why))?;
+        let value: Value = serde_json::from_reader(BufReader::new(file))
+            .map_err(|why| format!("couldn't parse {}: {}", path.display(), why))?;
+
+        if value.get("entry_point").is_none() || value.get("tests").is_none() {
+            return Ok(None);
+        }
+
+        serde_json::from_value(value)
+            .map(Some)
+            .map_err(|why| format!("invalid test file {}: {}", path.display(), why))
+    }
+}
+
+impl Test {
+    pub fn inputs<T: Field>(&self) -> Result<Vec<T>, String> {
+        parse_values(&self.input.values)
+    }
+
+    /// Compares the result of the program to the expected one, returning a description of the
+    /// difference if they do not match
+    pub fn check<T: Field>(&self, result: ir::ExecutionResult<T>) -> Result<(), String> {
+        let expected = match self.output {
+            Ok(ref output) => Ok(parse_values::<T>(&output.values)?),
+            Err(ref e) => Err(e),
+        };
+
+        let found = match result {
+            Ok(ref witness) => Ok(witness.return_values()),
+            Err(ref e) => Err(e),
+        };
+
+        if expected == found {
+            return Ok(());
+        }
+
+        Err(format!(
+            "expected: {}\n   found: {}",
+            format_result(&expected),
+            format_result(&found)
+        ))
+    }
+}
+
+/// Returns the JSON files under `directory` in a deterministic order, skipping hidden files and
+/// directories as well as the directories in `exclude`
+pub fn find_test_files(directory: &Path, exclude: &[PathBuf]) -> io::Result<Vec<PathBuf>> {
+    let mut entries = fs::read_dir(directory)?
+        .map(|entry| entry.map(|e| e.path()))
+        .collect::<io::Result<Vec<_>>>()?;
+    entries.sort();
+
+    let mut files = vec![];
+
+    for path in entries {
+        let hidden = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .map(|name| name.starts_with('.'))
+            .unwrap_or(false);
+
+        if hidden || exclude.contains(&path) {
+            continue;
+        }
+
+        if path.is_dir() {
+            files.extend(find_test_files(&path, exclude)?);
+        } else if path.extension().map(|e| e == "json").unwrap_or(false) {
+            files.push(path);
+        }
+    }
+
+    Ok(files)
+}
+
+fn parse_values<T: Field>(values: &[String]) -> Result<Vec<T>, String> {
+    values
+        .iter()
+        .map(|v| T::try_from_dec_str(v).map_err(|_| format!("invalid field element: {}", v)))
+        .collect()
+}
+
+fn format_result<T: Field>(result: &Result<Vec<T>, &ir::Error>) -> String {
+    match result {
+        Ok(values) => format!(
+            "[{}]",
+            values
+                .iter()
+                .map(|v| v.to_dec_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        Err(e) => format!("error: {}", e),
+    }
+}
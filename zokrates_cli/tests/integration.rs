@@ -54,9 +54,15 @@ mod integration {
         let tmp_base = tmp_dir.path();
         let test_case_path = tmp_base.join(program_name);
         let flattened_path = tmp_base.join(program_name).join("out");
+        let abi_spec_path = tmp_base.join(program_name).join("abi.json");
         let witness_path = tmp_base.join(program_name).join("witness");
         let inline_witness_path = tmp_base.join(program_name).join("inline_witness");
+        let abi_witness_path = tmp_base.join(program_name).join("abi_witness");
+        let json_witness_path = tmp_base.join(program_name).join("witness.json");
+        let wtns_witness_path = tmp_base.join(program_name).join("witness.wtns");
         let proof_path = tmp_base.join(program_name).join("proof.json");
+        let r1cs_path = tmp_base.join(program_name).join("out.r1cs");
+        let r1cs_json_path = tmp_base.join(program_name).join("out.r1cs.json");
         let verification_key_path = tmp_base
             .join(program_name)
             .join("verification")
@@ -81,12 +87,17 @@ mod integration {
             program_path.to_str().unwrap(),
             "-o",
             flattened_path.to_str().unwrap(),
+            "-s",
+            abi_spec_path.to_str().unwrap(),
             "--light",
         ];
 
         // compile
         assert_cli::Assert::command(&compile).succeeds().unwrap();
 
+        // the ABI of main should be a valid JSON file
+        let _: Value = serde_json::from_reader(File::open(&abi_spec_path).unwrap()).unwrap();
+
         // COMPUTE_WITNESS
         let arguments: Value =
             serde_json::from_reader(File::open(arguments_path).unwrap()).unwrap();
@@ -161,6 +172,33 @@ mod integration {
 
         assert_eq!(inline_witness, witness);
 
+        // WITH `--abi-input <inputs>`, IF A JSON INPUT FILE IS PROVIDED
+
+        let abi_inputs_path = program_path.with_extension("inputs.json");
+
+        if abi_inputs_path.exists() {
+            assert_cli::Assert::command(&[
+                "../target/release/zokrates",
+                "compute-witness",
+                "-i",
+                flattened_path.to_str().unwrap(),
+                "-s",
+                abi_spec_path.to_str().unwrap(),
+                "-o",
+                abi_witness_path.to_str().unwrap(),
+                "--abi-input",
+                abi_inputs_path.to_str().unwrap(),
+            ])
+            .succeeds()
+            .unwrap();
+
+            let mut abi_witness_file = File::open(&abi_witness_path).unwrap();
+            let mut abi_witness = String::new();
+            abi_witness_file.read_to_string(&mut abi_witness).unwrap();
+
+            assert_eq!(abi_witness, witness);
+        }
+
         for line in expected_witness.as_str().split("\n") {
             assert!(
                 witness.contains(line),
@@ -170,10 +208,53 @@ mod integration {
             );
         }
 
+        // WITH `--format <format>`
+
+        for (format, path) in &[("json", &json_witness_path), ("wtns", &wtns_witness_path)] {
+            assert_cli::Assert::command(&[
+                "../target/release/zokrates",
+                "compute-witness",
+                "-i",
+                flattened_path.to_str().unwrap(),
+                "-o",
+                path.to_str().unwrap(),
+                "--format",
+                format,
+            ])
+            .stdin(&arguments_str_list.join(" "))
+            .succeeds()
+            .unwrap();
+        }
+
+        let json_witness: Value =
+            serde_json::from_reader(File::open(&json_witness_path).unwrap()).unwrap();
+
+        for line in witness.lines() {
+            let mut entry = line.split(' ');
+            let (variable, value) = (entry.next().unwrap(), entry.next().unwrap());
+            assert_eq!(json_witness[variable], *value);
+        }
+
+        // EXPORT-R1CS
+        for (format, path) in &[("iden3", &r1cs_path), ("json", &r1cs_json_path)] {
+            assert_cli::Assert::command(&[
+                "../target/release/zokrates",
+                "export-r1cs",
+                "-i",
+                flattened_path.to_str().unwrap(),
+                "-o",
+                path.to_str().unwrap(),
+                "--format",
+                format,
+            ])
+            .succeeds()
+            .unwrap();
+        }
+
         #[cfg(feature = "libsnark")]
         let schemes = ["pghr13", "gm17", "g16"];
         #[cfg(not(feature = "libsnark"))]
-        let schemes = ["g16"];
+        let schemes = ["gm17", "g16"];
 
         for scheme in &schemes {
             // SETUP
@@ -224,6 +305,24 @@ mod integration {
             .succeeds()
             .unwrap();
 
+            // VERIFY
+            if *scheme != "pghr13" {
+                assert_cli::Assert::command(&[
+                    "../target/release/zokrates",
+                    "verify",
+                    "-j",
+                    proof_path.to_str().unwrap(),
+                    "-v",
+                    verification_key_path.to_str().unwrap(),
+                    "--proving-scheme",
+                    scheme,
+                ])
+                .succeeds()
+                .stdout()
+                .contains("PASSED")
+                .unwrap();
+            }
+
             // TEST VERIFIER
 
             assert_cli::Assert::command(&[
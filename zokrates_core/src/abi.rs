@@ -0,0 +1,513 @@
+//! Module containing the ABI of a compiled program, which describes the inputs and outputs of `main`
+//!
+//! The ABI is serialized to JSON, where each type is represented as an object with a `type` field
+//! and, for arrays and structs, a `components` field:
+//!
+//! ```json
+//! {
+//!   "inputs": [
+//!     { "name": "a", "public": true, "type": "field" },
+//!     { "name": "b", "public": false, "type": "array", "components": { "size": 2, "type": "bool" } }
+//!   ],
+//!   "outputs": [
+//!     { "type": "u32" }
+//!   ]
+//! }
+//! ```
+
+use crate::types::{Signature, StructMember, StructType, Type};
+use num_bigint::BigUint;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde_json::Value;
+use std::fmt;
+use zokrates_field::field::Field;
+
+/// A named input of the `main` function
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct AbiInput {
+    pub name: String,
+    pub public: bool,
+    #[serde(flatten, with = "abi_type")]
+    pub ty: Type,
+}
+
+/// The inputs and outputs of the `main` function of a program
+#[derive(Serialize, Deserialize, Debug, PartialEq, Clone)]
+pub struct Abi {
+    pub inputs: Vec<AbiInput>,
+    #[serde(with = "abi_types")]
+    pub outputs: Vec<Type>,
+}
+
+impl Abi {
+    pub fn signature(&self) -> Signature {
+        Signature::new()
+            .inputs(self.inputs.iter().map(|i| i.ty.clone()).collect())
+            .outputs(self.outputs.clone())
+    }
+
+    /// Encode a JSON object of arguments keyed by input name into the flat list of field elements
+    /// expected by the compiled program, checking each value against the type of its input
+    pub fn encode_inputs<T: Field>(&self, inputs: &Value) -> Result<Vec<T>, Error> {
+        let inputs = match inputs {
+            Value::Object(inputs) => inputs,
+            v => {
+                return Err(Error::Type {
+                    path: String::from("inputs"),
+                    expected: String::from("an object"),
+                    found: v.to_string(),
+                })
+            }
+        };
+
+        if let Some(name) = inputs
+            .keys()
+            .find(|name| !self.inputs.iter().any(|i| &&i.name == name))
+        {
+            return Err(Error::UnexpectedInput(name.clone()));
+        }
+
+        let mut res = vec![];
+        for input in &self.inputs {
+            let value = inputs
+                .get(&input.name)
+                .ok_or_else(|| Error::MissingInput(input.name.clone()))?;
+            encode(value, &input.ty, input.name.clone(), &mut res)?;
+        }
+        Ok(res)
+    }
+}
+
+/// An error encountered while encoding arguments according to an `Abi`
+#[derive(Debug, PartialEq)]
+pub enum Error {
+    MissingInput(String),
+    UnexpectedInput(String),
+    Type {
+        path: String,
+        expected: String,
+        found: String,
+    },
+    Value {
+        path: String,
+        ty: Type,
+        value: String,
+    },
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::MissingInput(name) => write!(f, "Missing value for input `{}`", name),
+            Error::UnexpectedInput(name) => write!(f, "Unexpected input `{}`", name),
+            Error::Type {
+                path,
+                expected,
+                found,
+            } => write!(f, "Expected {} for `{}`, found {}", expected, path, found),
+            Error::Value { path, ty, value } => write!(
+                f,
+                "Value {} for `{}` is not a valid {}",
+                value, path, ty
+            ),
+        }
+    }
+}
+
+// parse a non-negative integer given either as a JSON number or as a decimal or `0x`-prefixed
+// hexadecimal string
+fn parse_integer(value: &Value) -> Option<BigUint> {
+    match value {
+        Value::Number(n) => n.as_u64().map(BigUint::from),
+        Value::String(s) => match s.starts_with("0x") {
+            true => BigUint::parse_bytes(s[2..].as_bytes(), 16),
+            false => BigUint::parse_bytes(s.as_bytes(), 10),
+        },
+        _ => None,
+    }
+}
+
+fn encode<T: Field>(value: &Value, ty: &Type, path: String, res: &mut Vec<T>) -> Result<(), Error> {
+    let type_error = |expected: String| Error::Type {
+        path: path.clone(),
+        expected,
+        found: value.to_string(),
+    };
+    let value_error = || Error::Value {
+        path: path.clone(),
+        ty: ty.clone(),
+        value: value.to_string(),
+    };
+
+    match ty {
+        Type::Boolean => match value {
+            Value::Bool(b) => {
+                res.push(T::from(*b as u32));
+                Ok(())
+            }
+            _ => Err(type_error(String::from("a boolean"))),
+        },
+        Type::FieldElement | Type::Uint(..) => {
+            let bound = match ty {
+                Type::Uint(bitwidth) => BigUint::from(1u32) << *bitwidth,
+                _ => {
+                    BigUint::parse_bytes(T::max_value().to_dec_string().as_bytes(), 10).unwrap()
+                        + BigUint::from(1u32)
+                }
+            };
+            match value {
+                Value::Number(..) | Value::String(..) => match parse_integer(value) {
+                    Some(ref v) if v < &bound => {
+                        res.push(T::try_from_dec_str(&v.to_str_radix(10)).unwrap());
+                        Ok(())
+                    }
+                    _ => Err(value_error()),
+                },
+                _ => Err(type_error(String::from("a number"))),
+            }
+        }
+        Type::Array(array_type) => match value {
+            Value::Array(values) if values.len() == array_type.size => {
+                for (index, value) in values.iter().enumerate() {
+                    encode(value, &array_type.ty, format!("{}[{}]", path, index), res)?;
+                }
+                Ok(())
+            }
+            _ => Err(type_error(format!(
+                "an array of {} elements",
+                array_type.size
+            ))),
+        },
+        Type::Struct(struct_type) => match value {
+            Value::Object(values) => {
+                if let Some(name) = values
+                    .keys()
+                    .find(|name| !struct_type.iter().any(|m| &&m.id == name))
+                {
+                    return Err(Error::UnexpectedInput(format!("{}.{}", path, name)));
+                }
+                for member in struct_type.iter() {
+                    let member_path = format!("{}.{}", path, member.id);
+                    let value = values
+                        .get(&member.id)
+                        .ok_or_else(|| Error::MissingInput(member_path.clone()))?;
+                    encode(value, &member.ty, member_path, res)?;
+                }
+                Ok(())
+            }
+            _ => Err(type_error(String::from("an object"))),
+        },
+    }
+}
+
+// the JSON representation of a `Type`, which is kept separate from `Type` itself as the latter
+// is also serialized to the binary format of compiled programs
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "type")]
+enum AbiType {
+    #[serde(rename = "field")]
+    FieldElement,
+    #[serde(rename = "bool")]
+    Boolean,
+    #[serde(rename = "u8")]
+    U8,
+    #[serde(rename = "u16")]
+    U16,
+    #[serde(rename = "u32")]
+    U32,
+    #[serde(rename = "u64")]
+    U64,
+    #[serde(rename = "array")]
+    Array { components: Box<AbiArrayType> },
+    #[serde(rename = "struct")]
+    Struct { components: AbiStructType },
+}
+
+#[derive(Serialize, Deserialize)]
+struct AbiArrayType {
+    size: usize,
+    #[serde(flatten)]
+    ty: AbiType,
+}
+
+#[derive(Serialize, Deserialize)]
+struct AbiStructType {
+    name: String,
+    members: Vec<AbiStructMember>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct AbiStructMember {
+    name: String,
+    #[serde(flatten)]
+    ty: AbiType,
+}
+
+impl<'a> From<&'a Type> for AbiType {
+    fn from(ty: &'a Type) -> AbiType {
+        match ty {
+            Type::FieldElement => AbiType::FieldElement,
+            Type::Boolean => AbiType::Boolean,
+            Type::Uint(8) => AbiType::U8,
+            Type::Uint(16) => AbiType::U16,
+            Type::Uint(32) => AbiType::U32,
+            Type::Uint(64) => AbiType::U64,
+            Type::Uint(bitwidth) => panic!("unsupported bitwidth {}", bitwidth),
+            Type::Array(array_type) => AbiType::Array {
+                components: box AbiArrayType {
+                    size: array_type.size,
+                    ty: AbiType::from(&*array_type.ty),
+                },
+            },
+            Type::Struct(struct_type) => AbiType::Struct {
+                components: AbiStructType {
+                    name: struct_type.id.clone(),
+                    members: struct_type
+                        .iter()
+                        .map(|m| AbiStructMember {
+                            name: m.id.clone(),
+                            ty: AbiType::from(&*m.ty),
+                        })
+                        .collect(),
+                },
+            },
+        }
+    }
+}
+
+impl From<AbiType> for Type {
+    fn from(ty: AbiType) -> Type {
+        match ty {
+            AbiType::FieldElement => Type::FieldElement,
+            AbiType::Boolean => Type::Boolean,
+            AbiType::U8 => Type::Uint(8),
+            AbiType::U16 => Type::Uint(16),
+            AbiType::U32 => Type::Uint(32),
+            AbiType::U64 => Type::Uint(64),
+            AbiType::Array { components } => Type::array(components.ty.into(), components.size),
+            AbiType::Struct { components } => Type::Struct(StructType::new(
+                components.name,
+                components
+                    .members
+                    .into_iter()
+                    .map(|m| StructMember::new(m.name, m.ty.into()))
+                    .collect(),
+            )),
+        }
+    }
+}
+
+mod abi_type {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(ty: &Type, serializer: S) -> Result<S::Ok, S::Error> {
+        AbiType::from(ty).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Type, D::Error> {
+        AbiType::deserialize(deserializer).map(Type::from)
+    }
+}
+
+mod abi_types {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(types: &[Type], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(types.iter().map(AbiType::from))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<Type>, D::Error> {
+        Vec::<AbiType>::deserialize(deserializer)
+            .map(|types| types.into_iter().map(Type::from).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serialize_empty() {
+        let abi = Abi {
+            inputs: vec![],
+            outputs: vec![],
+        };
+
+        assert_eq!(
+            serde_json::to_string(&abi).unwrap(),
+            r#"{"inputs":[],"outputs":[]}"#
+        );
+    }
+
+    #[test]
+    fn serialize_and_deserialize() {
+        let abi = Abi {
+            inputs: vec![
+                AbiInput {
+                    name: "a".into(),
+                    public: true,
+                    ty: Type::FieldElement,
+                },
+                AbiInput {
+                    name: "b".into(),
+                    public: false,
+                    ty: Type::array(Type::array(Type::Boolean, 3), 2),
+                },
+                AbiInput {
+                    name: "c".into(),
+                    public: false,
+                    ty: Type::Struct(StructType::new(
+                        "Point".into(),
+                        vec![
+                            StructMember::new("x".into(), Type::Uint(8)),
+                            StructMember::new("y".into(), Type::array(Type::FieldElement, 2)),
+                        ],
+                    )),
+                },
+            ],
+            outputs: vec![Type::Uint(32)],
+        };
+
+        let json = serde_json::to_string(&abi).unwrap();
+
+        assert_eq!(
+            json,
+            r#"{"inputs":[{"name":"a","public":true,"type":"field"},{"name":"b","public":false,"type":"array","components":{"size":2,"type":"array","components":{"size":3,"type":"bool"}}},{"name":"c","public":false,"type":"struct","components":{"name":"Point","members":[{"name":"x","type":"u8"},{"name":"y","type":"array","components":{"size":2,"type":"field"}}]}}],"outputs":[{"type":"u32"}]}"#
+        );
+
+        assert_eq!(serde_json::from_str::<Abi>(&json).unwrap(), abi);
+    }
+
+    mod encode {
+        use super::*;
+        use zokrates_field::field::FieldPrime;
+
+        fn abi() -> Abi {
+            Abi {
+                inputs: vec![
+                    AbiInput {
+                        name: "a".into(),
+                        public: true,
+                        ty: Type::FieldElement,
+                    },
+                    AbiInput {
+                        name: "b".into(),
+                        public: false,
+                        ty: Type::array(Type::array(Type::Boolean, 2), 2),
+                    },
+                    AbiInput {
+                        name: "c".into(),
+                        public: false,
+                        ty: Type::Struct(StructType::new(
+                            "Point".into(),
+                            vec![
+                                StructMember::new("x".into(), Type::Uint(8)),
+                                StructMember::new("y".into(), Type::FieldElement),
+                            ],
+                        )),
+                    },
+                ],
+                outputs: vec![],
+            }
+        }
+
+        #[test]
+        fn valid() {
+            let inputs = serde_json::json!({
+                "c": { "y": "0x10", "x": 255 },
+                "a": "42",
+                "b": [[true, false], [false, true]]
+            });
+
+            assert_eq!(
+                abi().encode_inputs::<FieldPrime>(&inputs),
+                Ok(vec![
+                    FieldPrime::from(42),
+                    FieldPrime::from(1),
+                    FieldPrime::from(0),
+                    FieldPrime::from(0),
+                    FieldPrime::from(1),
+                    FieldPrime::from(255),
+                    FieldPrime::from(16),
+                ])
+            );
+        }
+
+        #[test]
+        fn missing_and_unexpected_inputs() {
+            let inputs = serde_json::json!({
+                "a": 42,
+                "b": [[true, false], [false, true]]
+            });
+            assert_eq!(
+                abi().encode_inputs::<FieldPrime>(&inputs),
+                Err(Error::MissingInput("c".into()))
+            );
+
+            let inputs = serde_json::json!({
+                "a": 42,
+                "b": [[true, false], [false, true]],
+                "c": { "x": 1, "y": 2, "z": 3 }
+            });
+            assert_eq!(
+                abi().encode_inputs::<FieldPrime>(&inputs),
+                Err(Error::UnexpectedInput("c.z".into()))
+            );
+        }
+
+        #[test]
+        fn wrong_types() {
+            let inputs = serde_json::json!({
+                "a": 42,
+                "b": [[true, false], [false]],
+                "c": { "x": 1, "y": 2 }
+            });
+            assert_eq!(
+                abi().encode_inputs::<FieldPrime>(&inputs),
+                Err(Error::Type {
+                    path: "b[1]".into(),
+                    expected: "an array of 2 elements".into(),
+                    found: "[false]".into()
+                })
+            );
+
+            let inputs = serde_json::json!({
+                "a": 42,
+                "b": [[true, false], [false, 1]],
+                "c": { "x": 1, "y": 2 }
+            });
+            assert_eq!(
+                abi().encode_inputs::<FieldPrime>(&inputs),
+                Err(Error::Type {
+                    path: "b[1][1]".into(),
+                    expected: "a boolean".into(),
+                    found: "1".into()
+                })
+            );
+        }
+
+        #[test]
+        fn out_of_range() {
+            let inputs = serde_json::json!({
+                "a": 42,
+                "b": [[true, false], [false, true]],
+                "c": { "x": "0x100", "y": 2 }
+            });
+            assert_eq!(
+                abi().encode_inputs::<FieldPrime>(&inputs),
+                Err(Error::Value {
+                    path: "c.x".into(),
+                    ty: Type::Uint(8),
+                    value: "\"0x100\"".into()
+                })
+            );
+
+            let inputs = serde_json::json!({
+                "a": "21888242871839275222246405745257275088548364400416034343698204186575808495617",
+                "b": [[true, false], [false, true]],
+                "c": { "x": 1, "y": 2 }
+            });
+            assert!(abi().encode_inputs::<FieldPrime>(&inputs).is_err());
+        }
+    }
+}
@@ -1,12 +1,17 @@
 use absy;
+use absy::UnresolvedType;
 use imports;
-use types::Type;
 use zokrates_field::field::Field;
 use zokrates_pest_ast as pest;
 
 impl<'ast, T: Field> From<pest::File<'ast>> for absy::Prog<'ast, T> {
     fn from(prog: pest::File<'ast>) -> absy::Prog<T> {
         absy::Prog {
+            structs: prog
+                .structs
+                .into_iter()
+                .map(|s| absy::StructDefinitionNode::from(s))
+                .collect(),
             functions: prog
                 .functions
                 .into_iter()
@@ -15,20 +20,62 @@ impl<'ast, T: Field> From<pest::File<'ast>> for absy::Prog<'ast, T> {
             imports: prog
                 .imports
                 .into_iter()
-                .map(|i| absy::ImportNode::from(i))
+                .flat_map(|i| import_directive_to_imports(i))
                 .collect(),
             imported_functions: vec![],
         }
     }
 }
 
-impl<'ast> From<pest::ImportDirective<'ast>> for absy::ImportNode {
-    fn from(import: pest::ImportDirective<'ast>) -> absy::ImportNode {
-        use absy::NodeValue;
+/// Converts an import directive to the imports it declares, one per imported function
+fn import_directive_to_imports(import: pest::ImportDirective) -> Vec<absy::ImportNode> {
+    use absy::NodeValue;
 
-        imports::Import::new(import.source.value)
+    match import {
+        pest::ImportDirective::Main(import) => vec![imports::Import::new(import.source.value)
             .alias(import.alias.map(|a| a.value))
-            .span(import.span)
+            .span(import.span)],
+        pest::ImportDirective::From(import) => {
+            let source = import.source.value;
+            import
+                .symbols
+                .into_iter()
+                .map(|symbol| {
+                    imports::Import::new(source.clone())
+                        .symbol(Some(symbol.id.value))
+                        .alias(symbol.alias.map(|a| a.value))
+                        .span(symbol.span)
+                })
+                .collect()
+        }
+    }
+}
+
+impl<'ast> From<pest::StructDefinition<'ast>> for absy::StructDefinitionNode<'ast> {
+    fn from(definition: pest::StructDefinition<'ast>) -> absy::StructDefinitionNode<'ast> {
+        use absy::NodeValue;
+
+        absy::StructDefinition {
+            id: definition.id.span.as_str(),
+            fields: definition
+                .fields
+                .into_iter()
+                .map(|f| absy::StructFieldNode::from(f))
+                .collect(),
+        }
+        .span(definition.span)
+    }
+}
+
+impl<'ast> From<pest::StructField<'ast>> for absy::StructFieldNode<'ast> {
+    fn from(field: pest::StructField<'ast>) -> absy::StructFieldNode<'ast> {
+        use absy::NodeValue;
+
+        absy::StructField {
+            id: field.id.span.as_str(),
+            ty: UnresolvedType::from(field.ty).span(field.span.clone()),
+        }
+        .span(field.span)
     }
 }
 
@@ -38,13 +85,13 @@ impl<'ast, T: Field> From<pest::Function<'ast>> for absy::FunctionNode<'ast, T>
 
         let span = function.span;
 
-        let signature = absy::Signature::new()
+        let signature = absy::UnresolvedSignature::new()
             .inputs(
                 function
                     .parameters
                     .clone()
                     .into_iter()
-                    .map(|p| absy::ParameterNode::from(p).value.id.value.get_type())
+                    .map(|p| UnresolvedType::from(p.ty).span(p.span))
                     .collect(),
             )
             .outputs(
@@ -52,7 +99,8 @@ impl<'ast, T: Field> From<pest::Function<'ast>> for absy::FunctionNode<'ast, T>
                     .returns
                     .clone()
                     .into_iter()
-                    .map(|r| Type::from(r))
+                    // return types do not carry a span yet, point to the function instead
+                    .map(|r| UnresolvedType::from(r).span(span.clone()))
                     .collect(),
             );
 
@@ -86,8 +134,8 @@ impl<'ast> From<pest::Parameter<'ast>> for absy::ParameterNode<'ast> {
             })
             .unwrap_or(false);
 
-        let variable =
-            absy::Variable::new(param.id.span.as_str(), Type::from(param.ty)).span(param.id.span);
+        let variable = absy::Variable::new(param.id.span.as_str(), UnresolvedType::from(param.ty))
+            .span(param.id.span);
 
         absy::Parameter::new(variable, private).span(param.span)
     }
@@ -118,7 +166,8 @@ fn statements_from_multi_assignment<'ast, T: Field>(
         .filter(|i| i.ty.is_some())
         .map(|i| {
             absy::Statement::Declaration(
-                absy::Variable::new(i.id.span.as_str(), Type::from(i.ty.unwrap())).span(i.id.span),
+                absy::Variable::new(i.id.span.as_str(), UnresolvedType::from(i.ty.unwrap()))
+                    .span(i.id.span),
             )
             .span(i.span)
         });
@@ -153,7 +202,7 @@ fn statements_from_definition<'ast, T: Field>(
 
     vec![
         absy::Statement::Declaration(
-            absy::Variable::new(definition.id.span.as_str(), Type::from(definition.ty))
+            absy::Variable::new(definition.id.span.as_str(), UnresolvedType::from(definition.ty))
                 .span(definition.id.span.clone()),
         )
         .span(definition.span.clone()),
@@ -213,7 +262,7 @@ impl<'ast, T: Field> From<pest::IterationStatement<'ast>> for absy::StatementNod
         let from = absy::ExpressionNode::from(statement.from);
         let to = absy::ExpressionNode::from(statement.to);
         let index = statement.index.span.as_str();
-        let ty = Type::from(statement.ty);
+        let ty = UnresolvedType::from(statement.ty);
         let statements: Vec<absy::StatementNode<T>> = statement
             .statements
             .into_iter()
@@ -257,6 +306,7 @@ impl<'ast, T: Field> From<pest::Expression<'ast>> for absy::ExpressionNode<'ast,
             pest::Expression::Identifier(e) => absy::ExpressionNode::from(e),
             pest::Expression::Postfix(e) => absy::ExpressionNode::from(e),
             pest::Expression::InlineArray(e) => absy::ExpressionNode::from(e),
+            pest::Expression::InlineStruct(e) => absy::ExpressionNode::from(e),
             pest::Expression::ArrayInitializer(e) => absy::ExpressionNode::from(e),
             pest::Expression::Unary(e) => absy::ExpressionNode::from(e),
         }
@@ -315,6 +365,26 @@ impl<'ast, T: Field> From<pest::BinaryExpression<'ast>> for absy::ExpressionNode
                 box absy::ExpressionNode::from(*expression.left),
                 box absy::ExpressionNode::from(*expression.right),
             ),
+            pest::BinaryOperator::BitAnd => absy::Expression::BitAnd(
+                box absy::ExpressionNode::from(*expression.left),
+                box absy::ExpressionNode::from(*expression.right),
+            ),
+            pest::BinaryOperator::BitOr => absy::Expression::BitOr(
+                box absy::ExpressionNode::from(*expression.left),
+                box absy::ExpressionNode::from(*expression.right),
+            ),
+            pest::BinaryOperator::Xor => absy::Expression::BitXor(
+                box absy::ExpressionNode::from(*expression.left),
+                box absy::ExpressionNode::from(*expression.right),
+            ),
+            pest::BinaryOperator::LeftShift => absy::Expression::LeftShift(
+                box absy::ExpressionNode::from(*expression.left),
+                box absy::ExpressionNode::from(*expression.right),
+            ),
+            pest::BinaryOperator::RightShift => absy::Expression::RightShift(
+                box absy::ExpressionNode::from(*expression.left),
+                box absy::ExpressionNode::from(*expression.right),
+            ),
             o => unimplemented!("Operator {:?} not implemented", o),
         }
         .span(expression.span)
@@ -409,6 +479,25 @@ impl<'ast, T: Field> From<pest::InlineArrayExpression<'ast>> for absy::Expressio
     }
 }
 
+impl<'ast, T: Field> From<pest::InlineStructExpression<'ast>> for absy::ExpressionNode<'ast, T> {
+    fn from(s: pest::InlineStructExpression<'ast>) -> absy::ExpressionNode<'ast, T> {
+        use absy::NodeValue;
+        absy::Expression::InlineStruct(
+            s.ty.span.as_str().to_string(),
+            s.members
+                .into_iter()
+                .map(|member| {
+                    (
+                        member.id.span.as_str(),
+                        absy::ExpressionNode::from(member.expression),
+                    )
+                })
+                .collect(),
+        )
+        .span(s.span)
+    }
+}
+
 impl<'ast, T: Field> From<pest::ArrayInitializerExpression<'ast>>
     for absy::ExpressionNode<'ast, T>
 {
@@ -443,22 +532,36 @@ impl<'ast, T: Field> From<pest::PostfixExpression<'ast>> for absy::ExpressionNod
     fn from(expression: pest::PostfixExpression<'ast>) -> absy::ExpressionNode<'ast, T> {
         use absy::NodeValue;
 
-        assert!(expression.access.len() == 1); // we only allow a single access: function call or array access
+        let span = expression.span;
+        let id_str = expression.id.span.as_str();
+        let id = absy::ExpressionNode::from(expression.id);
 
-        match expression.access[0].clone() {
-            pest::Access::Call(a) => absy::Expression::FunctionCall(
-                expression.id.value,
-                a.expressions
+        // pest::PostFixExpression contains an optional call and an array of "accesses": `a(34)[42]` is represented as `[a, Call(34), [Select(42)]]`, but absy::ExpressionNode
+        // is recursive, so it is `Select(Call(a, 34), 42)`. We apply this transformation here
+
+        // only identifiers are callable, which the grammar enforces by only allowing a call first
+        let base = match expression.call {
+            Some(call) => absy::Expression::FunctionCall(
+                id_str.to_string(),
+                call.expressions
                     .into_iter()
                     .map(|e| absy::ExpressionNode::from(e))
                     .collect(),
-            ),
-            pest::Access::Select(a) => absy::Expression::Select(
-                box absy::ExpressionNode::from(expression.id),
-                box absy::RangeOrExpression::from(a.expression),
-            ),
-        }
-        .span(expression.span)
+            )
+            .span(span.clone()),
+            None => id,
+        };
+
+        // we start with the base, and we fold the array of accesses by wrapping the current value
+        expression.access.into_iter().fold(base, |acc, a| match a {
+            pest::Access::Select(a) => {
+                absy::Expression::Select(box acc, box absy::RangeOrExpression::from(a.expression))
+                    .span(span.clone())
+            }
+            pest::Access::Member(m) => {
+                absy::Expression::Member(box acc, box m.id.span.as_str()).span(span.clone())
+            }
+        })
     }
 }
 
@@ -472,6 +575,21 @@ impl<'ast, T: Field> From<pest::ConstantExpression<'ast>> for absy::ExpressionNo
             pest::ConstantExpression::DecimalNumber(n) => {
                 absy::Expression::FieldConstant(T::try_from_dec_str(&n.value).unwrap()).span(n.span)
             }
+            pest::ConstantExpression::HexLiteral(h) => match h.value {
+                pest::HexNumberExpression::U8(n) => {
+                    absy::Expression::U8Constant(u8::from_str_radix(&n.value, 16).unwrap())
+                }
+                pest::HexNumberExpression::U16(n) => {
+                    absy::Expression::U16Constant(u16::from_str_radix(&n.value, 16).unwrap())
+                }
+                pest::HexNumberExpression::U32(n) => {
+                    absy::Expression::U32Constant(u32::from_str_radix(&n.value, 16).unwrap())
+                }
+                pest::HexNumberExpression::U64(n) => {
+                    absy::Expression::U64Constant(u64::from_str_radix(&n.value, 16).unwrap())
+                }
+            }
+            .span(h.span),
         }
     }
 }
@@ -496,52 +614,72 @@ impl<'ast, T: Field> From<pest::Assignee<'ast>> for absy::AssigneeNode<'ast, T>
         use absy::NodeValue;
 
         let a = absy::AssigneeNode::from(assignee.id);
-        match assignee.indices.len() {
-            0 => a,
-            1 => absy::Assignee::ArrayElement(
-                box a,
-                box absy::RangeOrExpression::from(assignee.indices[0].clone()),
-            )
-            .span(assignee.span),
-            n => unimplemented!("Array should have one dimension, found {} in {}", n, a),
+        let span = assignee.span;
+
+        // `a[i][j]` is the element `j` of the element `i` of `a`
+        assignee.indices.into_iter().fold(a, |acc, index| {
+            absy::Assignee::ArrayElement(box acc, box absy::RangeOrExpression::from(index))
+                .span(span.clone())
+        })
+    }
+}
+
+impl<'ast> From<pest::BasicType<'ast>> for UnresolvedType {
+    fn from(t: pest::BasicType<'ast>) -> UnresolvedType {
+        match t {
+            pest::BasicType::Field(_) => UnresolvedType::FieldElement,
+            pest::BasicType::Boolean(_) => UnresolvedType::Boolean,
+            pest::BasicType::U8(_) => UnresolvedType::Uint(8),
+            pest::BasicType::U16(_) => UnresolvedType::Uint(16),
+            pest::BasicType::U32(_) => UnresolvedType::Uint(32),
+            pest::BasicType::U64(_) => UnresolvedType::Uint(64),
         }
     }
 }
 
-impl<'ast> From<pest::Type<'ast>> for Type {
-    fn from(t: pest::Type<'ast>) -> Type {
+impl<'ast> From<pest::Type<'ast>> for UnresolvedType {
+    fn from(t: pest::Type<'ast>) -> UnresolvedType {
         match t {
-            pest::Type::Basic(t) => match t {
-                pest::BasicType::Field(_) => Type::FieldElement,
-                pest::BasicType::Boolean(_) => Type::Boolean,
-            },
+            pest::Type::Basic(t) => t.into(),
             pest::Type::Array(t) => {
-                let size = match t.size {
-                    pest::Expression::Constant(c) => match c {
-                        pest::ConstantExpression::DecimalNumber(n) => {
-                            str::parse::<usize>(&n.value).unwrap()
-                        }
-                        _ => unimplemented!(
-                            "Array size should be a decimal number, found {}",
-                            c.span().as_str()
-                        ),
-                    },
-                    e => {
-                        unimplemented!("Array size should be constant, found {}", e.span().as_str())
-                    }
+                let inner_type = match t.ty {
+                    pest::BasicOrStructType::Basic(t) => t.into(),
+                    pest::BasicOrStructType::Struct(t) => t.into(),
                 };
-                match t.ty {
-                    pest::BasicType::Field(_) => Type::FieldElementArray(size),
-                    _ => unimplemented!(
-                        "Array elements should be field elements, found {}",
-                        t.span.as_str()
-                    ),
-                }
+
+                // `field[2][3]` is an array of 2 elements of type `field[3]`
+                // so we wrap the inner type starting from the last dimension
+                t.dimensions
+                    .into_iter()
+                    .map(|s| match s {
+                        pest::Expression::Constant(c) => match c {
+                            pest::ConstantExpression::DecimalNumber(n) => {
+                                str::parse::<usize>(&n.value).unwrap()
+                            }
+                            _ => unimplemented!(
+                                "Array size should be a decimal number, found {}",
+                                c.span().as_str()
+                            ),
+                        },
+                        e => unimplemented!(
+                            "Array size should be constant, found {}",
+                            e.span().as_str()
+                        ),
+                    })
+                    .rev()
+                    .fold(inner_type, |acc, size| UnresolvedType::array(acc, size))
             }
+            pest::Type::Struct(s) => s.into(),
         }
     }
 }
 
+impl<'ast> From<pest::StructType<'ast>> for UnresolvedType {
+    fn from(t: pest::StructType<'ast>) -> UnresolvedType {
+        UnresolvedType::User(t.id.span.as_str().to_string())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -565,11 +703,12 @@ mod tests {
                     .into(),
                 )
                 .into()],
-                signature: absy::Signature::new()
+                signature: absy::UnresolvedSignature::new()
                     .inputs(vec![])
-                    .outputs(vec![Type::FieldElement]),
+                    .outputs(vec![UnresolvedType::FieldElement.into()]),
             }
             .into()],
+            structs: vec![],
             imports: vec![],
             imported_functions: vec![],
         };
@@ -592,17 +731,137 @@ mod tests {
                     .into(),
                 )
                 .into()],
-                signature: absy::Signature::new()
+                signature: absy::UnresolvedSignature::new()
                     .inputs(vec![])
-                    .outputs(vec![Type::Boolean]),
+                    .outputs(vec![UnresolvedType::Boolean.into()]),
             }
             .into()],
+            structs: vec![],
             imports: vec![],
             imported_functions: vec![],
         };
         assert_eq!(absy::Prog::<FieldPrime>::from(ast), expected);
     }
 
+    #[test]
+    fn return_u32() {
+        let source = "def main() -> (u32): return 0x0000002a
+        ";
+        let ast = pest::generate_ast(&source).unwrap();
+        let expected: absy::Prog<FieldPrime> = absy::Prog {
+            functions: vec![absy::Function {
+                id: &source[4..8],
+                arguments: vec![],
+                statements: vec![absy::Statement::Return(
+                    absy::ExpressionList {
+                        expressions: vec![absy::Expression::U32Constant(42).into()],
+                    }
+                    .into(),
+                )
+                .into()],
+                signature: absy::UnresolvedSignature::new()
+                    .inputs(vec![])
+                    .outputs(vec![UnresolvedType::Uint(32).into()]),
+            }
+            .into()],
+            structs: vec![],
+            imports: vec![],
+            imported_functions: vec![],
+        };
+        assert_eq!(absy::Prog::<FieldPrime>::from(ast), expected);
+    }
+
+    #[test]
+    fn struct_member() {
+        let source = "struct Foo {\n\tfield a\n}\n\ndef main(Foo f) -> (field): return f.a
+        ";
+        let ast = pest::generate_ast(&source).unwrap();
+        let expected: absy::Prog<FieldPrime> = absy::Prog {
+            functions: vec![absy::Function {
+                id: &source[29..33],
+                arguments: vec![absy::Parameter::public(
+                    absy::Variable::new(&source[38..39], UnresolvedType::User("Foo".into()))
+                        .into(),
+                )
+                .into()],
+                statements: vec![absy::Statement::Return(
+                    absy::ExpressionList {
+                        expressions: vec![absy::Expression::Member(
+                            box absy::Expression::Identifier(&source[60..61]).into(),
+                            box &source[62..63],
+                        )
+                        .into()],
+                    }
+                    .into(),
+                )
+                .into()],
+                signature: absy::UnresolvedSignature::new()
+                    .inputs(vec![UnresolvedType::User("Foo".into()).into()])
+                    .outputs(vec![UnresolvedType::FieldElement.into()]),
+            }
+            .into()],
+            structs: vec![absy::StructDefinition {
+                id: &source[7..10],
+                fields: vec![absy::StructField {
+                    id: &source[20..21],
+                    ty: UnresolvedType::FieldElement.into(),
+                }
+                .into()],
+            }
+            .into()],
+            imports: vec![],
+            imported_functions: vec![],
+        };
+        assert_eq!(absy::Prog::<FieldPrime>::from(ast), expected);
+    }
+
+    #[test]
+    fn multidimensional_array() {
+        let source = "def main(bool[2][3] a) -> (bool): return a[1][2]
+        ";
+        let ast = pest::generate_ast(&source).unwrap();
+
+        let ty = UnresolvedType::array(UnresolvedType::array(UnresolvedType::Boolean, 3), 2);
+
+        let expected: absy::Prog<FieldPrime> = absy::Prog {
+            functions: vec![absy::Function {
+                id: &source[4..8],
+                arguments: vec![absy::Parameter::public(
+                    absy::Variable::new(&source[20..21], ty.clone()).into(),
+                )
+                .into()],
+                statements: vec![absy::Statement::Return(
+                    absy::ExpressionList {
+                        expressions: vec![absy::Expression::Select(
+                            box absy::Expression::Select(
+                                box absy::Expression::Identifier(&source[41..42]).into(),
+                                box absy::RangeOrExpression::Expression(
+                                    absy::Expression::FieldConstant(FieldPrime::from(1)).into(),
+                                ),
+                            )
+                            .into(),
+                            box absy::RangeOrExpression::Expression(
+                                absy::Expression::FieldConstant(FieldPrime::from(2)).into(),
+                            ),
+                        )
+                        .into()],
+                    }
+                    .into(),
+                )
+                .into()],
+                signature: absy::UnresolvedSignature::new()
+                    .inputs(vec![ty.into()])
+                    .outputs(vec![UnresolvedType::Boolean.into()]),
+            }
+            .into()],
+            structs: vec![],
+            imports: vec![],
+            imported_functions: vec![],
+        };
+
+        assert_eq!(absy::Prog::<FieldPrime>::from(ast), expected);
+    }
+
     #[test]
     fn arguments() {
         let source = "def main(private field a, bool b) -> (field): return 42
@@ -626,11 +885,15 @@ mod tests {
                     .into(),
                 )
                 .into()],
-                signature: absy::Signature::new()
-                    .inputs(vec![Type::FieldElement, Type::Boolean])
-                    .outputs(vec![Type::FieldElement]),
+                signature: absy::UnresolvedSignature::new()
+                    .inputs(vec![
+                        UnresolvedType::FieldElement.into(),
+                        UnresolvedType::Boolean.into(),
+                    ])
+                    .outputs(vec![UnresolvedType::FieldElement.into()]),
             }
             .into()],
+            structs: vec![],
             imports: vec![],
             imported_functions: vec![],
         };
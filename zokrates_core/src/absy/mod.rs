@@ -8,12 +8,13 @@
 mod from_ast;
 mod node;
 pub mod parameter;
+pub mod types;
 pub mod variable;
 
 pub use crate::absy::node::{Node, NodeValue};
 pub use crate::absy::parameter::{Parameter, ParameterNode};
+pub use crate::absy::types::{UnresolvedSignature, UnresolvedType, UnresolvedTypeNode, UserTypeId};
 pub use crate::absy::variable::{Variable, VariableNode};
-use crate::types::Signature;
 
 use crate::flat_absy::*;
 use crate::imports::ImportNode;
@@ -24,6 +25,8 @@ pub type Identifier<'ast> = &'ast str;
 
 #[derive(Clone, PartialEq)]
 pub struct Prog<'ast, T: Field> {
+    /// Struct types of the program
+    pub structs: Vec<StructDefinitionNode<'ast>>,
     /// Functions of the program
     pub functions: Vec<FunctionNode<'ast, T>>,
     pub imports: Vec<ImportNode>,
@@ -45,6 +48,12 @@ impl<'ast, T: Field> fmt::Display for Prog<'ast, T> {
                 .map(|x| format!("{}", x))
                 .collect::<Vec<_>>(),
         );
+        res.extend(
+            self.structs
+                .iter()
+                .map(|x| format!("{}", x))
+                .collect::<Vec<_>>(),
+        );
         res.extend(
             self.functions
                 .iter()
@@ -59,12 +68,17 @@ impl<'ast, T: Field> fmt::Debug for Prog<'ast, T> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(
             f,
-            "program(\n\timports:\n\t\t{}\n\tfunctions:\n\t\t{}{}\n)",
+            "program(\n\timports:\n\t\t{}\n\tstructs:\n\t\t{}\n\tfunctions:\n\t\t{}{}\n)",
             self.imports
                 .iter()
                 .map(|x| format!("{:?}", x))
                 .collect::<Vec<_>>()
                 .join("\n\t\t"),
+            self.structs
+                .iter()
+                .map(|x| format!("{:?}", x))
+                .collect::<Vec<_>>()
+                .join("\n\t\t"),
             self.imported_functions
                 .iter()
                 .map(|x| format!("{}", x))
@@ -79,6 +93,61 @@ impl<'ast, T: Field> fmt::Debug for Prog<'ast, T> {
     }
 }
 
+#[derive(Clone, PartialEq)]
+pub struct StructDefinition<'ast> {
+    /// Name of the struct
+    pub id: Identifier<'ast>,
+    /// Members of the struct, in declaration order
+    pub fields: Vec<StructFieldNode<'ast>>,
+}
+
+pub type StructDefinitionNode<'ast> = Node<StructDefinition<'ast>>;
+
+impl<'ast> fmt::Display for StructDefinition<'ast> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "struct {} {{\n{}\n}}",
+            self.id,
+            self.fields
+                .iter()
+                .map(|x| format!("\t{}", x))
+                .collect::<Vec<_>>()
+                .join("\n")
+        )
+    }
+}
+
+impl<'ast> fmt::Debug for StructDefinition<'ast> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "StructDefinition(id: {:?}, fields: {:?})",
+            self.id, self.fields
+        )
+    }
+}
+
+#[derive(Clone, PartialEq)]
+pub struct StructField<'ast> {
+    pub id: Identifier<'ast>,
+    pub ty: UnresolvedTypeNode,
+}
+
+pub type StructFieldNode<'ast> = Node<StructField<'ast>>;
+
+impl<'ast> fmt::Display for StructField<'ast> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}", self.ty, self.id)
+    }
+}
+
+impl<'ast> fmt::Debug for StructField<'ast> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "StructField(id: {:?}, ty: {:?})", self.id, self.ty)
+    }
+}
+
 #[derive(Clone, PartialEq)]
 pub struct Function<'ast, T: Field> {
     /// Name of the program
@@ -88,7 +157,7 @@ pub struct Function<'ast, T: Field> {
     /// Vector of statements that are executed when running the function
     pub statements: Vec<StatementNode<'ast, T>>,
     /// function signature
-    pub signature: Signature,
+    pub signature: UnresolvedSignature,
 }
 
 pub type FunctionNode<'ast, T> = Node<Function<'ast, T>>;
@@ -316,6 +385,10 @@ impl<'ast, T: Field> fmt::Debug for Range<T> {
 pub enum Expression<'ast, T: Field> {
     FieldConstant(T),
     BooleanConstant(bool),
+    U8Constant(u8),
+    U16Constant(u16),
+    U32Constant(u32),
+    U64Constant(u64),
     Identifier(Identifier<'ast>),
     Add(Box<ExpressionNode<'ast, T>>, Box<ExpressionNode<'ast, T>>),
     Sub(Box<ExpressionNode<'ast, T>>, Box<ExpressionNode<'ast, T>>),
@@ -341,6 +414,13 @@ pub enum Expression<'ast, T: Field> {
         Box<RangeOrExpression<'ast, T>>,
     ),
     Or(Box<ExpressionNode<'ast, T>>, Box<ExpressionNode<'ast, T>>),
+    BitAnd(Box<ExpressionNode<'ast, T>>, Box<ExpressionNode<'ast, T>>),
+    BitOr(Box<ExpressionNode<'ast, T>>, Box<ExpressionNode<'ast, T>>),
+    BitXor(Box<ExpressionNode<'ast, T>>, Box<ExpressionNode<'ast, T>>),
+    LeftShift(Box<ExpressionNode<'ast, T>>, Box<ExpressionNode<'ast, T>>),
+    RightShift(Box<ExpressionNode<'ast, T>>, Box<ExpressionNode<'ast, T>>),
+    InlineStruct(UserTypeId, Vec<(Identifier<'ast>, ExpressionNode<'ast, T>)>),
+    Member(Box<ExpressionNode<'ast, T>>, Box<Identifier<'ast>>),
 }
 
 pub type ExpressionNode<'ast, T> = Node<Expression<'ast, T>>;
@@ -349,6 +429,10 @@ impl<'ast, T: Field> fmt::Display for Expression<'ast, T> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
             Expression::FieldConstant(ref i) => write!(f, "{}", i),
+            Expression::U8Constant(ref i) => write!(f, "{:#04x}", i),
+            Expression::U16Constant(ref i) => write!(f, "{:#06x}", i),
+            Expression::U32Constant(ref i) => write!(f, "{:#010x}", i),
+            Expression::U64Constant(ref i) => write!(f, "{:#018x}", i),
             Expression::Identifier(ref var) => write!(f, "{}", var),
             Expression::Add(ref lhs, ref rhs) => write!(f, "({} + {})", lhs, rhs),
             Expression::Sub(ref lhs, ref rhs) => write!(f, "({} - {})", lhs, rhs),
@@ -390,6 +474,22 @@ impl<'ast, T: Field> fmt::Display for Expression<'ast, T> {
             }
             Expression::Select(ref array, ref index) => write!(f, "{}[{}]", array, index),
             Expression::Or(ref lhs, ref rhs) => write!(f, "{} || {}", lhs, rhs),
+            Expression::BitAnd(ref lhs, ref rhs) => write!(f, "{} & {}", lhs, rhs),
+            Expression::BitOr(ref lhs, ref rhs) => write!(f, "{} | {}", lhs, rhs),
+            Expression::BitXor(ref lhs, ref rhs) => write!(f, "{} ^ {}", lhs, rhs),
+            Expression::LeftShift(ref lhs, ref rhs) => write!(f, "{} << {}", lhs, rhs),
+            Expression::RightShift(ref lhs, ref rhs) => write!(f, "{} >> {}", lhs, rhs),
+            Expression::InlineStruct(ref id, ref members) => {
+                r#try!(write!(f, "{} {{", id));
+                for (i, (member_id, e)) in members.iter().enumerate() {
+                    r#try!(write!(f, "{}: {}", member_id, e));
+                    if i < members.len() - 1 {
+                        r#try!(write!(f, ", "));
+                    }
+                }
+                write!(f, "}}")
+            }
+            Expression::Member(ref struc, ref id) => write!(f, "{}.{}", struc, id),
         }
     }
 }
@@ -398,6 +498,10 @@ impl<'ast, T: Field> fmt::Debug for Expression<'ast, T> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
             Expression::FieldConstant(ref i) => write!(f, "Num({})", i),
+            Expression::U8Constant(ref i) => write!(f, "U8({:#04x})", i),
+            Expression::U16Constant(ref i) => write!(f, "U16({:#06x})", i),
+            Expression::U32Constant(ref i) => write!(f, "U32({:#010x})", i),
+            Expression::U64Constant(ref i) => write!(f, "U64({:#018x})", i),
             Expression::Identifier(ref var) => write!(f, "Ide({})", var),
             Expression::Add(ref lhs, ref rhs) => write!(f, "Add({:?}, {:?})", lhs, rhs),
             Expression::Sub(ref lhs, ref rhs) => write!(f, "Sub({:?}, {:?})", lhs, rhs),
@@ -429,6 +533,17 @@ impl<'ast, T: Field> fmt::Debug for Expression<'ast, T> {
             }
             Expression::Select(ref array, ref index) => write!(f, "{}[{}]", array, index),
             Expression::Or(ref lhs, ref rhs) => write!(f, "{} || {}", lhs, rhs),
+            Expression::BitAnd(ref lhs, ref rhs) => write!(f, "{} & {}", lhs, rhs),
+            Expression::BitOr(ref lhs, ref rhs) => write!(f, "{} | {}", lhs, rhs),
+            Expression::BitXor(ref lhs, ref rhs) => write!(f, "{} ^ {}", lhs, rhs),
+            Expression::LeftShift(ref lhs, ref rhs) => write!(f, "{} << {}", lhs, rhs),
+            Expression::RightShift(ref lhs, ref rhs) => write!(f, "{} >> {}", lhs, rhs),
+            Expression::InlineStruct(ref id, ref members) => {
+                r#try!(write!(f, "InlineStruct({:?}, [", id));
+                r#try!(f.debug_list().entries(members.iter()).finish());
+                write!(f, "]")
+            }
+            Expression::Member(ref struc, ref id) => write!(f, "Member({:?}, {:?})", struc, id),
         }
     }
 }
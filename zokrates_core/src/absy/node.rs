@@ -72,6 +72,9 @@ impl<'ast, T: Field> NodeValue for Spread<'ast, T> {}
 impl<T: Field> NodeValue for Range<T> {}
 impl<'ast> NodeValue for Variable<'ast> {}
 impl<'ast> NodeValue for Parameter<'ast> {}
+impl<'ast> NodeValue for StructDefinition<'ast> {}
+impl<'ast> NodeValue for StructField<'ast> {}
+impl NodeValue for UnresolvedType {}
 impl NodeValue for Import {}
 
 impl<T: NodeValue> std::cmp::PartialEq for Node<T> {
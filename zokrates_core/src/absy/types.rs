@@ -0,0 +1,110 @@
+use crate::absy::Node;
+use std::fmt;
+
+pub type UserTypeId = String;
+
+/// A type as written in the source, before user-defined types are resolved
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+pub enum UnresolvedType {
+    FieldElement,
+    Boolean,
+    Uint(usize),
+    Array(Box<UnresolvedType>, usize),
+    User(UserTypeId),
+}
+
+impl UnresolvedType {
+    pub fn array(ty: UnresolvedType, size: usize) -> Self {
+        UnresolvedType::Array(box ty, size)
+    }
+}
+
+impl fmt::Display for UnresolvedType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            UnresolvedType::FieldElement => write!(f, "field"),
+            UnresolvedType::Boolean => write!(f, "bool"),
+            UnresolvedType::Uint(bitwidth) => write!(f, "u{}", bitwidth),
+            UnresolvedType::Array(ref ty, size) => {
+                // dimensions are displayed outermost first, as they are written
+                let mut dimensions = vec![*size];
+                let mut ty = ty;
+                while let UnresolvedType::Array(ref inner, size) = **ty {
+                    dimensions.push(size);
+                    ty = inner;
+                }
+                r#try!(write!(f, "{}", ty));
+                for size in dimensions {
+                    r#try!(write!(f, "[{}]", size));
+                }
+                Ok(())
+            }
+            UnresolvedType::User(i) => write!(f, "{}", i),
+        }
+    }
+}
+
+pub type UnresolvedTypeNode = Node<UnresolvedType>;
+
+pub use self::signature::UnresolvedSignature;
+
+mod signature {
+    use std::fmt;
+
+    use crate::absy::UnresolvedTypeNode;
+
+    #[derive(Clone, PartialEq)]
+    pub struct UnresolvedSignature {
+        pub inputs: Vec<UnresolvedTypeNode>,
+        pub outputs: Vec<UnresolvedTypeNode>,
+    }
+
+    impl fmt::Debug for UnresolvedSignature {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(
+                f,
+                "Signature(inputs: {:?}, outputs: {:?})",
+                self.inputs, self.outputs
+            )
+        }
+    }
+
+    impl fmt::Display for UnresolvedSignature {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            r#try!(write!(f, "("));
+            for (i, t) in self.inputs.iter().enumerate() {
+                r#try!(write!(f, "{}", t));
+                if i < self.inputs.len() - 1 {
+                    r#try!(write!(f, ", "));
+                }
+            }
+            r#try!(write!(f, ") -> ("));
+            for (i, t) in self.outputs.iter().enumerate() {
+                r#try!(write!(f, "{}", t));
+                if i < self.outputs.len() - 1 {
+                    r#try!(write!(f, ", "));
+                }
+            }
+            write!(f, ")")
+        }
+    }
+
+    impl UnresolvedSignature {
+        pub fn new() -> UnresolvedSignature {
+            UnresolvedSignature {
+                inputs: vec![],
+                outputs: vec![],
+            }
+        }
+
+        pub fn inputs(mut self, inputs: Vec<UnresolvedTypeNode>) -> Self {
+            self.inputs = inputs;
+            self
+        }
+
+        pub fn outputs(mut self, outputs: Vec<UnresolvedTypeNode>) -> Self {
+            self.outputs = outputs;
+            self
+        }
+    }
+}
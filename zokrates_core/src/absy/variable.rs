@@ -1,5 +1,5 @@
 use crate::absy::Node;
-use crate::types::Type;
+use crate::absy::UnresolvedType;
 use std::fmt;
 
 use crate::absy::Identifier;
@@ -7,13 +7,13 @@ use crate::absy::Identifier;
 #[derive(Clone, PartialEq, Hash, Eq)]
 pub struct Variable<'ast> {
     pub id: Identifier<'ast>,
-    pub _type: Type,
+    pub _type: UnresolvedType,
 }
 
 pub type VariableNode<'ast> = Node<Variable<'ast>>;
 
 impl<'ast> Variable<'ast> {
-    pub fn new<S: Into<&'ast str>>(id: S, t: Type) -> Variable<'ast> {
+    pub fn new<S: Into<&'ast str>>(id: S, t: UnresolvedType) -> Variable<'ast> {
         Variable {
             id: id.into(),
             _type: t,
@@ -23,25 +23,32 @@ impl<'ast> Variable<'ast> {
     pub fn field_element<S: Into<&'ast str>>(id: S) -> Variable<'ast> {
         Variable {
             id: id.into(),
-            _type: Type::FieldElement,
+            _type: UnresolvedType::FieldElement,
         }
     }
 
     pub fn boolean<S: Into<&'ast str>>(id: S) -> Variable<'ast> {
         Variable {
             id: id.into(),
-            _type: Type::Boolean,
+            _type: UnresolvedType::Boolean,
+        }
+    }
+
+    pub fn uint<S: Into<&'ast str>>(id: S, bitwidth: usize) -> Variable<'ast> {
+        Variable {
+            id: id.into(),
+            _type: UnresolvedType::Uint(bitwidth),
         }
     }
 
     pub fn field_array<S: Into<&'ast str>>(id: S, size: usize) -> Variable<'ast> {
         Variable {
             id: id.into(),
-            _type: Type::FieldElementArray(size),
+            _type: UnresolvedType::array(UnresolvedType::FieldElement, size),
         }
     }
 
-    pub fn get_type(&self) -> Type {
+    pub fn get_type(&self) -> UnresolvedType {
         self._type.clone()
     }
 }
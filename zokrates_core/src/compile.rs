@@ -3,17 +3,19 @@
 //! @file compile.rs
 //! @author Thibaut Schaeffer <thibaut@schaeff.fr>
 //! @date 2018
+use abi::Abi;
 use absy::Prog;
 use flat_absy::FlatProg;
 use flatten::Flattener;
-use imports::{self, Importer};
-use ir;
+use imports::{self, Importer, Resolver};
+use ir::{self, Profile};
 use optimizer::Optimize;
 use semantics::{self, Checker};
 use static_analysis::Analyse;
 use std::fmt;
 use std::io;
 use std::io::BufRead;
+use typed_absy::TypedProg;
 use zokrates_field::field::Field;
 use zokrates_pest_ast as pest;
 
@@ -123,58 +125,156 @@ impl fmt::Display for CompileErrorInner {
     }
 }
 
-pub fn compile<T: Field, R: BufRead, S: BufRead, E: Into<imports::Error>>(
+/// The result of compiling a program: the compiled program and the ABI of its `main` function
+#[derive(Debug)]
+pub struct CompilationArtifacts<T: Field> {
+    prog: ir::Prog<T>,
+    abi: Abi,
+}
+
+impl<T: Field> CompilationArtifacts<T> {
+    pub fn prog(&self) -> &ir::Prog<T> {
+        &self.prog
+    }
+
+    pub fn abi(&self) -> &Abi {
+        &self.abi
+    }
+}
+
+pub fn compile<T: Field, R: BufRead>(
+    reader: &mut R,
+    location: Option<String>,
+    resolver: Option<&dyn Resolver>,
+) -> Result<CompilationArtifacts<T>, CompileErrors> {
+    let (compiled, abi) = compile_program(reader, location, resolver, false)?;
+    Ok(CompilationArtifacts {
+        prog: ir::Prog::from(compiled).optimize(),
+        abi,
+    })
+}
+
+/// Compiles a program keeping track of the function and line each statement originates from, and
+/// returns the resulting breakdown of its cost
+pub fn profile<T: Field, R: BufRead>(
     reader: &mut R,
     location: Option<String>,
-    resolve_option: Option<fn(&Option<String>, &String) -> Result<(S, String, String), E>>,
-) -> Result<ir::Prog<T>, CompileErrors> {
-    let compiled = compile_aux(reader, location, resolve_option)?;
-    Ok(ir::Prog::from(compiled).optimize())
+    resolver: Option<&dyn Resolver>,
+) -> Result<Profile, CompileErrors> {
+    let (compiled, _) = compile_program(reader, location, resolver, true)?;
+    Ok(ir::Prog::<T>::from(compiled).optimize().profile())
 }
 
-pub fn compile_aux<T: Field, R: BufRead, S: BufRead, E: Into<imports::Error>>(
+pub fn compile_aux<T: Field, R: BufRead>(
     reader: &mut R,
     location: Option<String>,
-    resolve_option: Option<fn(&Option<String>, &String) -> Result<(S, String, String), E>>,
+    resolver: Option<&dyn Resolver>,
+    profile: bool,
 ) -> Result<FlatProg<T>, CompileErrors> {
+    compile_program(reader, location, resolver, profile).map(|(program, _)| program)
+}
+
+fn compile_program<T: Field, R: BufRead>(
+    reader: &mut R,
+    location: Option<String>,
+    resolver: Option<&dyn Resolver>,
+    profile: bool,
+) -> Result<(FlatProg<T>, Abi), CompileErrors> {
     let mut source = String::new();
     reader.read_to_string(&mut source).unwrap();
-    let ast = pest::generate_ast(&source)
+
+    let typed_ast: TypedProg<T> = check_program(&source, location.clone(), resolver, profile)?;
+
+    // keep the interface of main before it gets inlined
+    let abi = typed_ast.abi();
+
+    Ok((flatten_program(typed_ast, location), abi))
+}
+
+/// Compiles a module which was already parsed, such as a module whose `main` was replaced by the
+/// function selected by an import
+pub fn compile_module<'ast, T: Field>(
+    program: Prog<'ast, T>,
+    location: Option<String>,
+    resolver: Option<&dyn Resolver>,
+    profile: bool,
+) -> Result<FlatProg<T>, CompileErrors> {
+    let typed_ast = check_module(program, location.clone(), resolver, profile)?;
+
+    Ok(flatten_program(typed_ast, location))
+}
+
+fn flatten_program<'ast, T: Field>(
+    typed_ast: TypedProg<'ast, T>,
+    location: Option<String>,
+) -> FlatProg<T> {
+    // analyse (unroll and constant propagation)
+    let typed_ast = typed_ast.analyse();
+
+    // flatten input program
+    let program_flattened = Flattener::flatten(typed_ast, location);
+
+    // analyse (constant propagation after call resolution)
+    program_flattened.analyse()
+}
+
+/// Parses a program and checks its semantics, returning the typed program which can be
+/// interpreted without being compiled
+pub fn check<'ast, T: Field>(
+    source: &'ast str,
+    location: Option<String>,
+    resolver: Option<&dyn Resolver>,
+) -> Result<TypedProg<'ast, T>, CompileErrors> {
+    check_program(source, location, resolver, false)
+}
+
+fn check_program<'ast, T: Field>(
+    source: &'ast str,
+    location: Option<String>,
+    resolver: Option<&dyn Resolver>,
+    profile: bool,
+) -> Result<TypedProg<'ast, T>, CompileErrors> {
+    let ast = pest::generate_ast(source)
         .map_err(|e| CompileErrors::from(CompileErrorInner::from(e).with_context(&location)))?;
     let program_ast_without_imports: Prog<T> = Prog::from(ast);
 
-    let program_ast = Importer::new().apply_imports(
+    check_module(program_ast_without_imports, location, resolver, profile)
+}
+
+fn check_module<'ast, T: Field>(
+    program_ast_without_imports: Prog<'ast, T>,
+    location: Option<String>,
+    resolver: Option<&dyn Resolver>,
+    profile: bool,
+) -> Result<TypedProg<'ast, T>, CompileErrors> {
+    let program_ast = Importer::new().profile(profile).apply_imports(
         program_ast_without_imports,
         location.clone(),
-        resolve_option,
+        resolver,
     )?;
 
-    // check semantics
-    let typed_ast = Checker::check(program_ast).map_err(|errors| {
+    // check semantics, marking the origin of statements when profiling
+    match profile {
+        true => Checker::check_with_markers(program_ast),
+        false => Checker::check(program_ast),
+    }
+    .map_err(|errors| {
         CompileErrors(
             errors
                 .into_iter()
                 .map(|e| CompileErrorInner::from(e).with_context(&location))
                 .collect(),
         )
-    })?;
-
-    // analyse (unroll and constant propagation)
-    let typed_ast = typed_ast.analyse();
-
-    // flatten input program
-    let program_flattened = Flattener::flatten(typed_ast);
-
-    // analyse (constant propagation after call resolution)
-    let program_flattened = program_flattened.analyse();
-
-    Ok(program_flattened)
+    })
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    use std::io::{BufReader, Empty};
+    use abi::AbiInput;
+    use imports::MemoryResolver;
+    use std::io::BufReader;
+    use types::Type;
     use zokrates_field::field::FieldPrime;
 
     #[test]
@@ -187,16 +287,8 @@ mod test {
 		"#
             .as_bytes(),
         );
-        let res: Result<ir::Prog<FieldPrime>, CompileErrors> = compile(
-            &mut r,
-            Some(String::from("./path/to/file")),
-            None::<
-                fn(
-                    &Option<String>,
-                    &String,
-                ) -> Result<(BufReader<Empty>, String, String), io::Error>,
-            >,
-        );
+        let res: Result<CompilationArtifacts<FieldPrime>, CompileErrors> =
+            compile(&mut r, Some(String::from("./path/to/file")), None);
 
         assert!(res
             .unwrap_err()
@@ -213,16 +305,140 @@ mod test {
 		"#
             .as_bytes(),
         );
-        let res: Result<ir::Prog<FieldPrime>, CompileErrors> = compile(
-            &mut r,
+        let res: Result<CompilationArtifacts<FieldPrime>, CompileErrors> =
+            compile(&mut r, Some(String::from("./path/to/file")), None);
+        assert!(res.is_ok());
+    }
+
+    #[test]
+    fn profile_by_function() {
+        let source = r#"
+			def foo(field a) -> (field):
+			   return a * a
+			def main(field a) -> (field):
+			   field b = foo(a)
+			   return b * a
+		"#;
+
+        let p: Profile = profile::<FieldPrime, _>(
+            &mut BufReader::new(source.as_bytes()),
+            Some(String::from("./path/to/file")),
+            None,
+        )
+        .unwrap();
+
+        let artifacts: CompilationArtifacts<FieldPrime> = compile(
+            &mut BufReader::new(source.as_bytes()),
             Some(String::from("./path/to/file")),
-            None::<
-                fn(
-                    &Option<String>,
-                    &String,
-                ) -> Result<(BufReader<Empty>, String, String), io::Error>,
-            >,
+            None,
+        )
+        .unwrap();
+
+        // profiling does not change the compiled program
+        assert_eq!(p.total.constraints, artifacts.prog().constraint_count());
+
+        let mut functions: Vec<_> = p.functions.iter().map(|f| f.function.as_str()).collect();
+        functions.sort();
+        assert_eq!(functions, vec!["foo", "main"]);
+        assert!(p
+            .lines
+            .iter()
+            .all(|l| l.module == Some(String::from("./path/to/file"))));
+    }
+
+    #[test]
+    fn abi() {
+        let mut r = BufReader::new(
+            r#"
+			def main(field a, private bool[2] b) -> (field):
+			   return a
+		"#
+            .as_bytes(),
         );
-        assert!(res.is_ok());
+        let artifacts: CompilationArtifacts<FieldPrime> =
+            compile(&mut r, Some(String::from("./path/to/file")), None).unwrap();
+
+        assert_eq!(
+            artifacts.abi(),
+            &Abi {
+                inputs: vec![
+                    AbiInput {
+                        name: "a".into(),
+                        public: true,
+                        ty: Type::FieldElement,
+                    },
+                    AbiInput {
+                        name: "b".into(),
+                        public: false,
+                        ty: Type::array(Type::Boolean, 2),
+                    },
+                ],
+                outputs: vec![Type::FieldElement],
+            }
+        );
+    }
+
+    #[test]
+    fn memory_resolver() {
+        let resolver = MemoryResolver::new()
+            .module(
+                "lib/square.code",
+                "import \"./mul.code\" as mul\ndef main(field a) -> (field):\n  return mul(a, a)\n",
+            )
+            .module(
+                "lib/mul.code",
+                "def main(field a, field b) -> (field):\n  return a * b\n",
+            );
+
+        let artifacts: CompilationArtifacts<FieldPrime> = compile(
+            &mut "import \"./lib/square.code\" as square\ndef main(field a) -> (field):\n  return square(a)\n".as_bytes(),
+            Some(String::new()),
+            Some(&resolver),
+        )
+        .unwrap();
+
+        assert_eq!(
+            artifacts
+                .prog()
+                .execute(&vec![FieldPrime::from(3)])
+                .unwrap()
+                .return_values(),
+            vec![FieldPrime::from(9)]
+        );
+    }
+
+    #[test]
+    fn import_symbols() {
+        // `compress` calls another function of the module, which has no `main`
+        let resolver = MemoryResolver::new().module(
+            "utils.code",
+            "def hash(field a) -> (field):\n  return a * a\ndef double(field a) -> (field):\n  return a + a\ndef compress(field a, field b) -> (field):\n  return double(a) + b\n",
+        );
+
+        let artifacts: CompilationArtifacts<FieldPrime> = compile(
+            &mut "from \"./utils.code\" import hash, compress as c\ndef main(field a) -> (field):\n  return c(hash(a), 1)\n".as_bytes(),
+            Some(String::new()),
+            Some(&resolver),
+        )
+        .unwrap();
+
+        assert_eq!(
+            artifacts
+                .prog()
+                .execute(&vec![FieldPrime::from(3)])
+                .unwrap()
+                .return_values(),
+            vec![FieldPrime::from(19)]
+        );
+
+        let res: Result<CompilationArtifacts<FieldPrime>, CompileErrors> = compile(
+            &mut "from \"./utils.code\" import hash, square\ndef main(field a) -> (field):\n  return square(a)\n".as_bytes(),
+            Some(String::new()),
+            Some(&resolver),
+        );
+        assert!(res
+            .unwrap_err()
+            .to_string()
+            .contains("Function square not found in ./utils.code"));
     }
 }
@@ -81,6 +81,12 @@ impl FlatVariable {
     pub fn is_output(&self) -> bool {
         self.id < 0
     }
+
+    /// Returns `i` for `~out_i`
+    pub fn output_index(&self) -> usize {
+        assert!(self.id < 0);
+        -(self.id + 1) as usize
+    }
 }
 
 #[cfg(test)]
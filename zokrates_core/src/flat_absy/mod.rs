@@ -12,6 +12,7 @@ pub use self::flat_parameter::FlatParameter;
 pub use self::flat_variable::FlatVariable;
 
 use crate::helpers::{DirectiveStatement, Executable};
+use crate::parser::Position;
 use crate::types::Signature;
 use std::collections::{BTreeMap, HashMap};
 use std::fmt;
@@ -93,7 +94,7 @@ impl<T: Field> FlatFunction<T> {
                     let s = expr.solve(&mut witness);
                     witness.insert(id.clone(), s);
                 }
-                FlatStatement::Condition(ref lhs, ref rhs) => {
+                FlatStatement::Condition(ref lhs, ref rhs, _) => {
                     if lhs.solve(&mut witness) != rhs.solve(&mut witness) {
                         return Err(Error {
                             message: format!(
@@ -116,6 +117,7 @@ impl<T: Field> FlatFunction<T> {
                         Err(message) => return Err(Error { message: message }),
                     };
                 }
+                FlatStatement::Marker(..) => {}
             }
         }
         Ok(witness)
@@ -169,12 +171,45 @@ impl<T: Field> fmt::Debug for FlatFunction<T> {
 ///
 /// * r1cs - R1CS in standard JSON data format
 
+/// The location of an assertion in the source code
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct SourceLocation {
+    /// Path of the module the assertion is in, if known
+    pub module: Option<String>,
+    pub position: Position,
+}
+
+impl fmt::Display for SourceLocation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.module {
+            Some(ref module) => write!(f, "{}:{}", module, self.position),
+            None => write!(f, "{}", self.position),
+        }
+    }
+}
+
+/// The statement of the source code some statements originate from
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct Origin {
+    /// Name of the function the statement is in, before inlining
+    pub function: String,
+    pub location: SourceLocation,
+}
+
+impl fmt::Display for Origin {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} ({})", self.location, self.function)
+    }
+}
+
 #[derive(Clone, PartialEq)]
 pub enum FlatStatement<T: Field> {
     Return(FlatExpressionList<T>),
-    Condition(FlatExpression<T>, FlatExpression<T>),
+    Condition(FlatExpression<T>, FlatExpression<T>, Option<SourceLocation>),
     Definition(FlatVariable, FlatExpression<T>),
     Directive(DirectiveStatement<T>),
+    /// Marks the statements which follow as originating from a statement of the source code
+    Marker(Origin),
 }
 
 impl<T: Field> fmt::Display for FlatStatement<T> {
@@ -182,8 +217,9 @@ impl<T: Field> fmt::Display for FlatStatement<T> {
         match *self {
             FlatStatement::Definition(ref lhs, ref rhs) => write!(f, "{} = {}", lhs, rhs),
             FlatStatement::Return(ref expr) => write!(f, "return {}", expr),
-            FlatStatement::Condition(ref lhs, ref rhs) => write!(f, "{} == {}", lhs, rhs),
+            FlatStatement::Condition(ref lhs, ref rhs, _) => write!(f, "{} == {}", lhs, rhs),
             FlatStatement::Directive(ref d) => write!(f, "{}", d),
+            FlatStatement::Marker(ref origin) => write!(f, "// {}", origin),
         }
     }
 }
@@ -193,10 +229,11 @@ impl<T: Field> fmt::Debug for FlatStatement<T> {
         match *self {
             FlatStatement::Definition(ref lhs, ref rhs) => write!(f, "{} = {}", lhs, rhs),
             FlatStatement::Return(ref expr) => write!(f, "FlatReturn({:?})", expr),
-            FlatStatement::Condition(ref lhs, ref rhs) => {
-                write!(f, "FlatCondition({:?}, {:?})", lhs, rhs)
+            FlatStatement::Condition(ref lhs, ref rhs, ref location) => {
+                write!(f, "FlatCondition({:?}, {:?}, {:?})", lhs, rhs, location)
             }
             FlatStatement::Directive(ref d) => write!(f, "{:?}", d),
+            FlatStatement::Marker(ref origin) => write!(f, "Marker({:?})", origin),
         }
     }
 }
@@ -212,9 +249,10 @@ impl<T: Field> FlatStatement<T> {
                 x.apply_substitution(substitution),
             ),
             FlatStatement::Return(x) => FlatStatement::Return(x.apply_substitution(substitution)),
-            FlatStatement::Condition(x, y) => FlatStatement::Condition(
+            FlatStatement::Condition(x, y, location) => FlatStatement::Condition(
                 x.apply_substitution(substitution),
                 y.apply_substitution(substitution),
+                location,
             ),
             FlatStatement::Directive(d) => {
                 let outputs = d
@@ -234,6 +272,7 @@ impl<T: Field> FlatStatement<T> {
                     ..d
                 })
             }
+            FlatStatement::Marker(origin) => FlatStatement::Marker(origin),
         }
     }
 }
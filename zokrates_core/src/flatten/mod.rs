@@ -9,8 +9,7 @@ use crate::flat_absy::*;
 use crate::helpers::{DirectiveStatement, Helper, RustHelper};
 use crate::typed_absy::*;
 use crate::types::conversions::cast;
-use crate::types::Signature;
-use crate::types::Type;
+use crate::types::{MemberId, Signature, Type};
 use std::collections::HashMap;
 use zokrates_field::field::Field;
 
@@ -21,10 +20,19 @@ pub struct Flattener<'ast> {
     next_var_idx: usize,
     ///
     layout: HashMap<Identifier<'ast>, Vec<FlatVariable>>,
+    /// Path of the module being flattened, used to locate its assertions
+    module: Option<String>,
+    /// Origin of the statement being flattened, if the program was marked for profiling
+    origin: Option<Origin>,
 }
 impl<'ast> Flattener<'ast> {
-    pub fn flatten<T: Field>(p: TypedProg<T>) -> FlatProg<T> {
-        Flattener::new().flatten_program(p)
+    /// Flattens the program `p` read from `module`
+    pub fn flatten<T: Field>(p: TypedProg<T>, module: Option<String>) -> FlatProg<T> {
+        Flattener {
+            module,
+            ..Flattener::new()
+        }
+        .flatten_program(p)
     }
 
     /// Returns a `Flattener` with fresh a fresh [substitution] and [variables].
@@ -37,6 +45,8 @@ impl<'ast> Flattener<'ast> {
         Flattener {
             next_var_idx: 0,
             layout: HashMap::new(),
+            module: None,
+            origin: None,
         }
     }
 
@@ -153,7 +163,7 @@ impl<'ast> Flattener<'ast> {
                     // add a directive to get the bits
                     statements_flattened.push(FlatStatement::Directive(DirectiveStatement::new(
                         lhs_bits.clone(),
-                        Helper::bits(),
+                        Helper::bits::<T>(),
                         vec![lhs_id],
                     )));
 
@@ -165,6 +175,7 @@ impl<'ast> Flattener<'ast> {
                                 box FlatExpression::Identifier(lhs_bits[i + 2]),
                                 box FlatExpression::Identifier(lhs_bits[i + 2]),
                             ),
+                            None,
                         ));
                     }
 
@@ -184,6 +195,7 @@ impl<'ast> Flattener<'ast> {
                     statements_flattened.push(FlatStatement::Condition(
                         FlatExpression::Identifier(lhs_id),
                         lhs_sum,
+                        None,
                     ));
                 }
 
@@ -200,7 +212,7 @@ impl<'ast> Flattener<'ast> {
                     // add a directive to get the bits
                     statements_flattened.push(FlatStatement::Directive(DirectiveStatement::new(
                         rhs_bits.clone(),
-                        Helper::bits(),
+                        Helper::bits::<T>(),
                         vec![rhs_id],
                     )));
 
@@ -212,6 +224,7 @@ impl<'ast> Flattener<'ast> {
                                 box FlatExpression::Identifier(rhs_bits[i + 2]),
                                 box FlatExpression::Identifier(rhs_bits[i + 2]),
                             ),
+                            None,
                         ));
                     }
 
@@ -231,58 +244,26 @@ impl<'ast> Flattener<'ast> {
                     statements_flattened.push(FlatStatement::Condition(
                         FlatExpression::Identifier(rhs_id),
                         rhs_sum,
+                        None,
                     ));
                 }
 
-                // sym := (lhs * 2) - (rhs * 2)
-                let subtraction_result = FlatExpression::Sub(
-                    box FlatExpression::Mult(
-                        box FlatExpression::Number(T::from(2)),
+                // lhs and rhs are smaller than 2**(bitwidth - 2), so lhs - rhs + 2**(bitwidth - 2)
+                // fits in bitwidth - 1 bits, and its highest bit is set iff lhs >= rhs
+                let shifted_difference = FlatExpression::Add(
+                    box FlatExpression::Sub(
                         box FlatExpression::Identifier(lhs_id),
-                    ),
-                    box FlatExpression::Mult(
-                        box FlatExpression::Number(T::from(2)),
                         box FlatExpression::Identifier(rhs_id),
                     ),
+                    box FlatExpression::Number(T::from(2).pow(bitwidth - 2)),
                 );
 
-                // define variables for the bits
-                let sub_bits: Vec<FlatVariable> = (0..bitwidth).map(|_| self.use_sym()).collect();
+                let bits = self.uint_bits(statements_flattened, shifted_difference, bitwidth - 1);
 
-                // add a directive to get the bits
-                statements_flattened.push(FlatStatement::Directive(DirectiveStatement::new(
-                    sub_bits.clone(),
-                    Helper::bits(),
-                    vec![subtraction_result.clone()],
-                )));
-
-                // bitness checks
-                for i in 0..bitwidth {
-                    statements_flattened.push(FlatStatement::Condition(
-                        FlatExpression::Identifier(sub_bits[i]),
-                        FlatExpression::Mult(
-                            box FlatExpression::Identifier(sub_bits[i]),
-                            box FlatExpression::Identifier(sub_bits[i]),
-                        ),
-                    ));
-                }
-
-                // sum(sym_b{i} * 2**i)
-                let mut expr = FlatExpression::Number(T::from(0));
-
-                for i in 0..bitwidth {
-                    expr = FlatExpression::Add(
-                        box expr,
-                        box FlatExpression::Mult(
-                            box FlatExpression::Identifier(sub_bits[i]),
-                            box FlatExpression::Number(T::from(2).pow(bitwidth - i - 1)),
-                        ),
-                    );
-                }
-
-                statements_flattened.push(FlatStatement::Condition(subtraction_result, expr));
-
-                FlatExpression::Identifier(sub_bits[0])
+                FlatExpression::Sub(
+                    box FlatExpression::Number(T::one()),
+                    box FlatExpression::Identifier(bits[0]),
+                )
             }
             BooleanExpression::Eq(box lhs, box rhs) => {
                 // We know from semantic checking that lhs and rhs have the same type
@@ -312,6 +293,7 @@ impl<'ast> Flattener<'ast> {
                 statements_flattened.push(FlatStatement::Condition(
                     FlatExpression::Identifier(name_y),
                     FlatExpression::Mult(box x.clone(), box FlatExpression::Identifier(name_m)),
+                    None,
                 ));
 
                 let res = FlatExpression::Sub(
@@ -322,6 +304,7 @@ impl<'ast> Flattener<'ast> {
                 statements_flattened.push(FlatStatement::Condition(
                     FlatExpression::Number(T::zero()),
                     FlatExpression::Mult(box res.clone(), box x),
+                    None,
                 ));
 
                 res
@@ -391,10 +374,127 @@ impl<'ast> Flattener<'ast> {
                     self.flatten_boolean_expression(functions_flattened, statements_flattened, exp);
                 FlatExpression::Sub(box FlatExpression::Number(T::one()), box x)
             }
+            BooleanExpression::Member(box s, id) => self
+                .flatten_member_expression(functions_flattened, statements_flattened, s, id)[0]
+                .clone(),
+            BooleanExpression::IfElse(box condition, box consequence, box alternative) => {
+                let condition_flattened = self.flatten_boolean_expression(
+                    functions_flattened,
+                    statements_flattened,
+                    condition,
+                );
+                let consequence_flattened = self.flatten_boolean_expression(
+                    functions_flattened,
+                    statements_flattened,
+                    consequence,
+                );
+                let alternative_flattened = self.flatten_boolean_expression(
+                    functions_flattened,
+                    statements_flattened,
+                    alternative,
+                );
+
+                // condition * (consequence - alternative) + alternative
+                let condition_times_difference = self.use_sym();
+                statements_flattened.push(FlatStatement::Definition(
+                    condition_times_difference,
+                    FlatExpression::Mult(
+                        box condition_flattened,
+                        box FlatExpression::Sub(
+                            box consequence_flattened,
+                            box alternative_flattened.clone(),
+                        ),
+                    ),
+                ));
+
+                FlatExpression::Add(
+                    box condition_times_difference.into(),
+                    box alternative_flattened,
+                )
+            }
+            BooleanExpression::Select(box array, box index) => self.flatten_select_expression(
+                functions_flattened,
+                statements_flattened,
+                array,
+                index,
+            )[0]
+            .clone(),
             BooleanExpression::Value(b) => FlatExpression::Number(match b {
                 true => T::from(1),
                 false => T::from(0),
             }),
+            BooleanExpression::UintEq(box lhs, box rhs) => {
+                // same as for field elements, as two unsigned integers are equal iff their values are equal
+                let name_y = self.use_sym();
+                let name_m = self.use_sym();
+
+                let lhs_flattened =
+                    self.flatten_uint_expression(functions_flattened, statements_flattened, lhs);
+                let rhs_flattened =
+                    self.flatten_uint_expression(functions_flattened, statements_flattened, rhs);
+
+                let x = FlatExpression::Sub(box lhs_flattened, box rhs_flattened);
+
+                statements_flattened.push(FlatStatement::Directive(DirectiveStatement::new(
+                    vec![name_y, name_m],
+                    Helper::Rust(RustHelper::ConditionEq),
+                    vec![x.clone()],
+                )));
+                statements_flattened.push(FlatStatement::Condition(
+                    FlatExpression::Identifier(name_y),
+                    FlatExpression::Mult(box x.clone(), box FlatExpression::Identifier(name_m)),
+                    None,
+                ));
+
+                let res = FlatExpression::Sub(
+                    box FlatExpression::Number(T::one()),
+                    box FlatExpression::Identifier(name_y),
+                );
+
+                statements_flattened.push(FlatStatement::Condition(
+                    FlatExpression::Number(T::zero()),
+                    FlatExpression::Mult(box res.clone(), box x),
+                    None,
+                ));
+
+                res
+            }
+            BooleanExpression::UintGe(box lhs, box rhs) => {
+                let bitwidth = lhs.bitwidth();
+
+                let lhs_flattened =
+                    self.flatten_uint_expression(functions_flattened, statements_flattened, lhs);
+                let rhs_flattened =
+                    self.flatten_uint_expression(functions_flattened, statements_flattened, rhs);
+
+                // lhs - rhs + 2**bitwidth fits in bitwidth + 1 bits, and its highest bit is set iff lhs >= rhs
+                let shifted_difference = FlatExpression::Add(
+                    box FlatExpression::Sub(box lhs_flattened, box rhs_flattened),
+                    box FlatExpression::Number(T::from(2).pow(bitwidth)),
+                );
+
+                let bits = self.uint_bits(statements_flattened, shifted_difference, bitwidth + 1);
+
+                FlatExpression::Identifier(bits[0])
+            }
+            BooleanExpression::UintLt(box lhs, box rhs) => {
+                let ge = self.flatten_boolean_expression(
+                    functions_flattened,
+                    statements_flattened,
+                    BooleanExpression::UintGe(box lhs, box rhs),
+                );
+                FlatExpression::Sub(box FlatExpression::Number(T::one()), box ge)
+            }
+            BooleanExpression::UintLe(lhs, rhs) => self.flatten_boolean_expression(
+                functions_flattened,
+                statements_flattened,
+                BooleanExpression::UintGe(rhs, lhs),
+            ),
+            BooleanExpression::UintGt(lhs, rhs) => self.flatten_boolean_expression(
+                functions_flattened,
+                statements_flattened,
+                BooleanExpression::UintLt(rhs, lhs),
+            ),
         }
     }
 
@@ -448,6 +548,23 @@ impl<'ast> Flattener<'ast> {
                 _ => false,
             });
 
+        // when profiling, the statements of a function which was not marked (such as an embedded
+        // function) originate from that function called at the current statement
+        let caller_origin = self.origin.clone();
+        let is_marked = statements.iter().any(|s| match s {
+            FlatStatement::Marker(..) => true,
+            _ => false,
+        });
+
+        if let Some(ref origin) = caller_origin {
+            if !is_marked {
+                statements_flattened.push(FlatStatement::Marker(Origin {
+                    function: funct.id.clone(),
+                    ..origin.clone()
+                }));
+            }
+        }
+
         let statements: Vec<_> = statements
             .into_iter()
             .map(|stat| match stat {
@@ -459,10 +576,10 @@ impl<'ast> Flattener<'ast> {
                     let new_rhs = rhs.apply_substitution(&replacement_map);
                     FlatStatement::Definition(new_var, new_rhs)
                 }
-                FlatStatement::Condition(lhs, rhs) => {
+                FlatStatement::Condition(lhs, rhs, location) => {
                     let new_lhs = lhs.apply_substitution(&replacement_map);
                     let new_rhs = rhs.apply_substitution(&replacement_map);
-                    FlatStatement::Condition(new_lhs, new_rhs)
+                    FlatStatement::Condition(new_lhs, new_rhs, location)
                 }
                 FlatStatement::Directive(d) => {
                     let new_outputs = d
@@ -485,11 +602,17 @@ impl<'ast> Flattener<'ast> {
                         inputs: new_inputs,
                     })
                 }
+                FlatStatement::Marker(origin) => FlatStatement::Marker(origin),
             })
             .collect();
 
         statements_flattened.extend(statements);
 
+        // the statements which follow originate from the caller again
+        if let Some(origin) = caller_origin {
+            statements_flattened.push(FlatStatement::Marker(origin));
+        }
+
         match return_statements[0].clone() {
             FlatStatement::Return(list) => FlatExpressionList {
                 expressions: list
@@ -515,12 +638,127 @@ impl<'ast> Flattener<'ast> {
             TypedExpression::Boolean(e) => {
                 vec![self.flatten_boolean_expression(functions_flattened, statements_flattened, e)]
             }
-            TypedExpression::FieldElementArray(e) => {
-                self.flatten_field_array_expression(functions_flattened, statements_flattened, e)
+            TypedExpression::Array(e) => {
+                self.flatten_array_expression(functions_flattened, statements_flattened, e)
+            }
+            TypedExpression::Uint(e) => {
+                vec![self.flatten_uint_expression(functions_flattened, statements_flattened, e)]
+            }
+            TypedExpression::Struct(e) => {
+                self.flatten_struct_expression(functions_flattened, statements_flattened, e)
+            }
+        }
+    }
+
+    /// Flattens a struct expression to the primitive values of its members, in declaration order
+    ///
+    /// # Arguments
+    ///
+    /// * `statements_flattened` - Vector where new flattened statements can be added.
+    /// * `expr` - `StructExpression` that will be flattened.
+    fn flatten_struct_expression<T: Field>(
+        &mut self,
+        functions_flattened: &Vec<FlatFunction<T>>,
+        statements_flattened: &mut Vec<FlatStatement<T>>,
+        expr: StructExpression<'ast, T>,
+    ) -> Vec<FlatExpression<T>> {
+        let ty = Type::Struct(expr.ty().clone());
+
+        match expr.into_inner() {
+            StructExpressionInner::Identifier(x) => self
+                .layout
+                .get(&x)
+                .unwrap()
+                .iter()
+                .map(|v| FlatExpression::Identifier(v.clone()))
+                .collect(),
+            StructExpressionInner::Value(values) => values
+                .into_iter()
+                .flat_map(|v| self.flatten_expression(functions_flattened, statements_flattened, v))
+                .collect(),
+            StructExpressionInner::FunctionCall(ref id, ref param_expressions) => {
+                let exprs_flattened = self.flatten_function_call(
+                    functions_flattened,
+                    statements_flattened,
+                    id,
+                    vec![ty.clone()],
+                    param_expressions,
+                );
+                // outside of MultipleDefinition, FunctionCalls must return a single value
+                assert!(exprs_flattened.expressions.len() == ty.get_primitive_count());
+                exprs_flattened.expressions
+            }
+            StructExpressionInner::IfElse(box condition, box consequence, box alternative) => {
+                let condition_flattened = self.flatten_boolean_expression(
+                    functions_flattened,
+                    statements_flattened,
+                    condition,
+                );
+                let consequence_flattened = self.flatten_struct_expression(
+                    functions_flattened,
+                    statements_flattened,
+                    consequence,
+                );
+                let alternative_flattened = self.flatten_struct_expression(
+                    functions_flattened,
+                    statements_flattened,
+                    alternative,
+                );
+
+                // condition * (consequence - alternative) + alternative, for each primitive value
+                consequence_flattened
+                    .into_iter()
+                    .zip(alternative_flattened.into_iter())
+                    .map(|(consequence, alternative)| {
+                        let condition_times_difference = self.use_sym();
+                        statements_flattened.push(FlatStatement::Definition(
+                            condition_times_difference,
+                            FlatExpression::Mult(
+                                box condition_flattened.clone(),
+                                box FlatExpression::Sub(box consequence, box alternative.clone()),
+                            ),
+                        ));
+
+                        FlatExpression::Add(box condition_times_difference.into(), box alternative)
+                    })
+                    .collect()
+            }
+            StructExpressionInner::Member(box s, id) => {
+                self.flatten_member_expression(functions_flattened, statements_flattened, s, id)
             }
+            StructExpressionInner::Select(box array, box index) => self
+                .flatten_select_expression(functions_flattened, statements_flattened, array, index),
         }
     }
 
+    /// Flattens the member `id` of the struct expression `s` to the primitive values of that member
+    fn flatten_member_expression<T: Field>(
+        &mut self,
+        functions_flattened: &Vec<FlatFunction<T>>,
+        statements_flattened: &mut Vec<FlatStatement<T>>,
+        s: StructExpression<'ast, T>,
+        id: MemberId,
+    ) -> Vec<FlatExpression<T>> {
+        let members = s.ty().clone();
+        let flattened =
+            self.flatten_struct_expression(functions_flattened, statements_flattened, s);
+
+        // members are laid out in declaration order, so we skip the ones before `id`
+        let offset: usize = members
+            .iter()
+            .take_while(|m| m.id != id)
+            .map(|m| m.ty.get_primitive_count())
+            .sum();
+        let size = members
+            .iter()
+            .find(|m| m.id == id)
+            .expect("member access on a struct should have been checked at semantic stage")
+            .ty
+            .get_primitive_count();
+
+        flattened[offset..offset + size].to_vec()
+    }
+
     fn flatten_field_expression<T: Field>(
         &mut self,
         functions_flattened: &Vec<FlatFunction<T>>,
@@ -627,6 +865,7 @@ impl<'ast> Flattener<'ast> {
                 statements_flattened.push(FlatStatement::Condition(
                     FlatExpression::Number(T::one()),
                     FlatExpression::Mult(box invb.into(), box new_right.clone().into()),
+                    None,
                 ));
 
                 // # c = a/b
@@ -640,6 +879,7 @@ impl<'ast> Flattener<'ast> {
                 statements_flattened.push(FlatStatement::Condition(
                     new_left.into(),
                     FlatExpression::Mult(box new_right, box inverse.into()),
+                    None,
                 ));
 
                 inverse.into()
@@ -731,217 +971,458 @@ impl<'ast> Flattener<'ast> {
                     vec![Type::FieldElement],
                     &vec![condition.into(), consequent.into(), alternative.into()],
                 )
-                .expressions[0]
-                .clone(),
-            FieldElementExpression::FunctionCall(ref id, ref param_expressions) => {
+                .expressions[0]
+                .clone(),
+            FieldElementExpression::FunctionCall(ref id, ref param_expressions) => {
+                let exprs_flattened = self.flatten_function_call(
+                    functions_flattened,
+                    statements_flattened,
+                    id,
+                    vec![Type::FieldElement],
+                    param_expressions,
+                );
+                assert!(exprs_flattened.expressions.len() == 1); // outside of MultipleDefinition, FunctionCalls must return a single value
+                exprs_flattened.expressions[0].clone()
+            }
+            FieldElementExpression::Member(box s, id) => self
+                .flatten_member_expression(functions_flattened, statements_flattened, s, id)[0]
+                .clone(),
+            FieldElementExpression::Select(box array, box index) => self.flatten_select_expression(
+                functions_flattened,
+                statements_flattened,
+                array,
+                index,
+            )[0]
+            .clone(),
+        }
+    }
+
+    /// Flattens an array expression to the primitive values of its elements, in order
+    ///
+    /// # Arguments
+    ///
+    /// * `statements_flattened` - Vector where new flattened statements can be added.
+    /// * `expr` - `ArrayExpression` that will be flattened.
+    fn flatten_array_expression<T: Field>(
+        &mut self,
+        functions_flattened: &Vec<FlatFunction<T>>,
+        statements_flattened: &mut Vec<FlatStatement<T>>,
+        expr: ArrayExpression<'ast, T>,
+    ) -> Vec<FlatExpression<T>> {
+        let size = expr.size();
+        let ty = Type::array(expr.inner_type().clone(), size);
+
+        match expr.into_inner() {
+            ArrayExpressionInner::Identifier(x) => self
+                .layout
+                .get(&x)
+                .unwrap()
+                .iter()
+                .map(|v| FlatExpression::Identifier(v.clone()))
+                .collect(),
+            ArrayExpressionInner::Value(values) => values
+                .into_iter()
+                .flat_map(|v| self.flatten_expression(functions_flattened, statements_flattened, v))
+                .collect(),
+            ArrayExpressionInner::FunctionCall(ref id, ref param_expressions) => {
+                let exprs_flattened = self.flatten_function_call(
+                    functions_flattened,
+                    statements_flattened,
+                    id,
+                    vec![ty.clone()],
+                    param_expressions,
+                );
+                // outside of MultipleDefinition, FunctionCalls must return a single value
+                assert!(exprs_flattened.expressions.len() == ty.get_primitive_count());
+                exprs_flattened.expressions
+            }
+            ArrayExpressionInner::IfElse(box condition, box consequence, box alternative) => {
+                // [if cond then [a, b] else [c, d]] ==
+                // [if cond then a else c, if cond then b else d]
+                (0..size)
+                    .flat_map(|i| {
+                        let index = FieldElementExpression::Number(T::from(i));
+                        self.flatten_expression(
+                            functions_flattened,
+                            statements_flattened,
+                            TypedExpression::if_else(
+                                condition.clone(),
+                                consequence.clone().select(index.clone()),
+                                alternative.clone().select(index),
+                            ),
+                        )
+                    })
+                    .collect()
+            }
+            ArrayExpressionInner::Member(box s, id) => {
+                self.flatten_member_expression(functions_flattened, statements_flattened, s, id)
+            }
+            ArrayExpressionInner::Select(box array, box index) => self
+                .flatten_select_expression(functions_flattened, statements_flattened, array, index),
+        }
+    }
+
+    /// Flattens the element at `index` of `array` to its primitive values
+    ///
+    /// If `index` is not a constant, the element is looked up as
+    /// `sum(if index == i then array[i] else 0)`, after checking that `index` is in range
+    fn flatten_select_expression<T: Field>(
+        &mut self,
+        functions_flattened: &Vec<FlatFunction<T>>,
+        statements_flattened: &mut Vec<FlatStatement<T>>,
+        array: ArrayExpression<'ast, T>,
+        index: FieldElementExpression<'ast, T>,
+    ) -> Vec<FlatExpression<T>> {
+        let size = array.size();
+        let element_count = array.inner_type().get_primitive_count();
+
+        match index {
+            FieldElementExpression::Number(n) => {
+                let n = n.to_dec_string().parse::<usize>().unwrap();
+                assert!(n < size);
+                match array.as_inner() {
+                    // only flatten the selected element
+                    ArrayExpressionInner::Value(expressions) => self.flatten_expression(
+                        functions_flattened,
+                        statements_flattened,
+                        expressions[n].clone(),
+                    ),
+                    _ => {
+                        let flattened = self.flatten_array_expression(
+                            functions_flattened,
+                            statements_flattened,
+                            array,
+                        );
+                        flattened[n * element_count..(n + 1) * element_count].to_vec()
+                    }
+                }
+            }
+            e => {
+                let flattened =
+                    self.flatten_array_expression(functions_flattened, statements_flattened, array);
+
+                // we have array[e] with e an arbitrary expression
+                // for each i in 0..array.len(), we compute `e == i`
+                let conditions: Vec<FlatVariable> = (0..size)
+                    .map(|i| {
+                        let condition = self.flatten_boolean_expression(
+                            functions_flattened,
+                            statements_flattened,
+                            BooleanExpression::Eq(
+                                box e.clone(),
+                                box FieldElementExpression::Number(T::from(i)),
+                            ),
+                        );
+                        let condition_id = self.use_sym();
+                        statements_flattened
+                            .push(FlatStatement::Definition(condition_id, condition));
+                        condition_id
+                    })
+                    .collect();
+
+                // we check that e is in 0..array.len(), so we check that sum(e == i) == 1
+                // here depending on the size, we could use a proper range check based on bits
+                let range_check = conditions
+                    .iter()
+                    .fold(FlatExpression::Number(T::from(0)), |acc, c| {
+                        FlatExpression::Add(box acc, box c.clone().into())
+                    });
+                statements_flattened.push(FlatStatement::Condition(
+                    FlatExpression::Number(T::from(1)),
+                    range_check,
+                    None,
+                ));
+
+                // now we flatten each primitive value p to sum((e == i) * array[i][p])
+                (0..element_count)
+                    .map(|p| {
+                        conditions.iter().enumerate().fold(
+                            FlatExpression::Number(T::from(0)),
+                            |acc, (i, c)| {
+                                let term = self.use_sym();
+                                statements_flattened.push(FlatStatement::Definition(
+                                    term,
+                                    FlatExpression::Mult(
+                                        box c.clone().into(),
+                                        box flattened[i * element_count + p].clone(),
+                                    ),
+                                ));
+                                FlatExpression::Add(box acc, box term.into())
+                            },
+                        )
+                    })
+                    .collect()
+            }
+        }
+    }
+
+    /// Flattens an unsigned integer expression
+    ///
+    /// # Arguments
+    ///
+    /// * `statements_flattened` - Vector where new flattened statements can be added.
+    /// * `expr` - `UExpression` that will be flattened.
+    ///
+    /// # Postconditions
+    ///
+    /// * `flatten_uint_expression` always returns a linear expression whose value is in `0..2**bitwidth`
+    fn flatten_uint_expression<T: Field>(
+        &mut self,
+        functions_flattened: &Vec<FlatFunction<T>>,
+        statements_flattened: &mut Vec<FlatStatement<T>>,
+        expr: UExpression<'ast, T>,
+    ) -> FlatExpression<T> {
+        let bitwidth = expr.bitwidth();
+
+        match expr.into_inner() {
+            UExpressionInner::Value(v) => {
+                FlatExpression::Number(T::try_from_dec_str(&v.to_string()).unwrap())
+            }
+            UExpressionInner::Identifier(x) => {
+                FlatExpression::Identifier(self.layout.get(&x).unwrap().clone()[0])
+            }
+            UExpressionInner::Add(box left, box right) => {
+                let left_flattened =
+                    self.flatten_uint_expression(functions_flattened, statements_flattened, left);
+                let right_flattened =
+                    self.flatten_uint_expression(functions_flattened, statements_flattened, right);
+
+                // the sum fits in bitwidth + 1 bits, we drop the highest one
+                let bits = self.uint_bits(
+                    statements_flattened,
+                    FlatExpression::Add(box left_flattened, box right_flattened),
+                    bitwidth + 1,
+                );
+
+                Self::recompose(&bits[1..])
+            }
+            UExpressionInner::Sub(box left, box right) => {
+                let left_flattened =
+                    self.flatten_uint_expression(functions_flattened, statements_flattened, left);
+                let right_flattened =
+                    self.flatten_uint_expression(functions_flattened, statements_flattened, right);
+
+                // left - right + 2**bitwidth is positive and fits in bitwidth + 1 bits, we drop the highest one
+                let bits = self.uint_bits(
+                    statements_flattened,
+                    FlatExpression::Add(
+                        box FlatExpression::Sub(box left_flattened, box right_flattened),
+                        box FlatExpression::Number(T::from(2).pow(bitwidth)),
+                    ),
+                    bitwidth + 1,
+                );
+
+                Self::recompose(&bits[1..])
+            }
+            UExpressionInner::Mult(box left, box right) => {
+                let left_flattened =
+                    self.flatten_uint_expression(functions_flattened, statements_flattened, left);
+                let right_flattened =
+                    self.flatten_uint_expression(functions_flattened, statements_flattened, right);
+
+                let product = self.use_sym();
+                statements_flattened.push(FlatStatement::Definition(
+                    product,
+                    FlatExpression::Mult(box left_flattened, box right_flattened),
+                ));
+
+                // the product fits in 2 * bitwidth bits, we keep the lowest bitwidth ones
+                let bits = self.uint_bits(statements_flattened, product.into(), 2 * bitwidth);
+
+                Self::recompose(&bits[bitwidth..])
+            }
+            UExpressionInner::Xor(box left, box right) => self.flatten_bitwise(
+                functions_flattened,
+                statements_flattened,
+                left,
+                right,
+                // x + y - 2xy
+                |x, y, xy| vec![(T::one(), x), (T::one(), y), (T::zero() - T::from(2), xy)],
+            ),
+            UExpressionInner::And(box left, box right) => self.flatten_bitwise(
+                functions_flattened,
+                statements_flattened,
+                left,
+                right,
+                // xy
+                |_, _, xy| vec![(T::one(), xy)],
+            ),
+            UExpressionInner::Or(box left, box right) => self.flatten_bitwise(
+                functions_flattened,
+                statements_flattened,
+                left,
+                right,
+                // x + y - xy
+                |x, y, xy| vec![(T::one(), x), (T::one(), y), (T::zero() - T::one(), xy)],
+            ),
+            UExpressionInner::Not(box e) => {
+                let e_flattened =
+                    self.flatten_uint_expression(functions_flattened, statements_flattened, e);
+
+                // flipping all bits of e is the same as computing 2**bitwidth - 1 - e
+                FlatExpression::Sub(
+                    box FlatExpression::Number(T::from(2).pow(bitwidth) - T::one()),
+                    box e_flattened,
+                )
+            }
+            UExpressionInner::LeftShift(box e, by) => {
+                let e_flattened =
+                    self.flatten_uint_expression(functions_flattened, statements_flattened, e);
+
+                let bits = self.uint_bits(statements_flattened, e_flattened, bitwidth);
+
+                // keep the lowest bitwidth - by bits and append `by` zeroes
+                bits.into_iter().enumerate().skip(by).fold(
+                    FlatExpression::Number(T::zero()),
+                    |acc, (i, b)| {
+                        FlatExpression::Add(
+                            box acc,
+                            box FlatExpression::Mult(
+                                box FlatExpression::Identifier(b),
+                                box FlatExpression::Number(T::from(2).pow(bitwidth - i - 1 + by)),
+                            ),
+                        )
+                    },
+                )
+            }
+            UExpressionInner::RightShift(box e, by) => {
+                let e_flattened =
+                    self.flatten_uint_expression(functions_flattened, statements_flattened, e);
+
+                let bits = self.uint_bits(statements_flattened, e_flattened, bitwidth);
+
+                // keep the highest bitwidth - by bits
+                match by < bitwidth {
+                    true => Self::recompose(&bits[..bitwidth - by]),
+                    false => FlatExpression::Number(T::zero()),
+                }
+            }
+            UExpressionInner::Member(box s, id) => self
+                .flatten_member_expression(functions_flattened, statements_flattened, s, id)[0]
+                .clone(),
+            UExpressionInner::Select(box array, box index) => self.flatten_select_expression(
+                functions_flattened,
+                statements_flattened,
+                array,
+                index,
+            )[0]
+            .clone(),
+            UExpressionInner::IfElse(box condition, box consequence, box alternative) => {
+                let condition_flattened = self.flatten_boolean_expression(
+                    functions_flattened,
+                    statements_flattened,
+                    condition,
+                );
+                let consequence_flattened = self.flatten_uint_expression(
+                    functions_flattened,
+                    statements_flattened,
+                    consequence,
+                );
+                let alternative_flattened = self.flatten_uint_expression(
+                    functions_flattened,
+                    statements_flattened,
+                    alternative,
+                );
+
+                // condition * (consequence - alternative) + alternative
+                let condition_times_difference = self.use_sym();
+                statements_flattened.push(FlatStatement::Definition(
+                    condition_times_difference,
+                    FlatExpression::Mult(
+                        box condition_flattened,
+                        box FlatExpression::Sub(
+                            box consequence_flattened,
+                            box alternative_flattened.clone(),
+                        ),
+                    ),
+                ));
+
+                FlatExpression::Add(
+                    box condition_times_difference.into(),
+                    box alternative_flattened,
+                )
+            }
+            UExpressionInner::FunctionCall(ref id, ref param_expressions) => {
                 let exprs_flattened = self.flatten_function_call(
                     functions_flattened,
                     statements_flattened,
                     id,
-                    vec![Type::FieldElement],
+                    vec![Type::Uint(bitwidth)],
                     param_expressions,
                 );
                 assert!(exprs_flattened.expressions.len() == 1); // outside of MultipleDefinition, FunctionCalls must return a single value
                 exprs_flattened.expressions[0].clone()
             }
-            FieldElementExpression::Select(box array, box index) => {
-                match index {
-                    FieldElementExpression::Number(n) => match array {
-                        FieldElementArrayExpression::Identifier(size, id) => {
-                            assert!(n < T::from(size));
-                            FlatExpression::Identifier(
-                                self.layout.get(&id).unwrap().clone()
-                                    [n.to_dec_string().parse::<usize>().unwrap()],
-                            )
-                        }
-                        FieldElementArrayExpression::Value(size, expressions) => {
-                            assert!(n < T::from(size));
-                            self.flatten_field_expression(
-                                functions_flattened,
-                                statements_flattened,
-                                expressions[n.to_dec_string().parse::<usize>().unwrap()].clone(),
-                            )
-                        }
-                        FieldElementArrayExpression::FunctionCall(..) => {
-                            unimplemented!("please use intermediate variables for now")
-                        }
-                        FieldElementArrayExpression::IfElse(
-                            condition,
-                            consequence,
-                            alternative,
-                        ) => {
-                            // [if cond then [a, b] else [c, d]][1] == if cond then [a, b][1] else [c, d][1]
-                            self.flatten_field_expression(
-                                functions_flattened,
-                                statements_flattened,
-                                FieldElementExpression::IfElse(
-                                    condition,
-                                    box FieldElementExpression::Select(
-                                        consequence,
-                                        box FieldElementExpression::Number(n.clone()),
-                                    ),
-                                    box FieldElementExpression::Select(
-                                        alternative,
-                                        box FieldElementExpression::Number(n),
-                                    ),
-                                ),
-                            )
-                        }
-                    },
-                    e => {
-                        let size = array.size();
-                        // we have array[e] with e an arbitrary expression
-                        // first we check that e is in 0..array.len(), so we check that sum(if e == i then 1 else 0) == 1
-                        // here depending on the size, we could use a proper range check based on bits
-                        let range_check = (0..size)
-                            .map(|i| {
-                                FieldElementExpression::IfElse(
-                                    box BooleanExpression::Eq(
-                                        box e.clone(),
-                                        box FieldElementExpression::Number(T::from(i)),
-                                    ),
-                                    box FieldElementExpression::Number(T::from(1)),
-                                    box FieldElementExpression::Number(T::from(0)),
-                                )
-                            })
-                            .fold(FieldElementExpression::Number(T::from(0)), |acc, e| {
-                                FieldElementExpression::Add(box acc, box e)
-                            });
-
-                        let range_check_statement = TypedStatement::Condition(
-                            FieldElementExpression::Number(T::from(1)).into(),
-                            range_check.into(),
-                        );
-
-                        self.flatten_statement(
-                            functions_flattened,
-                            statements_flattened,
-                            range_check_statement,
-                        );
-
-                        // now we flatten to sum(if e == i then array[i] else 0)
-                        let lookup = (0..size)
-                            .map(|i| {
-                                FieldElementExpression::IfElse(
-                                    box BooleanExpression::Eq(
-                                        box e.clone(),
-                                        box FieldElementExpression::Number(T::from(i)),
-                                    ),
-                                    box match array.clone() {
-                                        FieldElementArrayExpression::Identifier(size, id) => {
-                                            FieldElementExpression::Select(
-                                                box FieldElementArrayExpression::Identifier(
-                                                    size, id,
-                                                ),
-                                                box FieldElementExpression::Number(T::from(i)),
-                                            )
-                                        }
-                                        FieldElementArrayExpression::Value(size, expressions) => {
-                                            assert_eq!(size, expressions.len());
-                                            expressions[i].clone()
-                                        }
-                                        FieldElementArrayExpression::FunctionCall(..) => {
-                                            unimplemented!(
-                                                "please use intermediate variables for now"
-                                            )
-                                        }
-                                        FieldElementArrayExpression::IfElse(
-                                            condition,
-                                            consequence,
-                                            alternative,
-                                        ) => FieldElementExpression::IfElse(
-                                            condition,
-                                            box FieldElementExpression::Select(
-                                                consequence,
-                                                box FieldElementExpression::Number(T::from(i)),
-                                            ),
-                                            box FieldElementExpression::Select(
-                                                alternative,
-                                                box FieldElementExpression::Number(T::from(i)),
-                                            ),
-                                        ),
-                                    },
-                                    box FieldElementExpression::Number(T::from(0)),
-                                )
-                            })
-                            .fold(FieldElementExpression::Number(T::from(0)), |acc, e| {
-                                FieldElementExpression::Add(box acc, box e)
-                            });
-
-                        self.flatten_field_expression(
-                            functions_flattened,
-                            statements_flattened,
-                            lookup,
-                        )
-                    }
-                }
-            }
         }
     }
 
-    fn flatten_field_array_expression<T: Field>(
+    /// Flattens a bitwise operation on two unsigned integers, given a function returning each bit
+    /// of the result as a linear combination of the matching bits `x`, `y` of the operands and their product `xy`
+    fn flatten_bitwise<T: Field, F>(
         &mut self,
         functions_flattened: &Vec<FlatFunction<T>>,
         statements_flattened: &mut Vec<FlatStatement<T>>,
-        expr: FieldElementArrayExpression<'ast, T>,
-    ) -> Vec<FlatExpression<T>> {
-        match expr {
-            FieldElementArrayExpression::Identifier(_, x) => self
-                .layout
-                .get(&x)
-                .unwrap()
-                .iter()
-                .map(|v| FlatExpression::Identifier(v.clone()))
-                .collect(),
-            FieldElementArrayExpression::Value(size, values) => {
-                assert_eq!(size, values.len());
-                values
+        left: UExpression<'ast, T>,
+        right: UExpression<'ast, T>,
+        f: F,
+    ) -> FlatExpression<T>
+    where
+        F: Fn(FlatVariable, FlatVariable, FlatVariable) -> Vec<(T, FlatVariable)>,
+    {
+        let bitwidth = left.bitwidth();
+
+        let left_flattened =
+            self.flatten_uint_expression(functions_flattened, statements_flattened, left);
+        let right_flattened =
+            self.flatten_uint_expression(functions_flattened, statements_flattened, right);
+
+        let left_bits = self.uint_bits(statements_flattened, left_flattened, bitwidth);
+        let right_bits = self.uint_bits(statements_flattened, right_flattened, bitwidth);
+
+        left_bits
+            .into_iter()
+            .zip(right_bits.into_iter())
+            .enumerate()
+            .fold(FlatExpression::Number(T::zero()), |acc, (i, (x, y))| {
+                let xy = self.use_sym();
+                statements_flattened.push(FlatStatement::Definition(
+                    xy,
+                    FlatExpression::Mult(box x.into(), box y.into()),
+                ));
+                let weight = T::from(2).pow(bitwidth - i - 1);
+                f(x, y, xy)
                     .into_iter()
-                    .map(|v| {
-                        self.flatten_field_expression(functions_flattened, statements_flattened, v)
-                    })
-                    .collect()
-            }
-            FieldElementArrayExpression::FunctionCall(size, ref id, ref param_expressions) => {
-                let exprs_flattened = self.flatten_function_call(
-                    functions_flattened,
-                    statements_flattened,
-                    id,
-                    vec![Type::FieldElementArray(size)],
-                    param_expressions,
-                );
-                assert!(exprs_flattened.expressions.len() == size); // outside of MultipleDefinition, FunctionCalls must return a single value
-                exprs_flattened.expressions
-            }
-            FieldElementArrayExpression::IfElse(
-                ref condition,
-                ref consequence,
-                ref alternative,
-            ) => {
-                let size = match consequence.get_type() {
-                    Type::FieldElementArray(n) => n,
-                    _ => unreachable!(),
-                };
-                (0..size)
-                    .map(|i| {
-                        self.flatten_field_expression(
-                            functions_flattened,
-                            statements_flattened,
-                            FieldElementExpression::IfElse(
-                                condition.clone(),
-                                box FieldElementExpression::Select(
-                                    consequence.clone(),
-                                    box FieldElementExpression::Number(T::from(i)),
-                                ),
-                                box FieldElementExpression::Select(
-                                    alternative.clone(),
-                                    box FieldElementExpression::Number(T::from(i)),
-                                ),
+                    .fold(acc, |acc, (coefficient, v)| {
+                        FlatExpression::Add(
+                            box acc,
+                            box FlatExpression::Mult(
+                                box FlatExpression::Identifier(v),
+                                box FlatExpression::Number(coefficient * &weight),
                             ),
                         )
                     })
-                    .collect()
-            }
+            })
+    }
+
+    /// Adds a condition checking that `lhs` equals `rhs`. The left side of a condition has to be
+    /// linear, so if neither side is, the left side is defined as an intermediate variable.
+    fn flatten_condition<T: Field>(
+        &mut self,
+        statements_flattened: &mut Vec<FlatStatement<T>>,
+        lhs: FlatExpression<T>,
+        rhs: FlatExpression<T>,
+        location: Option<SourceLocation>,
+    ) {
+        if lhs.is_linear() {
+            statements_flattened.push(FlatStatement::Condition(lhs, rhs, location));
+        } else if rhs.is_linear() {
+            // swap so that left side is linear
+            statements_flattened.push(FlatStatement::Condition(rhs, lhs, location));
+        } else {
+            // define a variable for the left side so that it is linear
+            let id = self.use_sym();
+            statements_flattened.push(FlatStatement::Definition(id, lhs));
+            statements_flattened.push(FlatStatement::Condition(id.into(), rhs, location));
         }
     }
 
@@ -980,7 +1461,7 @@ impl<'ast> Flattener<'ast> {
                 );
 
                 match expr.get_type() {
-                    Type::FieldElement | Type::Boolean => {
+                    Type::FieldElement | Type::Boolean | Type::Uint(..) => {
                         match assignee {
                             TypedAssignee::Identifier(ref v) => {
                                 let var = self.use_variable(&v)[0];
@@ -1013,7 +1494,7 @@ impl<'ast> Flattener<'ast> {
                                         // first we check that e is in 0..array.len(), so we check that sum(if e == i then 1 else 0) == 1
                                         // here depending on the size, we could use a proper range check based on bits
                                         let size = match array.get_type() {
-                                            Type::FieldElementArray(n) => n,
+                                            Type::Array(array_type) => array_type.size,
                                             _ => panic!("checker should generate array element based on non array")
                                         };
                                         let range_check = (0..size)
@@ -1039,6 +1520,7 @@ impl<'ast> Flattener<'ast> {
                                         let range_check_statement = TypedStatement::Condition(
                                             FieldElementExpression::Number(T::from(1)).into(),
                                             range_check.into(),
+                                            None,
                                         );
 
                                         self.flatten_statement(
@@ -1085,7 +1567,7 @@ impl<'ast> Flattener<'ast> {
                             }
                         }
                     }
-                    Type::FieldElementArray(..) => {
+                    Type::Array(..) | Type::Struct(..) => {
                         let vars = match assignee {
                             TypedAssignee::Identifier(v) => self.use_variable(&v),
                             _ => unimplemented!(),
@@ -1098,7 +1580,12 @@ impl<'ast> Flattener<'ast> {
                     }
                 }
             }
-            TypedStatement::Condition(expr1, expr2) => {
+            TypedStatement::Condition(expr1, expr2, pos) => {
+                let location = pos.map(|position| SourceLocation {
+                    module: self.module.clone(),
+                    position,
+                });
+
                 // flatten expr1 and expr2 to n flattened expressions with n the number of primitive types for expr1
                 // add n conditions to check equality of the n expressions
 
@@ -1117,14 +1604,7 @@ impl<'ast> Flattener<'ast> {
                             ),
                         );
 
-                        if lhs.is_linear() {
-                            statements_flattened.push(FlatStatement::Condition(lhs, rhs));
-                        } else if rhs.is_linear() {
-                            // swap so that left side is linear
-                            statements_flattened.push(FlatStatement::Condition(rhs, lhs));
-                        } else {
-                            unimplemented!()
-                        }
+                        self.flatten_condition(statements_flattened, lhs, rhs, location);
                     }
                     (TypedExpression::Boolean(e1), TypedExpression::Boolean(e2)) => {
                         let (lhs, rhs) = (
@@ -1140,26 +1620,52 @@ impl<'ast> Flattener<'ast> {
                             ),
                         );
 
-                        if lhs.is_linear() {
-                            statements_flattened.push(FlatStatement::Condition(lhs, rhs));
-                        } else if rhs.is_linear() {
-                            // swap so that left side is linear
-                            statements_flattened.push(FlatStatement::Condition(rhs, lhs));
-                        } else {
-                            unimplemented!()
+                        self.flatten_condition(statements_flattened, lhs, rhs, location);
+                    }
+                    (TypedExpression::Uint(e1), TypedExpression::Uint(e2)) => {
+                        let (lhs, rhs) = (
+                            self.flatten_uint_expression(
+                                functions_flattened,
+                                statements_flattened,
+                                e1,
+                            ),
+                            self.flatten_uint_expression(
+                                functions_flattened,
+                                statements_flattened,
+                                e2,
+                            ),
+                        );
+
+                        self.flatten_condition(statements_flattened, lhs, rhs, location);
+                    }
+                    (TypedExpression::Array(e1), TypedExpression::Array(e2)) => {
+                        let (lhs, rhs) = (
+                            self.flatten_array_expression(
+                                functions_flattened,
+                                statements_flattened,
+                                e1,
+                            ),
+                            self.flatten_array_expression(
+                                functions_flattened,
+                                statements_flattened,
+                                e2,
+                            ),
+                        );
+
+                        assert_eq!(lhs.len(), rhs.len());
+
+                        for (l, r) in lhs.into_iter().zip(rhs.into_iter()) {
+                            self.flatten_condition(statements_flattened, l, r, location.clone());
                         }
                     }
-                    (
-                        TypedExpression::FieldElementArray(e1),
-                        TypedExpression::FieldElementArray(e2),
-                    ) => {
+                    (TypedExpression::Struct(e1), TypedExpression::Struct(e2)) => {
                         let (lhs, rhs) = (
-                            self.flatten_field_array_expression(
+                            self.flatten_struct_expression(
                                 functions_flattened,
                                 statements_flattened,
                                 e1,
                             ),
-                            self.flatten_field_array_expression(
+                            self.flatten_struct_expression(
                                 functions_flattened,
                                 statements_flattened,
                                 e2,
@@ -1169,14 +1675,7 @@ impl<'ast> Flattener<'ast> {
                         assert_eq!(lhs.len(), rhs.len());
 
                         for (l, r) in lhs.into_iter().zip(rhs.into_iter()) {
-                            if l.is_linear() {
-                                statements_flattened.push(FlatStatement::Condition(l, r));
-                            } else if r.is_linear() {
-                                // swap so that left side is linear
-                                statements_flattened.push(FlatStatement::Condition(r, l));
-                            } else {
-                                unimplemented!()
-                            }
+                            self.flatten_condition(statements_flattened, l, r, location.clone());
                         }
                     }
                     _ => panic!(
@@ -1222,6 +1721,17 @@ impl<'ast> Flattener<'ast> {
                     }
                 }
             }
+            TypedStatement::Marker(function, position) => {
+                let origin = Origin {
+                    function: function.to_string(),
+                    location: SourceLocation {
+                        module: self.module.clone(),
+                        position,
+                    },
+                };
+                self.origin = Some(origin.clone());
+                statements_flattened.push(FlatStatement::Marker(origin));
+            }
         }
     }
 
@@ -1239,6 +1749,7 @@ impl<'ast> Flattener<'ast> {
         self.layout = HashMap::new();
 
         self.next_var_idx = 0;
+        self.origin = None;
         let mut statements_flattened: Vec<FlatStatement<T>> = Vec::new();
 
         // push parameters
@@ -1293,7 +1804,10 @@ impl<'ast> Flattener<'ast> {
         let vars = match variable.get_type() {
             Type::FieldElement => self.issue_new_variables(1),
             Type::Boolean => self.issue_new_variables(1),
-            Type::FieldElementArray(size) => self.issue_new_variables(size),
+            Type::Uint(..) => self.issue_new_variables(1),
+            t @ Type::Array(..) | t @ Type::Struct(..) => {
+                self.issue_new_variables(t.get_primitive_count())
+            }
         };
 
         self.layout.insert(variable.id.clone(), vars.clone());
@@ -1306,10 +1820,7 @@ impl<'ast> Flattener<'ast> {
         statements: &mut Vec<FlatStatement<T>>,
     ) -> Vec<FlatParameter> {
         let variables = self.use_variable(&parameter.id);
-        match parameter.id.get_type() {
-            Type::Boolean => statements.extend(Self::boolean_constraint(&variables)),
-            _ => {}
-        };
+        self.constrain_type(&parameter.id.get_type(), &variables, statements);
 
         variables
             .into_iter()
@@ -1320,6 +1831,37 @@ impl<'ast> Flattener<'ast> {
             .collect()
     }
 
+    /// Constrains `variables` to hold a valid value of type `ty`
+    fn constrain_type<T: Field>(
+        &mut self,
+        ty: &Type,
+        variables: &[FlatVariable],
+        statements: &mut Vec<FlatStatement<T>>,
+    ) {
+        match ty {
+            Type::Boolean => statements.extend(Self::boolean_constraint(&variables.to_vec())),
+            Type::Uint(bitwidth) => {
+                // decomposing the parameter checks that it is in range
+                self.uint_bits(statements, variables[0].into(), *bitwidth);
+            }
+            Type::Struct(struct_type) => {
+                let mut offset = 0;
+                for member in struct_type.iter() {
+                    let count = member.ty.get_primitive_count();
+                    self.constrain_type(&member.ty, &variables[offset..offset + count], statements);
+                    offset += count;
+                }
+            }
+            Type::Array(array_type) => {
+                let count = array_type.ty.get_primitive_count();
+                for element in variables.chunks(count) {
+                    self.constrain_type(&array_type.ty, element, statements);
+                }
+            }
+            Type::FieldElement => {}
+        };
+    }
+
     fn issue_new_variables(&mut self, count: usize) -> Vec<FlatVariable> {
         (0..count)
             .map(|_| {
@@ -1340,11 +1882,59 @@ impl<'ast> Flattener<'ast> {
                         box FlatExpression::Identifier(*v),
                         box FlatExpression::Identifier(*v),
                     ),
+                    None,
                 )
             })
             .collect()
     }
 
+    /// Decomposes `e` into `bitwidth` bits, most significant first, and constrains the decomposition.
+    /// Fails at witness generation if `e` does not fit in `bitwidth` bits.
+    fn uint_bits<T: Field>(
+        &mut self,
+        statements: &mut Vec<FlatStatement<T>>,
+        e: FlatExpression<T>,
+        bitwidth: usize,
+    ) -> Vec<FlatVariable> {
+        let e = match e.is_linear() {
+            true => e,
+            false => {
+                let id = self.use_sym();
+                statements.push(FlatStatement::Definition(id, e));
+                id.into()
+            }
+        };
+
+        let bits: Vec<FlatVariable> = (0..bitwidth).map(|_| self.use_sym()).collect();
+
+        statements.push(FlatStatement::Directive(DirectiveStatement::new(
+            bits.clone(),
+            Helper::Rust(RustHelper::Bits(bitwidth)),
+            vec![e.clone()],
+        )));
+
+        statements.extend(Self::boolean_constraint(&bits));
+
+        statements.push(FlatStatement::Condition(e, Self::recompose(&bits), None));
+
+        bits
+    }
+
+    /// Returns the linear combination of `bits`, most significant first
+    fn recompose<T: Field>(bits: &[FlatVariable]) -> FlatExpression<T> {
+        bits.iter()
+            .enumerate()
+            .fold(FlatExpression::Number(T::zero()), |acc, (i, b)| {
+                FlatExpression::Add(
+                    box acc,
+                    box FlatExpression::Mult(
+                        box FlatExpression::Identifier(*b),
+                        box FlatExpression::Number(T::from(2).pow(bits.len() - i - 1)),
+                    ),
+                )
+            })
+    }
+
     // create an internal variable. We do not register it in the layout
     fn use_sym(&mut self) -> FlatVariable {
         let var = self.issue_new_variables(1);
@@ -1369,6 +1959,7 @@ mod tests {
     use super::*;
     use crate::types::Signature;
     use crate::types::Type;
+    use crate::types::{StructMember, StructType};
     use zokrates_field::field::FieldPrime;
 
     mod boolean_checks {
@@ -1407,6 +1998,7 @@ mod tests {
                             box FlatExpression::Identifier(FlatVariable::new(0)),
                             box FlatExpression::Identifier(FlatVariable::new(0)),
                         ),
+                        None,
                     ),
                     FlatStatement::Return(FlatExpressionList {
                         expressions: vec![FlatExpression::Identifier(FlatVariable::new(0))],
@@ -1425,6 +2017,139 @@ mod tests {
         }
     }
 
+    mod uint_checks {
+        use super::*;
+        use zokrates_field::field::Pow;
+
+        #[test]
+        fn uint_arg() {
+            // def main(u8 a) -> (u8):
+            //    return a
+            //
+            // -> should flatten to
+            //
+            // def main(_0) -> (1):
+            //    # _1, ..., _8 = Bits(8)(_0)
+            //    _i * _i == _i for i in 1..9
+            //    _0 == sum(_i * 2**(8 - i))
+            //    return _0
+
+            let function: TypedFunction<FieldPrime> = TypedFunction {
+                id: "main",
+                arguments: vec![Parameter::private(Variable::uint("a".into(), 8))],
+                statements: vec![TypedStatement::Return(vec![UExpressionInner::Identifier(
+                    "a".into(),
+                )
+                .annotate(8)
+                .into()])],
+                signature: Signature::new()
+                    .inputs(vec![Type::Uint(8)])
+                    .outputs(vec![Type::Uint(8)]),
+            };
+
+            let bits: Vec<_> = (1..9).map(|i| FlatVariable::new(i)).collect();
+
+            let expected = FlatFunction {
+                id: String::from("main"),
+                arguments: vec![FlatParameter::private(FlatVariable::new(0))],
+                statements: std::iter::once(FlatStatement::Directive(DirectiveStatement::new(
+                    bits.clone(),
+                    Helper::Rust(RustHelper::Bits(8)),
+                    vec![FlatVariable::new(0)],
+                )))
+                .chain(Flattener::boolean_constraint(&bits))
+                .chain(std::iter::once(FlatStatement::Condition(
+                    FlatVariable::new(0).into(),
+                    bits.iter()
+                        .enumerate()
+                        .fold(FlatExpression::Number(FieldPrime::from(0)), |acc, (i, b)| {
+                            FlatExpression::Add(
+                                box acc,
+                                box FlatExpression::Mult(
+                                    box FlatExpression::Identifier(*b),
+                                    box FlatExpression::Number(
+                                        FieldPrime::from(2).pow(7 - i as usize),
+                                    ),
+                                ),
+                            )
+                        }),
+                    None,
+                )))
+                .chain(std::iter::once(FlatStatement::Return(FlatExpressionList {
+                    expressions: vec![FlatExpression::Identifier(FlatVariable::new(0))],
+                })))
+                .collect(),
+                signature: Signature::new()
+                    .inputs(vec![Type::Uint(8)])
+                    .outputs(vec![Type::Uint(8)]),
+            };
+
+            let mut flattener = Flattener::new();
+
+            let flat_function = flattener.flatten_function(&mut vec![], function);
+
+            assert_eq!(flat_function, expected);
+        }
+
+        #[test]
+        fn uint_comparison() {
+            // 32 < 4 with 16 bit operands
+            //
+            // -> should flatten to
+            //
+            // # _0, ..., _16 = Bits(17)(32 - 4 + 2**16)
+            // _i * _i == _i for i in 0..17
+            // 32 - 4 + 2**16 == sum(_i * 2**(16 - i))
+            // 1 - _0
+
+            let mut flattener = Flattener::new();
+
+            let expression: BooleanExpression<FieldPrime> = BooleanExpression::UintLt(
+                box UExpressionInner::Value(32).annotate(16),
+                box UExpressionInner::Value(4).annotate(16),
+            );
+
+            let mut statements_flattened = vec![];
+            let flattened = flattener.flatten_boolean_expression(
+                &mut vec![],
+                &mut statements_flattened,
+                expression,
+            );
+
+            let shifted_difference = FlatExpression::Add(
+                box FlatExpression::Sub(
+                    box FlatExpression::Number(FieldPrime::from(32)),
+                    box FlatExpression::Number(FieldPrime::from(4)),
+                ),
+                box FlatExpression::Number(FieldPrime::from(2).pow(16)),
+            );
+            let bits: Vec<_> = (0..17).map(|i| FlatVariable::new(i)).collect();
+
+            let expected_statements: Vec<_> =
+                std::iter::once(FlatStatement::Directive(DirectiveStatement::new(
+                    bits.clone(),
+                    Helper::Rust(RustHelper::Bits(17)),
+                    vec![shifted_difference.clone()],
+                )))
+                .chain(Flattener::boolean_constraint(&bits))
+                .chain(std::iter::once(FlatStatement::Condition(
+                    shifted_difference,
+                    Flattener::recompose(&bits),
+                    None,
+                )))
+                .collect();
+
+            assert_eq!(statements_flattened, expected_statements);
+            assert_eq!(
+                flattened,
+                FlatExpression::Sub(
+                    box FlatExpression::Number(FieldPrime::from(1)),
+                    box FlatExpression::Identifier(bits[0])
+                )
+            );
+        }
+    }
+
     #[test]
     fn multiple_definition() {
         // def foo()
@@ -2152,6 +2877,7 @@ mod tests {
                 FlatStatement::Condition(
                     FlatExpression::Number(FieldPrime::from(1)),
                     FlatExpression::Mult(box invb0.into(), box b0.into()),
+                    None,
                 ),
                 // execute div
                 FlatStatement::Directive(DirectiveStatement::new(
@@ -2162,6 +2888,7 @@ mod tests {
                 FlatStatement::Condition(
                     five.into(),
                     FlatExpression::Mult(box b0.into(), box sym_0.into()),
+                    None,
                 ),
                 // inputs to second div (res/b)
                 FlatStatement::Definition(sym_1, sym_0.into()),
@@ -2175,6 +2902,7 @@ mod tests {
                 FlatStatement::Condition(
                     FlatExpression::Number(FieldPrime::from(1)),
                     FlatExpression::Mult(box invb1.into(), box b1.into()),
+                    None,
                 ),
                 // execute div
                 FlatStatement::Directive(DirectiveStatement::new(
@@ -2185,6 +2913,7 @@ mod tests {
                 FlatStatement::Condition(
                     sym_1.into(),
                     FlatExpression::Mult(box b1.into(), box sym_2.into()),
+                    None,
                 ),
                 // result
                 FlatStatement::Definition(a, sym_2.into()),
@@ -2201,17 +2930,16 @@ mod tests {
         let mut statements_flattened = vec![];
         let statement = TypedStatement::Definition(
             TypedAssignee::Identifier(Variable::field_array("foo".into(), 3)),
-            FieldElementArrayExpression::Value(
-                3,
-                vec![
-                    FieldElementExpression::Number(FieldPrime::from(1)),
-                    FieldElementExpression::Number(FieldPrime::from(2)),
-                    FieldElementExpression::Number(FieldPrime::from(3)),
-                ],
-            )
+            ArrayExpressionInner::Value(vec![
+                FieldElementExpression::Number(FieldPrime::from(1)).into(),
+                FieldElementExpression::Number(FieldPrime::from(2)).into(),
+                FieldElementExpression::Number(FieldPrime::from(3)).into(),
+            ])
+            .annotate(Type::FieldElement, 3)
             .into(),
         );
-        let expression = FieldElementArrayExpression::Identifier(3, "foo".into());
+        let expression =
+            ArrayExpressionInner::Identifier("foo".into()).annotate(Type::FieldElement, 3);
 
         flattener.flatten_statement(
             &mut functions_flattened,
@@ -2219,7 +2947,7 @@ mod tests {
             statement,
         );
 
-        let expressions = flattener.flatten_field_array_expression(
+        let expressions = flattener.flatten_array_expression(
             &mut functions_flattened,
             &mut statements_flattened,
             expression,
@@ -2244,14 +2972,12 @@ mod tests {
         let mut statements_flattened = vec![];
         let statement = TypedStatement::Definition(
             TypedAssignee::Identifier(Variable::field_array("foo".into(), 3)),
-            FieldElementArrayExpression::Value(
-                3,
-                vec![
-                    FieldElementExpression::Number(FieldPrime::from(1)),
-                    FieldElementExpression::Number(FieldPrime::from(2)),
-                    FieldElementExpression::Number(FieldPrime::from(3)),
-                ],
-            )
+            ArrayExpressionInner::Value(vec![
+                FieldElementExpression::Number(FieldPrime::from(1)).into(),
+                FieldElementExpression::Number(FieldPrime::from(2)).into(),
+                FieldElementExpression::Number(FieldPrime::from(3)).into(),
+            ])
+            .annotate(Type::FieldElement, 3)
             .into(),
         );
 
@@ -2290,19 +3016,18 @@ mod tests {
         let mut statements_flattened = vec![];
         let statement = TypedStatement::Definition(
             TypedAssignee::Identifier(Variable::field_array("foo".into(), 3)),
-            FieldElementArrayExpression::Value(
-                3,
-                vec![
-                    FieldElementExpression::Number(FieldPrime::from(1)),
-                    FieldElementExpression::Number(FieldPrime::from(2)),
-                    FieldElementExpression::Number(FieldPrime::from(3)),
-                ],
-            )
+            ArrayExpressionInner::Value(vec![
+                FieldElementExpression::Number(FieldPrime::from(1)).into(),
+                FieldElementExpression::Number(FieldPrime::from(2)).into(),
+                FieldElementExpression::Number(FieldPrime::from(3)).into(),
+            ])
+            .annotate(Type::FieldElement, 3)
             .into(),
         );
 
         let expression = FieldElementExpression::Select(
-            box FieldElementArrayExpression::Identifier(3, "foo".into()),
+            box ArrayExpressionInner::Identifier("foo".into())
+                .annotate(Type::FieldElement, 3),
             box FieldElementExpression::Number(FieldPrime::from(1)),
         );
 
@@ -2335,14 +3060,12 @@ mod tests {
         let mut statements_flattened = vec![];
         let def = TypedStatement::Definition(
             TypedAssignee::Identifier(Variable::field_array("foo".into(), 3)),
-            FieldElementArrayExpression::Value(
-                3,
-                vec![
-                    FieldElementExpression::Number(FieldPrime::from(1)),
-                    FieldElementExpression::Number(FieldPrime::from(2)),
-                    FieldElementExpression::Number(FieldPrime::from(3)),
-                ],
-            )
+            ArrayExpressionInner::Value(vec![
+                FieldElementExpression::Number(FieldPrime::from(1)).into(),
+                FieldElementExpression::Number(FieldPrime::from(2)).into(),
+                FieldElementExpression::Number(FieldPrime::from(3)).into(),
+            ])
+            .annotate(Type::FieldElement, 3)
             .into(),
         );
 
@@ -2351,16 +3074,19 @@ mod tests {
             FieldElementExpression::Add(
                 box FieldElementExpression::Add(
                     box FieldElementExpression::Select(
-                        box FieldElementArrayExpression::Identifier(3, "foo".into()),
+                        box ArrayExpressionInner::Identifier("foo".into())
+                            .annotate(Type::FieldElement, 3),
                         box FieldElementExpression::Number(FieldPrime::from(0)),
                     ),
                     box FieldElementExpression::Select(
-                        box FieldElementArrayExpression::Identifier(3, "foo".into()),
+                        box ArrayExpressionInner::Identifier("foo".into())
+                            .annotate(Type::FieldElement, 3),
                         box FieldElementExpression::Number(FieldPrime::from(1)),
                     ),
                 ),
                 box FieldElementExpression::Select(
-                    box FieldElementArrayExpression::Identifier(3, "foo".into()),
+                    box ArrayExpressionInner::Identifier("foo".into())
+                        .annotate(Type::FieldElement, 3),
                     box FieldElementExpression::Number(FieldPrime::from(2)),
                 ),
             )
@@ -2404,23 +3130,24 @@ mod tests {
             flattener.load_corelib(&mut functions_flattened);
             let mut statements_flattened = vec![];
 
-            let e = FieldElementArrayExpression::IfElse(
+            let e = ArrayExpressionInner::IfElse(
                 box BooleanExpression::Eq(
                     box FieldElementExpression::Number(FieldPrime::from(1)),
                     box FieldElementExpression::Number(FieldPrime::from(1)),
                 ),
-                box FieldElementArrayExpression::Value(
-                    1,
-                    vec![FieldElementExpression::Number(FieldPrime::from(1))],
-                ),
-                box FieldElementArrayExpression::Value(
-                    1,
-                    vec![FieldElementExpression::Number(FieldPrime::from(3))],
-                ),
-            );
+                box ArrayExpressionInner::Value(vec![
+                    FieldElementExpression::Number(FieldPrime::from(1)).into()
+                ])
+                .annotate(Type::FieldElement, 1),
+                box ArrayExpressionInner::Value(vec![
+                    FieldElementExpression::Number(FieldPrime::from(3)).into()
+                ])
+                .annotate(Type::FieldElement, 1),
+            )
+            .annotate(Type::FieldElement, 1);
 
             (
-                flattener.flatten_field_array_expression(
+                flattener.flatten_array_expression(
                     &mut functions_flattened,
                     &mut statements_flattened,
                     e,
@@ -2459,6 +3186,58 @@ mod tests {
         assert_eq!(with_arrays, without_arrays);
     }
 
+    #[test]
+    fn struct_condition() {
+        // Foo { a: x * y } == Foo { a: z * w }
+
+        let mut flattener = Flattener::new();
+        let mut functions_flattened = vec![];
+        let mut statements_flattened: Vec<FlatStatement<FieldPrime>> = vec![];
+
+        let [x, y, z, w] = [
+            flattener.use_variable(&Variable::field_element("x".into()))[0],
+            flattener.use_variable(&Variable::field_element("y".into()))[0],
+            flattener.use_variable(&Variable::field_element("z".into()))[0],
+            flattener.use_variable(&Variable::field_element("w".into()))[0],
+        ];
+
+        let foo = StructType::new(
+            "Foo".into(),
+            vec![StructMember::new("a".into(), Type::FieldElement)],
+        );
+        let product = |left: &str, right: &str| {
+            StructExpressionInner::Value(vec![FieldElementExpression::Mult(
+                box FieldElementExpression::Identifier(left.into()),
+                box FieldElementExpression::Identifier(right.into()),
+            )
+            .into()])
+            .annotate(foo.clone())
+        };
+
+        let statement =
+            TypedStatement::Condition(product("x", "y").into(), product("z", "w").into(), None);
+
+        flattener.flatten_statement(
+            &mut functions_flattened,
+            &mut statements_flattened,
+            statement,
+        );
+
+        // neither side is linear, so the left side is defined first
+        let sym = FlatVariable::new(4);
+        assert_eq!(
+            statements_flattened,
+            vec![
+                FlatStatement::Definition(sym, FlatExpression::Mult(box x.into(), box y.into())),
+                FlatStatement::Condition(
+                    sym.into(),
+                    FlatExpression::Mult(box z.into(), box w.into()),
+                    None
+                ),
+            ]
+        );
+    }
+
     #[test]
     fn next_variable() {
         let mut flattener = Flattener::new();
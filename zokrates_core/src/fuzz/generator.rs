@@ -0,0 +1,687 @@
+//! Module containing a generator of random well-typed programs
+//!
+//! Generated programs avoid the constructs which the compiler is known to treat differently from
+//! the typed semantics on purpose, such as the constant folding of expressions which would fail at
+//! runtime. To do so, the generator keeps track of which expressions are *varying*, ie depend on the
+//! inputs of the function they are in, so that they cannot be reduced to a constant at compile time:
+//! * divisors, conditions and operands of `&&` and `||` are varying
+//! * comparisons of field elements have at least one varying operand
+//! * exponents and shift amounts are literals, and the base of a power is an identifier
+//! * array elements are only assigned varying expressions at literal indices
+//! * variables which are varying are only ever redefined to varying expressions
+
+use super::rand::Rng;
+use crate::absy::*;
+use zokrates_field::field::Field;
+
+/// Names of the variables of a function, including its parameters
+const VARIABLES: [&'static str; 24] = [
+    "a", "b", "c", "d", "e", "f", "g", "h", "i", "j", "k", "l", "m", "n", "o", "p", "q", "r", "s",
+    "t", "u", "v", "w", "x",
+];
+
+/// Names of the functions called by `main`
+const FUNCTIONS: [&'static str; 3] = ["foo", "bar", "baz"];
+
+/// How many times we try to generate a varying expression before falling back to a simple one
+const ATTEMPTS: usize = 8;
+
+/// Maximum depth of the generated expressions
+const DEPTH: usize = 3;
+
+/// The types used by generated programs
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Ty {
+    FieldElement,
+    Boolean,
+    U8,
+    FieldArray(usize),
+}
+
+impl Ty {
+    fn unresolved(&self) -> UnresolvedType {
+        match *self {
+            Ty::FieldElement => UnresolvedType::FieldElement,
+            Ty::Boolean => UnresolvedType::Boolean,
+            Ty::U8 => UnresolvedType::Uint(8),
+            Ty::FieldArray(size) => UnresolvedType::array(UnresolvedType::FieldElement, size),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Var {
+    id: &'static str,
+    ty: Ty,
+    varying: bool,
+}
+
+#[derive(Debug, Clone)]
+struct Declaration {
+    id: &'static str,
+    inputs: Vec<Ty>,
+    output: Ty,
+}
+
+pub struct Generator<'a, R: Rng + 'a> {
+    rng: &'a mut R,
+    /// Functions which can be called from the function being generated
+    functions: Vec<Declaration>,
+    /// Variables in scope in the function being generated
+    scope: Vec<Var>,
+    /// Number of names used in the function being generated
+    names: usize,
+    /// Line of the next statement
+    line: usize,
+    /// Types of the inputs of `main`
+    inputs: Vec<Ty>,
+}
+
+impl<'a, R: Rng> Generator<'a, R> {
+    pub fn new(rng: &'a mut R) -> Self {
+        Generator {
+            rng,
+            functions: vec![],
+            scope: vec![],
+            names: 0,
+            line: 1,
+            inputs: vec![],
+        }
+    }
+
+    /// Returns a random program whose last function is `main`
+    pub fn program<T: Field>(&mut self) -> Prog<'static, T> {
+        let count = self.rng.gen_range(0, FUNCTIONS.len() + 1);
+
+        let mut functions: Vec<_> = FUNCTIONS[..count]
+            .iter()
+            .map(|id| self.function(*id))
+            .collect();
+        functions.push(self.main());
+
+        Prog {
+            structs: vec![],
+            functions,
+            imports: vec![],
+            imported_functions: vec![],
+        }
+    }
+
+    /// Returns random inputs for the last generated `main`, flattened to field elements
+    pub fn inputs<T: Field>(&mut self) -> Vec<T> {
+        let inputs = self.inputs.clone();
+        inputs
+            .into_iter()
+            .flat_map(|ty| match ty {
+                Ty::FieldElement => vec![self.field_value()],
+                Ty::Boolean => vec![T::from(self.rng.gen_range(0, 2))],
+                Ty::U8 => vec![T::from(self.rng.gen_range(0, 256))],
+                Ty::FieldArray(size) => (0..size).map(|_| self.field_value()).collect(),
+            })
+            .collect()
+    }
+
+    fn field_value<T: Field>(&mut self) -> T {
+        match self.rng.gen_range(0, 10) {
+            0 => T::max_value(),
+            1 => {
+                let mut value = T::zero();
+                for _ in 0..8 {
+                    value = value * T::from(2).pow(32usize) + T::from(self.rng.gen::<u32>());
+                }
+                value
+            }
+            _ => T::from(self.rng.gen_range(0, 4)),
+        }
+    }
+
+    fn main<T: Field>(&mut self) -> FunctionNode<'static, T> {
+        let mut inputs = vec![Ty::FieldElement; self.rng.gen_range(1, 4)];
+        if self.rng.gen() {
+            inputs.push(Ty::Boolean);
+        }
+        if self.rng.gen() {
+            inputs.push(Ty::U8);
+        }
+        if self.rng.gen() {
+            inputs.push(Ty::FieldArray(self.rng.gen_range(2, 4)));
+        }
+        self.rng.shuffle(&mut inputs);
+        self.inputs = inputs.clone();
+
+        let outputs = (0..self.rng.gen_range(1, 3))
+            .map(|_| self.scalar_type())
+            .collect();
+
+        self.function_with("main", inputs, outputs)
+    }
+
+    fn function<T: Field>(&mut self, id: &'static str) -> FunctionNode<'static, T> {
+        // functions which only take arrays are always inlined, the others never are as their arguments are varying
+        let inputs = match self.rng.gen_weighted_bool(3) {
+            true => vec![Ty::FieldArray(self.rng.gen_range(2, 4))],
+            false => {
+                let mut inputs = vec![Ty::FieldElement; self.rng.gen_range(1, 3)];
+                if self.rng.gen() {
+                    inputs.push(self.scalar_type());
+                }
+                self.rng.shuffle(&mut inputs);
+                inputs
+            }
+        };
+        let output = self.scalar_type();
+
+        let function = self.function_with(id, inputs.clone(), vec![output]);
+
+        self.functions.push(Declaration { id, inputs, output });
+
+        function
+    }
+
+    fn function_with<T: Field>(
+        &mut self,
+        id: &'static str,
+        inputs: Vec<Ty>,
+        outputs: Vec<Ty>,
+    ) -> FunctionNode<'static, T> {
+        self.scope = vec![];
+        self.names = 0;
+
+        let arguments = inputs
+            .iter()
+            .map(|ty| {
+                let id = self.declare(*ty, true);
+                let variable = Variable::new(id, ty.unresolved()).into();
+                let parameter = match self.rng.gen() {
+                    true => Parameter::private(variable),
+                    false => Parameter::public(variable),
+                };
+                parameter.into()
+            })
+            .collect();
+
+        let mut statements = vec![];
+        for _ in 0..self.rng.gen_range(1, 7) {
+            statements.extend(self.statement());
+        }
+
+        // outputs of functions other than main are varying, as calls to them are assumed to be
+        let expressions = outputs
+            .iter()
+            .map(|ty| match id {
+                "main" => self.expression(*ty, DEPTH).0,
+                _ => self.varying(*ty, DEPTH),
+            })
+            .collect();
+        statements.push(self.at(Statement::Return(ExpressionList { expressions }.into())));
+
+        Function {
+            id,
+            arguments,
+            statements,
+            signature: UnresolvedSignature::new()
+                .inputs(inputs.iter().map(|ty| ty.unresolved().into()).collect())
+                .outputs(outputs.iter().map(|ty| ty.unresolved().into()).collect()),
+        }
+        .into()
+    }
+
+    fn scalar_type(&mut self) -> Ty {
+        *self
+            .rng
+            .choose(&[Ty::FieldElement, Ty::FieldElement, Ty::Boolean, Ty::U8])
+            .unwrap()
+    }
+
+    /// Adds a variable with a fresh name to the scope and returns its name
+    fn declare(&mut self, ty: Ty, varying: bool) -> &'static str {
+        let id = VARIABLES[self.names];
+        self.names += 1;
+        self.scope.push(Var { id, ty, varying });
+        id
+    }
+
+    fn at<T: Field>(&mut self, statement: Statement<'static, T>) -> StatementNode<'static, T> {
+        self.line += 1;
+        statement.at(self.line, 1, 0)
+    }
+
+    fn statement<T: Field>(&mut self) -> Vec<StatementNode<'static, T>> {
+        let full = self.names == VARIABLES.len();
+
+        match self.rng.gen_range(0, 6) {
+            0 | 1 if !full => {
+                let ty = self.scalar_type();
+                let (e, varying) = self.expression(ty, DEPTH);
+                let id = self.declare(ty, varying);
+                vec![
+                    self.at(Statement::Declaration(
+                        Variable::new(id, ty.unresolved()).into(),
+                    )),
+                    self.at(Statement::Definition(Assignee::Identifier(id).into(), e)),
+                ]
+            }
+            2 if !full && !self.functions.is_empty() => {
+                let function = self.rng.choose(&self.functions).unwrap().clone();
+                let arrays: Vec<_> = function
+                    .inputs
+                    .iter()
+                    .filter_map(|ty| match *ty {
+                        Ty::FieldArray(size) => Some(size),
+                        _ => None,
+                    })
+                    .collect();
+                // arrays can only be passed if one of the right size is in scope
+                if arrays.iter().any(|size| self.array(*size).is_none()) {
+                    return self.assertion();
+                }
+
+                let arguments = function
+                    .inputs
+                    .iter()
+                    .map(|ty| match *ty {
+                        Ty::FieldArray(size) => {
+                            Expression::Identifier(self.array(size).unwrap()).into()
+                        }
+                        ty => self.varying(ty, DEPTH - 1),
+                    })
+                    .collect();
+                let id = self.declare(function.output, true);
+                vec![
+                    self.at(Statement::Declaration(
+                        Variable::new(id, function.output.unresolved()).into(),
+                    )),
+                    self.at(Statement::MultipleDefinition(
+                        vec![Assignee::Identifier(id).into()],
+                        Expression::FunctionCall(function.id.to_string(), arguments).into(),
+                    )),
+                ]
+            }
+            3 if !full => {
+                self.line += 1;
+                let line = self.line;
+                let to = self.rng.gen_range(0, 4);
+
+                // loops only redefine variables, so the loop variable is the last one in scope
+                let id = self.declare(Ty::FieldElement, false);
+                let mut statements = vec![];
+                for _ in 0..self.rng.gen_range(1, 3) {
+                    statements.extend(match self.rng.gen() {
+                        true => self.redefinition(),
+                        false => self.assertion(),
+                    });
+                }
+                self.scope.pop();
+
+                vec![Statement::For(
+                    Variable::field_element(id).into(),
+                    T::from(0),
+                    T::from(to),
+                    statements,
+                )
+                .at(line, 1, 0)]
+            }
+            4 => self.redefinition(),
+            _ => self.assertion(),
+        }
+    }
+
+    /// Redefines a varying variable or an element of an array
+    fn redefinition<T: Field>(&mut self) -> Vec<StatementNode<'static, T>> {
+        let candidates: Vec<_> = self.scope.iter().filter(|v| v.varying).cloned().collect();
+
+        match self.rng.choose(&candidates).cloned() {
+            Some(Var {
+                id,
+                ty: Ty::FieldArray(size),
+                ..
+            }) => {
+                let index = self.rng.gen_range(0, size);
+                let e = self.varying(Ty::FieldElement, DEPTH);
+                vec![self.at(Statement::Definition(
+                    Assignee::ArrayElement(
+                        box Assignee::Identifier(id).into(),
+                        box RangeOrExpression::Expression(
+                            Expression::FieldConstant(T::from(index)).into(),
+                        ),
+                    )
+                    .into(),
+                    e,
+                ))]
+            }
+            Some(Var { id, ty, .. }) => {
+                let e = self.varying(ty, DEPTH);
+                vec![self.at(Statement::Definition(Assignee::Identifier(id).into(), e))]
+            }
+            None => self.assertion(),
+        }
+    }
+
+    fn assertion<T: Field>(&mut self) -> Vec<StatementNode<'static, T>> {
+        let ty = self.scalar_type();
+        let (lhs, rhs) = match self.rng.gen_range(0, 5) {
+            // an assertion which holds unless evaluating its sides fails
+            0..=2 => {
+                let e = self.expression(ty, DEPTH).0;
+                (e.clone(), e)
+            }
+            // an assertion which often holds
+            3 => (
+                self.expression(Ty::Boolean, DEPTH).0,
+                Expression::BooleanConstant(self.rng.gen()).into(),
+            ),
+            _ => (self.expression(ty, DEPTH).0, self.expression(ty, DEPTH).0),
+        };
+        vec![self.at(Statement::Condition(lhs, rhs))]
+    }
+
+    /// Returns the name of a field array of size `size` in scope, if any
+    fn array(&mut self, size: usize) -> Option<&'static str> {
+        let candidates: Vec<_> = self
+            .scope
+            .iter()
+            .filter(|v| v.ty == Ty::FieldArray(size))
+            .map(|v| v.id)
+            .collect();
+        self.rng.choose(&candidates).cloned()
+    }
+
+    /// Returns a random variable of type `ty` in scope, if any, only picking varying ones if `varying` is set
+    fn variable(&mut self, ty: Ty, varying: bool) -> Option<Var> {
+        let candidates: Vec<_> = self
+            .scope
+            .iter()
+            .filter(|v| v.ty == ty && (v.varying || !varying))
+            .cloned()
+            .collect();
+        self.rng.choose(&candidates).cloned()
+    }
+
+    /// Returns a random expression of type `ty` and whether it is varying
+    fn expression<T: Field>(&mut self, ty: Ty, depth: usize) -> (ExpressionNode<'static, T>, bool) {
+        match ty {
+            Ty::FieldElement => self.field_element(depth),
+            Ty::Boolean => self.boolean(depth),
+            Ty::U8 => self.uint(depth),
+            Ty::FieldArray(..) => unreachable!("array expressions are only identifiers"),
+        }
+    }
+
+    /// Returns a random varying expression of type `ty`
+    fn varying<T: Field>(&mut self, ty: Ty, depth: usize) -> ExpressionNode<'static, T> {
+        for _ in 0..ATTEMPTS {
+            let (e, varying) = self.expression(ty, depth);
+            if varying {
+                return e;
+            }
+        }
+        self.varying_leaf(ty)
+    }
+
+    fn varying_leaf<T: Field>(&mut self, ty: Ty) -> ExpressionNode<'static, T> {
+        match ty {
+            // every function has a varying field element or field array in scope
+            Ty::FieldElement => {
+                let arrays: Vec<_> = self
+                    .scope
+                    .iter()
+                    .filter_map(|v| match v.ty {
+                        Ty::FieldArray(size) => Some((v.id, size)),
+                        _ => None,
+                    })
+                    .collect();
+                match self.variable(Ty::FieldElement, true) {
+                    Some(v) if arrays.is_empty() || self.rng.gen() => {
+                        Expression::Identifier(v.id).into()
+                    }
+                    _ => {
+                        let (id, size) = *self
+                            .rng
+                            .choose(&arrays)
+                            .expect("a varying field element should be in scope");
+                        self.select(id, size)
+                    }
+                }
+            }
+            Ty::Boolean => match self.variable(Ty::Boolean, true) {
+                Some(v) => Expression::Identifier(v.id).into(),
+                None => Expression::Eq(
+                    box self.varying_leaf(Ty::FieldElement),
+                    box Expression::FieldConstant(T::from(0)).into(),
+                )
+                .into(),
+            },
+            Ty::U8 => match self.variable(Ty::U8, true) {
+                Some(v) => Expression::Identifier(v.id).into(),
+                None => Expression::IfElse(
+                    box self.varying_leaf(Ty::Boolean),
+                    box Expression::U8Constant(self.rng.gen()).into(),
+                    box Expression::U8Constant(self.rng.gen()).into(),
+                )
+                .into(),
+            },
+            Ty::FieldArray(..) => unreachable!("array expressions are only identifiers"),
+        }
+    }
+
+    /// Selects an element of the array `id` at a literal index
+    fn select<T: Field>(&mut self, id: &'static str, size: usize) -> ExpressionNode<'static, T> {
+        Expression::Select(
+            box Expression::Identifier(id).into(),
+            box RangeOrExpression::Expression(
+                Expression::FieldConstant(T::from(self.rng.gen_range(0, size))).into(),
+            ),
+        )
+        .into()
+    }
+
+    fn field_literal<T: Field>(&mut self) -> T {
+        match self.rng.gen_weighted_bool(10) {
+            true => T::max_value(),
+            false => T::from(self.rng.gen_range(0, 4)),
+        }
+    }
+
+    fn field_element<T: Field>(&mut self, depth: usize) -> (ExpressionNode<'static, T>, bool) {
+        if depth == 0 || self.rng.gen_weighted_bool(4) {
+            return match (
+                self.rng.gen_range(0, 3),
+                self.variable(Ty::FieldElement, false),
+            ) {
+                (0, _) | (_, None) => (
+                    Expression::FieldConstant(self.field_literal()).into(),
+                    false,
+                ),
+                (1, _) => (self.varying_leaf(Ty::FieldElement), true),
+                (_, Some(v)) => (Expression::Identifier(v.id).into(), v.varying),
+            };
+        }
+
+        let depth = depth - 1;
+
+        match self.rng.gen_range(0, 8) {
+            0 => {
+                let ((e1, v1), (e2, v2)) = (self.field_element(depth), self.field_element(depth));
+                (Expression::Add(box e1, box e2).into(), v1 || v2)
+            }
+            1 => {
+                let ((e1, v1), (e2, v2)) = (self.field_element(depth), self.field_element(depth));
+                (Expression::Sub(box e1, box e2).into(), v1 || v2)
+            }
+            2 | 3 => {
+                let ((e1, v1), (e2, v2)) = (self.field_element(depth), self.field_element(depth));
+                (Expression::Mult(box e1, box e2).into(), v1 || v2)
+            }
+            4 => {
+                let (e1, _) = self.field_element(depth);
+                let e2 = self.varying(Ty::FieldElement, depth);
+                (Expression::Div(box e1, box e2).into(), true)
+            }
+            5 => match self.variable(Ty::FieldElement, false) {
+                Some(v) => {
+                    let exponent = T::from(self.rng.gen_range(1, 4));
+                    (
+                        Expression::Pow(
+                            box Expression::Identifier(v.id).into(),
+                            box Expression::FieldConstant(exponent).into(),
+                        )
+                        .into(),
+                        v.varying,
+                    )
+                }
+                None => self.field_element(depth),
+            },
+            6 => {
+                let condition = self.varying(Ty::Boolean, depth);
+                let ((e1, _), (e2, _)) = (self.field_element(depth), self.field_element(depth));
+                (
+                    Expression::IfElse(box condition, box e1, box e2).into(),
+                    true,
+                )
+            }
+            _ => {
+                let arrays: Vec<_> = self
+                    .scope
+                    .iter()
+                    .filter_map(|v| match v.ty {
+                        Ty::FieldArray(size) => Some((v.id, size)),
+                        _ => None,
+                    })
+                    .collect();
+                match self.rng.choose(&arrays).cloned() {
+                    // the index can be out of bounds, in which case both the interpreter and the compiled program fail
+                    Some((id, _)) if self.rng.gen() => {
+                        let index = self.varying(Ty::FieldElement, depth);
+                        (
+                            Expression::Select(
+                                box Expression::Identifier(id).into(),
+                                box RangeOrExpression::Expression(index),
+                            )
+                            .into(),
+                            true,
+                        )
+                    }
+                    Some((id, size)) => (self.select(id, size), true),
+                    None => self.field_element(depth),
+                }
+            }
+        }
+    }
+
+    fn boolean<T: Field>(&mut self, depth: usize) -> (ExpressionNode<'static, T>, bool) {
+        if depth == 0 || self.rng.gen_weighted_bool(4) {
+            return match self.variable(Ty::Boolean, false) {
+                Some(v) if self.rng.gen() => (Expression::Identifier(v.id).into(), v.varying),
+                _ => (Expression::BooleanConstant(self.rng.gen()).into(), false),
+            };
+        }
+
+        let depth = depth - 1;
+
+        match self.rng.gen_range(0, 8) {
+            0 => {
+                let ((e1, v1), (e2, v2)) = (self.field_element(depth), self.field_element(depth));
+                (Expression::Eq(box e1, box e2).into(), v1 || v2)
+            }
+            1 => {
+                let ((e1, v1), (e2, v2)) = (self.uint(depth), self.uint(depth));
+                let e = match self.rng.gen_range(0, 5) {
+                    0 => Expression::Eq(box e1, box e2),
+                    1 => Expression::Lt(box e1, box e2),
+                    2 => Expression::Le(box e1, box e2),
+                    3 => Expression::Gt(box e1, box e2),
+                    _ => Expression::Ge(box e1, box e2),
+                };
+                (e.into(), v1 || v2)
+            }
+            // comparisons of field elements are expensive to execute once compiled, keep them rare
+            2 => {
+                let (e1, v1) = self.field_element(depth);
+                let e2 = match v1 {
+                    true => self.field_element(depth).0,
+                    false => self.varying(Ty::FieldElement, depth),
+                };
+                let e = match self.rng.gen_range(0, 4) {
+                    0 => Expression::Lt(box e1, box e2),
+                    1 => Expression::Le(box e1, box e2),
+                    2 => Expression::Gt(box e1, box e2),
+                    _ => Expression::Ge(box e1, box e2),
+                };
+                (e.into(), true)
+            }
+            3 => {
+                let (e1, e2) = (
+                    self.varying(Ty::Boolean, depth),
+                    self.varying(Ty::Boolean, depth),
+                );
+                (Expression::And(box e1, box e2).into(), true)
+            }
+            4 => {
+                let (e1, e2) = (
+                    self.varying(Ty::Boolean, depth),
+                    self.varying(Ty::Boolean, depth),
+                );
+                (Expression::Or(box e1, box e2).into(), true)
+            }
+            5 | 6 => {
+                let (e, v) = self.boolean(depth);
+                (Expression::Not(box e).into(), v)
+            }
+            _ => {
+                let condition = self.varying(Ty::Boolean, depth);
+                let ((e1, _), (e2, _)) = (self.boolean(depth), self.boolean(depth));
+                (
+                    Expression::IfElse(box condition, box e1, box e2).into(),
+                    true,
+                )
+            }
+        }
+    }
+
+    fn uint<T: Field>(&mut self, depth: usize) -> (ExpressionNode<'static, T>, bool) {
+        if depth == 0 || self.rng.gen_weighted_bool(4) {
+            return match self.variable(Ty::U8, false) {
+                Some(v) if self.rng.gen() => (Expression::Identifier(v.id).into(), v.varying),
+                _ => (Expression::U8Constant(self.rng.gen()).into(), false),
+            };
+        }
+
+        let depth = depth - 1;
+
+        match self.rng.gen_range(0, 10) {
+            0..=5 => {
+                let ((e1, v1), (e2, v2)) = (self.uint(depth), self.uint(depth));
+                let e = match self.rng.gen_range(0, 6) {
+                    0 => Expression::Add(box e1, box e2),
+                    1 => Expression::Sub(box e1, box e2),
+                    2 => Expression::Mult(box e1, box e2),
+                    3 => Expression::BitAnd(box e1, box e2),
+                    4 => Expression::BitOr(box e1, box e2),
+                    _ => Expression::BitXor(box e1, box e2),
+                };
+                (e.into(), v1 || v2)
+            }
+            6 => {
+                let (e, v) = self.uint(depth);
+                (Expression::Not(box e).into(), v)
+            }
+            7 | 8 => {
+                let (e, v) = self.uint(depth);
+                let by = Expression::FieldConstant(T::from(self.rng.gen_range(0, 10))).into();
+                let e = match self.rng.gen() {
+                    true => Expression::LeftShift(box e, box by),
+                    false => Expression::RightShift(box e, box by),
+                };
+                (e.into(), v)
+            }
+            _ => {
+                let condition = self.varying(Ty::Boolean, depth);
+                let ((e1, _), (e2, _)) = (self.uint(depth), self.uint(depth));
+                (
+                    Expression::IfElse(box condition, box e1, box e2).into(),
+                    true,
+                )
+            }
+        }
+    }
+}
@@ -0,0 +1,239 @@
+//! Module containing a differential fuzzer for the compiler passes
+//!
+//! Random well-typed programs are compiled with and without each optional pass, and executed on
+//! random inputs. Each compiled program must behave like the interpreter of the typed program: it
+//! returns the same outputs when the interpreter succeeds, and fails when the interpreter fails.
+//!
+//! The number of programs and the seed of the generator can be set with the `ZOKRATES_FUZZ_CASES`
+//! and `ZOKRATES_FUZZ_SEED` environment variables.
+
+extern crate rand;
+
+mod generator;
+
+use self::generator::Generator;
+use self::rand::{SeedableRng, StdRng};
+use crate::flatten::Flattener;
+use crate::ir;
+use crate::optimizer::{
+    DuplicateOptimizer, LinearOptimizer, RedefinitionOptimizer, TautologyOptimizer,
+};
+use crate::semantics::Checker;
+use crate::static_analysis::{DeadCode, Inliner, Propagator, Unroller};
+use crate::typed_absy::TypedProg;
+use std::env;
+use std::panic::{self, AssertUnwindSafe};
+use zokrates_field::field::{Field, FieldPrime};
+
+/// Number of programs generated by default
+const CASES: usize = 20;
+
+/// Number of inputs each program is executed on
+const INPUTS: usize = 4;
+
+/// The optional passes of the compiler. Unrolling and dead code elimination are always applied, as
+/// flattening requires them.
+#[derive(Debug, Clone, Copy)]
+struct Passes {
+    propagation: bool,
+    inlining: bool,
+    flat_propagation: bool,
+    redefinition: bool,
+    duplicate: bool,
+    linear: bool,
+    tautology: bool,
+}
+
+impl Passes {
+    fn all() -> Self {
+        Passes {
+            propagation: true,
+            inlining: true,
+            flat_propagation: true,
+            redefinition: true,
+            duplicate: true,
+            linear: true,
+            tautology: true,
+        }
+    }
+
+    fn none() -> Self {
+        Passes {
+            propagation: false,
+            inlining: false,
+            flat_propagation: false,
+            redefinition: false,
+            duplicate: false,
+            linear: false,
+            tautology: false,
+        }
+    }
+
+    /// Returns all passes, all passes but one for each of them, and no pass at all
+    fn configurations() -> Vec<Passes> {
+        let all = Passes::all();
+        vec![
+            all,
+            Passes {
+                propagation: false,
+                ..all
+            },
+            Passes {
+                inlining: false,
+                ..all
+            },
+            Passes {
+                flat_propagation: false,
+                ..all
+            },
+            Passes {
+                redefinition: false,
+                ..all
+            },
+            Passes {
+                duplicate: false,
+                ..all
+            },
+            Passes {
+                linear: false,
+                ..all
+            },
+            Passes {
+                tautology: false,
+                ..all
+            },
+            Passes::none(),
+        ]
+    }
+
+    /// Compiles `program` in the same order as `compile`, skipping the passes which are not set
+    fn compile<'ast, T: Field>(&self, program: TypedProg<'ast, T>) -> ir::Prog<T> {
+        let r = Unroller::unroll(program);
+        let r = match self.propagation {
+            true => Propagator::propagate(r),
+            false => r,
+        };
+        let r = match self.inlining {
+            true => Inliner::inline(r),
+            false => r,
+        };
+        let r = match self.propagation {
+            true => Propagator::propagate(r),
+            false => r,
+        };
+        let r = DeadCode::clean(r);
+
+        let r = Flattener::flatten(r, None);
+        let r = match self.flat_propagation {
+            true => r.propagate(),
+            false => r,
+        };
+
+        let r = ir::Prog::from(r);
+        let r = match self.redefinition {
+            true => RedefinitionOptimizer::optimize(r),
+            false => r,
+        };
+        let r = match self.duplicate {
+            true => DuplicateOptimizer::optimize(r),
+            false => r,
+        };
+        let r = match self.linear {
+            true => LinearOptimizer::optimize(r),
+            false => r,
+        };
+        match self.tautology {
+            true => TautologyOptimizer::optimize(r),
+            false => r,
+        }
+    }
+}
+
+/// The outcome of executing a program, where failures are only compared by their existence
+#[derive(Debug)]
+enum Outcome<T> {
+    Success(Vec<T>),
+    Failure(String),
+}
+
+impl<T: Field> Outcome<T> {
+    fn agrees(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Outcome::Success(left), Outcome::Success(right)) => left == right,
+            (Outcome::Failure(..), Outcome::Failure(..)) => true,
+            _ => false,
+        }
+    }
+}
+
+fn env_or(key: &str, default: usize) -> usize {
+    env::var(key)
+        .ok()
+        .and_then(|v| v.parse().ok())
+        .unwrap_or(default)
+}
+
+#[test]
+fn compiled_programs_agree_with_interpreter() {
+    let cases = env_or("ZOKRATES_FUZZ_CASES", CASES);
+    let seed = env_or("ZOKRATES_FUZZ_SEED", 42);
+
+    let mut rng: StdRng = SeedableRng::from_seed(&[seed][..]);
+
+    for case in 0..cases {
+        let mut generator = Generator::new(&mut rng);
+
+        let program = generator.program::<FieldPrime>();
+        let typed = Checker::check(program.clone()).unwrap_or_else(|errors| {
+            panic!(
+                "case {} (seed {}): generated program is ill-typed:\n{}\n\n{}",
+                case,
+                seed,
+                program,
+                errors
+                    .into_iter()
+                    .map(|e| e.to_string())
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            )
+        });
+
+        let compiled: Vec<_> = Passes::configurations()
+            .into_iter()
+            .map(|passes| {
+                let compiled =
+                    panic::catch_unwind(AssertUnwindSafe(|| passes.compile(typed.clone())))
+                        .unwrap_or_else(|_| {
+                            panic!(
+                                "case {} (seed {}): compilation with {:?} panicked:\n{}",
+                                case, seed, passes, typed
+                            )
+                        });
+                (passes, compiled)
+            })
+            .collect();
+
+        for _ in 0..INPUTS {
+            let inputs: Vec<FieldPrime> = generator.inputs();
+
+            let expected = match typed.execute(&inputs) {
+                Ok(outputs) => Outcome::Success(outputs),
+                Err(e) => Outcome::Failure(e.to_string()),
+            };
+
+            for (passes, prog) in &compiled {
+                let outcome = match prog.execute(&inputs) {
+                    Ok(witness) => Outcome::Success(witness.return_values()),
+                    Err(e) => Outcome::Failure(e.to_string()),
+                };
+
+                if !expected.agrees(&outcome) {
+                    panic!(
+                        "case {} (seed {}): compiled with {:?}, the program\n{}\non inputs {:?}\nreturns {:?} instead of {:?}",
+                        case, seed, passes, typed, inputs, outcome, expected
+                    );
+                }
+            }
+        }
+    }
+}
@@ -66,8 +66,12 @@ impl Helper {
         Helper::Wasm(WasmHelper::from_hex(WasmHelper::IDENTITY_WASM))
     }
 
-    pub fn bits() -> Self {
-        Helper::Wasm(WasmHelper::from(WasmHelper::BITS_WASM))
+    pub fn bits<T: Field>() -> Self {
+        match T::get_required_bits() {
+            // the wasm module decomposes into the 254 bits of the BN128 field
+            254 => Helper::Wasm(WasmHelper::from(WasmHelper::BITS_WASM)),
+            bitwidth => Helper::Rust(RustHelper::Bits(bitwidth)),
+        }
     }
 }
 
@@ -77,8 +81,8 @@ impl Helper {
         Helper::Rust(RustHelper::Identity)
     }
 
-    pub fn bits() -> Self {
-        Helper::Rust(RustHelper::Bits)
+    pub fn bits<T: Field>() -> Self {
+        Helper::Rust(RustHelper::Bits(T::get_required_bits()))
     }
 }
 
@@ -7,7 +7,7 @@ use zokrates_field::field::Field;
 pub enum RustHelper {
     Identity,
     ConditionEq,
-    Bits,
+    Bits(usize),
     Div,
     Sha256Round,
 }
@@ -23,7 +23,7 @@ impl Signed for RustHelper {
         match self {
             RustHelper::Identity => (1, 1),
             RustHelper::ConditionEq => (1, 2),
-            RustHelper::Bits => (1, 254),
+            RustHelper::Bits(bitwidth) => (1, *bitwidth),
             RustHelper::Div => (2, 1),
             RustHelper::Sha256Round => (768, 26935),
         }
@@ -38,11 +38,10 @@ impl<T: Field> Executable<T> for RustHelper {
                 true => Ok(vec![T::zero(), T::one()]),
                 false => Ok(vec![T::one(), T::one() / inputs[0].clone()]),
             },
-            RustHelper::Bits => {
+            RustHelper::Bits(bitwidth) => {
                 let mut num = inputs[0].clone();
                 let mut res = vec![];
-                let bits = 254;
-                for i in (0..bits).rev() {
+                for i in (0..*bitwidth).rev() {
                     if T::from(2).pow(i) <= num {
                         num = num - T::from(2).pow(i);
                         res.push(T::one());
@@ -50,10 +49,18 @@ impl<T: Field> Executable<T> for RustHelper {
                         res.push(T::zero());
                     }
                 }
-                assert_eq!(num, T::zero());
-                Ok(res)
+                match num == T::zero() {
+                    true => Ok(res),
+                    false => Err(format!(
+                        "{} does not fit in {} bits",
+                        inputs[0], bitwidth
+                    )),
+                }
             }
-            RustHelper::Div => Ok(vec![inputs[0].clone() / inputs[1].clone()]),
+            RustHelper::Div => match inputs[1].is_zero() {
+                true => Err(String::from("division by zero")),
+                false => Ok(vec![inputs[0].clone() / inputs[1].clone()]),
+            },
             RustHelper::Sha256Round => {
                 let i = &inputs[0..512];
                 let h = &inputs[512..];
@@ -77,7 +84,7 @@ mod tests {
     #[test]
     fn bits_of_one() {
         let inputs = vec![FieldPrime::from(1)];
-        let res = RustHelper::Bits.execute(&inputs).unwrap();
+        let res = RustHelper::Bits(254).execute(&inputs).unwrap();
         assert_eq!(res[253], FieldPrime::from(1));
         for i in 0..252 {
             assert_eq!(res[i], FieldPrime::from(0));
@@ -87,7 +94,7 @@ mod tests {
     #[test]
     fn bits_of_42() {
         let inputs = vec![FieldPrime::from(42)];
-        let res = RustHelper::Bits.execute(&inputs).unwrap();
+        let res = RustHelper::Bits(254).execute(&inputs).unwrap();
         assert_eq!(res[253], FieldPrime::from(0));
         assert_eq!(res[252], FieldPrime::from(1));
         assert_eq!(res[251], FieldPrime::from(0));
@@ -96,4 +103,29 @@ mod tests {
         assert_eq!(res[248], FieldPrime::from(1));
         assert_eq!(res[247], FieldPrime::from(0));
     }
+
+    #[test]
+    fn bits_of_42_on_8_bits() {
+        let inputs = vec![FieldPrime::from(42)];
+        let res = RustHelper::Bits(8).execute(&inputs).unwrap();
+        assert_eq!(
+            res,
+            vec![0, 0, 1, 0, 1, 0, 1, 0]
+                .into_iter()
+                .map(|i| FieldPrime::from(i))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn bits_overflow() {
+        let inputs = vec![FieldPrime::from(256)];
+        assert!(RustHelper::Bits(8).execute(&inputs).is_err());
+    }
+
+    #[test]
+    fn div_by_zero() {
+        let inputs = vec![FieldPrime::from(42), FieldPrime::from(0)];
+        assert!(RustHelper::Div.execute(&inputs).is_err());
+    }
 }
@@ -5,14 +5,16 @@
 //! @date 2018
 
 use crate::absy::*;
-use crate::compile::compile_aux;
+use crate::compile::{compile_aux, compile_module};
 use crate::compile::{CompileErrorInner, CompileErrors};
 use crate::flat_absy::*;
 use crate::parser::Position;
+use std::collections::BTreeMap;
 use std::fmt;
 use std::io;
-use std::io::BufRead;
+use std::path::Path;
 use zokrates_field::field::Field;
+use zokrates_pest_ast as pest;
 
 pub struct CompiledImport<T: Field> {
     pub flat_func: FlatFunction<T>,
@@ -73,6 +75,8 @@ impl From<io::Error> for Error {
 #[derive(PartialEq, Clone, Serialize, Deserialize)]
 pub struct Import {
     source: String,
+    /// The imported function, `main` if none is given
+    symbol: Option<String>,
     alias: Option<String>,
 }
 
@@ -82,6 +86,7 @@ impl Import {
     pub fn new(source: String) -> Import {
         Import {
             source: source,
+            symbol: None,
             alias: None,
         }
     }
@@ -93,6 +98,7 @@ impl Import {
     pub fn new_with_alias(source: String, alias: &String) -> Import {
         Import {
             source: source,
+            symbol: None,
             alias: Some(alias.clone()),
         }
     }
@@ -105,40 +111,173 @@ impl Import {
     pub fn get_source(&self) -> &String {
         &self.source
     }
+
+    pub fn symbol(mut self, symbol: Option<String>) -> Self {
+        self.symbol = symbol;
+        self
+    }
+
+    pub fn get_symbol(&self) -> &Option<String> {
+        &self.symbol
+    }
 }
 
 impl fmt::Display for Import {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.symbol {
+            Some(ref symbol) => write!(f, "from {} import {}", self.source, symbol)?,
+            None => write!(f, "import {}", self.source)?,
+        };
         match self.alias {
-            Some(ref alias) => write!(f, "import {} as {}", self.source, alias),
-            None => write!(f, "import {}", self.source),
+            Some(ref alias) => write!(f, " as {}", alias),
+            None => Ok(()),
         }
     }
 }
 
 impl fmt::Debug for Import {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match self.alias {
-            Some(ref alias) => write!(f, "import(source: {}, alias: {})", self.source, alias),
-            None => write!(f, "import(source: {})", self.source),
+        write!(f, "import(source: {}", self.source)?;
+        if let Some(ref symbol) = self.symbol {
+            write!(f, ", symbol: {}", symbol)?;
         }
+        if let Some(ref alias) = self.alias {
+            write!(f, ", alias: {}", alias)?;
+        }
+        write!(f, ")")
+    }
+}
+
+/// A source of the modules imported by programs
+pub trait Resolver {
+    /// Resolves `import_location`, imported from the module at `current_location`, returning the
+    /// source code of the imported module, its location and the default alias of its `main`
+    fn resolve(
+        &self,
+        current_location: &Option<String>,
+        import_location: &String,
+    ) -> Result<(String, String, String), Error>;
+}
+
+/// Resolves imports from sources held in memory, each module being identified by a path such as
+/// `lib/utils.code`
+///
+/// Paths starting with `./` or `../` are relative to the importing module, other paths are
+/// relative to the root.
+#[derive(Default)]
+pub struct MemoryResolver {
+    sources: BTreeMap<String, String>,
+}
+
+impl MemoryResolver {
+    pub fn new() -> MemoryResolver {
+        MemoryResolver::default()
+    }
+
+    pub fn module<P: Into<String>, S: Into<String>>(mut self, path: P, source: S) -> Self {
+        self.sources.insert(path.into(), source.into());
+        self
     }
 }
 
-pub struct Importer {}
+impl Resolver for MemoryResolver {
+    fn resolve(
+        &self,
+        current_location: &Option<String>,
+        import_location: &String,
+    ) -> Result<(String, String, String), Error> {
+        let relative = import_location.starts_with("./") || import_location.starts_with("../");
+
+        let base = match current_location {
+            Some(location) if relative => location.as_str(),
+            _ => "",
+        };
+
+        let mut path: Vec<&str> = vec![];
+
+        for component in base.split('/').chain(import_location.split('/')) {
+            match component {
+                "" | "." => {}
+                ".." => {
+                    if path.pop().is_none() {
+                        return Err(Error::new(format!(
+                            "Import {} is outside of the root",
+                            import_location
+                        )));
+                    }
+                }
+                c => path.push(c),
+            }
+        }
+
+        let not_found = || Error::new(format!("Module {} not found", import_location));
+
+        let source = self.sources.get(&path.join("/")).ok_or_else(not_found)?;
+        let (name, location) = path.split_last().ok_or_else(not_found)?;
+        let alias = Path::new(name).file_stem().unwrap().to_string_lossy();
+
+        Ok((source.clone(), location.join("/"), alias.to_string()))
+    }
+}
+
+/// Tries resolvers in order, returning the first module found or the error of the last resolver
+#[derive(Default)]
+pub struct ChainResolver<'a> {
+    resolvers: Vec<Box<dyn Resolver + 'a>>,
+}
+
+impl<'a> ChainResolver<'a> {
+    pub fn new() -> ChainResolver<'a> {
+        ChainResolver::default()
+    }
+
+    pub fn resolver<R: Resolver + 'a>(mut self, resolver: R) -> Self {
+        self.resolvers.push(Box::new(resolver));
+        self
+    }
+}
+
+impl<'a> Resolver for ChainResolver<'a> {
+    fn resolve(
+        &self,
+        current_location: &Option<String>,
+        import_location: &String,
+    ) -> Result<(String, String, String), Error> {
+        let mut error = Error::new(format!("No resolver for {}", import_location));
+
+        for resolver in &self.resolvers {
+            match resolver.resolve(current_location, import_location) {
+                Ok(module) => return Ok(module),
+                Err(e) => error = e,
+            }
+        }
+
+        Err(error)
+    }
+}
+
+pub struct Importer {
+    /// Whether imported modules are compiled for profiling
+    profile: bool,
+}
 
 impl Importer {
     pub fn new() -> Importer {
-        Importer {}
+        Importer { profile: false }
+    }
+
+    pub fn profile(mut self, profile: bool) -> Self {
+        self.profile = profile;
+        self
     }
 
     // Inject dependencies declared for `destination`
     // The lifetime of the Program before injection outlives the lifetime after
-    pub fn apply_imports<'before, 'after, T: Field, S: BufRead, E: Into<Error>>(
+    pub fn apply_imports<'before, 'after, T: Field>(
         &self,
         destination: Prog<'before, T>,
         location: Option<String>,
-        resolve_option: Option<fn(&Option<String>, &String) -> Result<(S, String, String), E>>,
+        resolver: Option<&dyn Resolver>,
     ) -> Result<Prog<'after, T>, CompileErrors>
     where
         'before: 'after,
@@ -148,6 +287,24 @@ impl Importer {
         for import in destination.imports.iter() {
             let pos = import.pos();
             let import = &import.value;
+            let embedded =
+                import.source.starts_with("BELLMAN") || import.source.starts_with("PACKING");
+            // embedded functions are modules with a single `main`
+            match import.symbol {
+                Some(ref symbol) if embedded && symbol != "main" => {
+                    return Err(CompileErrorInner::ImportError(
+                        Error::new(format!(
+                            "Function {} not found in {}",
+                            symbol, import.source
+                        ))
+                        .with_pos(Some(pos)),
+                    )
+                    .with_context(&location)
+                    .into());
+                }
+                _ => {}
+            }
+
             // handle the case of special bellman and packing imports
             if import.source.starts_with("BELLMAN") {
                 match import.source.as_ref() {
@@ -197,23 +354,54 @@ impl Importer {
                 }
             } else {
                 // to resolve imports, we need a resolver
-                match resolve_option {
-                    Some(resolve) => match resolve(&location, &import.source) {
-                        Ok((mut reader, location, auto_alias)) => {
-                            let compiled = compile_aux(&mut reader, Some(location), resolve_option)
-                                .map_err(|e| e.with_context(Some(import.source.clone())))?;
-                            let alias = match import.alias {
-                                Some(ref alias) => alias.clone(),
-                                None => auto_alias,
+                match resolver {
+                    Some(resolver) => match resolver.resolve(&location, &import.source) {
+                        Ok((source, module_location, auto_alias)) => {
+                            let compiled = match import.symbol {
+                                None => compile_aux(
+                                    &mut source.as_bytes(),
+                                    Some(module_location),
+                                    Some(resolver),
+                                    self.profile,
+                                ),
+                                Some(ref symbol) => {
+                                    let ast = pest::generate_ast(&source).map_err(|e| {
+                                        CompileErrors::from(
+                                            CompileErrorInner::from(e)
+                                                .with_context(&Some(import.source.clone())),
+                                        )
+                                    })?;
+                                    // compile the imported function in place of `main`
+                                    let module =
+                                        with_entry_point(Prog::from(ast), symbol, &import.source)
+                                            .map_err(|e| {
+                                            CompileErrors::from(
+                                                CompileErrorInner::ImportError(
+                                                    e.with_pos(Some(pos)),
+                                                )
+                                                .with_context(&location),
+                                            )
+                                        })?;
+                                    compile_module(
+                                        module,
+                                        Some(module_location),
+                                        Some(resolver),
+                                        self.profile,
+                                    )
+                                }
+                            }
+                            .map_err(|e| e.with_context(Some(import.source.clone())))?;
+                            let alias = match (&import.alias, &import.symbol) {
+                                (Some(alias), _) => alias.clone(),
+                                (None, Some(symbol)) => symbol.clone(),
+                                (None, None) => auto_alias,
                             };
                             origins.push(CompiledImport::new(compiled, alias));
                         }
                         Err(err) => {
-                            return Err(CompileErrorInner::ImportError(
-                                err.into().with_pos(Some(pos)),
-                            )
-                            .with_context(&location)
-                            .into());
+                            return Err(CompileErrorInner::ImportError(err.with_pos(Some(pos)))
+                                .with_context(&location)
+                                .into());
                         }
                     },
                     None => {
@@ -229,12 +417,48 @@ impl Importer {
 
         Ok(Prog {
             imports: vec![],
+            structs: destination.clone().structs,
             functions: destination.clone().functions,
             imported_functions: origins.into_iter().map(|o| o.flat_func).collect(),
         })
     }
 }
 
+/// Makes the function `symbol` of `program` its `main`, so that compiling the program compiles
+/// this function. The other functions are kept as `symbol` may call them, except the original
+/// `main` which would clash with the new one.
+fn with_entry_point<'ast, T: Field>(
+    mut program: Prog<'ast, T>,
+    symbol: &str,
+    source: &str,
+) -> Result<Prog<'ast, T>, Error> {
+    let mut candidates = program.functions.iter().filter(|f| f.value.id == symbol);
+
+    let mut main = match (candidates.next(), candidates.next()) {
+        (Some(function), None) => function.clone(),
+        (None, _) => {
+            return Err(Error::new(format!(
+                "Function {} not found in {}",
+                symbol, source
+            )))
+        }
+        (Some(_), Some(_)) => {
+            return Err(Error::new(format!(
+                "Function {} is declared more than once in {}, it cannot be imported",
+                symbol, source
+            )))
+        }
+    };
+
+    if symbol != "main" {
+        main.value.id = "main";
+        program.functions.retain(|f| f.value.id != "main");
+        program.functions.push(main);
+    }
+
+    Ok(program)
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -246,6 +470,7 @@ mod tests {
             Import::new("./foo/bar/baz.code".to_string()),
             Import {
                 source: String::from("./foo/bar/baz.code"),
+                symbol: None,
                 alias: None,
             }
         );
@@ -257,8 +482,83 @@ mod tests {
             Import::new_with_alias("./foo/bar/baz.code".to_string(), &"myalias".to_string()),
             Import {
                 source: String::from("./foo/bar/baz.code"),
+                symbol: None,
                 alias: Some("myalias".to_string()),
             }
         );
     }
+
+    #[test]
+    fn create_with_symbol() {
+        let import = Import::new("./foo/bar/baz.code".to_string())
+            .symbol(Some("hash".to_string()))
+            .alias(Some("h".to_string()));
+        assert_eq!(import.get_symbol(), &Some("hash".to_string()));
+        assert_eq!(
+            format!("{}", import),
+            "from ./foo/bar/baz.code import hash as h"
+        );
+    }
+
+    #[test]
+    fn memory_resolver() {
+        let resolver = MemoryResolver::new()
+            .module("main.code", "<main>")
+            .module("lib/hash.code", "<hash>")
+            .module("lib/utils.code", "<utils>");
+
+        // relative to the importing module
+        assert_eq!(
+            resolver.resolve(&Some(String::from("lib")), &String::from("./hash.code")),
+            Ok((
+                String::from("<hash>"),
+                String::from("lib"),
+                String::from("hash")
+            ))
+        );
+        assert_eq!(
+            resolver.resolve(&Some(String::from("lib")), &String::from("../main.code")),
+            Ok((String::from("<main>"), String::new(), String::from("main")))
+        );
+        // relative to the root
+        assert_eq!(
+            resolver.resolve(&Some(String::from("lib")), &String::from("lib/utils.code")),
+            Ok((
+                String::from("<utils>"),
+                String::from("lib"),
+                String::from("utils")
+            ))
+        );
+
+        assert!(resolver
+            .resolve(&Some(String::new()), &String::from("../main.code"))
+            .is_err());
+        assert!(resolver
+            .resolve(&Some(String::from("lib")), &String::from("./main.code"))
+            .is_err());
+    }
+
+    #[test]
+    fn chain_resolver() {
+        let resolver = ChainResolver::new()
+            .resolver(MemoryResolver::new().module("a.code", "<first a>"))
+            .resolver(
+                MemoryResolver::new()
+                    .module("a.code", "<second a>")
+                    .module("b.code", "<second b>"),
+            );
+
+        let source = |path: &str| {
+            resolver
+                .resolve(&None, &String::from(path))
+                .map(|(source, _, _)| source)
+        };
+
+        assert_eq!(source("a.code"), Ok(String::from("<first a>")));
+        assert_eq!(source("b.code"), Ok(String::from("<second b>")));
+        assert_eq!(source("c.code"), Err(Error::new("Module c.code not found")));
+        assert!(ChainResolver::new()
+            .resolve(&None, &String::from("a.code"))
+            .is_err());
+    }
 }
@@ -42,6 +42,7 @@ pub fn fold_program<T: Field, F: Folder<T>>(f: &mut F, p: Prog<T>) -> Prog<T> {
     Prog {
         main: f.fold_function(p.main),
         private: p.private,
+        signature: p.signature,
     }
 }
 
@@ -86,22 +87,34 @@ pub fn fold_directive<T: Field, F: Folder<T>>(f: &mut F, ds: Directive<T>) -> Di
 }
 
 pub fn fold_function<T: Field, F: Folder<T>>(f: &mut F, fun: Function<T>) -> Function<T> {
+    let arguments = fun
+        .arguments
+        .into_iter()
+        .map(|a| f.fold_argument(a))
+        .collect();
+
+    // the statements a statement is folded into keep its source location and origin
+    let mut statements = vec![];
+    let mut source_map = SourceMap::default();
+    for (index, s) in fun.statements.into_iter().enumerate() {
+        let folded = f.fold_statement(s);
+        source_map.extend_from(
+            &fun.source_map,
+            index,
+            statements.len()..statements.len() + folded.len(),
+        );
+        statements.extend(folded);
+    }
+
     Function {
-        arguments: fun
-            .arguments
-            .into_iter()
-            .map(|a| f.fold_argument(a))
-            .collect(),
-        statements: fun
-            .statements
-            .into_iter()
-            .flat_map(|s| f.fold_statement(s))
-            .collect(),
+        arguments,
+        statements,
         returns: fun
             .returns
             .into_iter()
             .map(|v| f.fold_variable(v))
             .collect(),
+        source_map,
         ..fun
     }
 }
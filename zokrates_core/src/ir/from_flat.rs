@@ -1,6 +1,6 @@
 use crate::flat_absy::{FlatExpression, FlatFunction, FlatProg, FlatStatement, FlatVariable};
 use crate::helpers;
-use crate::ir::{Directive, Function, LinComb, Prog, QuadComb, Statement};
+use crate::ir::{Directive, Function, LinComb, Prog, QuadComb, SourceMap, Statement};
 use num::Zero;
 use zokrates_field::field::Field;
 
@@ -15,6 +15,36 @@ impl<T: Field> From<FlatFunction<T>> for Function<T> {
             })
             .next()
             .unwrap();
+
+        // keep track of the assertions the statements come from, as well as their origin if
+        // the program was marked for profiling
+        let mut source_map = SourceMap::default();
+        let mut origin = None;
+        let mut statements: Vec<Statement<T>> = vec![];
+
+        for s in flat_function.statements {
+            match s {
+                FlatStatement::Return(..) => {}
+                FlatStatement::Marker(o) => origin = Some(o),
+                s => {
+                    if let FlatStatement::Condition(_, _, Some(ref location)) = s {
+                        source_map.insert(statements.len(), location.clone());
+                    }
+                    if let Some(ref origin) = origin {
+                        source_map.insert_origin(statements.len(), origin.clone());
+                    }
+                    statements.push(s.into());
+                }
+            }
+        }
+
+        // the return statement comes last, so the constraints binding the outputs keep its origin
+        if let Some(ref origin) = origin {
+            for index in statements.len()..statements.len() + return_expressions.len() {
+                source_map.insert_origin(index, origin.clone());
+            }
+        }
+
         Function {
             id: flat_function.id,
             arguments: flat_function.arguments.into_iter().map(|p| p.id).collect(),
@@ -23,13 +53,8 @@ impl<T: Field> From<FlatFunction<T>> for Function<T> {
                 .enumerate()
                 .map(|(index, _)| FlatVariable::public(index))
                 .collect(),
-            statements: flat_function
-                .statements
+            statements: statements
                 .into_iter()
-                .filter_map(|s| match s {
-                    FlatStatement::Return(..) => None,
-                    s => Some(s.into()),
-                })
                 .chain(
                     return_expressions
                         .into_iter()
@@ -42,6 +67,7 @@ impl<T: Field> From<FlatFunction<T>> for Function<T> {
                         }),
                 )
                 .collect(),
+            source_map,
         }
     }
 }
@@ -73,9 +99,16 @@ impl<T: Field> From<FlatProg<T>> for Prog<T> {
         // get the interface of the program, ie which inputs are private and public
         let private = main.arguments.iter().map(|p| p.private).collect();
 
+        // keep the signature of the main function to know how to interpret its inputs and outputs
+        let signature = main.signature.clone();
+
         let main = main.into();
 
-        Prog { private, main }
+        Prog {
+            private,
+            main,
+            signature,
+        }
     }
 }
 
@@ -103,7 +136,7 @@ impl<T: Field> From<FlatExpression<T>> for LinComb<T> {
 impl<T: Field> From<FlatStatement<T>> for Statement<T> {
     fn from(flat_statement: FlatStatement<T>) -> Statement<T> {
         match flat_statement {
-            FlatStatement::Condition(linear, quadratic) => match quadratic {
+            FlatStatement::Condition(linear, quadratic, _) => match quadratic {
                 FlatExpression::Mult(box lhs, box rhs) => Statement::Constraint(
                     QuadComb::from_linear_combinations(lhs.into(), rhs.into()),
                     linear.into(),
@@ -118,7 +151,7 @@ impl<T: Field> From<FlatStatement<T>> for Statement<T> {
                 e => Statement::Constraint(LinComb::from(e).into(), var.into()),
             },
             FlatStatement::Directive(ds) => Statement::Directive(ds.into()),
-            _ => panic!("return should be handled at the function level"),
+            _ => panic!("returns and markers should be handled at the function level"),
         }
     }
 }
@@ -136,6 +169,9 @@ impl<T: Field> From<helpers::DirectiveStatement<T>> for Directive<T> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::flat_absy::{FlatExpressionList, FlatParameter, SourceLocation};
+    use crate::parser::Position;
+    use crate::types::{Signature, Type};
     use zokrates_field::field::FieldPrime;
 
     #[test]
@@ -209,4 +245,48 @@ mod tests {
             + LinComb::summand(21, FlatVariable::new(21));
         assert_eq!(LinComb::from(add), expected);
     }
+
+    #[test]
+    fn source_locations() {
+        // def main(x):
+        //   y = x
+        //   y == x * x
+        //   return y
+        let location = SourceLocation {
+            module: Some(String::from("main.code")),
+            position: Position { line: 3, col: 3 },
+        };
+
+        let flat_function: FlatFunction<FieldPrime> = FlatFunction {
+            id: String::from("main"),
+            arguments: vec![FlatParameter::private(FlatVariable::new(0))],
+            statements: vec![
+                FlatStatement::Definition(
+                    FlatVariable::new(1),
+                    FlatExpression::Identifier(FlatVariable::new(0)),
+                ),
+                FlatStatement::Condition(
+                    FlatExpression::Identifier(FlatVariable::new(1)),
+                    FlatExpression::Mult(
+                        box FlatExpression::Identifier(FlatVariable::new(0)),
+                        box FlatExpression::Identifier(FlatVariable::new(0)),
+                    ),
+                    Some(location.clone()),
+                ),
+                FlatStatement::Return(FlatExpressionList {
+                    expressions: vec![FlatExpression::Identifier(FlatVariable::new(1))],
+                }),
+            ],
+            signature: Signature::new()
+                .inputs(vec![Type::FieldElement])
+                .outputs(vec![Type::FieldElement]),
+        };
+
+        let function = Function::from(flat_function);
+
+        assert_eq!(function.statements.len(), 3);
+        assert_eq!(function.source_map.get(0), None);
+        assert_eq!(function.source_map.get(1), Some(&location));
+        assert_eq!(function.source_map.get(2), None);
+    }
 }
@@ -1,7 +1,7 @@
 use crate::flat_absy::flat_variable::FlatVariable;
+use crate::flat_absy::SourceLocation;
 use crate::helpers::Executable;
 use crate::ir::{LinComb, Prog, QuadComb, Statement, Witness};
-use std::collections::BTreeMap;
 use std::fmt;
 use zokrates_field::field::Field;
 
@@ -11,13 +11,14 @@ impl<T: Field> Prog<T> {
     pub fn execute<U: Into<T> + Clone>(&self, inputs: &Vec<U>) -> ExecutionResult<T> {
         let main = &self.main;
         self.check_inputs(&inputs)?;
-        let mut witness = BTreeMap::new();
+        // most statements set a variable
+        let mut witness = Witness::with_capacity(1 + main.arguments.len() + main.statements.len());
         witness.insert(FlatVariable::one(), T::one());
         for (arg, value) in main.arguments.iter().zip(inputs.iter()) {
             witness.insert(arg.clone(), value.clone().into());
         }
 
-        for statement in &main.statements {
+        for (index, statement) in main.statements.iter().enumerate() {
             match statement {
                 Statement::Constraint(quad, lin) => match lin.is_assignee(&witness) {
                     true => {
@@ -31,6 +32,7 @@ impl<T: Field> Prog<T> {
                             return Err(Error::UnsatisfiedConstraint {
                                 left: lhs_value.to_dec_string(),
                                 right: rhs_value.to_dec_string(),
+                                location: main.source_map.get(index).cloned(),
                             });
                         }
                     }
@@ -54,7 +56,7 @@ impl<T: Field> Prog<T> {
             }
         }
 
-        Ok(Witness(witness))
+        Ok(witness)
     }
 
     fn check_inputs<U>(&self, inputs: &Vec<U>) -> Result<(), Error> {
@@ -70,7 +72,7 @@ impl<T: Field> Prog<T> {
 }
 
 impl<T: Field> LinComb<T> {
-    fn evaluate(&self, witness: &BTreeMap<FlatVariable, T>) -> Result<T, ()> {
+    fn evaluate(&self, witness: &Witness<T>) -> Result<T, ()> {
         self.0
             .iter()
             .map(|(var, mult)| witness.get(var).map(|v| v.clone() * mult).ok_or(())) // get each term
@@ -78,15 +80,15 @@ impl<T: Field> LinComb<T> {
             .map(|v| v.iter().fold(T::from(0), |acc, t| acc + t)) // return the sum
     }
 
-    fn is_assignee<U>(&self, witness: &BTreeMap<FlatVariable, U>) -> bool {
+    fn is_assignee(&self, witness: &Witness<T>) -> bool {
         self.0.iter().count() == 1
             && self.0.iter().next().unwrap().1 == T::from(1)
-            && !witness.contains_key(&self.0.iter().next().unwrap().0)
+            && !witness.contains(&self.0.iter().next().unwrap().0)
     }
 }
 
 impl<T: Field> QuadComb<T> {
-    pub fn evaluate(&self, witness: &BTreeMap<FlatVariable, T>) -> Result<T, ()> {
+    pub fn evaluate(&self, witness: &Witness<T>) -> Result<T, ()> {
         let left = self.left.evaluate(&witness)?;
         let right = self.right.evaluate(&witness)?;
         Ok(left * right)
@@ -95,7 +97,11 @@ impl<T: Field> QuadComb<T> {
 
 #[derive(PartialEq, Serialize, Deserialize)]
 pub enum Error {
-    UnsatisfiedConstraint { left: String, right: String },
+    UnsatisfiedConstraint {
+        left: String,
+        right: String,
+        location: Option<SourceLocation>,
+    },
     Solver,
     WrongInputCount { expected: usize, received: usize },
 }
@@ -106,7 +112,13 @@ impl fmt::Display for Error {
             Error::UnsatisfiedConstraint {
                 ref left,
                 ref right,
-            } => write!(f, "Expected {} to equal {}", left, right),
+                ref location,
+            } => match location {
+                Some(location) => {
+                    write!(f, "Expected {} to equal {} at {}", left, right, location)
+                }
+                None => write!(f, "Expected {} to equal {}", left, right),
+            },
             Error::Solver => write!(f, ""),
             Error::WrongInputCount { expected, received } => write!(
                 f,
@@ -1,6 +1,7 @@
 use crate::flat_absy::flat_parameter::FlatParameter;
 use crate::flat_absy::FlatVariable;
 use crate::helpers::Helper;
+use crate::types::Signature;
 use std::fmt;
 use zokrates_field::field::Field;
 
@@ -8,12 +9,20 @@ mod expression;
 pub mod folder;
 mod from_flat;
 mod interpreter;
+mod profile;
+mod r1cs;
+mod soundness;
+mod source_map;
 mod witness;
 
 use self::expression::QuadComb;
 pub use self::expression::{CanonicalLinComb, LinComb};
 
 pub use self::interpreter::{Error, ExecutionResult};
+pub use self::profile::{Cost, FunctionProfile, LineProfile, Profile};
+pub use self::r1cs::{R1cs, Wires};
+pub use self::soundness::Underconstrained;
+pub use self::source_map::SourceMap;
 pub use self::witness::Witness;
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
@@ -74,6 +83,8 @@ pub struct Function<T: Field> {
     pub statements: Vec<Statement<T>>,
     pub arguments: Vec<FlatVariable>,
     pub returns: Vec<FlatVariable>,
+    /// The assertions of the source code the statements check
+    pub source_map: SourceMap,
 }
 
 impl<T: Field> fmt::Display for Function<T> {
@@ -90,7 +101,11 @@ impl<T: Field> fmt::Display for Function<T> {
             self.returns.len(),
             self.statements
                 .iter()
-                .map(|s| format!("\t{}", s))
+                .enumerate()
+                .map(|(index, s)| match self.source_map.get(index) {
+                    Some(location) => format!("\t{} // {}", s, location),
+                    None => format!("\t{}", s),
+                })
                 .collect::<Vec<_>>()
                 .join("\n"),
             self.returns
@@ -106,6 +121,7 @@ impl<T: Field> fmt::Display for Function<T> {
 pub struct Prog<T: Field> {
     pub main: Function<T>,
     pub private: Vec<bool>,
+    pub signature: Signature,
 }
 
 impl<T: Field> Prog<T> {
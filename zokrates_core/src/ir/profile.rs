@@ -0,0 +1,233 @@
+//! Module containing the breakdown of the cost of a program by origin
+//!
+//! The statements of a program compiled for profiling know the function and the line of the
+//! source code they were generated from, before any inlining. The cost of a function only includes
+//! the statements generated by its own body: the statements generated by the functions it calls
+//! are counted in these functions.
+
+use crate::flat_absy::Origin;
+use crate::ir::{Prog, Statement};
+use std::collections::BTreeMap;
+use std::fmt;
+use zokrates_field::field::Field;
+
+/// The number of constraints and directives some statements translate to
+#[derive(Debug, Serialize, Clone, PartialEq, Default)]
+pub struct Cost {
+    pub constraints: usize,
+    pub directives: usize,
+}
+
+impl Cost {
+    fn add<T: Field>(&mut self, statement: &Statement<T>) {
+        match statement {
+            Statement::Constraint(..) => self.constraints += 1,
+            Statement::Directive(..) => self.directives += 1,
+        }
+    }
+}
+
+/// The cost of the statements generated by the body of a function
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct FunctionProfile {
+    pub function: String,
+    #[serde(flatten)]
+    pub cost: Cost,
+}
+
+/// The cost of the statements generated by a line of the source code
+#[derive(Debug, Serialize, Clone, PartialEq)]
+pub struct LineProfile {
+    /// Path of the module the line is in, if known
+    pub module: Option<String>,
+    pub line: usize,
+    /// The function the line is in
+    pub function: String,
+    #[serde(flatten)]
+    pub cost: Cost,
+}
+
+/// The cost of a program broken down by function and by line, from the most to the least
+/// expensive. Statements whose origin is unknown are only counted in the total.
+#[derive(Debug, Serialize, Clone, PartialEq, Default)]
+pub struct Profile {
+    pub total: Cost,
+    pub functions: Vec<FunctionProfile>,
+    pub lines: Vec<LineProfile>,
+}
+
+impl<T: Field> Prog<T> {
+    /// Returns the profile of the program, which is only detailed if it was compiled for profiling
+    pub fn profile(&self) -> Profile {
+        let mut total = Cost::default();
+        let mut functions: BTreeMap<&str, Cost> = BTreeMap::new();
+        let mut lines: BTreeMap<(&Option<String>, usize, &str), Cost> = BTreeMap::new();
+
+        for (index, statement) in self.main.statements.iter().enumerate() {
+            total.add(statement);
+
+            if let Some(Origin { function, location }) = self.main.source_map.get_origin(index) {
+                functions
+                    .entry(function)
+                    .or_insert_with(Cost::default)
+                    .add(statement);
+                lines
+                    .entry((&location.module, location.position.line, function))
+                    .or_insert_with(Cost::default)
+                    .add(statement);
+            }
+        }
+
+        let mut functions: Vec<_> = functions
+            .into_iter()
+            .map(|(function, cost)| FunctionProfile {
+                function: function.to_string(),
+                cost,
+            })
+            .collect();
+        functions.sort_by(|a, b| b.cost.constraints.cmp(&a.cost.constraints));
+
+        let mut lines: Vec<_> = lines
+            .into_iter()
+            .map(|((module, line, function), cost)| LineProfile {
+                module: module.clone(),
+                line,
+                function: function.to_string(),
+                cost,
+            })
+            .collect();
+        lines.sort_by(|a, b| b.cost.constraints.cmp(&a.cost.constraints));
+
+        Profile {
+            total,
+            functions,
+            lines,
+        }
+    }
+}
+
+impl fmt::Display for Profile {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{:>12} {:>12}  function", "constraints", "directives")?;
+        for p in &self.functions {
+            writeln!(
+                f,
+                "{:>12} {:>12}  {}",
+                p.cost.constraints, p.cost.directives, p.function
+            )?;
+        }
+        writeln!(f)?;
+        writeln!(f, "{:>12} {:>12}  line", "constraints", "directives")?;
+        for p in &self.lines {
+            let module = match p.module {
+                Some(ref module) => format!("{}:", module),
+                None => String::new(),
+            };
+            writeln!(
+                f,
+                "{:>12} {:>12}  {}{} ({})",
+                p.cost.constraints, p.cost.directives, module, p.line, p.function
+            )?;
+        }
+        writeln!(f)?;
+        write!(
+            f,
+            "{:>12} {:>12}  total",
+            self.total.constraints, self.total.directives
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::flat_absy::{FlatVariable, SourceLocation};
+    use crate::helpers::{Helper, RustHelper};
+    use crate::ir::{Directive, Function, SourceMap};
+    use crate::parser::Position;
+    use crate::types::Signature;
+    use zokrates_field::field::FieldPrime;
+
+    fn origin(function: &str, line: usize) -> Origin {
+        Origin {
+            function: String::from(function),
+            location: SourceLocation {
+                module: None,
+                position: Position { line, col: 1 },
+            },
+        }
+    }
+
+    #[test]
+    fn profile() {
+        let x = FlatVariable::new(0);
+        let y = FlatVariable::new(1);
+        let z = FlatVariable::new(2);
+
+        let mut source_map = SourceMap::default();
+        source_map.insert_origin(0, origin("main", 2));
+        source_map.insert_origin(1, origin("foo", 5));
+        source_map.insert_origin(2, origin("foo", 5));
+        source_map.insert_origin(3, origin("foo", 6));
+
+        let prog: Prog<FieldPrime> = Prog {
+            main: Function {
+                id: String::from("main"),
+                arguments: vec![x],
+                statements: vec![
+                    Statement::definition(y, x),
+                    Statement::Directive(Directive {
+                        inputs: vec![x.into()],
+                        outputs: vec![z],
+                        helper: Helper::Rust(RustHelper::Identity),
+                    }),
+                    Statement::constraint(z, x),
+                    Statement::constraint(y, z),
+                    Statement::definition(FlatVariable::public(0), y),
+                ],
+                returns: vec![FlatVariable::public(0)],
+                source_map,
+            },
+            private: vec![false],
+            signature: Signature::new(),
+        };
+
+        let profile = prog.profile();
+
+        assert_eq!(
+            profile.total,
+            Cost {
+                constraints: 4,
+                directives: 1
+            }
+        );
+        assert_eq!(
+            profile.functions,
+            vec![
+                FunctionProfile {
+                    function: String::from("foo"),
+                    cost: Cost {
+                        constraints: 2,
+                        directives: 1
+                    }
+                },
+                FunctionProfile {
+                    function: String::from("main"),
+                    cost: Cost {
+                        constraints: 1,
+                        directives: 0
+                    }
+                }
+            ]
+        );
+        assert_eq!(profile.lines.len(), 3);
+        assert_eq!(profile.lines[1].line, 5);
+        assert_eq!(
+            profile.lines[1].cost,
+            Cost {
+                constraints: 1,
+                directives: 1
+            }
+        );
+    }
+}
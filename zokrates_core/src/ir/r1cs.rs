@@ -0,0 +1,354 @@
+//! Module containing the export of a program as a rank-1 constraint system, in the `.r1cs` binary
+//! format of iden3 and as JSON
+//!
+//! The wires of the constraint system are ~one, followed by the outputs, the public inputs, the
+//! private inputs and the other variables in order of appearance. Variables which only appear in
+//! directives have no wire. The label of a wire identifies its variable: 0 for ~one, `1 + i` for
+//! `~out_i` and `1 + outputs + i` for `_i`.
+
+use crate::flat_absy::FlatVariable;
+use crate::ir::{LinComb, Prog, Statement};
+use num_bigint::BigUint;
+use serde_json;
+use std::collections::{BTreeMap, HashMap};
+use std::io::{self, Write};
+use zokrates_field::field::Field;
+
+const MAGIC: &[u8] = b"r1cs";
+const VERSION: u32 = 1;
+
+const HEADER_SECTION: u32 = 1;
+const CONSTRAINTS_SECTION: u32 = 2;
+const WIRE_TO_LABEL_SECTION: u32 = 3;
+
+/// A linear combination of wires, as pairs of a wire index and a coefficient
+pub type Wires<T> = Vec<(usize, T)>;
+
+/// A program as a system of constraints `A * B == C` over wires
+#[derive(Debug, Clone, PartialEq)]
+pub struct R1cs<T: Field> {
+    pub outputs: usize,
+    pub public_inputs: usize,
+    pub private_inputs: usize,
+    /// The variable of each wire
+    pub wires: Vec<FlatVariable>,
+    pub constraints: Vec<(Wires<T>, Wires<T>, Wires<T>)>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct R1csJson {
+    n8: usize,
+    prime: String,
+    n_vars: usize,
+    n_outputs: usize,
+    n_pub_inputs: usize,
+    n_prv_inputs: usize,
+    n_labels: u64,
+    n_constraints: usize,
+    constraints: Vec<Vec<BTreeMap<usize, String>>>,
+    map: Vec<u64>,
+}
+
+impl<T: Field> Prog<T> {
+    pub fn r1cs(&self) -> R1cs<T> {
+        let main = &self.main;
+
+        let public_inputs: Vec<_> = main
+            .arguments
+            .iter()
+            .zip(self.private.iter())
+            .filter(|(_, private)| !**private)
+            .map(|(a, _)| *a)
+            .collect();
+        let private_inputs: Vec<_> = main
+            .arguments
+            .iter()
+            .zip(self.private.iter())
+            .filter(|(_, private)| **private)
+            .map(|(a, _)| *a)
+            .collect();
+
+        let mut wires = vec![FlatVariable::one()];
+        wires.extend(main.returns.iter().cloned());
+        wires.extend(public_inputs.iter().cloned());
+        wires.extend(private_inputs.iter().cloned());
+
+        let mut indices: HashMap<FlatVariable, usize> =
+            wires.iter().enumerate().map(|(i, v)| (*v, i)).collect();
+
+        let constraints = main
+            .statements
+            .iter()
+            .filter_map(|s| match s {
+                Statement::Constraint(quad, lin) => Some((
+                    to_wires(&quad.left, &mut wires, &mut indices),
+                    to_wires(&quad.right, &mut wires, &mut indices),
+                    to_wires(lin, &mut wires, &mut indices),
+                )),
+                Statement::Directive(..) => None,
+            })
+            .collect();
+
+        R1cs {
+            outputs: main.returns.len(),
+            public_inputs: public_inputs.len(),
+            private_inputs: private_inputs.len(),
+            wires,
+            constraints,
+        }
+    }
+}
+
+fn to_wires<T: Field>(
+    lc: &LinComb<T>,
+    wires: &mut Vec<FlatVariable>,
+    indices: &mut HashMap<FlatVariable, usize>,
+) -> Wires<T> {
+    lc.as_canonical()
+        .0
+        .into_iter()
+        .map(|(variable, coefficient)| {
+            let index = *indices.entry(variable).or_insert_with(|| {
+                wires.push(variable);
+                wires.len() - 1
+            });
+            (index, coefficient)
+        })
+        .collect()
+}
+
+impl<T: Field> R1cs<T> {
+    /// Returns the label of each wire
+    pub fn labels(&self) -> Vec<u64> {
+        let outputs = &self.wires[1..1 + self.outputs];
+
+        self.wires
+            .iter()
+            .map(|w| match outputs.iter().position(|o| o == w) {
+                Some(i) => 1 + i as u64,
+                None if *w == FlatVariable::one() => 0,
+                None => (1 + self.outputs + w.id()) as u64,
+            })
+            .collect()
+    }
+
+    /// Writes the constraint system in the `.r1cs` binary format
+    pub fn write_iden3<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        let n8 = field_size::<T>();
+        let labels = self.labels();
+
+        let mut header = vec![];
+        header.extend_from_slice(&(n8 as u32).to_le_bytes());
+        header.extend(to_bytes(&prime::<T>(), n8));
+        header.extend_from_slice(&(self.wires.len() as u32).to_le_bytes());
+        header.extend_from_slice(&(self.outputs as u32).to_le_bytes());
+        header.extend_from_slice(&(self.public_inputs as u32).to_le_bytes());
+        header.extend_from_slice(&(self.private_inputs as u32).to_le_bytes());
+        header.extend_from_slice(&label_count(&labels).to_le_bytes());
+        header.extend_from_slice(&(self.constraints.len() as u32).to_le_bytes());
+
+        let mut constraints = vec![];
+        for (a, b, c) in &self.constraints {
+            for lc in &[a, b, c] {
+                constraints.extend_from_slice(&(lc.len() as u32).to_le_bytes());
+                for (wire, coefficient) in lc.iter() {
+                    constraints.extend_from_slice(&(*wire as u32).to_le_bytes());
+                    constraints.extend(to_bytes(&to_biguint(coefficient), n8));
+                }
+            }
+        }
+
+        let mut map = vec![];
+        for label in &labels {
+            map.extend_from_slice(&label.to_le_bytes());
+        }
+
+        writer.write_all(MAGIC)?;
+        writer.write_all(&VERSION.to_le_bytes())?;
+        writer.write_all(&3u32.to_le_bytes())?;
+        for (section_type, section) in &[
+            (HEADER_SECTION, header),
+            (CONSTRAINTS_SECTION, constraints),
+            (WIRE_TO_LABEL_SECTION, map),
+        ] {
+            writer.write_all(&section_type.to_le_bytes())?;
+            writer.write_all(&(section.len() as u64).to_le_bytes())?;
+            writer.write_all(section)?;
+        }
+        writer.flush()
+    }
+
+    /// Writes the constraint system as JSON, with the same fields as the binary format
+    pub fn write_json<W: Write>(&self, writer: W) -> serde_json::Result<()> {
+        let labels = self.labels();
+
+        let json = R1csJson {
+            n8: field_size::<T>(),
+            prime: prime::<T>().to_str_radix(10),
+            n_vars: self.wires.len(),
+            n_outputs: self.outputs,
+            n_pub_inputs: self.public_inputs,
+            n_prv_inputs: self.private_inputs,
+            n_labels: label_count(&labels),
+            n_constraints: self.constraints.len(),
+            constraints: self
+                .constraints
+                .iter()
+                .map(|(a, b, c)| {
+                    vec![a, b, c]
+                        .into_iter()
+                        .map(|lc| {
+                            lc.iter()
+                                .map(|(wire, coefficient)| (*wire, coefficient.to_dec_string()))
+                                .collect()
+                        })
+                        .collect()
+                })
+                .collect(),
+            map: labels,
+        };
+
+        serde_json::to_writer_pretty(writer, &json)
+    }
+}
+
+// the number of bytes of a field element, rounded up to a multiple of 8
+pub(super) fn field_size<T: Field>() -> usize {
+    (T::get_required_bits() + 63) / 64 * 8
+}
+
+pub(super) fn prime<T: Field>() -> BigUint {
+    to_biguint(&T::max_value()) + BigUint::from(1u32)
+}
+
+pub(super) fn to_biguint<T: Field>(value: &T) -> BigUint {
+    BigUint::from_bytes_le(&value.into_byte_vector())
+}
+
+// the little-endian representation of `value` on `size` bytes
+pub(super) fn to_bytes(value: &BigUint, size: usize) -> Vec<u8> {
+    let mut bytes = value.to_bytes_le();
+    bytes.resize(size, 0);
+    bytes
+}
+
+fn label_count(labels: &[u64]) -> u64 {
+    labels.iter().max().map(|l| l + 1).unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::flat_absy::{FlatExpression, FlatStatement};
+    use crate::ir::{Function, SourceMap};
+    use crate::types::Signature;
+    use zokrates_field::field::FieldPrime;
+
+    // def main(x, private y) -> (1):
+    //     _2 = x * y
+    //     ~out_0 = _2 * x
+    //     return ~out_0
+    fn prog() -> Prog<FieldPrime> {
+        let x = FlatVariable::new(0);
+        let y = FlatVariable::new(1);
+        let z = FlatVariable::new(2);
+        let out = FlatVariable::public(0);
+
+        Prog {
+            main: Function {
+                id: "main".to_string(),
+                arguments: vec![x, y],
+                statements: vec![
+                    FlatStatement::Definition(z, FlatExpression::Mult(box x.into(), box y.into()))
+                        .into(),
+                    FlatStatement::Definition(
+                        out,
+                        FlatExpression::Mult(box z.into(), box x.into()),
+                    )
+                    .into(),
+                ],
+                returns: vec![out],
+                source_map: SourceMap::default(),
+            },
+            private: vec![false, true],
+            signature: Signature::new(),
+        }
+    }
+
+    #[test]
+    fn wires() {
+        let r1cs = prog().r1cs();
+
+        assert_eq!(
+            r1cs.wires,
+            vec![
+                FlatVariable::one(),
+                FlatVariable::public(0),
+                FlatVariable::new(0),
+                FlatVariable::new(1),
+                FlatVariable::new(2)
+            ]
+        );
+        assert_eq!(
+            r1cs.constraints,
+            vec![
+                (
+                    vec![(2, FieldPrime::from(1))],
+                    vec![(3, FieldPrime::from(1))],
+                    vec![(4, FieldPrime::from(1))]
+                ),
+                (
+                    vec![(4, FieldPrime::from(1))],
+                    vec![(2, FieldPrime::from(1))],
+                    vec![(1, FieldPrime::from(1))]
+                )
+            ]
+        );
+        assert_eq!(r1cs.labels(), vec![0, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn iden3() {
+        let mut buffer = vec![];
+        prog().r1cs().write_iden3(&mut buffer).unwrap();
+
+        assert_eq!(&buffer[0..4], b"r1cs");
+        assert_eq!(&buffer[4..8], &1u32.to_le_bytes());
+        assert_eq!(&buffer[8..12], &3u32.to_le_bytes());
+
+        // header section
+        assert_eq!(&buffer[12..16], &1u32.to_le_bytes());
+        assert_eq!(&buffer[16..24], &64u64.to_le_bytes());
+        assert_eq!(&buffer[24..28], &32u32.to_le_bytes());
+        // 5 wires, 1 output, 1 public input and 1 private input
+        assert_eq!(&buffer[60..64], &5u32.to_le_bytes());
+        assert_eq!(&buffer[64..68], &1u32.to_le_bytes());
+        assert_eq!(&buffer[68..72], &1u32.to_le_bytes());
+        assert_eq!(&buffer[72..76], &1u32.to_le_bytes());
+
+        assert_eq!(&buffer[84..88], &2u32.to_le_bytes());
+
+        // 2 constraints of 3 linear combinations with a single term
+        assert_eq!(&buffer[88..92], &2u32.to_le_bytes());
+        assert_eq!(&buffer[92..100], &240u64.to_le_bytes());
+
+        // 5 labels
+        assert_eq!(&buffer[340..344], &3u32.to_le_bytes());
+        assert_eq!(&buffer[344..352], &40u64.to_le_bytes());
+        assert_eq!(buffer.len(), 392);
+    }
+
+    #[test]
+    fn json() {
+        let mut buffer = vec![];
+        prog().r1cs().write_json(&mut buffer).unwrap();
+
+        let json: serde_json::Value = serde_json::from_slice(&buffer).unwrap();
+
+        assert_eq!(json["nVars"], 5);
+        assert_eq!(json["nConstraints"], 2);
+        assert_eq!(json["constraints"][1][2]["1"], "1");
+        assert_eq!(json["map"][4], 4);
+    }
+}
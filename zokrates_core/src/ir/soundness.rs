@@ -0,0 +1,925 @@
+//! Module containing a conservative detection of underconstrained variables
+//!
+//! Directive outputs are hints computed by the prover: unless the constraints pin them down, a
+//! dishonest prover can pick other values and still produce a valid proof. Starting from the
+//! constant one and the arguments of `main`, a variable is marked as determined when it is the
+//! only undetermined variable of a constraint `L * R == C` matching one of these patterns:
+//! - the constraint is linear and the variable has a non-zero coefficient
+//! - `L` and `C` are determined, `L` is known to be non-zero and the variable appears in `R`
+//! - the variable is `C`, `L` is determined and `0 == L * F` where `F` only depends on the variable
+//!
+//! In a linear constraint whose undetermined variables are all boolean, such as a binary
+//! decomposition, the variable whose coefficient has the lowest 2-adic valuation is determined if
+//! that valuation is unique and the absolute values of the coefficients sum to less than the field
+//! modulus: two solutions differing on that variable would then differ by a non-zero integer.
+//! Besides variables constrained by `b * b == b`, a variable is known to be boolean if it is
+//! computed by a single constraint from at most a few boolean variables, like the output of a xor,
+//! and takes a boolean value for all of their values.
+//!
+//! The analysis is incomplete: a variable which is reported may still be determined by the
+//! constraints. It is sound however, in that all variables it does not report are determined.
+
+use crate::flat_absy::FlatVariable;
+use crate::helpers::Helper;
+use crate::ir::{CanonicalLinComb, Prog, Statement};
+use num::{One, Zero};
+use num_bigint::BigUint;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::fmt;
+use zokrates_field::field::Field;
+
+// the maximum number of boolean variables a boolean variable is computed from
+const MAX_BOOLEAN_INPUTS: usize = 4;
+
+/// A variable which is not uniquely determined by the arguments of a program
+#[derive(Debug, PartialEq, Clone)]
+pub struct Underconstrained {
+    pub variable: FlatVariable,
+    /// The helper of the directive computing the variable, if any
+    pub helper: Option<Helper>,
+    /// Whether the value of the variable cannot influence any other variable
+    pub harmless: bool,
+}
+
+impl fmt::Display for Underconstrained {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.variable)?;
+        if let Some(ref helper) = self.helper {
+            write!(f, " (computed by {})", helper)?;
+        }
+        Ok(())
+    }
+}
+
+// a term of a linear constraint, with its coefficient seen as an integer
+struct Term {
+    variable: FlatVariable,
+    magnitude: BigUint,
+    valuation: usize,
+}
+
+struct Constraint<T: Field> {
+    left: CanonicalLinComb<T>,
+    right: CanonicalLinComb<T>,
+    lin: CanonicalLinComb<T>,
+    // the linear combination which the constraint sets to zero, if it is linear
+    linear: Option<CanonicalLinComb<T>>,
+    terms: Vec<Term>,
+    // whether the magnitudes of the terms sum to less than the modulus
+    bounded: bool,
+}
+
+impl<T: Field> Constraint<T> {
+    fn new(
+        left: CanonicalLinComb<T>,
+        right: CanonicalLinComb<T>,
+        lin: CanonicalLinComb<T>,
+        modulus: &BigUint,
+    ) -> Self {
+        let linear = match (constant(&left), constant(&right)) {
+            (Some(k), _) => Some(scale_sub(&k, &right, &lin)),
+            (_, Some(k)) => Some(scale_sub(&k, &left, &lin)),
+            _ => None,
+        };
+
+        let terms: Vec<_> = linear
+            .iter()
+            .flat_map(|l| l.0.iter())
+            .filter(|(v, _)| **v != FlatVariable::one())
+            .map(|(v, c)| Term::new(*v, c, modulus))
+            .collect();
+
+        let bounded = terms
+            .iter()
+            .fold(BigUint::zero(), |acc, t| acc + &t.magnitude)
+            < *modulus;
+
+        Constraint {
+            left,
+            right,
+            lin,
+            linear,
+            terms,
+            bounded,
+        }
+    }
+
+    fn variables(&self) -> impl Iterator<Item = &FlatVariable> {
+        self.left
+            .0
+            .keys()
+            .chain(self.right.0.keys())
+            .chain(self.lin.0.keys())
+    }
+}
+
+impl Term {
+    fn new<T: Field>(variable: FlatVariable, coefficient: &T, modulus: &BigUint) -> Self {
+        let mut magnitude = to_biguint(coefficient);
+        // interpret coefficients as integers between -modulus/2 and modulus/2
+        if magnitude.clone() * BigUint::from(2u32) > *modulus {
+            magnitude = modulus.clone() - magnitude;
+        }
+
+        // the number of trailing zeros of the magnitude, which is not zero
+        let bytes = magnitude.to_bytes_le();
+        let zeros = bytes.iter().take_while(|b| **b == 0).count();
+        let valuation = zeros * 8 + bytes[zeros].trailing_zeros() as usize;
+
+        Term {
+            variable,
+            magnitude,
+            valuation,
+        }
+    }
+}
+
+// the value of `l` if it only depends on the constant one
+fn constant<T: Field>(l: &CanonicalLinComb<T>) -> Option<T> {
+    match l.0.len() {
+        0 => Some(T::zero()),
+        1 => l.0.get(&FlatVariable::one()).cloned(),
+        _ => None,
+    }
+}
+
+// k * a - c
+fn scale_sub<T: Field>(
+    k: &T,
+    a: &CanonicalLinComb<T>,
+    c: &CanonicalLinComb<T>,
+) -> CanonicalLinComb<T> {
+    let mut res = BTreeMap::new();
+    for (v, coeff) in &a.0 {
+        res.insert(*v, coeff.clone() * k);
+    }
+    for (v, coeff) in &c.0 {
+        let value = res.remove(v).unwrap_or(T::zero()) - coeff;
+        res.insert(*v, value);
+    }
+    CanonicalLinComb(res.into_iter().filter(|(_, c)| !c.is_zero()).collect())
+}
+
+// replace the variables of `l` which have a constant value by that value
+fn substitute<T: Field>(
+    l: &CanonicalLinComb<T>,
+    constants: &HashMap<FlatVariable, T>,
+) -> CanonicalLinComb<T> {
+    let mut res = BTreeMap::new();
+    for (v, coeff) in &l.0 {
+        let (v, coeff) = match constants.get(v) {
+            Some(value) => (FlatVariable::one(), coeff.clone() * value),
+            None => (*v, coeff.clone()),
+        };
+        let value = res.remove(&v).unwrap_or(T::zero()) + coeff;
+        res.insert(v, value);
+    }
+    CanonicalLinComb(res.into_iter().filter(|(_, c)| !c.is_zero()).collect())
+}
+
+// the value of `l` under `assignment`, where missing variables are zero
+fn evaluate<T: Field>(l: &CanonicalLinComb<T>, assignment: &HashMap<FlatVariable, T>) -> T {
+    l.0.iter().fold(T::zero(), |acc, (v, coeff)| {
+        let value = match *v == FlatVariable::one() {
+            true => T::one(),
+            false => assignment.get(v).cloned().unwrap_or(T::zero()),
+        };
+        acc + value * coeff
+    })
+}
+
+fn to_biguint<T: Field>(value: &T) -> BigUint {
+    BigUint::from_bytes_le(&value.into_byte_vector())
+}
+
+// the variables which the constraints force to a constant value
+fn propagate_constants<T: Field>(
+    constraints: &[(
+        CanonicalLinComb<T>,
+        CanonicalLinComb<T>,
+        CanonicalLinComb<T>,
+    )],
+) -> HashMap<FlatVariable, T> {
+    let mut constants = HashMap::new();
+
+    loop {
+        let mut changed = false;
+        for (left, right, lin) in constraints {
+            let left = substitute(left, &constants);
+            let right = substitute(right, &constants);
+            let lin = substitute(lin, &constants);
+
+            let linear = match (constant(&left), constant(&right)) {
+                (Some(k), _) => scale_sub(&k, &right, &lin),
+                (_, Some(k)) => scale_sub(&k, &left, &lin),
+                _ => continue,
+            };
+
+            let mut variables = linear.0.iter().filter(|(v, _)| **v != FlatVariable::one());
+            if let (Some((v, coeff)), None) = (variables.next(), variables.next()) {
+                // coeff * v + k == 0
+                let k = evaluate(&linear, &HashMap::new());
+                constants.insert(*v, (T::zero() - k) / coeff.clone());
+                changed = true;
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+
+    constants
+}
+
+// the variable `b` if `c` is equivalent to `b * b == b`
+fn boolean_variable<T: Field>(c: &Constraint<T>) -> Option<FlatVariable> {
+    let b = *c.variables().find(|v| **v != FlatVariable::one())?;
+
+    if c.variables().any(|v| *v != b && *v != FlatVariable::one()) {
+        return None;
+    }
+
+    let coefficients = |l: &CanonicalLinComb<T>| {
+        (
+            l.0.get(&b).cloned().unwrap_or(T::zero()),
+            l.0.get(&FlatVariable::one()).cloned().unwrap_or(T::zero()),
+        )
+    };
+
+    // (l1 * b + l0) * (r1 * b + r0) - (c1 * b + c0) must be a non-zero multiple of b * b - b
+    let (l1, l0) = coefficients(&c.left);
+    let (r1, r0) = coefficients(&c.right);
+    let (c1, c0) = coefficients(&c.lin);
+
+    let square = l1.clone() * &r1;
+    let linear = l1 * &r0 + l0.clone() * &r1 - c1;
+    let constant = l0 * &r0 - c0;
+
+    match !square.is_zero() && linear == T::zero() - square && constant.is_zero() {
+        true => Some(b),
+        false => None,
+    }
+}
+
+// whether `c` forces `x` to be boolean, given that all its other variables are boolean, which is
+// checked by enumerating the values of these variables which satisfy the `context` constraints
+fn computes_boolean<T: Field>(
+    c: &Constraint<T>,
+    x: &FlatVariable,
+    inputs: &[FlatVariable],
+    context: &[&Constraint<T>],
+) -> bool {
+    // the constraint must be of the form `coefficient * x + rest == 0` where `rest` does not
+    // depend on x, and `rest` is `linear` or `left * right - lin`
+    let coefficient = match c.linear {
+        Some(ref l) => l.0.get(x).cloned(),
+        None if !c.left.0.contains_key(x) && !c.right.0.contains_key(x) => {
+            c.lin.0.get(x).map(|k| T::zero() - k.clone())
+        }
+        None => None,
+    };
+
+    let coefficient = match coefficient {
+        Some(k) => k,
+        None => return false,
+    };
+
+    (0..1 << inputs.len()).all(|bits: usize| {
+        // x is left out of the assignment, so that it evaluates to zero
+        let assignment: HashMap<_, _> = inputs
+            .iter()
+            .enumerate()
+            .map(|(i, v)| (*v, T::from((bits >> i) & 1)))
+            .collect();
+
+        let satisfies = |c: &Constraint<T>| {
+            evaluate(&c.left, &assignment) * evaluate(&c.right, &assignment)
+                == evaluate(&c.lin, &assignment)
+        };
+        if !context.iter().all(|c| satisfies(c)) {
+            return true;
+        }
+
+        let rest = match c.linear {
+            Some(ref l) => evaluate(l, &assignment),
+            None => {
+                evaluate(&c.left, &assignment) * evaluate(&c.right, &assignment)
+                    - evaluate(&c.lin, &assignment)
+            }
+        };
+        // x is `-rest / coefficient`, which is boolean if `rest` is zero or `-coefficient`
+        rest.is_zero() || (rest + &coefficient).is_zero()
+    })
+}
+
+// the variables which are boolean in all solutions of the constraints
+fn infer_booleans<T: Field>(
+    constraints: &[Constraint<T>],
+    occurrences: &HashMap<FlatVariable, Vec<usize>>,
+) -> HashSet<FlatVariable> {
+    let mut booleans: HashSet<_> = constraints.iter().filter_map(boolean_variable).collect();
+
+    let mut queue: VecDeque<_> = (0..constraints.len()).collect();
+    let mut queued = vec![true; constraints.len()];
+
+    while let Some(i) = queue.pop_front() {
+        queued[i] = false;
+        let c = &constraints[i];
+
+        let variables: HashSet<_> = c
+            .variables()
+            .filter(|v| **v != FlatVariable::one())
+            .cloned()
+            .collect();
+        if variables.len() > MAX_BOOLEAN_INPUTS + 1 {
+            continue;
+        }
+
+        let (inputs, others): (Vec<_>, Vec<_>) =
+            variables.into_iter().partition(|v| booleans.contains(v));
+
+        if let [x] = others.as_slice() {
+            // the constraints relating the inputs rule out some of their values
+            let context: Vec<_> = inputs
+                .iter()
+                .flat_map(|v| &occurrences[v])
+                .map(|i| &constraints[*i])
+                .filter(|c| {
+                    c.variables()
+                        .all(|v| *v == FlatVariable::one() || inputs.contains(v))
+                })
+                .collect();
+
+            if computes_boolean(c, x, &inputs, &context) {
+                booleans.insert(*x);
+                for &j in &occurrences[x] {
+                    if !queued[j] {
+                        queued[j] = true;
+                        queue.push_back(j);
+                    }
+                }
+            }
+        }
+    }
+
+    booleans
+}
+
+struct Analysis<T: Field> {
+    constraints: Vec<Constraint<T>>,
+    modulus: BigUint,
+    determined: HashSet<FlatVariable>,
+    booleans: HashSet<FlatVariable>,
+    nonzero: HashSet<CanonicalLinComb<T>>,
+    // for each constraint `A * B == 0`, `B` indexed by `A` and `A` indexed by `B`
+    zero_products: HashMap<CanonicalLinComb<T>, Vec<CanonicalLinComb<T>>>,
+    // the indices of the constraints each variable appears in
+    occurrences: HashMap<FlatVariable, Vec<usize>>,
+}
+
+impl<T: Field> Analysis<T> {
+    fn new(prog: &Prog<T>) -> Self {
+        let modulus = to_biguint(&T::max_value()) + BigUint::one();
+
+        let constraints: Vec<_> = prog
+            .main
+            .statements
+            .iter()
+            .filter_map(|s| match s {
+                Statement::Constraint(quad, lin) => Some((
+                    quad.left.as_canonical(),
+                    quad.right.as_canonical(),
+                    lin.as_canonical(),
+                )),
+                Statement::Directive(..) => None,
+            })
+            .collect();
+
+        // variables which are equal to a constant are replaced by their value
+        let constants = propagate_constants(&constraints);
+        let constraints: Vec<_> = constraints
+            .into_iter()
+            .map(|(left, right, lin)| {
+                Constraint::new(
+                    substitute(&left, &constants),
+                    substitute(&right, &constants),
+                    substitute(&lin, &constants),
+                    &modulus,
+                )
+            })
+            .collect();
+
+        let determined = prog
+            .main
+            .arguments
+            .iter()
+            .cloned()
+            .chain(std::iter::once(FlatVariable::one()))
+            .chain(constants.keys().cloned())
+            .collect();
+
+        let mut occurrences = HashMap::new();
+        for (i, c) in constraints.iter().enumerate() {
+            for v in c.variables().collect::<HashSet<_>>() {
+                occurrences.entry(*v).or_insert_with(Vec::new).push(i);
+            }
+        }
+
+        let booleans = infer_booleans(&constraints, &occurrences);
+
+        let mut nonzero = HashSet::new();
+        let mut zero_products = HashMap::new();
+
+        for c in &constraints {
+            match constant(&c.lin) {
+                Some(ref k) if k.is_zero() => {
+                    zero_products
+                        .entry(c.left.clone())
+                        .or_insert_with(Vec::new)
+                        .push(c.right.clone());
+                    zero_products
+                        .entry(c.right.clone())
+                        .or_insert_with(Vec::new)
+                        .push(c.left.clone());
+                }
+                // both factors of a non-zero constant are non-zero
+                Some(_) => {
+                    nonzero.insert(c.left.clone());
+                    nonzero.insert(c.right.clone());
+                }
+                None => {}
+            }
+        }
+
+        Analysis {
+            constraints,
+            modulus,
+            determined,
+            booleans,
+            nonzero,
+            zero_products,
+            occurrences,
+        }
+    }
+
+    fn is_determined(&self, l: &CanonicalLinComb<T>) -> bool {
+        l.0.keys().all(|v| self.determined.contains(v))
+    }
+
+    fn is_nonzero(&self, l: &CanonicalLinComb<T>) -> bool {
+        match constant(l) {
+            Some(k) => !k.is_zero(),
+            None => self.nonzero.contains(l),
+        }
+    }
+
+    fn undetermined<'a>(&self, l: &'a CanonicalLinComb<T>) -> Vec<&'a FlatVariable> {
+        l.0.keys()
+            .filter(|v| !self.determined.contains(v))
+            .collect()
+    }
+
+    // the variables which `c` determines, given the variables which are already determined
+    fn deduce(&self, c: &Constraint<T>) -> Vec<FlatVariable> {
+        if c.linear.is_some() {
+            let undetermined: Vec<_> = c
+                .terms
+                .iter()
+                .filter(|t| !self.determined.contains(&t.variable))
+                .collect();
+
+            if undetermined.len() == 1 {
+                return vec![undetermined[0].variable];
+            }
+
+            let is_bounded = || {
+                c.bounded
+                    || undetermined
+                        .iter()
+                        .fold(BigUint::zero(), |acc, t| acc + &t.magnitude)
+                        < self.modulus
+            };
+
+            if undetermined.len() > 1
+                && undetermined
+                    .iter()
+                    .all(|t| self.booleans.contains(&t.variable))
+                && is_bounded()
+            {
+                let lowest = undetermined.iter().min_by_key(|t| t.valuation).unwrap();
+                if undetermined
+                    .iter()
+                    .filter(|t| t.valuation == lowest.valuation)
+                    .count()
+                    == 1
+                {
+                    return vec![lowest.variable];
+                }
+            }
+
+            return vec![];
+        }
+
+        if self.is_determined(&c.left) && self.is_determined(&c.right) {
+            return match self.undetermined(&c.lin).as_slice() {
+                [v] => vec![**v],
+                _ => vec![],
+            };
+        }
+
+        for &(x, y) in &[(&c.left, &c.right), (&c.right, &c.left)] {
+            if !self.is_determined(x) {
+                continue;
+            }
+
+            // x * y == c with x non-zero, so that y == c / x
+            if let [v] = self.undetermined(y).as_slice() {
+                if self.is_nonzero(x) && self.is_determined(&c.lin) {
+                    return vec![**v];
+                }
+            }
+
+            // c == x * y and 0 == x * f(c): c is zero if x is, and otherwise the root of f
+            if let [target] = self.undetermined(&c.lin).as_slice() {
+                let is_root = self.zero_products.get(x).map_or(false, |factors| {
+                    factors
+                        .iter()
+                        .any(|f| self.undetermined(f).as_slice() == [*target])
+                });
+                if is_root && !y.0.contains_key(target) {
+                    return vec![**target];
+                }
+            }
+        }
+
+        vec![]
+    }
+
+    fn run(&mut self) {
+        let mut queue: VecDeque<usize> = (0..self.constraints.len()).collect();
+        let mut queued = vec![true; self.constraints.len()];
+
+        loop {
+            while let Some(i) = queue.pop_front() {
+                queued[i] = false;
+                for v in self.deduce(&self.constraints[i]) {
+                    self.mark(v, &mut queue, &mut queued);
+                }
+            }
+
+            // the deductions on a constraint may depend on the constraints `A * B == 0` it
+            // matches, which are not revisited above
+            let mut changed = false;
+            for i in 0..self.constraints.len() {
+                for v in self.deduce(&self.constraints[i]) {
+                    changed |= self.mark(v, &mut queue, &mut queued);
+                }
+            }
+            if !changed {
+                break;
+            }
+        }
+    }
+
+    // mark `v` as determined and schedule the constraints it appears in
+    fn mark(&mut self, v: FlatVariable, queue: &mut VecDeque<usize>, queued: &mut [bool]) -> bool {
+        if !self.determined.insert(v) {
+            return false;
+        }
+        for &j in self.occurrences.get(&v).into_iter().flatten() {
+            if !queued[j] {
+                queued[j] = true;
+                queue.push_back(j);
+            }
+        }
+        true
+    }
+}
+
+impl<T: Field> Prog<T> {
+    /// Returns the variables of the program which are not determined by its arguments, in order
+    /// of appearance
+    pub fn underconstrained_variables(&self) -> Vec<Underconstrained> {
+        let mut analysis = Analysis::new(self);
+        analysis.run();
+
+        let helpers: HashMap<_, _> = self
+            .main
+            .statements
+            .iter()
+            .filter_map(|s| match s {
+                Statement::Directive(d) => Some(d),
+                _ => None,
+            })
+            .flat_map(|d| d.outputs.iter().map(move |o| (*o, d.helper.clone())))
+            .collect();
+
+        let mut seen = HashSet::new();
+
+        self.main
+            .statements
+            .iter()
+            .flat_map(|s| match s {
+                Statement::Directive(d) => d.outputs.clone(),
+                Statement::Constraint(quad, lin) => quad
+                    .left
+                    .0
+                    .iter()
+                    .chain(quad.right.0.iter())
+                    .chain(lin.0.iter())
+                    .map(|(v, _)| *v)
+                    .collect(),
+            })
+            .chain(self.main.returns.iter().cloned())
+            .filter(|v| !analysis.determined.contains(v) && seen.insert(*v))
+            .map(|v| {
+                // a variable which only appears in a constraint whose other variables are
+                // determined cannot influence other variables
+                let harmless = !self.main.returns.contains(&v)
+                    && match analysis.occurrences.get(&v).map(|c| c.as_slice()) {
+                        None => true,
+                        Some([i]) => analysis.constraints[*i]
+                            .variables()
+                            .all(|w| *w == v || analysis.determined.contains(w)),
+                        Some(_) => false,
+                    };
+
+                Underconstrained {
+                    variable: v,
+                    helper: helpers.get(&v).cloned(),
+                    harmless,
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::helpers::RustHelper;
+    use crate::ir::{Directive, Function, LinComb, QuadComb, SourceMap};
+    use crate::types::{Signature, Type};
+    use zokrates_field::field::{FieldPrime, Pow};
+
+    fn prog(statements: Vec<Statement<FieldPrime>>) -> Prog<FieldPrime> {
+        Prog {
+            main: Function {
+                id: String::from("main"),
+                statements,
+                arguments: vec![FlatVariable::new(0)],
+                returns: vec![FlatVariable::public(0)],
+                source_map: SourceMap::default(),
+            },
+            private: vec![true],
+            signature: Signature::new()
+                .inputs(vec![Type::FieldElement])
+                .outputs(vec![Type::FieldElement]),
+        }
+    }
+
+    fn directive(
+        outputs: Vec<FlatVariable>,
+        helper: RustHelper,
+        inputs: Vec<FlatVariable>,
+    ) -> Statement<FieldPrime> {
+        Statement::Directive(Directive {
+            inputs: inputs.into_iter().map(|i| i.into()).collect(),
+            outputs,
+            helper: Helper::Rust(helper),
+        })
+    }
+
+    fn product(
+        left: FlatVariable,
+        right: FlatVariable,
+        lin: LinComb<FieldPrime>,
+    ) -> Statement<FieldPrime> {
+        Statement::Constraint(
+            QuadComb::from_linear_combinations(left.into(), right.into()),
+            lin,
+        )
+    }
+
+    #[test]
+    fn definitions() {
+        // ~out_0 = _0 * _0
+        let p = prog(vec![product(
+            FlatVariable::new(0),
+            FlatVariable::new(0),
+            FlatVariable::public(0).into(),
+        )]);
+
+        assert_eq!(p.underconstrained_variables(), vec![]);
+    }
+
+    #[test]
+    fn unconstrained_directive() {
+        // # _1 = Identity(_0)
+        // ~out_0 = _1
+        let p = prog(vec![
+            directive(
+                vec![FlatVariable::new(1)],
+                RustHelper::Identity,
+                vec![FlatVariable::new(0)],
+            ),
+            Statement::definition(FlatVariable::public(0), FlatVariable::new(1)),
+        ]);
+
+        let expected: Vec<_> = vec![
+            Underconstrained {
+                variable: FlatVariable::new(1),
+                helper: Some(Helper::Rust(RustHelper::Identity)),
+                harmless: false,
+            },
+            Underconstrained {
+                variable: FlatVariable::public(0),
+                helper: None,
+                harmless: false,
+            },
+        ];
+
+        assert_eq!(p.underconstrained_variables(), expected);
+    }
+
+    #[test]
+    fn condition_eq() {
+        // # _1, _2 = ConditionEq(_0)
+        // _1 == _0 * _2
+        // 0 == (1 - _1) * _0
+        // ~out_0 = _1
+        let p = prog(vec![
+            directive(
+                vec![FlatVariable::new(1), FlatVariable::new(2)],
+                RustHelper::ConditionEq,
+                vec![FlatVariable::new(0)],
+            ),
+            product(
+                FlatVariable::new(0),
+                FlatVariable::new(2),
+                FlatVariable::new(1).into(),
+            ),
+            Statement::constraint(
+                QuadComb::from_linear_combinations(
+                    LinComb::one() - FlatVariable::new(1).into(),
+                    FlatVariable::new(0).into(),
+                ),
+                LinComb::zero(),
+            ),
+            Statement::definition(FlatVariable::public(0), FlatVariable::new(1)),
+        ]);
+
+        // _2 is free when _0 is zero, but it is then multiplied by zero
+        assert_eq!(
+            p.underconstrained_variables(),
+            vec![Underconstrained {
+                variable: FlatVariable::new(2),
+                helper: Some(Helper::Rust(RustHelper::ConditionEq)),
+                harmless: true,
+            }]
+        );
+    }
+
+    #[test]
+    fn division() {
+        // # _1 = Div(1, _0)
+        // 1 == _1 * _0
+        // # _2 = Div(_0, _0)
+        // _0 == _0 * _2
+        // ~out_0 = _2
+        let p = prog(vec![
+            directive(
+                vec![FlatVariable::new(1)],
+                RustHelper::Div,
+                vec![FlatVariable::one(), FlatVariable::new(0)],
+            ),
+            product(FlatVariable::new(1), FlatVariable::new(0), LinComb::one()),
+            directive(
+                vec![FlatVariable::new(2)],
+                RustHelper::Div,
+                vec![FlatVariable::new(0), FlatVariable::new(0)],
+            ),
+            product(
+                FlatVariable::new(0),
+                FlatVariable::new(2),
+                FlatVariable::new(0).into(),
+            ),
+            Statement::definition(FlatVariable::public(0), FlatVariable::new(2)),
+        ]);
+
+        assert_eq!(p.underconstrained_variables(), vec![]);
+    }
+
+    #[test]
+    fn division_by_zero() {
+        // # _2 = Div(_0, _0)
+        // _0 == _0 * _2
+        // ~out_0 = _2
+        let p = prog(vec![
+            directive(
+                vec![FlatVariable::new(2)],
+                RustHelper::Div,
+                vec![FlatVariable::new(0), FlatVariable::new(0)],
+            ),
+            product(
+                FlatVariable::new(0),
+                FlatVariable::new(2),
+                FlatVariable::new(0).into(),
+            ),
+            Statement::definition(FlatVariable::public(0), FlatVariable::new(2)),
+        ]);
+
+        assert_eq!(p.underconstrained_variables().len(), 2);
+    }
+
+    #[test]
+    fn packed_xor() {
+        // # _1, _2, _3 = Bits(_0)
+        // _i == _i * _i for i in 1, 2
+        // 2 * _1 * _2 == _1 + _2 - _3
+        // _0 == 4 * _1 + 2 * _2 + _3
+        // ~out_0 = _3
+        let p = prog(vec![
+            directive(
+                vec![
+                    FlatVariable::new(1),
+                    FlatVariable::new(2),
+                    FlatVariable::new(3),
+                ],
+                RustHelper::Bits(3),
+                vec![FlatVariable::new(0)],
+            ),
+            product(
+                FlatVariable::new(1),
+                FlatVariable::new(1),
+                FlatVariable::new(1).into(),
+            ),
+            product(
+                FlatVariable::new(2),
+                FlatVariable::new(2),
+                FlatVariable::new(2).into(),
+            ),
+            // _3 is boolean as the xor of two booleans
+            Statement::constraint(
+                QuadComb::from_linear_combinations(
+                    LinComb::summand(2, FlatVariable::new(1)),
+                    FlatVariable::new(2).into(),
+                ),
+                LinComb::from(FlatVariable::new(1)) + FlatVariable::new(2).into()
+                    - FlatVariable::new(3).into(),
+            ),
+            Statement::definition(
+                FlatVariable::new(0),
+                LinComb::summand(4, FlatVariable::new(1))
+                    + LinComb::summand(2, FlatVariable::new(2))
+                    + FlatVariable::new(3).into(),
+            ),
+            Statement::definition(FlatVariable::public(0), FlatVariable::new(3)),
+        ]);
+
+        assert_eq!(p.underconstrained_variables(), vec![]);
+    }
+
+    fn bits(bitwidth: usize) -> Prog<FieldPrime> {
+        // # _1, ..., _n = Bits(_0)
+        // _i == _i * _i
+        // _0 == sum(2**(n - i) * _i)
+        // ~out_0 = _1
+        let bits: Vec<_> = (1..=bitwidth).map(FlatVariable::new).collect();
+
+        prog(
+            std::iter::once(directive(
+                bits.clone(),
+                RustHelper::Bits(bitwidth),
+                vec![FlatVariable::new(0)],
+            ))
+            .chain(bits.iter().map(|b| product(*b, *b, (*b).into())))
+            .chain(std::iter::once(Statement::definition(
+                FlatVariable::new(0),
+                LinComb(
+                    bits.iter()
+                        .enumerate()
+                        .map(|(i, b)| (*b, FieldPrime::from(2).pow(bitwidth - i - 1)))
+                        .collect(),
+                ),
+            )))
+            .chain(std::iter::once(Statement::definition(
+                FlatVariable::public(0),
+                bits[0],
+            )))
+            .collect(),
+        )
+    }
+
+    #[test]
+    fn binary_decomposition() {
+        assert_eq!(bits(8).underconstrained_variables(), vec![]);
+        assert_eq!(bits(253).underconstrained_variables(), vec![]);
+    }
+
+    #[test]
+    fn ambiguous_binary_decomposition() {
+        // 2**254 - 1 exceeds the modulus, so that small values have two decompositions
+        assert_eq!(bits(254).underconstrained_variables().len(), 254 + 1);
+    }
+}
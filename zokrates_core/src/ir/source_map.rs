@@ -0,0 +1,46 @@
+use crate::flat_absy::{Origin, SourceLocation};
+use std::collections::BTreeMap;
+use std::ops::Range;
+
+/// The source locations of the statements of a function, indexed by statement. Only the
+/// constraints checking an assertion of the source code have a location. When the program was
+/// compiled for profiling, every statement also has the origin it was generated from.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Default)]
+pub struct SourceMap {
+    locations: BTreeMap<usize, SourceLocation>,
+    origins: BTreeMap<usize, Origin>,
+}
+
+impl SourceMap {
+    pub fn get(&self, statement: usize) -> Option<&SourceLocation> {
+        self.locations.get(&statement)
+    }
+
+    pub fn insert(&mut self, statement: usize, location: SourceLocation) {
+        self.locations.insert(statement, location);
+    }
+
+    pub fn get_origin(&self, statement: usize) -> Option<&Origin> {
+        self.origins.get(&statement)
+    }
+
+    pub fn insert_origin(&mut self, statement: usize, origin: Origin) {
+        self.origins.insert(statement, origin);
+    }
+
+    /// Gives the statements in `range` the location and origin of `statement` in `other`
+    pub fn extend_from(&mut self, other: &SourceMap, statement: usize, range: Range<usize>) {
+        for i in range {
+            if let Some(location) = other.get(statement) {
+                self.insert(i, location.clone());
+            }
+            if let Some(origin) = other.get_origin(statement) {
+                self.insert_origin(i, origin.clone());
+            }
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.locations.is_empty() && self.origins.is_empty()
+    }
+}
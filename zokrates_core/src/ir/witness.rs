@@ -1,30 +1,41 @@
 use crate::flat_absy::FlatVariable;
-use std::collections::{BTreeMap, HashMap};
+use crate::ir::r1cs::{field_size, prime, to_biguint, to_bytes};
+use num_bigint::BigUint;
+use serde_json;
+use std::collections::BTreeMap;
 use std::fmt;
 use std::io;
 use std::io::{Read, Write};
+use std::iter::FromIterator;
+use std::ops::Index;
 use zokrates_field::field::Field;
 
-#[derive(Clone, Debug, PartialEq)]
-pub struct Witness<T: Field>(pub BTreeMap<FlatVariable, T>);
+const WTNS_MAGIC: &[u8] = b"wtns";
+const WTNS_VERSION: u32 = 2;
+
+const HEADER_SECTION: u32 = 1;
+const WITNESS_SECTION: u32 = 2;
+
+/// An assignment of values to variables. As variables are numbered consecutively, the values are
+/// stored in vectors indexed by variable rather than in a map.
+#[derive(Clone, Debug)]
+pub struct Witness<T: Field> {
+    /// The values of ~one at index 0 and of the `_i` variables at index `i + 1`
+    values: Vec<Option<T>>,
+    /// The values of the `~out_i` variables at index `i`
+    outputs: Vec<Option<T>>,
+}
 
 impl<T: Field> Witness<T> {
     pub fn return_values(&self) -> Vec<T> {
-        let out = self
-            .0
+        self.outputs
             .iter()
-            .filter(|(k, _)| k.is_output())
-            .collect::<HashMap<_, _>>();
-
-        (0..out.len())
-            .map(|i| *out.get(&FlatVariable::public(i)).unwrap())
-            .cloned()
+            .map(|value| value.clone().unwrap())
             .collect()
     }
 
     pub fn format_outputs(&self) -> String {
-        self.0
-            .iter()
+        self.iter()
             .filter_map(|(variable, value)| match variable {
                 variable if variable.is_output() => Some(format!("{} {}", variable, value)),
                 _ => None,
@@ -34,9 +45,82 @@ impl<T: Field> Witness<T> {
     }
 
     pub fn empty() -> Self {
-        Witness(BTreeMap::new())
+        Witness {
+            values: vec![],
+            outputs: vec![],
+        }
+    }
+
+    /// Creates an empty witness with room for the values of `variables` variables other than the
+    /// outputs
+    pub fn with_capacity(variables: usize) -> Self {
+        Witness {
+            values: Vec::with_capacity(variables),
+            outputs: vec![],
+        }
+    }
+
+    pub fn get(&self, variable: &FlatVariable) -> Option<&T> {
+        let (values, index) = match variable.is_output() {
+            true => (&self.outputs, variable.output_index()),
+            false => (&self.values, index(variable)),
+        };
+        values.get(index).and_then(|value| value.as_ref())
+    }
+
+    pub fn contains(&self, variable: &FlatVariable) -> bool {
+        self.get(variable).is_some()
+    }
+
+    /// Sets the value of `variable`, returning its previous value
+    pub fn insert(&mut self, variable: FlatVariable, value: T) -> Option<T> {
+        let (values, index) = self.slot(&variable);
+        if index >= values.len() {
+            values.resize(index + 1, None);
+        }
+        values[index].replace(value)
+    }
+
+    pub fn remove(&mut self, variable: &FlatVariable) -> Option<T> {
+        let (values, index) = self.slot(variable);
+        values.get_mut(index).and_then(|value| value.take())
+    }
+
+    /// Returns the number of variables with a value
+    pub fn len(&self) -> usize {
+        self.iter().count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.iter().next().is_none()
+    }
+
+    /// Iterates over the variables with a value in the order of their ids: the outputs from last
+    /// to first, ~one and the other variables
+    pub fn iter(&self) -> impl Iterator<Item = (FlatVariable, &T)> {
+        let outputs = self
+            .outputs
+            .iter()
+            .enumerate()
+            .rev()
+            .filter_map(|(i, value)| value.as_ref().map(|value| (FlatVariable::public(i), value)));
+        let values = self.values.iter().enumerate().filter_map(|(i, value)| {
+            value.as_ref().map(|value| match i {
+                0 => (FlatVariable::one(), value),
+                i => (FlatVariable::new(i - 1), value),
+            })
+        });
+        outputs.chain(values)
+    }
+
+    fn slot(&mut self, variable: &FlatVariable) -> (&mut Vec<Option<T>>, usize) {
+        match variable.is_output() {
+            true => (&mut self.outputs, variable.output_index()),
+            false => (&mut self.values, index(variable)),
+        }
     }
 
+    /// Writes the witness as space-delimited `variable value` lines
     pub fn write<W: Write>(&self, writer: W) -> io::Result<()> {
         let mut wtr = csv::WriterBuilder::new()
             .delimiter(b' ')
@@ -45,47 +129,220 @@ impl<T: Field> Witness<T> {
             .from_writer(writer);
 
         // Write each line of the witness to the file
-        for (variable, value) in &self.0 {
+        for (variable, value) in self.iter() {
             wtr.serialize((variable.to_string(), value.to_dec_string()))?;
         }
 
         Ok(())
     }
 
-    pub fn read<R: Read>(mut reader: R) -> io::Result<Self> {
+    /// Writes the witness as a JSON object mapping each variable to its value
+    pub fn write_json<W: Write>(&self, writer: W) -> serde_json::Result<()> {
+        let map: BTreeMap<String, String> = self
+            .iter()
+            .map(|(variable, value)| (variable.to_string(), value.to_dec_string()))
+            .collect();
+
+        serde_json::to_writer_pretty(writer, &map)
+    }
+
+    /// Writes the values of `wires` in the `.wtns` binary format of iden3. Passing the wires of
+    /// `Prog::r1cs` orders the values like the exported constraint system.
+    pub fn write_wtns<W: Write>(&self, wires: &[FlatVariable], mut writer: W) -> io::Result<()> {
+        let n8 = field_size::<T>();
+
+        let mut header = vec![];
+        header.extend_from_slice(&(n8 as u32).to_le_bytes());
+        header.extend(to_bytes(&prime::<T>(), n8));
+        header.extend_from_slice(&(wires.len() as u32).to_le_bytes());
+
+        let mut values = vec![];
+        for variable in wires {
+            let value = self.get(variable).ok_or_else(|| {
+                invalid_witness(format!("Missing value of {} in witness", variable))
+            })?;
+            values.extend(to_bytes(&to_biguint(value), n8));
+        }
+
+        writer.write_all(WTNS_MAGIC)?;
+        writer.write_all(&WTNS_VERSION.to_le_bytes())?;
+        writer.write_all(&2u32.to_le_bytes())?;
+        for (section_type, section) in &[(HEADER_SECTION, header), (WITNESS_SECTION, values)] {
+            writer.write_all(&section_type.to_le_bytes())?;
+            writer.write_all(&(section.len() as u64).to_le_bytes())?;
+            writer.write_all(section)?;
+        }
+        writer.flush()
+    }
+
+    /// Reads a witness written by `write`, `write_json` or `write_wtns`, detecting its format. The
+    /// values of a `.wtns` witness are assigned to `wires` in order, the other formats ignore them.
+    pub fn read<R: Read>(mut reader: R, wires: &[FlatVariable]) -> io::Result<Self> {
+        let mut bytes = vec![];
+        reader.read_to_end(&mut bytes)?;
+
+        if bytes.starts_with(WTNS_MAGIC) {
+            return Self::read_wtns(&bytes[WTNS_MAGIC.len()..], wires);
+        }
+
+        match bytes.iter().find(|b| !b.is_ascii_whitespace()) {
+            Some(&b'{') => Self::read_json(&bytes),
+            _ => Self::read_csv(&bytes[..]),
+        }
+    }
+
+    fn read_csv<R: Read>(mut reader: R) -> io::Result<Self> {
         let mut rdr = csv::ReaderBuilder::new()
             .delimiter(b' ')
             .flexible(true)
             .has_headers(false)
             .from_reader(&mut reader);
 
-        let map = rdr
-            .deserialize::<(String, String)>()
+        rdr.deserialize::<(String, String)>()
             .map(|r| {
-                r.map(|(variable, value)| {
-                    let variable =
-                        FlatVariable::try_from_human_readable(&variable).map_err(|why| {
-                            io::Error::new(
-                                io::ErrorKind::Other,
-                                format!("Invalid variable in witness: {}", why),
-                            )
-                        })?;
-                    let value = T::try_from_dec_str(&value).map_err(|_| {
-                        io::Error::new(
-                            io::ErrorKind::Other,
-                            format!("Invalid value in witness: {}", value),
-                        )
-                    })?;
-                    Ok((variable, value))
-                })
-                .map_err(|e| match e.into_kind() {
-                    csv::ErrorKind::Io(e) => e,
-                    e => io::Error::new(io::ErrorKind::Other, format!("{:?}", e)),
-                })?
+                r.map(|(variable, value)| parse_entry(&variable, &value))
+                    .map_err(|e| match e.into_kind() {
+                        csv::ErrorKind::Io(e) => e,
+                        e => io::Error::new(io::ErrorKind::Other, format!("{:?}", e)),
+                    })?
             })
-            .collect::<io::Result<BTreeMap<FlatVariable, T>>>()?;
+            .collect::<io::Result<Witness<T>>>()
+    }
+
+    fn read_json(bytes: &[u8]) -> io::Result<Self> {
+        let entries: BTreeMap<String, String> = serde_json::from_slice(bytes)
+            .map_err(|why| invalid_witness(format!("Invalid JSON witness: {}", why)))?;
 
-        Ok(Witness(map))
+        entries
+            .iter()
+            .map(|(variable, value)| parse_entry(variable, value))
+            .collect::<io::Result<Witness<T>>>()
+    }
+
+    // `bytes` starts after the magic number
+    fn read_wtns(mut bytes: &[u8], wires: &[FlatVariable]) -> io::Result<Self> {
+        let version = read_u32(&mut bytes)?;
+        if version != WTNS_VERSION {
+            return Err(invalid_witness(format!(
+                "Unsupported wtns version: {}",
+                version
+            )));
+        }
+
+        let mut header = None;
+        let mut values = None;
+        for _ in 0..read_u32(&mut bytes)? {
+            let section_type = read_u32(&mut bytes)?;
+            let size = read_u64(&mut bytes)? as usize;
+            if size > bytes.len() {
+                return Err(io::ErrorKind::UnexpectedEof.into());
+            }
+            let (section, rest) = bytes.split_at(size);
+            bytes = rest;
+            match section_type {
+                HEADER_SECTION => header = Some(section),
+                WITNESS_SECTION => values = Some(section),
+                _ => {}
+            }
+        }
+
+        let mut header =
+            header.ok_or_else(|| invalid_witness("Missing wtns header section".to_string()))?;
+        let values =
+            values.ok_or_else(|| invalid_witness("Missing wtns witness section".to_string()))?;
+
+        let n8 = read_u32(&mut header)? as usize;
+        let prime = prime::<T>();
+        if n8 != field_size::<T>()
+            || header.len() < n8
+            || BigUint::from_bytes_le(&header[..n8]) != prime
+        {
+            return Err(invalid_witness(
+                "Witness is not defined over the field of the curve".to_string(),
+            ));
+        }
+        header = &header[n8..];
+
+        let count = read_u32(&mut header)? as usize;
+        if count != wires.len() || values.len() != count * n8 {
+            return Err(invalid_witness(format!(
+                "Witness has {} values, expected {}",
+                count,
+                wires.len()
+            )));
+        }
+
+        wires
+            .iter()
+            .zip(values.chunks(n8))
+            .map(|(variable, chunk)| {
+                let value = BigUint::from_bytes_le(chunk);
+                if value >= prime {
+                    return Err(invalid_witness(format!(
+                        "Invalid value in witness: {}",
+                        value
+                    )));
+                }
+                Ok((*variable, T::from_byte_vector(chunk.to_vec())))
+            })
+            .collect::<io::Result<Witness<T>>>()
+    }
+}
+
+fn parse_entry<T: Field>(variable: &str, value: &str) -> io::Result<(FlatVariable, T)> {
+    let variable = FlatVariable::try_from_human_readable(variable)
+        .map_err(|why| invalid_witness(format!("Invalid variable in witness: {}", why)))?;
+    let value = T::try_from_dec_str(value)
+        .map_err(|_| invalid_witness(format!("Invalid value in witness: {}", value)))?;
+    Ok((variable, value))
+}
+
+fn read_u32<R: Read>(reader: &mut R) -> io::Result<u32> {
+    let mut bytes = [0u8; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn read_u64<R: Read>(reader: &mut R) -> io::Result<u64> {
+    let mut bytes = [0u8; 8];
+    reader.read_exact(&mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+fn invalid_witness(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::Other, message)
+}
+
+// the index of a variable other than an output in `Witness::values`
+fn index(variable: &FlatVariable) -> usize {
+    match *variable == FlatVariable::one() {
+        true => 0,
+        false => variable.id() + 1,
+    }
+}
+
+impl<T: Field> PartialEq for Witness<T> {
+    fn eq(&self, other: &Witness<T>) -> bool {
+        self.iter().eq(other.iter())
+    }
+}
+
+impl<'a, T: Field> Index<&'a FlatVariable> for Witness<T> {
+    type Output = T;
+
+    fn index(&self, variable: &FlatVariable) -> &T {
+        self.get(variable)
+            .unwrap_or_else(|| panic!("No value for {} in witness", variable))
+    }
+}
+
+impl<T: Field> FromIterator<(FlatVariable, T)> for Witness<T> {
+    fn from_iter<I: IntoIterator<Item = (FlatVariable, T)>>(iter: I) -> Self {
+        let mut witness = Witness::empty();
+        for (variable, value) in iter {
+            witness.insert(variable, value);
+        }
+        witness
     }
 }
 
@@ -94,8 +351,7 @@ impl<T: Field> fmt::Display for Witness<T> {
         write!(
             f,
             "{}",
-            self.0
-                .iter()
+            self.iter()
                 .map(|(k, v)| format!("{} {}", k, v.to_dec_string()))
                 .collect::<Vec<_>>()
                 .join("\n")
@@ -108,32 +364,176 @@ mod tests {
     use super::*;
     use zokrates_field::field::FieldPrime;
 
-    mod io {
+    mod dense {
         use super::*;
-        use std::io::Cursor;
 
         #[test]
-        fn serialize_deserialize() {
-            let w = Witness(
+        fn insert_get_remove() {
+            let mut w = Witness::empty();
+
+            assert_eq!(w.insert(FlatVariable::new(3), FieldPrime::from(3)), None);
+            assert_eq!(w.insert(FlatVariable::public(1), FieldPrime::from(1)), None);
+            assert_eq!(
+                w.insert(FlatVariable::new(3), FieldPrime::from(4)),
+                Some(FieldPrime::from(3))
+            );
+
+            assert_eq!(w.get(&FlatVariable::new(3)), Some(&FieldPrime::from(4)));
+            assert_eq!(w.get(&FlatVariable::new(2)), None);
+            assert_eq!(w.get(&FlatVariable::new(42)), None);
+            assert_eq!(w.get(&FlatVariable::public(0)), None);
+            assert_eq!(w.len(), 2);
+
+            assert_eq!(
+                w.remove(&FlatVariable::public(1)),
+                Some(FieldPrime::from(1))
+            );
+            assert!(!w.contains(&FlatVariable::public(1)));
+            assert_eq!(w.len(), 1);
+        }
+
+        #[test]
+        fn iterate_in_order_of_ids() {
+            let w: Witness<FieldPrime> = vec![
+                (FlatVariable::new(1), FieldPrime::from(4)),
+                (FlatVariable::public(0), FieldPrime::from(1)),
+                (FlatVariable::one(), FieldPrime::from(1)),
+                (FlatVariable::public(1), FieldPrime::from(2)),
+                (FlatVariable::new(0), FieldPrime::from(3)),
+            ]
+            .into_iter()
+            .collect();
+
+            assert_eq!(
+                w.iter().map(|(v, _)| v).collect::<Vec<_>>(),
                 vec![
-                    (FlatVariable::new(42), FieldPrime::from(42)),
-                    (FlatVariable::public(8), FieldPrime::from(8)),
-                    (FlatVariable::one(), FieldPrime::from(1)),
+                    FlatVariable::public(1),
+                    FlatVariable::public(0),
+                    FlatVariable::one(),
+                    FlatVariable::new(0),
+                    FlatVariable::new(1)
                 ]
-                .into_iter()
-                .collect(),
             );
+            assert_eq!(
+                w.return_values(),
+                vec![FieldPrime::from(1), FieldPrime::from(2)]
+            );
+        }
+
+        #[test]
+        fn equal_after_removal() {
+            let mut w: Witness<FieldPrime> = vec![
+                (FlatVariable::one(), FieldPrime::from(1)),
+                (FlatVariable::new(7), FieldPrime::from(7)),
+            ]
+            .into_iter()
+            .collect();
+
+            w.remove(&FlatVariable::new(7));
+
+            assert_eq!(
+                w,
+                vec![(FlatVariable::one(), FieldPrime::from(1))]
+                    .into_iter()
+                    .collect()
+            );
+        }
+    }
+
+    mod io {
+        use super::*;
+        use std::io::Cursor;
+
+        fn witness() -> Witness<FieldPrime> {
+            vec![
+                (FlatVariable::new(42), FieldPrime::from(42)),
+                (FlatVariable::public(8), FieldPrime::from(8)),
+                (FlatVariable::one(), FieldPrime::from(1)),
+            ]
+            .into_iter()
+            .collect()
+        }
+
+        #[test]
+        fn serialize_deserialize() {
+            let w = witness();
 
             let mut buff = Cursor::new(vec![]);
 
             w.write(&mut buff).unwrap();
             buff.set_position(0);
 
-            let r = Witness::read(buff).unwrap();
+            let r = Witness::read(buff, &[]).unwrap();
 
             assert_eq!(w, r);
         }
 
+        #[test]
+        fn serialize_deserialize_json() {
+            let w = witness();
+
+            let mut buff = Cursor::new(vec![]);
+
+            w.write_json(&mut buff).unwrap();
+            buff.set_position(0);
+
+            let r = Witness::read(buff, &[]).unwrap();
+
+            assert_eq!(w, r);
+        }
+
+        #[test]
+        fn serialize_deserialize_wtns() {
+            let w = witness();
+            let wires = vec![
+                FlatVariable::one(),
+                FlatVariable::public(8),
+                FlatVariable::new(42),
+            ];
+
+            let mut buff = Cursor::new(vec![]);
+
+            w.write_wtns(&wires, &mut buff).unwrap();
+
+            // magic, version, 2 sections, a header of 40 bytes and 3 values of 32 bytes
+            assert_eq!(&buff.get_ref()[0..4], b"wtns");
+            assert_eq!(&buff.get_ref()[60..64], &3u32.to_le_bytes());
+            assert_eq!(buff.get_ref().len(), 172);
+            // ~one is the first value
+            assert_eq!(buff.get_ref()[76], 1);
+
+            buff.set_position(0);
+
+            let r = Witness::read(buff, &wires).unwrap();
+
+            assert_eq!(w, r);
+        }
+
+        #[test]
+        fn wtns_wrong_wires() {
+            let w = witness();
+
+            let mut buff = Cursor::new(vec![]);
+
+            w.write_wtns(&[FlatVariable::one()], &mut buff).unwrap();
+            buff.set_position(0);
+
+            assert!(Witness::<FieldPrime>::read(
+                buff,
+                &[FlatVariable::one(), FlatVariable::new(42)]
+            )
+            .is_err());
+        }
+
+        #[test]
+        fn wtns_missing_value() {
+            let w = witness();
+
+            assert!(w
+                .write_wtns(&[FlatVariable::new(43)], &mut Cursor::new(vec![]))
+                .is_err());
+        }
+
         #[test]
         fn wrong_value() {
             let mut buff = Cursor::new(vec![]);
@@ -141,7 +541,7 @@ mod tests {
             buff.write("_1 123bug".as_ref()).unwrap();
             buff.set_position(0);
 
-            assert!(Witness::<FieldPrime>::read(buff).is_err());
+            assert!(Witness::<FieldPrime>::read(buff, &[]).is_err());
         }
 
         #[test]
@@ -151,7 +551,7 @@ mod tests {
             buff.write("_1bug 123".as_ref()).unwrap();
             buff.set_position(0);
 
-            assert!(Witness::<FieldPrime>::read(buff).is_err());
+            assert!(Witness::<FieldPrime>::read(buff, &[]).is_err());
         }
 
         #[test]
@@ -160,7 +560,7 @@ mod tests {
             buff.write("whatwhat".as_ref()).unwrap();
             buff.set_position(0);
 
-            assert!(Witness::<FieldPrime>::read(buff).is_err());
+            assert!(Witness::<FieldPrime>::read(buff, &[]).is_err());
         }
     }
 }
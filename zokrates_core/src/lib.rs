@@ -26,18 +26,21 @@ extern crate zokrates_field;
 extern crate zokrates_pest_ast;
 
 mod flatten;
+#[cfg(test)]
+mod fuzz;
 mod helpers;
-mod imports;
-mod optimizer;
 mod parser;
 mod semantics;
 mod standard;
 mod static_analysis;
-mod typed_absy;
-mod types;
 
+pub mod abi;
 pub mod absy;
 pub mod compile;
 pub mod flat_absy;
+pub mod imports;
 pub mod ir;
+pub mod optimizer;
 pub mod proof_system;
+pub mod typed_absy;
+pub mod types;
@@ -0,0 +1,308 @@
+//! Module containing the `DuplicateOptimizer` to remove code of the form
+// ```
+// # b = Bits(a)
+// # c = Bits(a)
+// d = a * b
+// e = b * a
+// d == e
+// d == e
+// ```
+// and replace by
+// ```
+// # b = Bits(a)
+// d = a * b
+// d == d
+// ```
+// replacing later uses of `c` by `b` and of `e` by `d`
+
+use crate::flat_absy::flat_variable::FlatVariable;
+use crate::helpers::Helper;
+use crate::ir::folder::{fold_function, Folder};
+use crate::ir::*;
+use std::collections::{HashMap, HashSet};
+use zokrates_field::field::Field;
+
+type CanonicalQuadComb<T> = (CanonicalLinComb<T>, CanonicalLinComb<T>);
+
+#[derive(Debug)]
+pub struct DuplicateOptimizer<T: Field> {
+    /// Map of renamings for variables computed a second time
+    substitution: HashMap<FlatVariable, FlatVariable>,
+    /// Variables which have a value at this point of the program
+    known: HashSet<FlatVariable>,
+    /// Outputs of the function, which cannot be renamed
+    returns: HashSet<FlatVariable>,
+    /// Constraints seen so far
+    constraints: HashSet<(CanonicalQuadComb<T>, CanonicalLinComb<T>)>,
+    /// The variable each quadratic combination was assigned to
+    definitions: HashMap<CanonicalQuadComb<T>, FlatVariable>,
+    /// The helpers called on each list of inputs, and their outputs
+    directives: HashMap<Vec<CanonicalLinComb<T>>, Vec<(Helper, Vec<FlatVariable>)>>,
+}
+
+impl<T: Field> DuplicateOptimizer<T> {
+    fn new() -> DuplicateOptimizer<T> {
+        DuplicateOptimizer {
+            substitution: HashMap::new(),
+            known: HashSet::new(),
+            returns: HashSet::new(),
+            constraints: HashSet::new(),
+            definitions: HashMap::new(),
+            directives: HashMap::new(),
+        }
+    }
+
+    pub fn optimize(p: Prog<T>) -> Prog<T> {
+        DuplicateOptimizer::new().fold_program(p)
+    }
+}
+
+impl<T: Field> Folder<T> for DuplicateOptimizer<T> {
+    fn fold_statement(&mut self, s: Statement<T>) -> Vec<Statement<T>> {
+        match s {
+            Statement::Constraint(quad, lin) => {
+                let quad = self.fold_quadratic_combination(quad);
+                let lin = self.fold_linear_combination(lin);
+
+                // the product is commutative
+                let left = quad.left.as_canonical();
+                let right = quad.right.as_canonical();
+                let key = match left <= right {
+                    true => (left, right),
+                    false => (right, left),
+                };
+
+                // the interpreter assigns `quad` to `lin` if it is a single variable without a value
+                let assignee = match lin.0.len() == 1 && lin.0[0].1 == T::one() {
+                    true => Some(lin.0[0].0).filter(|v| !self.known.contains(v)),
+                    false => None,
+                };
+
+                if let Some(v) = assignee {
+                    match self.definitions.get(&key).cloned() {
+                        Some(previous) if !self.returns.contains(&v) => {
+                            self.substitution.insert(v, previous);
+                            return vec![];
+                        }
+                        _ => {
+                            self.definitions.entry(key.clone()).or_insert(v);
+                        }
+                    }
+                }
+
+                if !self.constraints.insert((key, lin.as_canonical())) {
+                    return vec![];
+                }
+
+                self.known.extend(
+                    quad.left
+                        .0
+                        .iter()
+                        .chain(&quad.right.0)
+                        .chain(&lin.0)
+                        .map(|(v, _)| *v),
+                );
+                vec![Statement::Constraint(quad, lin)]
+            }
+            Statement::Directive(d) => {
+                let d = self.fold_directive(d);
+                let inputs: Vec<_> = d.inputs.iter().map(|i| i.as_canonical()).collect();
+
+                let previous = self.directives.get(&inputs).and_then(|directives| {
+                    directives
+                        .iter()
+                        .find(|(helper, _)| *helper == d.helper)
+                        .map(|(_, outputs)| outputs.clone())
+                });
+
+                match previous {
+                    Some(ref outputs) if d.outputs.iter().all(|o| !self.returns.contains(o)) => {
+                        self.substitution
+                            .extend(d.outputs.into_iter().zip(outputs.iter().cloned()));
+                        vec![]
+                    }
+                    _ => {
+                        self.known.extend(d.outputs.iter().cloned());
+                        self.directives
+                            .entry(inputs)
+                            .or_insert_with(Vec::new)
+                            .push((d.helper.clone(), d.outputs.clone()));
+                        vec![Statement::Directive(d)]
+                    }
+                }
+            }
+        }
+    }
+
+    fn fold_variable(&mut self, v: FlatVariable) -> FlatVariable {
+        *self.substitution.get(&v).unwrap_or(&v)
+    }
+
+    fn fold_argument(&mut self, a: FlatVariable) -> FlatVariable {
+        self.known.insert(a);
+        a
+    }
+
+    fn fold_function(&mut self, fun: Function<T>) -> Function<T> {
+        self.substitution.clear();
+        self.constraints.clear();
+        self.definitions.clear();
+        self.directives.clear();
+
+        self.known = vec![FlatVariable::one()].into_iter().collect();
+        self.returns = fun.returns.iter().cloned().collect();
+
+        fold_function(self, fun)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::flat_absy::{FlatExpression, FlatStatement};
+    use crate::helpers::RustHelper;
+    use zokrates_field::field::FieldPrime;
+
+    fn product(a: FlatVariable, b: FlatVariable) -> FlatExpression<FieldPrime> {
+        FlatExpression::Mult(box a.into(), box b.into())
+    }
+
+    #[test]
+    fn remove_duplicate_definitions() {
+        // def main(x, y) -> (1):
+        //     a = x * y
+        //     b = y * x
+        //     out = a * b
+        //     return out
+
+        // ->
+
+        // def main(x, y) -> (1):
+        //     a = x * y
+        //     out = a * a
+        //     return out
+
+        let x = FlatVariable::new(0);
+        let y = FlatVariable::new(1);
+        let a = FlatVariable::new(2);
+        let b = FlatVariable::new(3);
+        let out = FlatVariable::public(0);
+
+        let f: Function<FieldPrime> = Function {
+            id: "foo".to_string(),
+            arguments: vec![x, y],
+            statements: vec![
+                FlatStatement::Definition(a, product(x, y)).into(),
+                FlatStatement::Definition(b, product(y, x)).into(),
+                FlatStatement::Definition(out, product(a, b)).into(),
+            ],
+            returns: vec![out],
+            source_map: SourceMap::default(),
+        };
+
+        let optimized: Function<FieldPrime> = Function {
+            id: "foo".to_string(),
+            arguments: vec![x, y],
+            statements: vec![
+                FlatStatement::Definition(a, product(x, y)).into(),
+                FlatStatement::Definition(out, product(a, a)).into(),
+            ],
+            returns: vec![out],
+            source_map: SourceMap::default(),
+        };
+
+        let mut optimizer = DuplicateOptimizer::new();
+        assert_eq!(optimizer.fold_function(f), optimized);
+    }
+
+    #[test]
+    fn remove_duplicate_directives() {
+        // def main(x) -> (1):
+        //     # a = Identity(x)
+        //     # b = Identity(x)
+        //     a * a == x
+        //     b * b == x
+        //     out = a * b
+        //     return out
+
+        // ->
+
+        // def main(x) -> (1):
+        //     # a = Identity(x)
+        //     a * a == x
+        //     out = a * a
+        //     return out
+
+        let x = FlatVariable::new(0);
+        let a = FlatVariable::new(1);
+        let b = FlatVariable::new(2);
+        let out = FlatVariable::public(0);
+
+        let identity = |o| {
+            Statement::Directive(Directive {
+                inputs: vec![x.into()],
+                outputs: vec![o],
+                helper: Helper::Rust(RustHelper::Identity),
+            })
+        };
+
+        let f: Function<FieldPrime> = Function {
+            id: "foo".to_string(),
+            arguments: vec![x],
+            statements: vec![
+                identity(a),
+                identity(b),
+                FlatStatement::Condition(x.into(), product(a, a), None).into(),
+                FlatStatement::Condition(x.into(), product(b, b), None).into(),
+                FlatStatement::Definition(out, product(a, b)).into(),
+            ],
+            returns: vec![out],
+            source_map: SourceMap::default(),
+        };
+
+        let optimized: Function<FieldPrime> = Function {
+            id: "foo".to_string(),
+            arguments: vec![x],
+            statements: vec![
+                identity(a),
+                FlatStatement::Condition(x.into(), product(a, a), None).into(),
+                FlatStatement::Definition(out, product(a, a)).into(),
+            ],
+            returns: vec![out],
+            source_map: SourceMap::default(),
+        };
+
+        let mut optimizer = DuplicateOptimizer::new();
+        assert_eq!(optimizer.fold_function(f), optimized);
+    }
+
+    #[test]
+    fn keep_definitions_of_returns() {
+        // def main(x) -> (2):
+        //     ~out_0 = x * x
+        //     ~out_1 = x * x
+        //     return ~out_0, ~out_1
+
+        // ->
+
+        // unchanged
+
+        let x = FlatVariable::new(0);
+
+        let f: Function<FieldPrime> = Function {
+            id: "foo".to_string(),
+            arguments: vec![x],
+            statements: vec![
+                FlatStatement::Definition(FlatVariable::public(0), product(x, x)).into(),
+                FlatStatement::Definition(FlatVariable::public(1), product(x, x)).into(),
+            ],
+            returns: vec![FlatVariable::public(0), FlatVariable::public(1)],
+            source_map: SourceMap::default(),
+        };
+
+        let optimized = f.clone();
+
+        let mut optimizer = DuplicateOptimizer::new();
+        assert_eq!(optimizer.fold_function(f), optimized);
+    }
+}
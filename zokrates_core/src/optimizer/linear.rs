@@ -0,0 +1,374 @@
+//! Module containing the `LinearOptimizer` to remove linear constraints by substitution
+// ```
+// c = a * b
+// c == a + 1
+// d = c * c
+// ```
+// and replace by
+// ```
+// a * b == a + 1
+// d = (a + 1) * (a + 1)
+// ```
+//
+// A linear constraint is removed by solving it for one of its variables, and substituting the
+// solution everywhere this variable is used. The witness is computed by executing the statements in
+// order, so the variable we solve for is the one which gets its value last: the other variables of
+// the solution are then known wherever it is substituted.
+
+use crate::flat_absy::flat_variable::FlatVariable;
+use crate::flat_absy::SourceLocation;
+use crate::ir::*;
+use std::collections::{HashMap, HashSet};
+use zokrates_field::field::Field;
+
+#[derive(Debug)]
+pub struct LinearOptimizer {
+    /// For each variable, 0 if it is known before any statement is executed, `index + 1` if it is
+    /// set by the statement at `index`
+    definitions: HashMap<FlatVariable, usize>,
+    /// Variables which must not be substituted: ~one, the arguments, the returns and the variables
+    /// the directives take as input
+    kept: HashSet<FlatVariable>,
+    /// The indices of the constraints each variable appears in
+    uses: HashMap<FlatVariable, Vec<usize>>,
+}
+
+impl LinearOptimizer {
+    fn new() -> LinearOptimizer {
+        LinearOptimizer {
+            definitions: HashMap::new(),
+            kept: HashSet::new(),
+            uses: HashMap::new(),
+        }
+    }
+
+    pub fn optimize<T: Field>(p: Prog<T>) -> Prog<T> {
+        Prog {
+            main: LinearOptimizer::new().optimize_function(p.main),
+            ..p
+        }
+    }
+
+    fn optimize_function<T: Field>(&mut self, f: Function<T>) -> Function<T> {
+        self.kept.insert(FlatVariable::one());
+        self.definitions.insert(FlatVariable::one(), 0);
+        for a in &f.arguments {
+            self.kept.insert(*a);
+            self.definitions.insert(*a, 0);
+        }
+        self.kept.extend(f.returns.iter().cloned());
+
+        for (index, s) in f.statements.iter().enumerate() {
+            match s {
+                Statement::Constraint(quad, lin) => {
+                    for (variable, _) in quad.left.0.iter().chain(&quad.right.0).chain(&lin.0) {
+                        self.add_use(*variable, index);
+                    }
+                    // the interpreter sets the variable of `quad == variable` if it is not known yet
+                    if lin.0.len() == 1 && lin.0[0].1 == T::one() {
+                        self.definitions.entry(lin.0[0].0).or_insert(index + 1);
+                    }
+                }
+                Statement::Directive(d) => {
+                    for i in &d.inputs {
+                        self.kept.extend(i.0.iter().map(|(variable, _)| *variable));
+                    }
+                    for o in &d.outputs {
+                        self.definitions.entry(*o).or_insert(index + 1);
+                    }
+                }
+            }
+        }
+
+        let mut statements: Vec<Option<Statement<T>>> =
+            f.statements.into_iter().map(Some).collect();
+        // locations of the removed assertions, which are now checked by the constraint which used to
+        // set the variable we solved for
+        let mut locations: HashMap<usize, SourceLocation> = HashMap::new();
+
+        for index in 0..statements.len() {
+            let solution = match statements[index] {
+                Some(Statement::Constraint(ref quad, ref lin)) => {
+                    quad.try_linear().and_then(|l| self.solve(l - lin.clone()))
+                }
+                _ => None,
+            };
+
+            if let Some((variable, value)) = solution {
+                statements[index] = None;
+
+                for u in self.uses.remove(&variable).unwrap_or_default() {
+                    if let Some(s) = statements[u].take() {
+                        statements[u] = Some(substitute_statement(s, &variable, &value));
+                        for (v, _) in &value.0 {
+                            self.add_use(*v, u);
+                        }
+                    }
+                }
+
+                let definition = self.definitions[&variable] - 1;
+                if let (Some(Statement::Constraint(..)), Some(location), None) = (
+                    &statements[definition],
+                    f.source_map.get(index),
+                    f.source_map.get(definition),
+                ) {
+                    locations
+                        .entry(definition)
+                        .or_insert_with(|| location.clone());
+                }
+            }
+        }
+
+        let mut optimized = vec![];
+        let mut source_map = SourceMap::default();
+        for (index, s) in statements.into_iter().enumerate() {
+            if let Some(s) = s {
+                if let Some(location) = f.source_map.get(index).or(locations.get(&index)) {
+                    source_map.insert(optimized.len(), location.clone());
+                }
+                if let Some(origin) = f.source_map.get_origin(index) {
+                    source_map.insert_origin(optimized.len(), origin.clone());
+                }
+                optimized.push(s);
+            }
+        }
+
+        Function {
+            statements: optimized,
+            source_map,
+            ..f
+        }
+    }
+
+    fn add_use(&mut self, variable: FlatVariable, index: usize) {
+        let uses = self.uses.entry(variable).or_insert_with(Vec::new);
+        if uses.last() != Some(&index) {
+            uses.push(index);
+        }
+    }
+
+    /// Solves `lc == 0` for the variable of `lc` which gets its value after all the others, if it
+    /// can be substituted
+    fn solve<T: Field>(&self, lc: LinComb<T>) -> Option<(FlatVariable, LinComb<T>)> {
+        let lc = lc.as_canonical().0;
+
+        let last = lc
+            .keys()
+            .map(|v| self.definitions.get(v).cloned())
+            .collect::<Option<Vec<_>>>()?
+            .into_iter()
+            .max()?;
+
+        // when several variables are set by the same directive, as in a binary decomposition, we
+        // keep the constraint: solving it would spread the decomposition over other constraints
+        let mut last_variables = lc.keys().filter(|v| self.definitions[*v] == last);
+        let variable = match (last_variables.next(), last_variables.next()) {
+            (Some(v), None) if !self.kept.contains(v) => *v,
+            _ => return None,
+        };
+
+        let inverse = lc[&variable].inverse_mul();
+        let value = LinComb(
+            lc.into_iter()
+                .filter(|(v, _)| *v != variable)
+                .map(|(v, coefficient)| (v, T::zero() - coefficient * &inverse))
+                .collect(),
+        );
+
+        Some((variable, value))
+    }
+}
+
+fn substitute_statement<T: Field>(
+    s: Statement<T>,
+    variable: &FlatVariable,
+    value: &LinComb<T>,
+) -> Statement<T> {
+    match s {
+        Statement::Constraint(mut quad, lin) => {
+            quad.left = substitute(quad.left, variable, value);
+            quad.right = substitute(quad.right, variable, value);
+            Statement::Constraint(quad, substitute(lin, variable, value))
+        }
+        s => s,
+    }
+}
+
+fn substitute<T: Field>(lc: LinComb<T>, variable: &FlatVariable, value: &LinComb<T>) -> LinComb<T> {
+    match lc.0.iter().any(|(v, _)| v == variable) {
+        true => {
+            let (matching, others): (Vec<_>, Vec<_>) =
+                lc.0.into_iter().partition(|(v, _)| v == variable);
+            let coefficient = matching.into_iter().fold(T::zero(), |acc, (_, c)| acc + c);
+            let canonical = (LinComb(others) + value.clone() * &coefficient).as_canonical();
+            LinComb(canonical.0.into_iter().collect())
+        }
+        false => lc,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::flat_absy::{FlatExpression, FlatStatement};
+    use crate::helpers::{Helper, RustHelper};
+    use crate::types::Signature;
+    use zokrates_field::field::FieldPrime;
+
+    fn prog(
+        arguments: Vec<FlatVariable>,
+        statements: Vec<Statement<FieldPrime>>,
+    ) -> Prog<FieldPrime> {
+        Prog {
+            private: vec![false; arguments.len()],
+            main: Function {
+                id: "main".to_string(),
+                arguments,
+                statements,
+                returns: vec![FlatVariable::public(0)],
+                source_map: SourceMap::default(),
+            },
+            signature: Signature::new(),
+        }
+    }
+
+    #[test]
+    fn substitute_constraint_defined_variable() {
+        // def main(a, b) -> (1):
+        //     c = a * b
+        //     c == a + 1
+        //     out = c * c
+        //     return out
+
+        // ->
+
+        // def main(a, b) -> (1):
+        //     a * b == a + 1
+        //     out = (a + 1) * (a + 1)
+        //     return out
+
+        let a = FlatVariable::new(0);
+        let b = FlatVariable::new(1);
+        let c = FlatVariable::new(2);
+        let out = FlatVariable::public(0);
+
+        let a_plus_one = || {
+            FlatExpression::Add(
+                box FlatExpression::Identifier(a),
+                box FlatExpression::Number(FieldPrime::from(1)),
+            )
+        };
+
+        let p = prog(
+            vec![a, b],
+            vec![
+                FlatStatement::Definition(c, FlatExpression::Mult(box a.into(), box b.into()))
+                    .into(),
+                Statement::constraint(c, LinComb::from(a) + LinComb::one()),
+                FlatStatement::Definition(out, FlatExpression::Mult(box c.into(), box c.into()))
+                    .into(),
+            ],
+        );
+
+        let optimized = LinearOptimizer::optimize(p);
+
+        assert_eq!(
+            optimized.main.statements,
+            vec![
+                FlatStatement::Condition(
+                    a_plus_one(),
+                    FlatExpression::Mult(box a.into(), box b.into()),
+                    None
+                )
+                .into(),
+                FlatStatement::Definition(
+                    out,
+                    FlatExpression::Mult(box a_plus_one(), box a_plus_one())
+                )
+                .into(),
+            ]
+        );
+    }
+
+    #[test]
+    fn substitute_directive_output() {
+        // def main(x) -> (1):
+        //     # y = Identity(x)
+        //     y == x
+        //     out = y * y
+        //     return out
+
+        // ->
+
+        // def main(x) -> (1):
+        //     # y = Identity(x)
+        //     out = x * x
+        //     return out
+
+        let x = FlatVariable::new(0);
+        let y = FlatVariable::new(1);
+        let out = FlatVariable::public(0);
+
+        let directive = Statement::Directive(Directive {
+            inputs: vec![x.into()],
+            outputs: vec![y],
+            helper: Helper::Rust(RustHelper::Identity),
+        });
+
+        let p = prog(
+            vec![x],
+            vec![
+                directive.clone(),
+                Statement::constraint(y, x),
+                FlatStatement::Definition(out, FlatExpression::Mult(box y.into(), box y.into()))
+                    .into(),
+            ],
+        );
+
+        let optimized = LinearOptimizer::optimize(p);
+
+        assert_eq!(
+            optimized.main.statements,
+            vec![
+                directive,
+                FlatStatement::Definition(out, FlatExpression::Mult(box x.into(), box x.into()))
+                    .into(),
+            ]
+        );
+    }
+
+    #[test]
+    fn keep_directive_inputs() {
+        // def main(x) -> (1):
+        //     y = x * x
+        //     # out = Identity(y)
+        //     y == x
+        //     return out
+
+        // ->
+
+        // unchanged
+
+        let x = FlatVariable::new(0);
+        let y = FlatVariable::new(1);
+        let out = FlatVariable::public(0);
+
+        let p = prog(
+            vec![x],
+            vec![
+                FlatStatement::Definition(y, FlatExpression::Mult(box x.into(), box x.into()))
+                    .into(),
+                Statement::Directive(Directive {
+                    inputs: vec![y.into()],
+                    outputs: vec![out],
+                    helper: Helper::Rust(RustHelper::Identity),
+                }),
+                Statement::constraint(y, x),
+            ],
+        );
+
+        let optimized = LinearOptimizer::optimize(p.clone());
+
+        assert_eq!(optimized.main, p.main);
+    }
+}
@@ -4,11 +4,15 @@
 //! @author Thibaut Schaeffer <thibaut@schaeff.fr>
 //! @date 2018
 
+mod duplicate;
+mod linear;
 mod redefinition;
 mod tautology;
 
-use self::redefinition::RedefinitionOptimizer;
-use self::tautology::TautologyOptimizer;
+pub use self::duplicate::DuplicateOptimizer;
+pub use self::linear::LinearOptimizer;
+pub use self::redefinition::RedefinitionOptimizer;
+pub use self::tautology::TautologyOptimizer;
 
 use crate::ir::Prog;
 use zokrates_field::field::Field;
@@ -21,6 +25,10 @@ impl<T: Field> Optimize for Prog<T> {
     fn optimize(self) -> Self {
         // remove redefinitions
         let r = RedefinitionOptimizer::optimize(self);
+        // remove duplicate constraints and directives
+        let r = DuplicateOptimizer::optimize(r);
+        // remove linear constraints by substitution
+        let r = LinearOptimizer::optimize(r);
         // remove constraints that are always satisfied
         let r = TautologyOptimizer::optimize(r);
         r
@@ -66,7 +66,15 @@ impl<T: Field> Folder<T> for RedefinitionOptimizer<T> {
                         self.substitution.insert(k, v);
                         vec![]
                     }
-                    None => vec![Statement::Constraint(quad, lin)],
+                    None => {
+                        // variables defined by a constraint we keep must not be redefined later
+                        for (variable, _) in lin.0.iter() {
+                            self.substitution
+                                .entry(variable.clone())
+                                .or_insert_with(|| variable.clone().into());
+                        }
+                        vec![Statement::Constraint(quad, lin)]
+                    }
                 }
             }
             Statement::Directive(d) => {
@@ -112,6 +120,7 @@ impl<T: Field> Folder<T> for RedefinitionOptimizer<T> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::flat_absy::{FlatExpression, FlatStatement};
     use zokrates_field::field::FieldPrime;
 
     #[test]
@@ -130,6 +139,7 @@ mod tests {
             arguments: vec![x],
             statements: vec![Statement::definition(y, x), Statement::definition(z, y)],
             returns: vec![z.into()],
+            source_map: SourceMap::default(),
         };
 
         let optimized: Function<FieldPrime> = Function {
@@ -137,6 +147,7 @@ mod tests {
             arguments: vec![x],
             statements: vec![Statement::definition(z, x)],
             returns: vec![z],
+            source_map: SourceMap::default(),
         };
 
         let mut optimizer = RedefinitionOptimizer::new();
@@ -170,6 +181,7 @@ mod tests {
                 Statement::constraint(z, y),
             ],
             returns: vec![z.into()],
+            source_map: SourceMap::default(),
         };
 
         let optimized: Function<FieldPrime> = Function {
@@ -177,6 +189,7 @@ mod tests {
             arguments: vec![x],
             statements: vec![Statement::definition(z, x), Statement::constraint(z, x)],
             returns: vec![z.into()],
+            source_map: SourceMap::default(),
         };
 
         let mut optimizer = RedefinitionOptimizer::new();
@@ -213,6 +226,7 @@ mod tests {
                 Statement::definition(w, t),
             ],
             returns: vec![z, w],
+            source_map: SourceMap::default(),
         };
 
         let optimized: Function<FieldPrime> = Function {
@@ -223,6 +237,7 @@ mod tests {
                 Statement::definition(w, FieldPrime::from(1)),
             ],
             returns: vec![z, w],
+            source_map: SourceMap::default(),
         };
 
         let mut optimizer = RedefinitionOptimizer::new();
@@ -267,6 +282,7 @@ mod tests {
                 Statement::definition(r, LinComb::from(a) + LinComb::from(b) + LinComb::from(c)),
             ],
             returns: vec![r],
+            source_map: SourceMap::default(),
         };
 
         let optimized: Function<FieldPrime> = Function {
@@ -280,6 +296,7 @@ mod tests {
                 Statement::definition(r, LinComb::summand(6, x) + LinComb::summand(6, y)),
             ],
             returns: vec![r],
+            source_map: SourceMap::default(),
         };
 
         let mut optimizer = RedefinitionOptimizer::new();
@@ -307,6 +324,42 @@ mod tests {
                 Statement::constraint(x, FieldPrime::from(2)),
             ],
             returns: vec![x.into()],
+            source_map: SourceMap::default(),
+        };
+
+        let optimized = f.clone();
+
+        let mut optimizer = RedefinitionOptimizer::new();
+        assert_eq!(optimizer.fold_function(f), optimized);
+    }
+
+    #[test]
+    fn keep_existing_quadratic_variable() {
+        // def main(x, y) -> (1):
+        //     z = x * y
+        //     z == x
+        //     return z
+
+        // ->
+
+        // unchanged
+
+        let x = FlatVariable::new(0);
+        let y = FlatVariable::new(1);
+        let z = FlatVariable::new(2);
+        let out = FlatVariable::public(0);
+
+        let f: Function<FieldPrime> = Function {
+            id: "foo".to_string(),
+            arguments: vec![x, y],
+            statements: vec![
+                FlatStatement::Definition(z, FlatExpression::Mult(box x.into(), box y.into()))
+                    .into(),
+                Statement::constraint(x, z),
+                Statement::definition(out, z),
+            ],
+            returns: vec![out.into()],
+            source_map: SourceMap::default(),
         };
 
         let optimized = f.clone();
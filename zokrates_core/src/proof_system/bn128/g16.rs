@@ -4,57 +4,70 @@ use crate::proof_system::bn128::utils::solidity::{
     SOLIDITY_G2_ADDITION_LIB, SOLIDITY_PAIRING_LIB, SOLIDITY_PAIRING_LIB_V2,
 };
 use crate::proof_system::ProofSystem;
-use bellman::groth16::Parameters;
+use bellman::groth16::{prepare_verifying_key, verify_proof, Parameters};
+use pairing::Engine;
 use regex::Regex;
 use std::fs::File;
-use std::io::{BufRead, BufReader, Write};
+use std::io::{self, BufRead, BufReader, Read, Write};
 use std::path::PathBuf;
-use zokrates_field::field::FieldPrime;
+use zokrates_field::field::Field;
 
 const G16_WARNING: &str = "WARNING: You are using the G16 scheme which is subject to malleability. See zokrates.github.io/reference/proving_schemes.html#g16-malleability for implications.";
 
 pub struct G16 {}
-impl ProofSystem for G16 {
-    fn setup(&self, program: ir::Prog<FieldPrime>, pk_path: &str, vk_path: &str) {
+
+impl G16 {
+    /// Writes the proving key and the verification key of `parameters`, as `setup` does
+    pub fn write_keys<E: Engine>(
+        parameters: &Parameters<E>,
+        pk_path: &str,
+        vk_path: &str,
+    ) -> io::Result<()> {
+        let parameters_file = File::create(PathBuf::from(pk_path))?;
+        parameters.write(parameters_file)?;
+        let mut vk_file = File::create(PathBuf::from(vk_path))?;
+        vk_file.write_all(serialize::serialize_vk(parameters.vk.clone()).as_ref())
+    }
+}
+
+impl<T: Field> ProofSystem<T> for G16 {
+    fn setup(&self, program: ir::Prog<T>, pk_path: &str, vk_path: &str) -> Result<(), String> {
         std::env::set_var("BELLMAN_VERBOSE", "0");
 
         println!("{}", G16_WARNING);
 
         let parameters = Computation::without_witness(program).setup();
-        let parameters_file = File::create(PathBuf::from(pk_path)).unwrap();
-        parameters.write(parameters_file).unwrap();
-        let mut vk_file = File::create(PathBuf::from(vk_path)).unwrap();
-        vk_file
-            .write(serialize::serialize_vk(parameters.vk).as_ref())
-            .unwrap();
+        G16::write_keys(&parameters, pk_path, vk_path)
+            .map_err(|why| format!("couldn't write the keys: {}", why))
     }
 
     fn generate_proof(
         &self,
-        program: ir::Prog<FieldPrime>,
-        witness: ir::Witness<FieldPrime>,
+        program: ir::Prog<T>,
+        witness: ir::Witness<T>,
         pk_path: &str,
         proof_path: &str,
-    ) -> bool {
+    ) -> Result<(), String> {
         std::env::set_var("BELLMAN_VERBOSE", "0");
 
         println!("{}", G16_WARNING);
 
         let computation = Computation::with_witness(program, witness);
-        let parameters_file = File::open(PathBuf::from(pk_path)).unwrap();
-
-        let params = Parameters::read(parameters_file, true).unwrap();
+        let params = File::open(PathBuf::from(pk_path))
+            .and_then(|parameters_file| Parameters::read(parameters_file, true))
+            .map_err(|why| format!("couldn't read the proving key {}: {}", pk_path, why))?;
 
         let proof = computation.clone().prove(&params);
 
-        let mut proof_file = File::create(PathBuf::from(proof_path)).unwrap();
-        write!(
-            proof_file,
-            "{}",
-            serialize::serialize_proof(&proof, &computation.public_inputs_values())
-        )
-        .unwrap();
-        true
+        File::create(PathBuf::from(proof_path))
+            .and_then(|mut proof_file| {
+                write!(
+                    proof_file,
+                    "{}",
+                    serialize::serialize_proof(&proof, &computation.public_inputs_values())
+                )
+            })
+            .map_err(|why| format!("couldn't write the proof {}: {}", proof_path, why))
     }
 
     fn export_solidity_verifier(&self, reader: BufReader<File>, is_abiv2: bool) -> String {
@@ -147,17 +160,41 @@ impl ProofSystem for G16 {
             SOLIDITY_G2_ADDITION_LIB, solidity_pairing_lib, template_text
         )
     }
+
+    fn verify(&self, vk_path: &str, proof_path: &str) -> Result<bool, String> {
+        let mut vk = String::new();
+        File::open(PathBuf::from(vk_path))
+            .and_then(|mut file| file.read_to_string(&mut vk))
+            .map_err(|why| format!("couldn't read {}: {}", vk_path, why))?;
+        let vk = serialize::deserialize_vk::<T::BellmanEngine>(&vk)
+            .map_err(|why| format!("invalid verification key {}: {}", vk_path, why))?;
+
+        let mut proof = String::new();
+        File::open(PathBuf::from(proof_path))
+            .and_then(|mut file| file.read_to_string(&mut proof))
+            .map_err(|why| format!("couldn't read {}: {}", proof_path, why))?;
+        let (proof, inputs) = serialize::deserialize_proof::<T::BellmanEngine>(&proof)
+            .map_err(|why| format!("invalid proof {}: {}", proof_path, why))?;
+
+        let pvk = prepare_verifying_key(&vk);
+
+        // a number of inputs which does not match the verification key is reported as an error
+        // by bellman, in which case the proof is simply not valid
+        Ok(verify_proof(&pvk, &proof, &inputs).unwrap_or(false))
+    }
 }
 
 mod serialize {
 
     use crate::proof_system::bn128::utils::bellman::{
-        parse_fr_json, parse_g1_hex, parse_g1_json, parse_g2_hex, parse_g2_json,
+        parse_fr_from_hex, parse_fr_json, parse_g1_from_hex, parse_g1_hex, parse_g1_json,
+        parse_g2_from_hex, parse_g2_hex, parse_g2_json,
     };
     use bellman::groth16::{Proof, VerifyingKey};
-    use pairing::bn256::{Bn256, Fr};
+    use pairing::{CurveAffine, Engine};
+    use regex::Regex;
 
-    pub fn serialize_vk(vk: VerifyingKey<Bn256>) -> String {
+    pub fn serialize_vk<E: Engine>(vk: VerifyingKey<E>) -> String {
         format!(
             "vk.alpha = {}
     vk.beta = {}
@@ -165,21 +202,21 @@ mod serialize {
     vk.delta = {}
     vk.gamma_abc.len() = {}
     {}",
-            parse_g1_hex(&vk.alpha_g1),
-            parse_g2_hex(&vk.beta_g2),
-            parse_g2_hex(&vk.gamma_g2),
-            parse_g2_hex(&vk.delta_g2),
+            parse_g1_hex::<E>(&vk.alpha_g1),
+            parse_g2_hex::<E>(&vk.beta_g2),
+            parse_g2_hex::<E>(&vk.gamma_g2),
+            parse_g2_hex::<E>(&vk.delta_g2),
             vk.ic.len(),
             vk.ic
                 .iter()
                 .enumerate()
-                .map(|(i, x)| format!("vk.gamma_abc[{}] = {}", i, parse_g1_hex(x)))
+                .map(|(i, x)| format!("vk.gamma_abc[{}] = {}", i, parse_g1_hex::<E>(x)))
                 .collect::<Vec<_>>()
                 .join("\n")
         )
     }
 
-    pub fn serialize_proof(p: &Proof<Bn256>, inputs: &Vec<Fr>) -> String {
+    pub fn serialize_proof<E: Engine>(p: &Proof<E>, inputs: &Vec<E::Fr>) -> String {
         format!(
             "{{
         \"proof\": {{
@@ -189,16 +226,113 @@ mod serialize {
         }},
         \"inputs\": [{}]
     }}",
-            parse_g1_json(&p.a),
-            parse_g2_json(&p.b),
-            parse_g1_json(&p.c),
+            parse_g1_json::<E>(&p.a),
+            parse_g2_json::<E>(&p.b),
+            parse_g1_json::<E>(&p.c),
             inputs
                 .iter()
-                .map(parse_fr_json)
+                .map(parse_fr_json::<E>)
                 .collect::<Vec<_>>()
                 .join(", "),
         )
     }
+
+    #[derive(Deserialize)]
+    struct ProofPoints {
+        a: [String; 2],
+        b: [[String; 2]; 2],
+        c: [String; 2],
+    }
+
+    #[derive(Deserialize)]
+    struct ProofWithInputs {
+        proof: ProofPoints,
+        inputs: Vec<String>,
+    }
+
+    pub fn deserialize_vk<E: Engine>(vk: &str) -> Result<VerifyingKey<E>, String> {
+        let hex_regex = Regex::new(r"0[xX][0-9a-fA-F]+").unwrap();
+
+        let mut alpha = None;
+        let mut beta = None;
+        let mut gamma = None;
+        let mut delta = None;
+        let mut gamma_abc_len = None;
+        let mut gamma_abc = vec![];
+
+        for line in vk.lines().map(|l| l.trim()).filter(|l| !l.is_empty()) {
+            let line_split: Vec<&str> = line.split('=').collect();
+            if line_split.len() != 2 {
+                return Err(format!("unexpected line `{}`", line));
+            }
+            let key = line_split[0].trim();
+            let values: Vec<&str> = hex_regex
+                .find_iter(line_split[1])
+                .map(|m| m.as_str())
+                .collect();
+
+            match (key, values.as_slice()) {
+                ("vk.alpha", [x, y]) => alpha = Some(parse_g1_from_hex::<E>(x, y)?),
+                ("vk.beta", [x1, x0, y1, y0]) => {
+                    beta = Some(parse_g2_from_hex::<E>(x1, x0, y1, y0)?)
+                }
+                ("vk.gamma", [x1, x0, y1, y0]) => {
+                    gamma = Some(parse_g2_from_hex::<E>(x1, x0, y1, y0)?)
+                }
+                ("vk.delta", [x1, x0, y1, y0]) => {
+                    delta = Some(parse_g2_from_hex::<E>(x1, x0, y1, y0)?)
+                }
+                ("vk.gamma_abc.len()", []) => {
+                    gamma_abc_len = Some(
+                        line_split[1]
+                            .trim()
+                            .parse::<usize>()
+                            .map_err(|e| e.to_string())?,
+                    )
+                }
+                (key, [x, y]) if key == format!("vk.gamma_abc[{}]", gamma_abc.len()) => {
+                    gamma_abc.push(parse_g1_from_hex::<E>(x, y)?)
+                }
+                _ => return Err(format!("unexpected line `{}`", line)),
+            }
+        }
+
+        if gamma_abc_len != Some(gamma_abc.len()) {
+            return Err(String::from("wrong number of elements in vk.gamma_abc"));
+        }
+
+        Ok(VerifyingKey {
+            alpha_g1: alpha.ok_or("missing vk.alpha")?,
+            beta_g2: beta.ok_or("missing vk.beta")?,
+            gamma_g2: gamma.ok_or("missing vk.gamma")?,
+            delta_g2: delta.ok_or("missing vk.delta")?,
+            ic: gamma_abc,
+            // beta and delta in G1 are only used to generate proofs, so they are not exported
+            beta_g1: E::G1Affine::zero(),
+            delta_g1: E::G1Affine::zero(),
+        })
+    }
+
+    pub fn deserialize_proof<E: Engine>(proof: &str) -> Result<(Proof<E>, Vec<E::Fr>), String> {
+        let p: ProofWithInputs = serde_json::from_str(proof).map_err(|e| e.to_string())?;
+
+        Ok((
+            Proof {
+                a: parse_g1_from_hex::<E>(&p.proof.a[0], &p.proof.a[1])?,
+                b: parse_g2_from_hex::<E>(
+                    &p.proof.b[0][0],
+                    &p.proof.b[0][1],
+                    &p.proof.b[1][0],
+                    &p.proof.b[1][1],
+                )?,
+                c: parse_g1_from_hex::<E>(&p.proof.c[0], &p.proof.c[1])?,
+            },
+            p.inputs
+                .iter()
+                .map(|i| parse_fr_from_hex::<E>(i))
+                .collect::<Result<_, _>>()?,
+        ))
+    }
 }
 
 const CONTRACT_TEMPLATE_V2: &str = r#"
@@ -330,6 +464,8 @@ contract Verifier {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use zokrates_field::field::FieldPrime;
+
     mod serialize {
         use super::*;
 
@@ -337,7 +473,14 @@ mod tests {
             use super::*;
             use crate::flat_absy::FlatVariable;
             use crate::ir::*;
-            use crate::proof_system::bn128::g16::serialize::serialize_proof;
+            use crate::proof_system::bn128::g16::serialize::{
+                deserialize_proof, deserialize_vk, serialize_proof, serialize_vk,
+            };
+            use crate::types::{Signature, Type};
+            use ff::PrimeField;
+            use pairing::bls12_381::Bls12;
+            use pairing::bn256::Fr;
+            use zokrates_field::field::Bls12Field;
 
             #[allow(dead_code)]
             #[derive(Deserialize)]
@@ -365,8 +508,12 @@ mod tests {
                             FlatVariable::new(0).into(),
                             FlatVariable::public(0).into(),
                         )],
+                        source_map: SourceMap::default(),
                     },
                     private: vec![false],
+                    signature: Signature::new()
+                        .inputs(vec![Type::FieldElement; 1])
+                        .outputs(vec![Type::FieldElement; 1]),
                 };
 
                 let witness = program
@@ -383,6 +530,98 @@ mod tests {
                 let serialized_proof = serialize_proof(&proof, &public_inputs_values);
                 serde_json::from_str::<G16Proof>(&serialized_proof).unwrap();
             }
+
+            #[test]
+            fn deserialize_and_verify() {
+                let program: Prog<FieldPrime> = Prog {
+                    main: Function {
+                        id: String::from("main"),
+                        arguments: vec![FlatVariable::new(0)],
+                        returns: vec![FlatVariable::public(0)],
+                        statements: vec![Statement::Constraint(
+                            FlatVariable::new(0).into(),
+                            FlatVariable::public(0).into(),
+                        )],
+                        source_map: SourceMap::default(),
+                    },
+                    private: vec![false],
+                    signature: Signature::new()
+                        .inputs(vec![Type::FieldElement; 1])
+                        .outputs(vec![Type::FieldElement; 1]),
+                };
+
+                let witness = program
+                    .clone()
+                    .execute::<FieldPrime>(&vec![FieldPrime::from(42)])
+                    .unwrap();
+                let computation = Computation::with_witness(program, witness);
+
+                let public_inputs_values = computation.public_inputs_values();
+
+                let params = computation.clone().setup();
+                let proof = computation.prove(&params);
+
+                let vk = deserialize_vk(&serialize_vk(params.vk.clone())).unwrap();
+                assert_eq!(vk.alpha_g1, params.vk.alpha_g1);
+                assert_eq!(vk.beta_g2, params.vk.beta_g2);
+                assert_eq!(vk.gamma_g2, params.vk.gamma_g2);
+                assert_eq!(vk.delta_g2, params.vk.delta_g2);
+                assert_eq!(vk.ic, params.vk.ic);
+
+                let (deserialized_proof, inputs) =
+                    deserialize_proof(&serialize_proof(&proof, &public_inputs_values)).unwrap();
+                assert_eq!(deserialized_proof, proof);
+                assert_eq!(inputs, public_inputs_values);
+
+                let pvk = prepare_verifying_key(&vk);
+                assert!(verify_proof(&pvk, &proof, &inputs).unwrap());
+
+                let wrong_inputs = vec![Fr::from_str("43").unwrap(); 2];
+                assert!(!verify_proof(&pvk, &proof, &wrong_inputs).unwrap());
+            }
+
+            #[test]
+            fn bls12_381() {
+                let program: Prog<Bls12Field> = Prog {
+                    main: Function {
+                        id: String::from("main"),
+                        arguments: vec![FlatVariable::new(0)],
+                        returns: vec![FlatVariable::public(0)],
+                        statements: vec![Statement::Constraint(
+                            FlatVariable::new(0).into(),
+                            FlatVariable::public(0).into(),
+                        )],
+                        source_map: SourceMap::default(),
+                    },
+                    private: vec![false],
+                    signature: Signature::new()
+                        .inputs(vec![Type::FieldElement; 1])
+                        .outputs(vec![Type::FieldElement; 1]),
+                };
+
+                let witness = program
+                    .clone()
+                    .execute::<Bls12Field>(&vec![Bls12Field::from(42)])
+                    .unwrap();
+                let computation = Computation::with_witness(program, witness);
+
+                let public_inputs_values = computation.public_inputs_values();
+
+                let params = computation.clone().setup();
+                let proof = computation.prove(&params);
+
+                let vk = deserialize_vk::<Bls12>(&serialize_vk(params.vk.clone())).unwrap();
+                assert_eq!(vk.ic, params.vk.ic);
+
+                let (deserialized_proof, inputs) =
+                    deserialize_proof::<Bls12>(&serialize_proof(&proof, &public_inputs_values))
+                        .unwrap();
+                assert_eq!(deserialized_proof, proof);
+                assert_eq!(inputs, public_inputs_values);
+
+                let pvk = prepare_verifying_key(&vk);
+                assert!(verify_proof(&pvk, &proof, &inputs).unwrap());
+            }
         }
     }
 }
@@ -1,110 +1,101 @@
-extern crate libc;
+extern crate rand;
 
-use self::libc::{c_char, c_int};
-use ir;
-use proof_system::bn128::utils::libsnark::{prepare_generate_proof, prepare_setup};
-use proof_system::bn128::utils::solidity::{
+use self::rand::thread_rng;
+use crate::flat_absy::FlatVariable;
+use crate::ir;
+use crate::proof_system::bn128::utils::bellman::{
+    parse_fr_from_hex, parse_fr_json, parse_g1_from_hex, parse_g1_hex, parse_g1_json,
+    parse_g2_from_hex, parse_g2_hex, parse_g2_json,
+};
+use crate::proof_system::bn128::utils::gm17::{
+    create_proof, generate_parameters, verify_proof, Proof, ProvingKey, Sap, VerifyingKey,
+};
+use crate::proof_system::bn128::utils::r1cs::r1cs_program;
+use crate::proof_system::bn128::utils::solidity::{
     SOLIDITY_G2_ADDITION_LIB, SOLIDITY_PAIRING_LIB, SOLIDITY_PAIRING_LIB_V2,
 };
-use proof_system::ProofSystem;
+use crate::proof_system::ProofSystem;
 use regex::Regex;
 use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::PathBuf;
 
-use zokrates_field::field::FieldPrime;
+use zokrates_field::field::Field;
 
 pub struct GM17 {}
 
-impl GM17 {
-    pub fn new() -> GM17 {
-        GM17 {}
-    }
-}
+// reduce the constraint system of `program` to a square arithmetic program
+fn sap<T: Field>(
+    program: ir::Prog<T>,
+) -> Result<(Vec<FlatVariable>, Sap<T::BellmanEngine>), String> {
+    let (variables, public_variables_count, a, b, c) = r1cs_program(program);
+
+    let into_bellman = |rows: Vec<Vec<(usize, T)>>| {
+        rows.into_iter()
+            .map(|row| {
+                row.into_iter()
+                    .map(|(index, value)| (index, value.into_bellman()))
+                    .collect()
+            })
+            .collect()
+    };
 
-extern "C" {
-    fn _gm17_setup(
-        A: *const u8,
-        B: *const u8,
-        C: *const u8,
-        A_len: c_int,
-        B_len: c_int,
-        C_len: c_int,
-        constraints: c_int,
-        variables: c_int,
-        inputs: c_int,
-        pk_path: *const c_char,
-        vk_path: *const c_char,
-    ) -> bool;
-
-    fn _gm17_generate_proof(
-        pk_path: *const c_char,
-        proof_path: *const c_char,
-        publquery_inputs: *const u8,
-        publquery_inputs_length: c_int,
-        private_inputs: *const u8,
-        private_inputs_length: c_int,
-    ) -> bool;
+    let sap = Sap::from_r1cs(
+        variables.len(),
+        public_variables_count,
+        into_bellman(a),
+        into_bellman(b),
+        into_bellman(c),
+    )?;
+
+    Ok((variables, sap))
 }
 
-impl ProofSystem for GM17 {
-    fn setup(&self, program: ir::Prog<FieldPrime>, pk_path: &str, vk_path: &str) {
-        let (
-            a_arr,
-            b_arr,
-            c_arr,
-            a_vec,
-            b_vec,
-            c_vec,
-            num_constraints,
-            num_variables,
-            num_inputs,
-            pk_path_cstring,
-            vk_path_cstring,
-        ) = prepare_setup(program, pk_path, vk_path);
-
-        unsafe {
-            _gm17_setup(
-                a_arr.as_ptr(),
-                b_arr.as_ptr(),
-                c_arr.as_ptr(),
-                a_vec.len() as i32,
-                b_vec.len() as i32,
-                c_vec.len() as i32,
-                num_constraints as i32,
-                num_variables as i32,
-                num_inputs as i32,
-                pk_path_cstring.as_ptr(),
-                vk_path_cstring.as_ptr(),
-            );
-        }
+impl<T: Field> ProofSystem<T> for GM17 {
+    fn setup(&self, program: ir::Prog<T>, pk_path: &str, vk_path: &str) -> Result<(), String> {
+        let (_, sap) = sap(program)?;
+
+        let pk = generate_parameters(&sap, &mut thread_rng());
+
+        let write_keys = || -> std::io::Result<()> {
+            let pk_file = File::create(PathBuf::from(pk_path))?;
+            pk.write(pk_file)?;
+            let mut vk_file = File::create(PathBuf::from(vk_path))?;
+            vk_file.write_all(serialize::serialize_vk(&pk.vk).as_ref())
+        };
+
+        write_keys().map_err(|why| format!("couldn't write the keys: {}", why))
     }
 
     fn generate_proof(
         &self,
-        program: ir::Prog<FieldPrime>,
-        witness: ir::Witness<FieldPrime>,
+        program: ir::Prog<T>,
+        witness: ir::Witness<T>,
         pk_path: &str,
         proof_path: &str,
-    ) -> bool {
-        let (
-            pk_path_cstring,
-            proof_path_cstring,
-            public_inputs_arr,
-            public_inputs_length,
-            private_inputs_arr,
-            private_inputs_length,
-        ) = prepare_generate_proof(program, witness, pk_path, proof_path);
-
-        unsafe {
-            _gm17_generate_proof(
-                pk_path_cstring.as_ptr(),
-                proof_path_cstring.as_ptr(),
-                public_inputs_arr[0].as_ptr(),
-                public_inputs_length as i32,
-                private_inputs_arr[0].as_ptr(),
-                private_inputs_length as i32,
-            )
-        }
+    ) -> Result<(), String> {
+        let (variables, sap) = sap(program)?;
+
+        // recover the assignment in the order of the variables of the constraint system
+        let assignment: Vec<_> = variables
+            .iter()
+            .map(|v| witness[v].clone().into_bellman())
+            .collect();
+
+        let pk = File::open(PathBuf::from(pk_path))
+            .and_then(ProvingKey::<T::BellmanEngine>::read)
+            .map_err(|why| format!("couldn't read the proving key {}: {}", pk_path, why))?;
+
+        let proof = create_proof(&pk, &sap, &assignment, &mut thread_rng());
+
+        // the constant one is not part of the public inputs
+        let inputs = &assignment[1..pk.vk.query.len()];
+
+        File::create(PathBuf::from(proof_path))
+            .and_then(|mut proof_file| {
+                write!(proof_file, "{}", serialize::serialize_proof(&proof, inputs))
+            })
+            .map_err(|why| format!("couldn't write the proof {}: {}", proof_path, why))
     }
 
     fn export_solidity_verifier(&self, reader: BufReader<File>, is_abiv2: bool) -> String {
@@ -194,6 +185,171 @@ impl ProofSystem for GM17 {
             SOLIDITY_G2_ADDITION_LIB, solidity_pairing_lib, template_text
         )
     }
+
+    fn verify(&self, vk_path: &str, proof_path: &str) -> Result<bool, String> {
+        let mut vk = String::new();
+        File::open(PathBuf::from(vk_path))
+            .and_then(|mut file| file.read_to_string(&mut vk))
+            .map_err(|why| format!("couldn't read {}: {}", vk_path, why))?;
+        let vk = serialize::deserialize_vk::<T::BellmanEngine>(&vk)
+            .map_err(|why| format!("invalid verification key {}: {}", vk_path, why))?;
+
+        let mut proof = String::new();
+        File::open(PathBuf::from(proof_path))
+            .and_then(|mut file| file.read_to_string(&mut proof))
+            .map_err(|why| format!("couldn't read {}: {}", proof_path, why))?;
+        let (proof, inputs) = serialize::deserialize_proof::<T::BellmanEngine>(&proof)
+            .map_err(|why| format!("invalid proof {}: {}", proof_path, why))?;
+
+        // a number of inputs which does not match the verification key makes the proof invalid
+        Ok(verify_proof(&vk, &proof, &inputs).unwrap_or(false))
+    }
+}
+
+mod serialize {
+
+    use super::*;
+    use pairing::Engine;
+
+    pub fn serialize_vk<E: Engine>(vk: &VerifyingKey<E>) -> String {
+        format!(
+            "vk.h = {}
+    vk.g_alpha = {}
+    vk.h_beta = {}
+    vk.g_gamma = {}
+    vk.h_gamma = {}
+    vk.query.len() = {}
+    {}",
+            parse_g2_hex::<E>(&vk.h),
+            parse_g1_hex::<E>(&vk.g_alpha),
+            parse_g2_hex::<E>(&vk.h_beta),
+            parse_g1_hex::<E>(&vk.g_gamma),
+            parse_g2_hex::<E>(&vk.h_gamma),
+            vk.query.len(),
+            vk.query
+                .iter()
+                .enumerate()
+                .map(|(i, x)| format!("vk.query[{}] = {}", i, parse_g1_hex::<E>(x)))
+                .collect::<Vec<_>>()
+                .join("\n")
+        )
+    }
+
+    pub fn serialize_proof<E: Engine>(p: &Proof<E>, inputs: &[E::Fr]) -> String {
+        format!(
+            "{{
+        \"proof\": {{
+            \"a\": {},
+            \"b\": {},
+            \"c\": {}
+        }},
+        \"inputs\": [{}]
+    }}",
+            parse_g1_json::<E>(&p.a),
+            parse_g2_json::<E>(&p.b),
+            parse_g1_json::<E>(&p.c),
+            inputs
+                .iter()
+                .map(parse_fr_json::<E>)
+                .collect::<Vec<_>>()
+                .join(", "),
+        )
+    }
+
+    #[derive(Deserialize)]
+    struct ProofPoints {
+        a: [String; 2],
+        b: [[String; 2]; 2],
+        c: [String; 2],
+    }
+
+    #[derive(Deserialize)]
+    struct ProofWithInputs {
+        proof: ProofPoints,
+        inputs: Vec<String>,
+    }
+
+    pub fn deserialize_vk<E: Engine>(vk: &str) -> Result<VerifyingKey<E>, String> {
+        let hex_regex = Regex::new(r"0[xX][0-9a-fA-F]+").unwrap();
+
+        let mut h = None;
+        let mut g_alpha = None;
+        let mut h_beta = None;
+        let mut g_gamma = None;
+        let mut h_gamma = None;
+        let mut query_len = None;
+        let mut query = vec![];
+
+        for line in vk.lines().map(|l| l.trim()).filter(|l| !l.is_empty()) {
+            let line_split: Vec<&str> = line.split('=').collect();
+            if line_split.len() != 2 {
+                return Err(format!("unexpected line `{}`", line));
+            }
+            let key = line_split[0].trim();
+            let values: Vec<&str> = hex_regex
+                .find_iter(line_split[1])
+                .map(|m| m.as_str())
+                .collect();
+
+            match (key, values.as_slice()) {
+                ("vk.h", [x1, x0, y1, y0]) => h = Some(parse_g2_from_hex::<E>(x1, x0, y1, y0)?),
+                ("vk.g_alpha", [x, y]) => g_alpha = Some(parse_g1_from_hex::<E>(x, y)?),
+                ("vk.h_beta", [x1, x0, y1, y0]) => {
+                    h_beta = Some(parse_g2_from_hex::<E>(x1, x0, y1, y0)?)
+                }
+                ("vk.g_gamma", [x, y]) => g_gamma = Some(parse_g1_from_hex::<E>(x, y)?),
+                ("vk.h_gamma", [x1, x0, y1, y0]) => {
+                    h_gamma = Some(parse_g2_from_hex::<E>(x1, x0, y1, y0)?)
+                }
+                ("vk.query.len()", []) => {
+                    query_len = Some(
+                        line_split[1]
+                            .trim()
+                            .parse::<usize>()
+                            .map_err(|e| e.to_string())?,
+                    )
+                }
+                (key, [x, y]) if key == format!("vk.query[{}]", query.len()) => {
+                    query.push(parse_g1_from_hex::<E>(x, y)?)
+                }
+                _ => return Err(format!("unexpected line `{}`", line)),
+            }
+        }
+
+        if query_len != Some(query.len()) {
+            return Err(String::from("wrong number of elements in vk.query"));
+        }
+
+        Ok(VerifyingKey {
+            h: h.ok_or("missing vk.h")?,
+            g_alpha: g_alpha.ok_or("missing vk.g_alpha")?,
+            h_beta: h_beta.ok_or("missing vk.h_beta")?,
+            g_gamma: g_gamma.ok_or("missing vk.g_gamma")?,
+            h_gamma: h_gamma.ok_or("missing vk.h_gamma")?,
+            query,
+        })
+    }
+
+    pub fn deserialize_proof<E: Engine>(proof: &str) -> Result<(Proof<E>, Vec<E::Fr>), String> {
+        let p: ProofWithInputs = serde_json::from_str(proof).map_err(|e| e.to_string())?;
+
+        Ok((
+            Proof {
+                a: parse_g1_from_hex::<E>(&p.proof.a[0], &p.proof.a[1])?,
+                b: parse_g2_from_hex::<E>(
+                    &p.proof.b[0][0],
+                    &p.proof.b[0][1],
+                    &p.proof.b[1][0],
+                    &p.proof.b[1][1],
+                )?,
+                c: parse_g1_from_hex::<E>(&p.proof.c[0], &p.proof.c[1])?,
+            },
+            p.inputs
+                .iter()
+                .map(|i| parse_fr_from_hex::<E>(i))
+                .collect::<Result<_, _>>()?,
+        ))
+    }
 }
 
 const CONTRACT_TEMPLATE_V2: &str = r#"
@@ -335,3 +491,80 @@ contract Verifier {
     }
 }
 "#;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ir::*;
+    use crate::types::{Signature, Type};
+    use zokrates_field::field::{Bls12Field, FieldPrime};
+
+    mod serialize {
+        use super::*;
+        use crate::proof_system::bn128::gm17::serialize::{
+            deserialize_proof, deserialize_vk, serialize_proof, serialize_vk,
+        };
+
+        // the identity function on one public input
+        fn program<T: Field>() -> Prog<T> {
+            Prog {
+                main: Function {
+                    id: String::from("main"),
+                    arguments: vec![FlatVariable::new(0)],
+                    returns: vec![FlatVariable::public(0)],
+                    statements: vec![Statement::Constraint(
+                        FlatVariable::new(0).into(),
+                        FlatVariable::public(0).into(),
+                    )],
+                    source_map: SourceMap::default(),
+                },
+                private: vec![false],
+                signature: Signature::new()
+                    .inputs(vec![Type::FieldElement; 1])
+                    .outputs(vec![Type::FieldElement; 1]),
+            }
+        }
+
+        fn deserialize_and_verify<T: Field>() {
+            let program = program::<T>();
+            let witness = program.clone().execute::<T>(&vec![T::from(42)]).unwrap();
+
+            let (variables, sap) = sap(program).unwrap();
+            let assignment: Vec<_> = variables
+                .iter()
+                .map(|v| witness[v].clone().into_bellman())
+                .collect();
+
+            let pk = generate_parameters(&sap, &mut thread_rng());
+            let proof = create_proof(&pk, &sap, &assignment, &mut thread_rng());
+            let public_inputs_values = &assignment[1..pk.vk.query.len()];
+
+            let vk = deserialize_vk::<T::BellmanEngine>(&serialize_vk(&pk.vk)).unwrap();
+            assert!(vk == pk.vk);
+
+            let (deserialized_proof, inputs) = deserialize_proof::<T::BellmanEngine>(
+                &serialize_proof(&proof, public_inputs_values),
+            )
+            .unwrap();
+            assert!(deserialized_proof == proof);
+            assert_eq!(inputs, public_inputs_values);
+
+            assert!(verify_proof(&vk, &proof, &inputs).unwrap());
+
+            let wrong_inputs = vec![T::from(43).into_bellman(); 2];
+            assert!(!verify_proof(&vk, &proof, &wrong_inputs).unwrap());
+            let wrong_inputs = vec![T::from(43).into_bellman(); 1];
+            assert!(verify_proof(&vk, &proof, &wrong_inputs).is_err());
+        }
+
+        #[test]
+        fn bn128() {
+            deserialize_and_verify::<FieldPrime>();
+        }
+
+        #[test]
+        fn bls12_381() {
+            deserialize_and_verify::<Bls12Field>();
+        }
+    }
+}
@@ -1,13 +1,13 @@
 mod g16;
-#[cfg(feature = "libsnark")]
 mod gm17;
+mod mpc;
 #[cfg(feature = "libsnark")]
 mod pghr13;
 
 mod utils;
 
 pub use self::g16::G16;
-#[cfg(feature = "libsnark")]
 pub use self::gm17::GM17;
+pub use self::mpc::MpcParameters;
 #[cfg(feature = "libsnark")]
 pub use self::pghr13::PGHR13;
@@ -0,0 +1,882 @@
+//! Module containing the phase 2 of a multi-party computation of Groth16 parameters, following
+//! "Scalable Multi-party Computation for zk-SNARK Parameters in the Random Beacon Model" by Bowe,
+//! Gabizon and Miers
+//!
+//! Phase 1 is a powers of tau ceremony which does not depend on the program. Its result is read
+//! from a `phase1radix2m{n}` file, which holds the Lagrange basis of a domain of size `2^n`
+//! evaluated at tau. Phase 2 starts from these values and the constraint system of a program, with
+//! delta set to 1. Each participant then multiplies delta by a secret value, publishing a proof of
+//! knowledge of it, so that anyone can check that the final parameters result from the
+//! contributions. The parameters are secure as long as one participant destroyed their secret.
+//!
+//! The phase 1 files and the parameters have the format of the `phase2` crate.
+
+extern crate blake2_rfc_bellman_edition as blake2_rfc;
+extern crate rand;
+
+use self::blake2_rfc::blake2b::Blake2b;
+use self::rand::{ChaChaRng, OsRng, Rng, SeedableRng};
+use crate::ir;
+use crate::proof_system::bn128::utils::bellman::Computation;
+use bellman::groth16::{Parameters, VerifyingKey};
+use bellman::{Circuit, ConstraintSystem, Index, LinearCombination, SynthesisError, Variable};
+use ff::{Field as _, PrimeField};
+use pairing::{CurveAffine, CurveProjective, EncodedPoint, Engine};
+use std::io::{self, Read, Write};
+use std::sync::Arc;
+use zokrates_field::field::Field;
+
+/// The parameters of a program during phase 2, along with the public keys of the contributions
+/// which led to them
+#[derive(Clone)]
+pub struct MpcParameters<E: Engine> {
+    params: Parameters<E>,
+    /// The hash of the initial parameters, which identifies the constraint system
+    cs_hash: [u8; 64],
+    contributions: Vec<PublicKey<E>>,
+}
+
+/// The public part of a contribution, proving knowledge of the secret delta it multiplied the
+/// parameters by
+#[derive(Clone)]
+struct PublicKey<E: Engine> {
+    /// delta in G1 after the contribution
+    delta_after: E::G1Affine,
+    /// A random point of G1, and its product with the secret
+    s: E::G1Affine,
+    s_delta: E::G1Affine,
+    /// The product of the secret with a point of G2 derived from the transcript
+    r_delta: E::G2Affine,
+    /// The hash of the initial parameters, the previous contributions, `s` and `s_delta`
+    transcript: [u8; 64],
+}
+
+/// The result of phase 1 for a domain of size `m`
+struct Phase1<E: Engine> {
+    alpha_g1: E::G1Affine,
+    beta_g1: E::G1Affine,
+    beta_g2: E::G2Affine,
+    /// The Lagrange basis at tau, in G1 and G2, and multiplied by alpha and beta in G1
+    coeffs_g1: Vec<E::G1Affine>,
+    coeffs_g2: Vec<E::G2Affine>,
+    alpha_coeffs_g1: Vec<E::G1Affine>,
+    beta_coeffs_g1: Vec<E::G1Affine>,
+    /// `tau^i * (tau^m - 1)` for `i` in `0..m - 1`
+    h: Vec<E::G1Affine>,
+}
+
+impl<E: Engine> MpcParameters<E> {
+    /// Computes the initial parameters of a program from the result of phase 1, read from the
+    /// `phase1radix2m{n}` file where `2^n` is the number of constraints rounded up to a power of 2
+    pub fn new<T: Field<BellmanEngine = E>, R: Read>(
+        program: ir::Prog<T>,
+        phase1: R,
+    ) -> Result<Self, String> {
+        let assembly = Assembly::synthesize(program)?;
+        let exponent = domain_exponent::<E>(assembly.num_constraints)?;
+        let phase1 = Phase1::read(phase1, exponent).map_err(|why| {
+            format!(
+                "Could not read the phase 1 file for 2^{} constraints (phase1radix2m{}): {}",
+                exponent, exponent, why
+            )
+        })?;
+
+        let (a_inputs, b_g1_inputs, b_g2_inputs, ic) = phase1.evaluate(
+            &assembly.at_inputs,
+            &assembly.bt_inputs,
+            &assembly.ct_inputs,
+        );
+        let (a_aux, b_g1_aux, b_g2_aux, l) =
+            phase1.evaluate(&assembly.at_aux, &assembly.bt_aux, &assembly.ct_aux);
+
+        // the parameters cannot hold points at infinity, so all variables must be constrained
+        if l.iter().any(|p| p.is_zero()) {
+            return Err("The program has unconstrained variables".to_string());
+        }
+
+        let params = Parameters {
+            vk: VerifyingKey {
+                alpha_g1: phase1.alpha_g1,
+                beta_g1: phase1.beta_g1,
+                beta_g2: phase1.beta_g2,
+                gamma_g2: E::G2Affine::one(),
+                delta_g1: E::G1Affine::one(),
+                delta_g2: E::G2Affine::one(),
+                ic,
+            },
+            h: Arc::new(phase1.h),
+            l: Arc::new(l),
+            a: nonzero(a_inputs.into_iter().chain(a_aux)),
+            b_g1: nonzero(b_g1_inputs.into_iter().chain(b_g1_aux)),
+            b_g2: nonzero(b_g2_inputs.into_iter().chain(b_g2_aux)),
+        };
+
+        let mut hasher = HashWriter::new();
+        params.write(&mut hasher).unwrap();
+
+        Ok(MpcParameters {
+            params,
+            cs_hash: hasher.into_hash(),
+            contributions: vec![],
+        })
+    }
+
+    /// The Groth16 parameters, which can be used as soon as one honest participant contributed
+    pub fn parameters(&self) -> &Parameters<E> {
+        &self.params
+    }
+
+    /// Contributes randomness drawn from the operating system and from `entropy`, returning the
+    /// hash of the contribution which the participant should publish
+    pub fn contribute(&mut self, entropy: &[u8]) -> io::Result<[u8; 64]> {
+        let mut seed = HashWriter::new();
+        let system: [u8; 32] = OsRng::new()?.gen();
+        seed.write_all(&system)?;
+        seed.write_all(entropy)?;
+
+        let mut rng = ChaChaRng::from_seed(&to_seed(&seed.into_hash())[..]);
+        Ok(self.contribute_with(&mut rng))
+    }
+
+    fn contribute_with<R: Rng>(&mut self, rng: &mut R) -> [u8; 64] {
+        let delta: E::Fr = rng.gen();
+        let delta_inverse = delta.inverse().expect("delta is not zero");
+
+        let s = rng.gen::<E::G1>().into_affine();
+        let s_delta = s.mul(delta).into_affine();
+
+        let mut transcript = HashWriter::new();
+        transcript.write_all(&self.cs_hash).unwrap();
+        for contribution in &self.contributions {
+            contribution.write(&mut transcript).unwrap();
+        }
+        transcript
+            .write_all(s.into_uncompressed().as_ref())
+            .unwrap();
+        transcript
+            .write_all(s_delta.into_uncompressed().as_ref())
+            .unwrap();
+        let transcript = transcript.into_hash();
+
+        let r = hash_to_g2::<E>(&transcript).into_affine();
+
+        let public_key = PublicKey {
+            delta_after: self.params.vk.delta_g1.mul(delta).into_affine(),
+            s,
+            s_delta,
+            r_delta: r.mul(delta).into_affine(),
+            transcript,
+        };
+
+        self.params.l = Arc::new(multiply(&self.params.l, delta_inverse));
+        self.params.h = Arc::new(multiply(&self.params.h, delta_inverse));
+        self.params.vk.delta_g1 = public_key.delta_after;
+        self.params.vk.delta_g2 = self.params.vk.delta_g2.mul(delta).into_affine();
+
+        let hash = public_key.hash();
+        self.contributions.push(public_key);
+        hash
+    }
+
+    /// Checks that the parameters result from contributions to the initial parameters of
+    /// `program`, returning the hashes of the contributions in order
+    pub fn verify<T: Field<BellmanEngine = E>, R: Read>(
+        &self,
+        program: ir::Prog<T>,
+        phase1: R,
+    ) -> Result<Vec<[u8; 64]>, String> {
+        let initial = MpcParameters::new(program, phase1)?;
+
+        let (before, after) = (&initial.params, &self.params);
+
+        if initial.cs_hash[..] != self.cs_hash[..]
+            || before.a != after.a
+            || before.b_g1 != after.b_g1
+            || before.b_g2 != after.b_g2
+            || before.vk.alpha_g1 != after.vk.alpha_g1
+            || before.vk.beta_g1 != after.vk.beta_g1
+            || before.vk.beta_g2 != after.vk.beta_g2
+            || before.vk.gamma_g2 != after.vk.gamma_g2
+            || before.vk.ic != after.vk.ic
+            || before.h.len() != after.h.len()
+            || before.l.len() != after.l.len()
+        {
+            return Err("The parameters were not computed for this program".to_string());
+        }
+
+        let mut transcript = HashWriter::new();
+        transcript.write_all(&initial.cs_hash).unwrap();
+
+        let mut delta = E::G1Affine::one();
+        let mut hashes = vec![];
+
+        for (index, contribution) in self.contributions.iter().enumerate() {
+            let invalid =
+                |reason| Err(format!("Contribution {} is invalid: {}", index + 1, reason));
+
+            let mut expected = transcript.clone();
+            expected
+                .write_all(contribution.s.into_uncompressed().as_ref())
+                .unwrap();
+            expected
+                .write_all(contribution.s_delta.into_uncompressed().as_ref())
+                .unwrap();
+            if expected.into_hash()[..] != contribution.transcript[..] {
+                return invalid("inconsistent transcript");
+            }
+
+            let r = hash_to_g2::<E>(&contribution.transcript).into_affine();
+
+            if !same_ratio(
+                (r, contribution.r_delta),
+                (contribution.s, contribution.s_delta),
+            ) {
+                return invalid("no knowledge of the secret");
+            }
+
+            if !same_ratio((delta, contribution.delta_after), (r, contribution.r_delta)) {
+                return invalid("delta was not multiplied by the secret");
+            }
+
+            contribution.write(&mut transcript).unwrap();
+            delta = contribution.delta_after;
+            hashes.push(contribution.hash());
+        }
+
+        if delta != after.vk.delta_g1
+            || !same_ratio(
+                (E::G1Affine::one(), delta),
+                (E::G2Affine::one(), after.vk.delta_g2),
+            )
+        {
+            return Err("delta does not result from the contributions".to_string());
+        }
+
+        // h and l are divided by delta
+        let rng = &mut rand::thread_rng();
+        if !same_ratio(
+            merge_pairs(&before.h, &after.h, rng),
+            (after.vk.delta_g2, E::G2Affine::one()),
+        ) || !same_ratio(
+            merge_pairs(&before.l, &after.l, rng),
+            (after.vk.delta_g2, E::G2Affine::one()),
+        ) {
+            return Err("The H and L queries were not divided by delta".to_string());
+        }
+
+        Ok(hashes)
+    }
+
+    pub fn write<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        self.params.write(&mut writer)?;
+        writer.write_all(&self.cs_hash)?;
+        writer.write_all(&(self.contributions.len() as u32).to_be_bytes())?;
+        for contribution in &self.contributions {
+            contribution.write(&mut writer)?;
+        }
+        Ok(())
+    }
+
+    /// Reads parameters, checking that their points are in the right subgroups if `checked` is set
+    pub fn read<R: Read>(mut reader: R, checked: bool) -> io::Result<Self> {
+        let params = Parameters::read(&mut reader, checked)?;
+
+        let mut cs_hash = [0; 64];
+        reader.read_exact(&mut cs_hash)?;
+
+        let mut count = [0; 4];
+        reader.read_exact(&mut count)?;
+        let contributions = (0..u32::from_be_bytes(count))
+            .map(|_| PublicKey::read(&mut reader))
+            .collect::<io::Result<_>>()?;
+
+        Ok(MpcParameters {
+            params,
+            cs_hash,
+            contributions,
+        })
+    }
+}
+
+impl<E: Engine> PublicKey<E> {
+    fn write<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(self.delta_after.into_uncompressed().as_ref())?;
+        writer.write_all(self.s.into_uncompressed().as_ref())?;
+        writer.write_all(self.s_delta.into_uncompressed().as_ref())?;
+        writer.write_all(self.r_delta.into_uncompressed().as_ref())?;
+        writer.write_all(&self.transcript)
+    }
+
+    fn read<R: Read>(mut reader: R) -> io::Result<Self> {
+        let delta_after = read_nonzero_point(&mut reader)?;
+        let s = read_nonzero_point(&mut reader)?;
+        let s_delta = read_nonzero_point(&mut reader)?;
+        let r_delta = read_nonzero_point(&mut reader)?;
+        let mut transcript = [0; 64];
+        reader.read_exact(&mut transcript)?;
+
+        Ok(PublicKey {
+            delta_after,
+            s,
+            s_delta,
+            r_delta,
+            transcript,
+        })
+    }
+
+    fn hash(&self) -> [u8; 64] {
+        let mut hasher = HashWriter::new();
+        self.write(&mut hasher).unwrap();
+        hasher.into_hash()
+    }
+}
+
+impl<E: Engine> Phase1<E> {
+    fn read<R: Read>(mut reader: R, exponent: usize) -> io::Result<Self> {
+        let m = 1 << exponent;
+
+        let phase1 = Phase1 {
+            alpha_g1: read_point(&mut reader)?,
+            beta_g1: read_point(&mut reader)?,
+            beta_g2: read_point(&mut reader)?,
+            coeffs_g1: read_points(&mut reader, m)?,
+            coeffs_g2: read_points(&mut reader, m)?,
+            alpha_coeffs_g1: read_points(&mut reader, m)?,
+            beta_coeffs_g1: read_points(&mut reader, m)?,
+            h: read_points(&mut reader, m - 1)?,
+        };
+
+        // a file for a larger domain would be read without error up to here
+        if reader.read(&mut [0])? != 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "unexpected data at the end of the file",
+            ));
+        }
+
+        Ok(phase1)
+    }
+
+    /// Evaluates the polynomials of variables at tau, returning `A` in G1, `B` in G1 and G2, and
+    /// `beta * A + alpha * B + C` in G1 for each variable
+    fn evaluate(
+        &self,
+        at: &[Vec<(E::Fr, usize)>],
+        bt: &[Vec<(E::Fr, usize)>],
+        ct: &[Vec<(E::Fr, usize)>],
+    ) -> (
+        Vec<E::G1Affine>,
+        Vec<E::G1Affine>,
+        Vec<E::G2Affine>,
+        Vec<E::G1Affine>,
+    ) {
+        let mut a_g1 = vec![];
+        let mut b_g1 = vec![];
+        let mut b_g2 = vec![];
+        let mut ext = vec![];
+
+        for ((at, bt), ct) in at.iter().zip(bt).zip(ct) {
+            let mut a = E::G1::zero();
+            let mut b1 = E::G1::zero();
+            let mut b2 = E::G2::zero();
+            let mut e = E::G1::zero();
+
+            for &(coeff, constraint) in at {
+                a.add_assign(&self.coeffs_g1[constraint].mul(coeff));
+                e.add_assign(&self.beta_coeffs_g1[constraint].mul(coeff));
+            }
+
+            for &(coeff, constraint) in bt {
+                b1.add_assign(&self.coeffs_g1[constraint].mul(coeff));
+                b2.add_assign(&self.coeffs_g2[constraint].mul(coeff));
+                e.add_assign(&self.alpha_coeffs_g1[constraint].mul(coeff));
+            }
+
+            for &(coeff, constraint) in ct {
+                e.add_assign(&self.coeffs_g1[constraint].mul(coeff));
+            }
+
+            a_g1.push(a);
+            b_g1.push(b1);
+            b_g2.push(b2);
+            ext.push(e);
+        }
+
+        (
+            to_affine(a_g1),
+            to_affine(b_g1),
+            to_affine(b_g2),
+            to_affine(ext),
+        )
+    }
+}
+
+/// A constraint system recording, for each variable, the coefficients it has in the `A`, `B` and
+/// `C` combinations of each constraint, as bellman does to generate parameters
+struct Assembly<E: Engine> {
+    num_inputs: usize,
+    num_aux: usize,
+    num_constraints: usize,
+    at_inputs: Vec<Vec<(E::Fr, usize)>>,
+    bt_inputs: Vec<Vec<(E::Fr, usize)>>,
+    ct_inputs: Vec<Vec<(E::Fr, usize)>>,
+    at_aux: Vec<Vec<(E::Fr, usize)>>,
+    bt_aux: Vec<Vec<(E::Fr, usize)>>,
+    ct_aux: Vec<Vec<(E::Fr, usize)>>,
+}
+
+impl<E: Engine> Assembly<E> {
+    fn synthesize<T: Field<BellmanEngine = E>>(program: ir::Prog<T>) -> Result<Self, String> {
+        let mut assembly = Assembly {
+            num_inputs: 0,
+            num_aux: 0,
+            num_constraints: 0,
+            at_inputs: vec![],
+            bt_inputs: vec![],
+            ct_inputs: vec![],
+            at_aux: vec![],
+            bt_aux: vec![],
+            ct_aux: vec![],
+        };
+
+        // ~one
+        assembly.alloc_input(|| "", || Ok(E::Fr::one())).unwrap();
+
+        Computation::without_witness(program)
+            .synthesize(&mut assembly)
+            .map_err(|why| format!("Could not synthesize the program: {}", why))?;
+
+        // make each input appear in A so that the IC query has no point at infinity
+        for i in 0..assembly.num_inputs {
+            assembly.enforce(
+                || "",
+                |lc| lc + Variable::new_unchecked(Index::Input(i)),
+                |lc| lc,
+                |lc| lc,
+            );
+        }
+
+        Ok(assembly)
+    }
+}
+
+impl<E: Engine> ConstraintSystem<E> for Assembly<E> {
+    type Root = Self;
+
+    fn alloc<F, A, AR>(&mut self, _: A, _: F) -> Result<Variable, SynthesisError>
+    where
+        F: FnOnce() -> Result<E::Fr, SynthesisError>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        let index = self.num_aux;
+        self.num_aux += 1;
+
+        self.at_aux.push(vec![]);
+        self.bt_aux.push(vec![]);
+        self.ct_aux.push(vec![]);
+
+        Ok(Variable::new_unchecked(Index::Aux(index)))
+    }
+
+    fn alloc_input<F, A, AR>(&mut self, _: A, _: F) -> Result<Variable, SynthesisError>
+    where
+        F: FnOnce() -> Result<E::Fr, SynthesisError>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        let index = self.num_inputs;
+        self.num_inputs += 1;
+
+        self.at_inputs.push(vec![]);
+        self.bt_inputs.push(vec![]);
+        self.ct_inputs.push(vec![]);
+
+        Ok(Variable::new_unchecked(Index::Input(index)))
+    }
+
+    fn enforce<A, AR, LA, LB, LC>(&mut self, _: A, a: LA, b: LB, c: LC)
+    where
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+        LA: FnOnce(LinearCombination<E>) -> LinearCombination<E>,
+        LB: FnOnce(LinearCombination<E>) -> LinearCombination<E>,
+        LC: FnOnce(LinearCombination<E>) -> LinearCombination<E>,
+    {
+        fn record<E: Engine>(
+            lc: LinearCombination<E>,
+            inputs: &mut [Vec<(E::Fr, usize)>],
+            aux: &mut [Vec<(E::Fr, usize)>],
+            constraint: usize,
+        ) {
+            for (variable, coeff) in lc.as_ref() {
+                match variable.get_unchecked() {
+                    Index::Input(i) => inputs[i].push((*coeff, constraint)),
+                    Index::Aux(i) => aux[i].push((*coeff, constraint)),
+                }
+            }
+        }
+
+        let constraint = self.num_constraints;
+        record(
+            a(LinearCombination::zero()),
+            &mut self.at_inputs,
+            &mut self.at_aux,
+            constraint,
+        );
+        record(
+            b(LinearCombination::zero()),
+            &mut self.bt_inputs,
+            &mut self.bt_aux,
+            constraint,
+        );
+        record(
+            c(LinearCombination::zero()),
+            &mut self.ct_inputs,
+            &mut self.ct_aux,
+            constraint,
+        );
+
+        self.num_constraints += 1;
+    }
+
+    fn push_namespace<NR, N>(&mut self, _: N)
+    where
+        NR: Into<String>,
+        N: FnOnce() -> NR,
+    {
+    }
+
+    fn pop_namespace(&mut self) {}
+
+    fn get_root(&mut self) -> &mut Self::Root {
+        self
+    }
+}
+
+/// A writer computing the BLAKE2b hash of what is written to it
+#[derive(Clone)]
+struct HashWriter(Blake2b);
+
+impl HashWriter {
+    fn new() -> Self {
+        HashWriter(Blake2b::new(64))
+    }
+
+    fn into_hash(self) -> [u8; 64] {
+        let mut hash = [0; 64];
+        hash.copy_from_slice(self.0.finalize().as_bytes());
+        hash
+    }
+}
+
+impl Write for HashWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Returns `n` such that `2^n` is the size of the domain for `constraints` constraints
+fn domain_exponent<E: Engine>(constraints: usize) -> Result<usize, String> {
+    let mut exponent = 0;
+    while (1 << exponent) < constraints {
+        exponent += 1;
+    }
+
+    let max = <E::Fr as PrimeField>::S as usize;
+
+    if exponent > max {
+        return Err(format!(
+            "The program has {} constraints, at most 2^{} are supported",
+            constraints, max
+        ));
+    }
+
+    Ok(exponent)
+}
+
+// the seed of a `ChaChaRng` from the first 32 bytes of a hash
+fn to_seed(hash: &[u8; 64]) -> Vec<u32> {
+    hash[..32]
+        .chunks(4)
+        .map(|c| u32::from_be_bytes([c[0], c[1], c[2], c[3]]))
+        .collect()
+}
+
+/// Derives a point of G2 of unknown discrete logarithm from a transcript
+fn hash_to_g2<E: Engine>(transcript: &[u8; 64]) -> E::G2 {
+    ChaChaRng::from_seed(&to_seed(transcript)[..]).gen()
+}
+
+/// Checks that `g1.1 / g1.0 == g2.1 / g2.0` in the exponent
+fn same_ratio<G: CurveAffine>(g1: (G, G), g2: (G::Pair, G::Pair)) -> bool {
+    g1.0.pairing_with(&g2.1) == g1.1.pairing_with(&g2.0)
+}
+
+/// Computes random linear combinations `(sum r_i * v1_i, sum r_i * v2_i)`: if `v2_i / v1_i` is the
+/// same ratio for all `i`, so is the ratio of the results, and otherwise it is with negligible
+/// probability
+fn merge_pairs<G: CurveAffine, R: Rng>(v1: &[G], v2: &[G], rng: &mut R) -> (G, G) {
+    let mut s = G::Projective::zero();
+    let mut sx = G::Projective::zero();
+
+    for (p1, p2) in v1.iter().zip(v2) {
+        let r: G::Scalar = rng.gen();
+        s.add_assign(&p1.mul(r));
+        sx.add_assign(&p2.mul(r));
+    }
+
+    (s.into_affine(), sx.into_affine())
+}
+
+/// Polynomials which are zero are left out of the A and B queries, as bellman does
+fn nonzero<G: CurveAffine, I: Iterator<Item = G>>(points: I) -> Arc<Vec<G>> {
+    Arc::new(points.filter(|p| !p.is_zero()).collect())
+}
+
+fn multiply<G: CurveAffine>(points: &[G], scalar: G::Scalar) -> Vec<G> {
+    to_affine(points.iter().map(|p| p.mul(scalar)).collect())
+}
+
+fn to_affine<G: CurveProjective>(mut points: Vec<G>) -> Vec<G::Affine> {
+    G::batch_normalization(&mut points);
+    points.iter().map(|p| p.into_affine()).collect()
+}
+
+fn read_point<G: CurveAffine, R: Read>(reader: &mut R) -> io::Result<G> {
+    let mut repr = G::Uncompressed::empty();
+    reader.read_exact(repr.as_mut())?;
+    repr.into_affine_unchecked()
+        .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+}
+
+fn read_points<G: CurveAffine, R: Read>(reader: &mut R, count: usize) -> io::Result<Vec<G>> {
+    (0..count).map(|_| read_point(reader)).collect()
+}
+
+fn read_nonzero_point<G: CurveAffine, R: Read>(reader: &mut R) -> io::Result<G> {
+    let mut repr = G::Uncompressed::empty();
+    reader.read_exact(repr.as_mut())?;
+    match repr.into_affine() {
+        Ok(ref p) if p.is_zero() => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "point at infinity",
+        )),
+        p => p.map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::flat_absy::FlatVariable;
+    use crate::ir::{Function, LinComb, Prog, QuadComb, SourceMap, Statement};
+    use crate::types::{Signature, Type};
+    use bellman::groth16::{prepare_verifying_key, verify_proof};
+    use pairing::bn256::{Bn256, Fr, G1Affine, G2Affine};
+    use zokrates_field::field::FieldPrime;
+
+    // def main(private field a, field b) -> (field): return a * a + b
+    fn program() -> Prog<FieldPrime> {
+        Prog {
+            main: Function {
+                id: String::from("main"),
+                arguments: vec![FlatVariable::new(0), FlatVariable::new(1)],
+                returns: vec![FlatVariable::public(0)],
+                statements: vec![
+                    Statement::Constraint(
+                        QuadComb::from_linear_combinations(
+                            FlatVariable::new(0).into(),
+                            FlatVariable::new(0).into(),
+                        ),
+                        FlatVariable::new(2).into(),
+                    ),
+                    Statement::Constraint(
+                        (LinComb::from(FlatVariable::new(2)) + LinComb::from(FlatVariable::new(1)))
+                            .into(),
+                        FlatVariable::public(0).into(),
+                    ),
+                ],
+                source_map: SourceMap::default(),
+            },
+            private: vec![true, false],
+            signature: Signature::new()
+                .inputs(vec![Type::FieldElement; 2])
+                .outputs(vec![Type::FieldElement; 1]),
+        }
+    }
+
+    fn write_point<G: CurveAffine<Scalar = Fr>>(bytes: &mut Vec<u8>, scalar: Fr) {
+        let point = G::one().mul(scalar).into_affine();
+        bytes.extend_from_slice(point.into_uncompressed().as_ref());
+    }
+
+    // the result of phase 1 for a domain of size `2^exponent`, computed from known secrets
+    fn phase1(exponent: usize) -> Vec<u8> {
+        let rng = &mut rand::thread_rng();
+        let (tau, alpha, beta): (Fr, Fr, Fr) = (rng.gen(), rng.gen(), rng.gen());
+
+        let m = 1 << exponent;
+
+        let mut omega = Fr::root_of_unity();
+        for _ in exponent..Fr::S as usize {
+            omega.square();
+        }
+
+        // tau^m - 1
+        let mut z = tau.pow(&[m as u64]);
+        z.sub_assign(&Fr::one());
+
+        // L_i(tau) = omega^i * (tau^m - 1) / (m * (tau - omega^i))
+        let m_inverse = Fr::from_str(&m.to_string()).unwrap().inverse().unwrap();
+        let mut omega_i = Fr::one();
+        let mut lagrange = vec![];
+        for _ in 0..m {
+            let mut l = tau;
+            l.sub_assign(&omega_i);
+            l = l.inverse().unwrap();
+            l.mul_assign(&omega_i);
+            l.mul_assign(&z);
+            l.mul_assign(&m_inverse);
+            lagrange.push(l);
+            omega_i.mul_assign(&omega);
+        }
+
+        let times = |l: &Fr, scalar: &Fr| {
+            let mut l = *l;
+            l.mul_assign(scalar);
+            l
+        };
+
+        let mut bytes = vec![];
+        write_point::<G1Affine>(&mut bytes, alpha);
+        write_point::<G1Affine>(&mut bytes, beta);
+        write_point::<G2Affine>(&mut bytes, beta);
+        for l in &lagrange {
+            write_point::<G1Affine>(&mut bytes, *l);
+        }
+        for l in &lagrange {
+            write_point::<G2Affine>(&mut bytes, *l);
+        }
+        for l in &lagrange {
+            write_point::<G1Affine>(&mut bytes, times(l, &alpha));
+        }
+        for l in &lagrange {
+            write_point::<G1Affine>(&mut bytes, times(l, &beta));
+        }
+        // tau^i * (tau^m - 1)
+        let mut h = z;
+        for _ in 0..m - 1 {
+            write_point::<G1Affine>(&mut bytes, h);
+            h.mul_assign(&tau);
+        }
+
+        bytes
+    }
+
+    // 2 constraints and 3 inputs including ~one, each with its own constraint
+    const EXPONENT: usize = 3;
+
+    #[test]
+    fn contributions() {
+        let phase1 = phase1(EXPONENT);
+        let mut mpc = MpcParameters::<Bn256>::new(program(), &phase1[..]).unwrap();
+
+        let first = mpc.contribute(b"first").unwrap();
+        let second = mpc.contribute(b"second").unwrap();
+
+        let mut buffer = vec![];
+        mpc.write(&mut buffer).unwrap();
+        let mpc = MpcParameters::<Bn256>::read(&buffer[..], true).unwrap();
+
+        assert_eq!(
+            mpc.verify(program(), &phase1[..])
+                .unwrap()
+                .iter()
+                .map(|h| h.to_vec())
+                .collect::<Vec<_>>(),
+            vec![first.to_vec(), second.to_vec()]
+        );
+
+        // the parameters can be used to prove and verify
+        let witness = program()
+            .execute::<FieldPrime>(&vec![FieldPrime::from(3), FieldPrime::from(4)])
+            .unwrap();
+        let computation = Computation::with_witness(program(), witness);
+        let public_inputs = computation.public_inputs_values();
+        let proof = computation.prove(mpc.parameters());
+
+        let pvk = prepare_verifying_key(&mpc.parameters().vk);
+        assert_eq!(
+            public_inputs,
+            vec![Fr::from_str("4").unwrap(), Fr::from_str("13").unwrap()]
+        );
+        assert!(verify_proof(&pvk, &proof, &public_inputs).unwrap());
+        assert!(!verify_proof(
+            &pvk,
+            &proof,
+            &[Fr::from_str("4").unwrap(), Fr::from_str("14").unwrap()]
+        )
+        .unwrap());
+    }
+
+    #[test]
+    fn tampered_contribution() {
+        let phase1 = phase1(EXPONENT);
+        let mut mpc = MpcParameters::<Bn256>::new(program(), &phase1[..]).unwrap();
+        mpc.contribute(b"first").unwrap();
+        mpc.contribute(b"second").unwrap();
+
+        // the second contribution does not prove knowledge of the secret delta was multiplied by
+        let contribution = &mut mpc.contributions[1];
+        contribution.r_delta = contribution
+            .r_delta
+            .mul(Fr::from_str("2").unwrap())
+            .into_affine();
+
+        assert_eq!(
+            mpc.verify(program(), &phase1[..]).err(),
+            Some(String::from(
+                "Contribution 2 is invalid: no knowledge of the secret"
+            ))
+        );
+    }
+
+    #[test]
+    fn tampered_delta() {
+        let phase1 = phase1(EXPONENT);
+        let mut mpc = MpcParameters::<Bn256>::new(program(), &phase1[..]).unwrap();
+        mpc.contribute(b"").unwrap();
+
+        mpc.params.vk.delta_g1 = mpc
+            .params
+            .vk
+            .delta_g1
+            .mul(Fr::from_str("2").unwrap())
+            .into_affine();
+
+        assert!(mpc.verify(program(), &phase1[..]).is_err());
+    }
+
+    #[test]
+    fn skipped_contribution() {
+        let phase1 = phase1(EXPONENT);
+        let mut mpc = MpcParameters::<Bn256>::new(program(), &phase1[..]).unwrap();
+        mpc.contribute(b"first").unwrap();
+        mpc.contribute(b"second").unwrap();
+        mpc.contributions.remove(0);
+
+        assert!(mpc.verify(program(), &phase1[..]).is_err());
+    }
+
+    #[test]
+    fn wrong_domain() {
+        assert!(MpcParameters::<Bn256>::new(program(), &phase1(EXPONENT + 1)[..]).is_err());
+        assert!(MpcParameters::<Bn256>::new(program(), &phase1(EXPONENT - 1)[..]).is_err());
+    }
+}
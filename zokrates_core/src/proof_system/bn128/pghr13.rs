@@ -47,8 +47,13 @@ extern "C" {
     ) -> bool;
 }
 
-impl ProofSystem for PGHR13 {
-    fn setup(&self, program: ir::Prog<FieldPrime>, pk_path: &str, vk_path: &str) {
+impl ProofSystem<FieldPrime> for PGHR13 {
+    fn setup(
+        &self,
+        program: ir::Prog<FieldPrime>,
+        pk_path: &str,
+        vk_path: &str,
+    ) -> Result<(), String> {
         let (
             a_arr,
             b_arr,
@@ -78,6 +83,8 @@ impl ProofSystem for PGHR13 {
                 vk_path_cstring.as_ptr(),
             );
         }
+
+        Ok(())
     }
 
     fn generate_proof(
@@ -86,7 +93,7 @@ impl ProofSystem for PGHR13 {
         witness: ir::Witness<FieldPrime>,
         pk_path: &str,
         proof_path: &str,
-    ) -> bool {
+    ) -> Result<(), String> {
         let (
             pk_path_cstring,
             proof_path_cstring,
@@ -101,7 +108,7 @@ impl ProofSystem for PGHR13 {
             (pk_path_cstring.clone(), proof_path_cstring.clone(),)
         );
 
-        unsafe {
+        let success = unsafe {
             _pghr13_generate_proof(
                 pk_path_cstring.as_ptr(),
                 proof_path_cstring.as_ptr(),
@@ -110,6 +117,11 @@ impl ProofSystem for PGHR13 {
                 private_inputs_arr[0].as_ptr(),
                 private_inputs_length as i32,
             )
+        };
+
+        match success {
+            true => Ok(()),
+            false => Err(String::from("couldn't generate the proof")),
         }
     }
 
@@ -197,6 +209,10 @@ impl ProofSystem for PGHR13 {
             SOLIDITY_G2_ADDITION_LIB, solidity_pairing_lib, template_text
         )
     }
+
+    fn verify(&self, _vk_path: &str, _proof_path: &str) -> Result<bool, String> {
+        Err(String::from("Verification is not supported for the PGHR13 scheme"))
+    }
 }
 
 const CONTRACT_TEMPLATE_V2: &str = r#"contract Verifier {
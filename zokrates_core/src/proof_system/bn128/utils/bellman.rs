@@ -1,5 +1,3 @@
-#![allow(deprecated)] // TODO remove when lazy_static is warning-free
-
 extern crate rand;
 
 use crate::ir::{CanonicalLinComb, Prog, Statement, Witness};
@@ -9,9 +7,10 @@ use bellman::groth16::{
     Parameters,
 };
 use bellman::{Circuit, ConstraintSystem, LinearCombination, SynthesisError, Variable};
-use pairing::bn256::{Bn256, Fr};
+use ff::ScalarEngine;
+use pairing::Engine;
 use std::collections::BTreeMap;
-use zokrates_field::field::{Field, FieldPrime};
+use zokrates_field::field::Field;
 
 use self::rand::*;
 use crate::flat_absy::FlatVariable;
@@ -40,12 +39,12 @@ impl<T: Field> Computation<T> {
     }
 }
 
-fn bellman_combination<CS: ConstraintSystem<Bn256>>(
-    l: CanonicalLinComb<FieldPrime>,
+fn bellman_combination<T: Field, CS: ConstraintSystem<T::BellmanEngine>>(
+    l: CanonicalLinComb<T>,
     cs: &mut CS,
     symbols: &mut BTreeMap<FlatVariable, Variable>,
-    witness: &mut Witness<FieldPrime>,
-) -> LinearCombination<Bn256> {
+    witness: &mut Witness<T>,
+) -> LinearCombination<T::BellmanEngine> {
     l.0.into_iter()
         .map(|(k, v)| {
             (
@@ -58,7 +57,6 @@ fn bellman_combination<CS: ConstraintSystem<Bn256>>(
                                 || format!("{}", k),
                                 || {
                                     Ok(witness
-                                        .0
                                         .remove(&k)
                                         .ok_or(SynthesisError::AssignmentMissing)?
                                         .into_bellman())
@@ -68,7 +66,6 @@ fn bellman_combination<CS: ConstraintSystem<Bn256>>(
                                 || format!("{}", k),
                                 || {
                                     Ok(witness
-                                        .0
                                         .remove(&k)
                                         .ok_or(SynthesisError::AssignmentMissing)?
                                         .into_bellman())
@@ -83,11 +80,11 @@ fn bellman_combination<CS: ConstraintSystem<Bn256>>(
         .fold(LinearCombination::zero(), |acc, e| acc + e)
 }
 
-impl Prog<FieldPrime> {
-    pub fn synthesize<CS: ConstraintSystem<Bn256>>(
+impl<T: Field> Prog<T> {
+    pub fn synthesize<CS: ConstraintSystem<T::BellmanEngine>>(
         self,
         cs: &mut CS,
-        witness: Option<Witness<FieldPrime>>,
+        witness: Option<Witness<T>>,
     ) -> Result<(), SynthesisError> {
         // mapping from IR variables
         let mut symbols = BTreeMap::new();
@@ -108,7 +105,6 @@ impl Prog<FieldPrime> {
                             || format!("PRIVATE_INPUT_{}", index),
                             || {
                                 Ok(witness
-                                    .0
                                     .remove(&var)
                                     .ok_or(SynthesisError::AssignmentMissing)?
                                     .into_bellman())
@@ -118,7 +114,6 @@ impl Prog<FieldPrime> {
                             || format!("PUBLIC_INPUT_{}", index),
                             || {
                                 Ok(witness
-                                    .0
                                     .remove(&var)
                                     .ok_or(SynthesisError::AssignmentMissing)?
                                     .into_bellman())
@@ -160,8 +155,8 @@ impl Prog<FieldPrime> {
     }
 }
 
-impl Computation<FieldPrime> {
-    pub fn prove(self, params: &Parameters<Bn256>) -> Proof<Bn256> {
+impl<T: Field> Computation<T> {
+    pub fn prove(self, params: &Parameters<T::BellmanEngine>) -> Proof<T::BellmanEngine> {
         let rng = &mut thread_rng();
         let proof = create_random_proof(self.clone(), params, rng).unwrap();
 
@@ -175,7 +170,7 @@ impl Computation<FieldPrime> {
         proof
     }
 
-    pub fn public_inputs_values(&self) -> Vec<Fr> {
+    pub fn public_inputs_values(&self) -> Vec<<T::BellmanEngine as ScalarEngine>::Fr> {
         self.program
             .main
             .arguments
@@ -184,88 +179,73 @@ impl Computation<FieldPrime> {
             .zip(self.program.private.clone())
             .filter(|(_, p)| !p)
             .map(|(a, _)| a)
-            .map(|v| self.witness.clone().unwrap().0.get(v).unwrap().clone())
+            .map(|v| self.witness.clone().unwrap().get(v).unwrap().clone())
             .chain(self.witness.clone().unwrap().return_values())
             .map(|v| v.clone().into_bellman())
             .collect()
     }
 
-    pub fn setup(self) -> Parameters<Bn256> {
+    pub fn setup(self) -> Parameters<T::BellmanEngine> {
         let rng = &mut thread_rng();
         // run setup phase
         generate_random_parameters(self, rng).unwrap()
     }
 }
 
-impl Circuit<Bn256> for Computation<FieldPrime> {
-    fn synthesize<CS: ConstraintSystem<Bn256>>(self, cs: &mut CS) -> Result<(), SynthesisError> {
+impl<T: Field> Circuit<T::BellmanEngine> for Computation<T> {
+    fn synthesize<CS: ConstraintSystem<T::BellmanEngine>>(
+        self,
+        cs: &mut CS,
+    ) -> Result<(), SynthesisError> {
         self.program.synthesize(cs, self.witness)
     }
 }
 
 mod parse {
-    use lazy_static::lazy_static;
-
     use super::*;
-    use regex::Regex;
-
-    lazy_static! {
-        static ref G2_REGEX: Regex = Regex::new(r"G2\(x=Fq2\(Fq\((?P<x0>0[xX][0-9a-fA-F]{64})\) \+ Fq\((?P<x1>0[xX][0-9a-fA-F]{64})\) \* u\), y=Fq2\(Fq\((?P<y0>0[xX][0-9a-fA-F]{64})\) \+ Fq\((?P<y1>0[xX][0-9a-fA-F]{64})\) \* u\)\)").unwrap();
-    }
-
-    lazy_static! {
-        static ref G1_REGEX: Regex = Regex::new(
-            r"G1\(x=Fq\((?P<x>0[xX][0-9a-fA-F]{64})\), y=Fq\((?P<y>0[xX][0-9a-fA-F]{64})\)\)"
-        )
-        .unwrap();
-    }
-
-    lazy_static! {
-        static ref FR_REGEX: Regex = Regex::new(r"Fr\((?P<x>0[xX][0-9a-fA-F]{64})\)").unwrap();
+    use ff::hex;
+    use ff::{PrimeField, PrimeFieldRepr};
+    use pairing::{CurveAffine, EncodedPoint};
+
+    // split the uncompressed encoding of a point into `count` coordinates, each formatted as a
+    // `0x`-prefixed big-endian hexadecimal number
+    fn coordinates<A: CurveAffine>(e: &A, count: usize) -> Vec<String> {
+        let encoded = e.into_uncompressed();
+        let bytes = encoded.as_ref();
+        bytes
+            .chunks(bytes.len() / count)
+            .map(|c| format!("0x{}", hex::encode(c)))
+            .collect()
     }
 
-    fn parse_g1(e: &<Bn256 as bellman::pairing::Engine>::G1Affine) -> (String, String) {
-        let raw_e = e.to_string();
-
-        let captures = G1_REGEX.captures(&raw_e).unwrap();
+    fn parse_g1<E: Engine>(e: &E::G1Affine) -> (String, String) {
+        let c = coordinates(e, 2);
 
-        (
-            captures.name(&"x").unwrap().as_str().to_string(),
-            captures.name(&"y").unwrap().as_str().to_string(),
-        )
+        (c[0].clone(), c[1].clone())
     }
 
-    fn parse_g2(
-        e: &<Bn256 as bellman::pairing::Engine>::G2Affine,
-    ) -> (String, String, String, String) {
-        let raw_e = e.to_string();
+    // the uncompressed encoding of a G2 point is x.c1, x.c0, y.c1, y.c0
+    fn parse_g2<E: Engine>(e: &E::G2Affine) -> (String, String, String, String) {
+        let c = coordinates(e, 4);
 
-        let captures = G2_REGEX.captures(&raw_e).unwrap();
-
-        (
-            captures.name(&"x1").unwrap().as_str().to_string(),
-            captures.name(&"x0").unwrap().as_str().to_string(),
-            captures.name(&"y1").unwrap().as_str().to_string(),
-            captures.name(&"y0").unwrap().as_str().to_string(),
-        )
+        (c[0].clone(), c[1].clone(), c[2].clone(), c[3].clone())
     }
 
-    fn parse_fr(e: &Fr) -> String {
-        let raw_e = e.to_string();
+    fn parse_fr<E: Engine>(e: &E::Fr) -> String {
+        let mut bytes = vec![];
+        e.into_repr().write_be(&mut bytes).unwrap();
 
-        let captures = FR_REGEX.captures(&raw_e).unwrap();
-
-        captures.name(&"x").unwrap().as_str().to_string()
+        format!("0x{}", hex::encode(&bytes))
     }
 
-    pub fn parse_g1_json(e: &<Bn256 as bellman::pairing::Engine>::G1Affine) -> String {
-        let parsed = parse_g1(e);
+    pub fn parse_g1_json<E: Engine>(e: &E::G1Affine) -> String {
+        let parsed = parse_g1::<E>(e);
 
         format!("[\"{}\", \"{}\"]", parsed.0, parsed.1)
     }
 
-    pub fn parse_g2_json(e: &<Bn256 as bellman::pairing::Engine>::G2Affine) -> String {
-        let parsed = parse_g2(e);
+    pub fn parse_g2_json<E: Engine>(e: &E::G2Affine) -> String {
+        let parsed = parse_g2::<E>(e);
 
         format!(
             "[[\"{}\", \"{}\"], [\"{}\", \"{}\"]]",
@@ -273,29 +253,71 @@ mod parse {
         )
     }
 
-    pub fn parse_fr_json(e: &Fr) -> String {
-        let parsed = parse_fr(e);
+    pub fn parse_fr_json<E: Engine>(e: &E::Fr) -> String {
+        let parsed = parse_fr::<E>(e);
 
         format!("\"{}\"", parsed)
     }
 
-    pub fn parse_g1_hex(e: &<Bn256 as bellman::pairing::Engine>::G1Affine) -> String {
-        let parsed = parse_g1(e);
+    pub fn parse_g1_hex<E: Engine>(e: &E::G1Affine) -> String {
+        let parsed = parse_g1::<E>(e);
 
         format!("{}, {}", parsed.0, parsed.1)
     }
 
-    pub fn parse_g2_hex(e: &<Bn256 as bellman::pairing::Engine>::G2Affine) -> String {
-        let parsed = parse_g2(e);
+    pub fn parse_g2_hex<E: Engine>(e: &E::G2Affine) -> String {
+        let parsed = parse_g2::<E>(e);
 
         format!("[{}, {}], [{}, {}]", parsed.0, parsed.1, parsed.2, parsed.3,)
     }
+
+    // decode `0x`-prefixed hexadecimal coordinates into the big-endian encoding used by bellman
+    fn decode_hex(coordinates: &[&str], res: &mut [u8]) -> Result<(), String> {
+        let size = res.len() / coordinates.len();
+        for (coordinate, chunk) in coordinates.iter().zip(res.chunks_mut(size)) {
+            let bytes = match coordinate.starts_with("0x") || coordinate.starts_with("0X") {
+                true => hex::decode(&coordinate[2..]).map_err(|e| e.to_string())?,
+                false => return Err(format!("{} is not a hexadecimal number", coordinate)),
+            };
+            if bytes.len() != size {
+                return Err(format!("{} is not {} bytes long", coordinate, size));
+            }
+            chunk.copy_from_slice(&bytes);
+        }
+        Ok(())
+    }
+
+    pub fn parse_g1_from_hex<E: Engine>(x: &str, y: &str) -> Result<E::G1Affine, String> {
+        let mut encoded = <E::G1Affine as CurveAffine>::Uncompressed::empty();
+        decode_hex(&[x, y], encoded.as_mut())?;
+        encoded.into_affine().map_err(|e| e.to_string())
+    }
+
+    pub fn parse_g2_from_hex<E: Engine>(
+        x1: &str,
+        x0: &str,
+        y1: &str,
+        y0: &str,
+    ) -> Result<E::G2Affine, String> {
+        let mut encoded = <E::G2Affine as CurveAffine>::Uncompressed::empty();
+        decode_hex(&[x1, x0, y1, y0], encoded.as_mut())?;
+        encoded.into_affine().map_err(|e| e.to_string())
+    }
+
+    pub fn parse_fr_from_hex<E: Engine>(x: &str) -> Result<E::Fr, String> {
+        let mut repr = <E::Fr as PrimeField>::Repr::default();
+        let mut encoded = vec![0u8; repr.as_ref().len() * 8];
+        decode_hex(&[x], &mut encoded)?;
+        repr.read_be(&encoded[..]).map_err(|e| e.to_string())?;
+        E::Fr::from_repr(repr).map_err(|e| e.to_string())
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::ir::{Function, LinComb};
+    use crate::ir::{Function, LinComb, SourceMap};
+    use crate::types::{Signature, Type};
     use zokrates_field::field::FieldPrime;
 
     mod prove {
@@ -309,8 +331,12 @@ mod tests {
                     arguments: vec![],
                     returns: vec![],
                     statements: vec![],
+                    source_map: SourceMap::default(),
                 },
                 private: vec![],
+                signature: Signature::new()
+                    .inputs(vec![])
+                    .outputs(vec![]),
             };
 
             let witness = program.clone().execute::<FieldPrime>(&vec![]).unwrap();
@@ -331,8 +357,12 @@ mod tests {
                         FlatVariable::new(0).into(),
                         FlatVariable::public(0).into(),
                     )],
+                    source_map: SourceMap::default(),
                 },
                 private: vec![true],
+                signature: Signature::new()
+                    .inputs(vec![Type::FieldElement; 1])
+                    .outputs(vec![Type::FieldElement; 1]),
             };
 
             let witness = program
@@ -356,8 +386,12 @@ mod tests {
                         FlatVariable::new(0).into(),
                         FlatVariable::public(0).into(),
                     )],
+                    source_map: SourceMap::default(),
                 },
                 private: vec![false],
+                signature: Signature::new()
+                    .inputs(vec![Type::FieldElement; 1])
+                    .outputs(vec![Type::FieldElement; 1]),
             };
 
             let witness = program
@@ -381,8 +415,12 @@ mod tests {
                         FlatVariable::one().into(),
                         FlatVariable::public(0).into(),
                     )],
+                    source_map: SourceMap::default(),
                 },
                 private: vec![],
+                signature: Signature::new()
+                    .inputs(vec![])
+                    .outputs(vec![Type::FieldElement; 1]),
             };
 
             let witness = program.clone().execute::<FieldPrime>(&vec![]).unwrap();
@@ -415,8 +453,12 @@ mod tests {
                             FlatVariable::public(1).into(),
                         ),
                     ],
+                    source_map: SourceMap::default(),
                 },
                 private: vec![true, false],
+                signature: Signature::new()
+                    .inputs(vec![Type::FieldElement; 2])
+                    .outputs(vec![Type::FieldElement; 2]),
             };
 
             let witness = program
@@ -440,8 +482,12 @@ mod tests {
                         (LinComb::from(FlatVariable::new(42)) + LinComb::one()).into(),
                         FlatVariable::public(0).into(),
                     )],
+                    source_map: SourceMap::default(),
                 },
                 private: vec![false],
+                signature: Signature::new()
+                    .inputs(vec![Type::FieldElement; 1])
+                    .outputs(vec![Type::FieldElement; 1]),
             };
 
             let witness = program
@@ -467,8 +513,12 @@ mod tests {
                         .into(),
                         FlatVariable::public(0).into(),
                     )],
+                    source_map: SourceMap::default(),
                 },
                 private: vec![true, false],
+                signature: Signature::new()
+                    .inputs(vec![Type::FieldElement; 2])
+                    .outputs(vec![Type::FieldElement; 1]),
             };
 
             let witness = program
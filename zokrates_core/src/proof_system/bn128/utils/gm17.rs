@@ -0,0 +1,801 @@
+//! A Rust implementation of the simulation-extractable zkSNARK of Groth and Maller
+//! (https://eprint.iacr.org/2017/540), over any pairing engine.
+//!
+//! As in libsnark's `r1cs_se_ppzksnark`, the rank-1 constraint system is first reduced to a square
+//! arithmetic program (SAP), from which keys and proofs are computed.
+
+extern crate rand;
+
+use self::rand::Rng;
+use ff::{Field as FfField, PrimeField, PrimeFieldRepr, ScalarEngine};
+use pairing::{CurveAffine, CurveProjective, EncodedPoint, Engine, Wnaf};
+use std::io::{self, Read, Write};
+
+type Fr<E> = <E as ScalarEngine>::Fr;
+
+/// A sparse linear combination of the variables of a square arithmetic program
+type Row<E> = Vec<(usize, Fr<E>)>;
+
+#[derive(Clone, Debug)]
+pub struct VerifyingKey<E: Engine> {
+    pub h: E::G2Affine,
+    pub g_alpha: E::G1Affine,
+    pub h_beta: E::G2Affine,
+    pub g_gamma: E::G1Affine,
+    pub h_gamma: E::G2Affine,
+    /// G^{(alpha + beta) * U_i(t) + gamma * W_i(t)} for the constant one and the public inputs
+    pub query: Vec<E::G1Affine>,
+}
+
+impl<E: Engine> PartialEq for VerifyingKey<E> {
+    fn eq(&self, other: &Self) -> bool {
+        self.h == other.h
+            && self.g_alpha == other.g_alpha
+            && self.h_beta == other.h_beta
+            && self.g_gamma == other.g_gamma
+            && self.h_gamma == other.h_gamma
+            && self.query == other.query
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ProvingKey<E: Engine> {
+    pub vk: VerifyingKey<E>,
+    /// G^{gamma * U_i(t)} for the variables of the constraint system
+    pub a_query: Vec<E::G1Affine>,
+    /// H^{gamma * U_i(t)} for the variables of the constraint system
+    pub b_query: Vec<E::G2Affine>,
+    /// G^{(alpha + beta) * gamma * U_i(t) + gamma^2 * W_i(t)} for the private variables of the SAP
+    pub c_query_1: Vec<E::G1Affine>,
+    /// G^{2 * gamma^2 * Z(t) * U_i(t)} for the variables of the constraint system
+    pub c_query_2: Vec<E::G1Affine>,
+    pub g_gamma_z: E::G1Affine,
+    pub h_gamma_z: E::G2Affine,
+    pub g_ab_gamma_z: E::G1Affine,
+    pub g_gamma2_z2: E::G1Affine,
+    /// G^{gamma^2 * Z(t) * t^i} for the coefficients of the quotient polynomial
+    pub g_gamma2_z_t: Vec<E::G1Affine>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Proof<E: Engine> {
+    pub a: E::G1Affine,
+    pub b: E::G2Affine,
+    pub c: E::G1Affine,
+}
+
+impl<E: Engine> PartialEq for Proof<E> {
+    fn eq(&self, other: &Self) -> bool {
+        self.a == other.a && self.b == other.b && self.c == other.c
+    }
+}
+
+/// A square arithmetic program: an assignment `z` satisfies it if `(sum z_i U_i(X))^2 - sum z_i W_i(X)`
+/// vanishes on the evaluation domain, where `U_i` and `W_i` interpolate the columns of `u` and `w`.
+///
+/// The variables are those of the constraint system, followed by one variable per constraint and
+/// one variable per public variable of the constraint system, all of which are private.
+pub struct Sap<E: Engine> {
+    u: Vec<Row<E>>,
+    w: Vec<Row<E>>,
+    // rows of the constraint system, used to extend assignments
+    a: Vec<Row<E>>,
+    b: Vec<Row<E>>,
+    num_variables: usize,
+    num_public: usize,
+    domain: Domain<Fr<E>>,
+}
+
+impl<E: Engine> Sap<E> {
+    /// Reduce the constraint system `<a_j, z> * <b_j, z> = <c_j, z>` over `num_variables` variables,
+    /// the first `num_public` of which are public and the first of which is the constant one.
+    pub fn from_r1cs(
+        num_variables: usize,
+        num_public: usize,
+        a: Vec<Row<E>>,
+        b: Vec<Row<E>>,
+        c: Vec<Row<E>>,
+    ) -> Result<Self, String> {
+        let num_constraints = a.len();
+
+        let one = Fr::<E>::one();
+        let mut minus_one = one;
+        minus_one.negate();
+        let four = Fr::<E>::from_str("4").unwrap();
+
+        let mut u = vec![];
+        let mut w = vec![];
+
+        for (j, ((a, b), c)) in a.iter().zip(b.iter()).zip(c.iter()).enumerate() {
+            let y = num_variables + j;
+
+            let minus_b = b.iter().map(|&(i, mut v)| {
+                v.negate();
+                (i, v)
+            });
+            let four_c = c.iter().map(|&(i, mut v)| {
+                v.mul_assign(&four);
+                (i, v)
+            });
+
+            // (a + b)^2 = 4 * c + y
+            u.push(a.iter().chain(b.iter()).cloned().collect());
+            w.push(four_c.chain(Some((y, one))).collect());
+            // (a - b)^2 = y
+            u.push(a.iter().cloned().chain(minus_b).collect());
+            w.push(vec![(y, one)]);
+        }
+
+        // bind the public variables so that their polynomials are linearly independent
+        for i in 0..num_public {
+            let e = num_variables + num_constraints + i;
+
+            // (z_i + 1)^2 = 4 * z_i + e
+            u.push(vec![(i, one), (0, one)]);
+            w.push(vec![(i, four), (e, one)]);
+            // (z_i - 1)^2 = e
+            u.push(vec![(i, one), (0, minus_one)]);
+            w.push(vec![(e, one)]);
+        }
+
+        let domain = Domain::new(u.len())?;
+
+        Ok(Sap {
+            u,
+            w,
+            a,
+            b,
+            num_variables,
+            num_public,
+            domain,
+        })
+    }
+
+    fn num_sap_variables(&self) -> usize {
+        self.num_variables + self.a.len() + self.num_public
+    }
+
+    // extend an assignment of the constraint system to the variables introduced by the reduction
+    fn extend(&self, assignment: &[Fr<E>]) -> Vec<Fr<E>> {
+        let mut z = assignment.to_vec();
+
+        for (a, b) in self.a.iter().zip(self.b.iter()) {
+            let mut y = evaluate::<E>(a, assignment);
+            y.sub_assign(&evaluate::<E>(b, assignment));
+            y.square();
+            z.push(y);
+        }
+
+        for value in &assignment[..self.num_public] {
+            let mut e = *value;
+            e.sub_assign(&Fr::<E>::one());
+            e.square();
+            z.push(e);
+        }
+
+        z
+    }
+
+    // evaluate the polynomials U_i and W_i at `t`
+    fn evaluate_at(&self, t: &Fr<E>) -> (Vec<Fr<E>>, Vec<Fr<E>>) {
+        let lagrange = self.domain.lagrange_coefficients(t);
+
+        let mut u = vec![Fr::<E>::zero(); self.num_sap_variables()];
+        let mut w = vec![Fr::<E>::zero(); self.num_sap_variables()];
+
+        for ((u_row, w_row), l) in self.u.iter().zip(self.w.iter()).zip(lagrange.iter()) {
+            for &(i, ref v) in u_row {
+                let mut v = *v;
+                v.mul_assign(l);
+                u[i].add_assign(&v);
+            }
+            for &(i, ref v) in w_row {
+                let mut v = *v;
+                v.mul_assign(l);
+                w[i].add_assign(&v);
+            }
+        }
+
+        (u, w)
+    }
+}
+
+fn evaluate<E: Engine>(row: &Row<E>, assignment: &[Fr<E>]) -> Fr<E> {
+    row.iter().fold(Fr::<E>::zero(), |mut acc, &(i, ref v)| {
+        let mut term = assignment[i];
+        term.mul_assign(v);
+        acc.add_assign(&term);
+        acc
+    })
+}
+
+/// A multiplicative subgroup of the scalar field whose size is a power of two
+struct Domain<F: PrimeField> {
+    size: usize,
+    log_size: u32,
+    omega: F,
+    omega_inv: F,
+    size_inv: F,
+}
+
+impl<F: PrimeField> Domain<F> {
+    fn new(min_size: usize) -> Result<Self, String> {
+        let mut size = 1;
+        let mut log_size = 0;
+        while size < min_size {
+            size *= 2;
+            log_size += 1;
+        }
+
+        if log_size > F::S {
+            return Err(String::from(
+                "The constraint system is too large for the scalar field of this curve",
+            ));
+        }
+
+        let mut omega = F::root_of_unity();
+        for _ in log_size..F::S {
+            omega.square();
+        }
+
+        Ok(Domain {
+            size,
+            log_size,
+            omega,
+            omega_inv: omega.inverse().unwrap(),
+            size_inv: F::from_str(&size.to_string()).unwrap().inverse().unwrap(),
+        })
+    }
+
+    // Z(t) = t^size - 1
+    fn z(&self, t: &F) -> F {
+        let mut res = t.pow([self.size as u64]);
+        res.sub_assign(&F::one());
+        res
+    }
+
+    // the values of the Lagrange basis polynomials of the domain at `t`, which must not be in the
+    // domain: L_j(t) = omega^j * Z(t) / (size * (t - omega^j))
+    fn lagrange_coefficients(&self, t: &F) -> Vec<F> {
+        let mut z = self.z(t);
+        z.mul_assign(&self.size_inv);
+
+        let mut omega_j = F::one();
+        (0..self.size)
+            .map(|_| {
+                let mut denominator = *t;
+                denominator.sub_assign(&omega_j);
+                let mut l = denominator.inverse().unwrap();
+                l.mul_assign(&omega_j);
+                l.mul_assign(&z);
+                omega_j.mul_assign(&self.omega);
+                l
+            })
+            .collect()
+    }
+
+    fn fft(&self, a: &mut [F]) {
+        serial_fft(a, &self.omega, self.log_size);
+    }
+
+    fn ifft(&self, a: &mut [F]) {
+        serial_fft(a, &self.omega_inv, self.log_size);
+        for v in a.iter_mut() {
+            v.mul_assign(&self.size_inv);
+        }
+    }
+
+    fn coset_fft(&self, a: &mut [F]) {
+        distribute_powers(a, &F::multiplicative_generator());
+        self.fft(a);
+    }
+
+    fn icoset_fft(&self, a: &mut [F]) {
+        self.ifft(a);
+        distribute_powers(a, &F::multiplicative_generator().inverse().unwrap());
+    }
+}
+
+fn distribute_powers<F: PrimeField>(a: &mut [F], g: &F) {
+    let mut power = F::one();
+    for v in a.iter_mut() {
+        v.mul_assign(&power);
+        power.mul_assign(g);
+    }
+}
+
+fn serial_fft<F: PrimeField>(a: &mut [F], omega: &F, log_n: u32) {
+    fn bitreverse(mut n: usize, l: u32) -> usize {
+        let mut r = 0;
+        for _ in 0..l {
+            r = (r << 1) | (n & 1);
+            n >>= 1;
+        }
+        r
+    }
+
+    let n = a.len();
+    assert_eq!(n, 1 << log_n);
+
+    for k in 0..n {
+        let rk = bitreverse(k, log_n);
+        if k < rk {
+            a.swap(rk, k);
+        }
+    }
+
+    let mut m = 1;
+    for _ in 0..log_n {
+        let w_m = omega.pow([(n / (2 * m)) as u64]);
+
+        let mut k = 0;
+        while k < n {
+            let mut w = F::one();
+            for j in 0..m {
+                let mut t = a[k + j + m];
+                t.mul_assign(&w);
+                let mut tmp = a[k + j];
+                tmp.sub_assign(&t);
+                a[k + j + m] = tmp;
+                a[k + j].add_assign(&t);
+                w.mul_assign(&w_m);
+            }
+
+            k += 2 * m;
+        }
+
+        m *= 2;
+    }
+}
+
+// compute `base^s` for each scalar `s`
+fn batch_exp<G: CurveProjective>(base: G, scalars: &[G::Scalar]) -> Vec<G::Affine> {
+    let mut wnaf = Wnaf::new();
+    let mut wnaf = wnaf.base(base, scalars.len());
+    let mut res: Vec<G> = scalars.iter().map(|s| wnaf.scalar(s.into_repr())).collect();
+    G::batch_normalization(&mut res);
+    res.into_iter().map(|p| p.into_affine()).collect()
+}
+
+// compute `sum s_i * p_i` with Pippenger's bucket method: the scalars are cut into windows of `c`
+// bits, and in each window every point is added to the bucket of its digit, so that a window costs
+// one addition per point and two per bucket instead of a scalar multiplication per point
+fn multiexp<G: CurveAffine>(points: &[G], scalars: &[G::Scalar]) -> G::Projective {
+    let scalars: Vec<_> = scalars.iter().map(|s| s.into_repr()).collect();
+
+    let c = match scalars.len() < 32 {
+        true => 3,
+        false => (scalars.len() as f64).ln().ceil() as u32,
+    };
+
+    let windows: Vec<_> = (0..G::Scalar::NUM_BITS)
+        .step_by(c as usize)
+        .map(|skip| {
+            let mut buckets = vec![G::Projective::zero(); (1 << c) - 1];
+
+            for (p, s) in points.iter().zip(scalars.iter()) {
+                let mut s = *s;
+                s.shr(skip);
+                let digit = (s.as_ref()[0] % (1 << c)) as usize;
+
+                if digit != 0 {
+                    buckets[digit - 1].add_assign_mixed(p);
+                }
+            }
+
+            // sum_j j * bucket_j, as the sum of the running sums from the highest bucket down
+            let mut running_sum = G::Projective::zero();
+            let mut window = G::Projective::zero();
+            for bucket in buckets.into_iter().rev() {
+                running_sum.add_assign(&bucket);
+                window.add_assign(&running_sum);
+            }
+            window
+        })
+        .collect();
+
+    // combine the windows from the most significant one
+    windows
+        .into_iter()
+        .rev()
+        .fold(G::Projective::zero(), |mut acc, window| {
+            for _ in 0..c {
+                acc.double();
+            }
+            acc.add_assign(&window);
+            acc
+        })
+}
+
+/// Generate the proving and verification keys of `sap`, using randomness from `rng`
+pub fn generate_parameters<E: Engine, R: Rng>(sap: &Sap<E>, rng: &mut R) -> ProvingKey<E> {
+    let nonzero = |rng: &mut R| loop {
+        let v: Fr<E> = rng.gen();
+        if !v.is_zero() {
+            break v;
+        }
+    };
+
+    let alpha = nonzero(rng);
+    let beta = nonzero(rng);
+    let gamma = nonzero(rng);
+    // the evaluation point must not be in the domain
+    let t = loop {
+        let t = nonzero(rng);
+        if !sap.domain.z(&t).is_zero() {
+            break t;
+        }
+    };
+
+    let g = E::G1::one();
+    let h = E::G2::one();
+
+    let (u, w) = sap.evaluate_at(&t);
+    let zt = sap.domain.z(&t);
+
+    let mut alpha_beta = alpha;
+    alpha_beta.add_assign(&beta);
+    let mut gamma2 = gamma;
+    gamma2.square();
+
+    let mul = |a: &Fr<E>, b: &Fr<E>| {
+        let mut res = *a;
+        res.mul_assign(b);
+        res
+    };
+
+    // (alpha + beta) * U_i(t) + gamma * W_i(t)
+    let query: Vec<_> = (0..sap.num_public)
+        .map(|i| {
+            let mut v = mul(&alpha_beta, &u[i]);
+            v.add_assign(&mul(&gamma, &w[i]));
+            v
+        })
+        .collect();
+
+    // gamma * U_i(t)
+    let gamma_u: Vec<_> = u[..sap.num_variables]
+        .iter()
+        .map(|u| mul(&gamma, u))
+        .collect();
+
+    // (alpha + beta) * gamma * U_i(t) + gamma^2 * W_i(t)
+    let c_1: Vec<_> = (sap.num_public..sap.num_sap_variables())
+        .map(|i| {
+            let mut v = mul(&mul(&alpha_beta, &gamma), &u[i]);
+            v.add_assign(&mul(&gamma2, &w[i]));
+            v
+        })
+        .collect();
+
+    // 2 * gamma^2 * Z(t) * U_i(t)
+    let mut two_gamma2_z = mul(&gamma2, &zt);
+    two_gamma2_z.double();
+    let c_2: Vec<_> = u[..sap.num_variables]
+        .iter()
+        .map(|u| mul(&two_gamma2_z, u))
+        .collect();
+
+    // gamma^2 * Z(t) * t^i
+    let gamma2_z = mul(&gamma2, &zt);
+    let mut t_i = Fr::<E>::one();
+    let z_t: Vec<_> = (0..sap.domain.size - 1)
+        .map(|_| {
+            let v = mul(&gamma2_z, &t_i);
+            t_i.mul_assign(&t);
+            v
+        })
+        .collect();
+
+    let gamma_z = mul(&gamma, &zt);
+
+    let vk = VerifyingKey {
+        h: h.into_affine(),
+        g_alpha: g.into_affine().mul(alpha).into_affine(),
+        h_beta: h.into_affine().mul(beta).into_affine(),
+        g_gamma: g.into_affine().mul(gamma).into_affine(),
+        h_gamma: h.into_affine().mul(gamma).into_affine(),
+        query: batch_exp(g, &query),
+    };
+
+    ProvingKey {
+        vk,
+        a_query: batch_exp(g, &gamma_u),
+        b_query: batch_exp(h, &gamma_u),
+        c_query_1: batch_exp(g, &c_1),
+        c_query_2: batch_exp(g, &c_2),
+        g_gamma_z: g.into_affine().mul(gamma_z).into_affine(),
+        h_gamma_z: h.into_affine().mul(gamma_z).into_affine(),
+        g_ab_gamma_z: g
+            .into_affine()
+            .mul(mul(&alpha_beta, &gamma_z))
+            .into_affine(),
+        g_gamma2_z2: g.into_affine().mul(mul(&gamma2_z, &zt)).into_affine(),
+        g_gamma2_z_t: batch_exp(g, &z_t),
+    }
+}
+
+/// Create a proof for `assignment`, which assigns the variables of the constraint system `sap`
+/// was reduced from, using randomness from `rng`
+pub fn create_proof<E: Engine, R: Rng>(
+    pk: &ProvingKey<E>,
+    sap: &Sap<E>,
+    assignment: &[Fr<E>],
+    rng: &mut R,
+) -> Proof<E> {
+    assert_eq!(assignment.len(), sap.num_variables);
+
+    let z = sap.extend(assignment);
+
+    // compute the coefficients of H(X) = (U(X)^2 - W(X)) / Z(X)
+    let mut u: Vec<_> = sap.u.iter().map(|row| evaluate::<E>(row, &z)).collect();
+    let mut w: Vec<_> = sap.w.iter().map(|row| evaluate::<E>(row, &z)).collect();
+    u.resize(sap.domain.size, Fr::<E>::zero());
+    w.resize(sap.domain.size, Fr::<E>::zero());
+
+    sap.domain.ifft(&mut u);
+    sap.domain.ifft(&mut w);
+    sap.domain.coset_fft(&mut u);
+    sap.domain.coset_fft(&mut w);
+
+    // Z is constant on the coset
+    let z_inv = sap
+        .domain
+        .z(&Fr::<E>::multiplicative_generator())
+        .inverse()
+        .unwrap();
+
+    let mut h: Vec<_> = u
+        .into_iter()
+        .zip(w.into_iter())
+        .map(|(mut u, w)| {
+            u.square();
+            u.sub_assign(&w);
+            u.mul_assign(&z_inv);
+            u
+        })
+        .collect();
+
+    sap.domain.icoset_fft(&mut h);
+
+    let r: Fr<E> = rng.gen();
+    let mut r2 = r;
+    r2.square();
+
+    let z_variables = &z[..sap.num_variables];
+
+    let mut a = multiexp(&pk.a_query, z_variables);
+    a.add_assign(&pk.g_gamma_z.mul(r));
+
+    let mut b = multiexp(&pk.b_query, z_variables);
+    b.add_assign(&pk.h_gamma_z.mul(r));
+
+    let mut c = multiexp(&pk.c_query_1, &z[sap.num_public..]);
+    c.add_assign(&multiexp(&pk.g_gamma2_z_t, &h[..sap.domain.size - 1]));
+    c.add_assign(&pk.g_ab_gamma_z.mul(r));
+    c.add_assign(&pk.g_gamma2_z2.mul(r2));
+    let mut c_2 = multiexp(&pk.c_query_2, z_variables);
+    c_2.mul_assign(r);
+    c.add_assign(&c_2);
+
+    Proof {
+        a: a.into_affine(),
+        b: b.into_affine(),
+        c: c.into_affine(),
+    }
+}
+
+/// Check `proof` against `vk` for the public inputs `inputs`, which exclude the constant one
+pub fn verify_proof<E: Engine>(
+    vk: &VerifyingKey<E>,
+    proof: &Proof<E>,
+    inputs: &[Fr<E>],
+) -> Result<bool, String> {
+    if inputs.len() + 1 != vk.query.len() {
+        return Err(format!(
+            "Expected {} public inputs, found {}",
+            vk.query.len() - 1,
+            inputs.len()
+        ));
+    }
+
+    let mut psi = vk.query[0].into_projective();
+    psi.add_assign(&multiexp(&vk.query[1..], inputs));
+
+    let mut a_alpha = proof.a.into_projective();
+    a_alpha.add_assign_mixed(&vk.g_alpha);
+    let mut b_beta = proof.b.into_projective();
+    b_beta.add_assign_mixed(&vk.h_beta);
+
+    // e(A * G^alpha, B * H^beta) = e(G^alpha, H^beta) * e(G^psi, H^gamma) * e(C, H)
+    let mut rhs = E::pairing(vk.g_alpha, vk.h_beta);
+    rhs.mul_assign(&E::pairing(psi, vk.h_gamma));
+    rhs.mul_assign(&E::pairing(proof.c, vk.h));
+
+    // e(A, H^gamma) = e(G^gamma, B)
+    Ok(E::pairing(a_alpha, b_beta) == rhs
+        && E::pairing(proof.a, vk.h_gamma) == E::pairing(vk.g_gamma, proof.b))
+}
+
+fn write_g1<E: Engine, W: Write>(writer: &mut W, points: &[E::G1Affine]) -> io::Result<()> {
+    writer.write_all(&(points.len() as u32).to_be_bytes())?;
+    for p in points {
+        writer.write_all(p.into_uncompressed().as_ref())?;
+    }
+    Ok(())
+}
+
+fn write_g2<E: Engine, W: Write>(writer: &mut W, points: &[E::G2Affine]) -> io::Result<()> {
+    writer.write_all(&(points.len() as u32).to_be_bytes())?;
+    for p in points {
+        writer.write_all(p.into_uncompressed().as_ref())?;
+    }
+    Ok(())
+}
+
+fn read_points<A: CurveAffine, R: Read>(reader: &mut R) -> io::Result<Vec<A>> {
+    let mut len = [0u8; 4];
+    reader.read_exact(&mut len)?;
+
+    (0..u32::from_be_bytes(len))
+        .map(|_| {
+            let mut encoded = A::Uncompressed::empty();
+            reader.read_exact(encoded.as_mut())?;
+            encoded
+                .into_affine()
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+        })
+        .collect()
+}
+
+fn read_point<A: CurveAffine, R: Read>(reader: &mut R) -> io::Result<A> {
+    let mut points = read_points(reader)?;
+    match points.len() {
+        1 => Ok(points.pop().unwrap()),
+        _ => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "expected a single point",
+        )),
+    }
+}
+
+impl<E: Engine> ProvingKey<E> {
+    pub fn write<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        write_g2::<E, _>(&mut writer, &[self.vk.h])?;
+        write_g1::<E, _>(&mut writer, &[self.vk.g_alpha])?;
+        write_g2::<E, _>(&mut writer, &[self.vk.h_beta])?;
+        write_g1::<E, _>(&mut writer, &[self.vk.g_gamma])?;
+        write_g2::<E, _>(&mut writer, &[self.vk.h_gamma])?;
+        write_g1::<E, _>(&mut writer, &self.vk.query)?;
+        write_g1::<E, _>(&mut writer, &self.a_query)?;
+        write_g2::<E, _>(&mut writer, &self.b_query)?;
+        write_g1::<E, _>(&mut writer, &self.c_query_1)?;
+        write_g1::<E, _>(&mut writer, &self.c_query_2)?;
+        write_g1::<E, _>(&mut writer, &[self.g_gamma_z])?;
+        write_g2::<E, _>(&mut writer, &[self.h_gamma_z])?;
+        write_g1::<E, _>(&mut writer, &[self.g_ab_gamma_z])?;
+        write_g1::<E, _>(&mut writer, &[self.g_gamma2_z2])?;
+        write_g1::<E, _>(&mut writer, &self.g_gamma2_z_t)
+    }
+
+    pub fn read<R: Read>(mut reader: R) -> io::Result<Self> {
+        Ok(ProvingKey {
+            vk: VerifyingKey {
+                h: read_point(&mut reader)?,
+                g_alpha: read_point(&mut reader)?,
+                h_beta: read_point(&mut reader)?,
+                g_gamma: read_point(&mut reader)?,
+                h_gamma: read_point(&mut reader)?,
+                query: read_points(&mut reader)?,
+            },
+            a_query: read_points(&mut reader)?,
+            b_query: read_points(&mut reader)?,
+            c_query_1: read_points(&mut reader)?,
+            c_query_2: read_points(&mut reader)?,
+            g_gamma_z: read_point(&mut reader)?,
+            h_gamma_z: read_point(&mut reader)?,
+            g_ab_gamma_z: read_point(&mut reader)?,
+            g_gamma2_z2: read_point(&mut reader)?,
+            g_gamma2_z_t: read_points(&mut reader)?,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::rand::thread_rng;
+    use super::*;
+    use pairing::bn256::{Bn256, Fr as BnFr, G1Affine, G1};
+
+    fn fr(v: &str) -> BnFr {
+        BnFr::from_str(v).unwrap()
+    }
+
+    // x * x = y, with y public
+    fn square() -> Sap<Bn256> {
+        Sap::from_r1cs(
+            3,
+            2,
+            vec![vec![(2, fr("1"))]],
+            vec![vec![(2, fr("1"))]],
+            vec![vec![(1, fr("1"))]],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn fft_round_trip() {
+        let domain = Domain::<BnFr>::new(8).unwrap();
+        let values: Vec<BnFr> = (0..8).map(|_| thread_rng().gen()).collect();
+
+        let mut v = values.clone();
+        domain.fft(&mut v);
+        domain.ifft(&mut v);
+        assert_eq!(v, values);
+
+        domain.coset_fft(&mut v);
+        domain.icoset_fft(&mut v);
+        assert_eq!(v, values);
+    }
+
+    #[test]
+    fn multiexp_matches_naive() {
+        let rng = &mut thread_rng();
+
+        for n in &[0, 1, 10, 100] {
+            let points: Vec<G1Affine> = (0..*n).map(|_| rng.gen::<G1>().into_affine()).collect();
+            let mut scalars: Vec<BnFr> = (0..*n).map(|_| rng.gen()).collect();
+            // zero and one are edge cases of the windows
+            if *n > 1 {
+                scalars[0] = BnFr::zero();
+                scalars[1] = BnFr::one();
+            }
+
+            let naive = points
+                .iter()
+                .zip(scalars.iter())
+                .fold(G1::zero(), |mut acc, (p, s)| {
+                    acc.add_assign(&p.mul(*s));
+                    acc
+                });
+
+            assert_eq!(multiexp(&points, &scalars), naive);
+        }
+    }
+
+    #[test]
+    fn prove_and_verify() {
+        let sap = square();
+        let pk = generate_parameters(&sap, &mut thread_rng());
+
+        let proof = create_proof(&pk, &sap, &[fr("1"), fr("9"), fr("3")], &mut thread_rng());
+
+        assert!(verify_proof(&pk.vk, &proof, &[fr("9")]).unwrap());
+        assert!(!verify_proof(&pk.vk, &proof, &[fr("4")]).unwrap());
+        assert!(verify_proof(&pk.vk, &proof, &[]).is_err());
+    }
+
+    #[test]
+    fn unsatisfied() {
+        let sap = square();
+        let pk = generate_parameters(&sap, &mut thread_rng());
+
+        let proof = create_proof(&pk, &sap, &[fr("1"), fr("10"), fr("3")], &mut thread_rng());
+
+        assert!(!verify_proof(&pk.vk, &proof, &[fr("10")]).unwrap());
+    }
+
+    #[test]
+    fn serialize_proving_key() {
+        let sap = square();
+        let pk = generate_parameters(&sap, &mut thread_rng());
+
+        let mut buffer = vec![];
+        pk.write(&mut buffer).unwrap();
+        let deserialized = ProvingKey::<Bn256>::read(&buffer[..]).unwrap();
+
+        assert_eq!(deserialized.vk, pk.vk);
+        assert_eq!(deserialized.a_query, pk.a_query);
+        assert_eq!(deserialized.g_gamma2_z_t, pk.g_gamma2_z_t);
+    }
+}
@@ -1,4 +1,6 @@
 pub mod bellman;
+pub mod gm17;
 #[cfg(feature = "libsnark")]
 pub mod libsnark;
+pub mod r1cs;
 pub mod solidity;
@@ -1,27 +1,37 @@
 mod bn128;
+mod threads;
 
 use std::fs::File;
-use zokrates_field::field::FieldPrime;
+use zokrates_field::field::Field;
 
+pub use self::bn128::MpcParameters;
 pub use self::bn128::G16;
-#[cfg(feature = "libsnark")]
 pub use self::bn128::GM17;
 #[cfg(feature = "libsnark")]
 pub use self::bn128::PGHR13;
+pub use self::threads::set_threads;
 
 use crate::ir;
 use std::io::BufReader;
 
-pub trait ProofSystem {
-    fn setup(&self, program: ir::Prog<FieldPrime>, pk_path: &str, vk_path: &str);
+pub trait ProofSystem<T: Field> {
+    /// Generate the proving key at `pk_path` and the verification key at `vk_path`, returning an
+    /// error if `program` is not supported by the scheme or the keys cannot be written
+    fn setup(&self, program: ir::Prog<T>, pk_path: &str, vk_path: &str) -> Result<(), String>;
 
+    /// Generate a proof of `witness` at `proof_path` with the proving key at `pk_path`, returning
+    /// an error if the key cannot be read or the proof cannot be written
     fn generate_proof(
         &self,
-        program: ir::Prog<FieldPrime>,
-        witness: ir::Witness<FieldPrime>,
+        program: ir::Prog<T>,
+        witness: ir::Witness<T>,
         pk_path: &str,
         proof_path: &str,
-    ) -> bool;
+    ) -> Result<(), String>;
 
     fn export_solidity_verifier(&self, reader: BufReader<File>, is_abiv2: bool) -> String;
+
+    /// Check the proof at `proof_path` against the verification key at `vk_path`, returning an
+    /// error if either of them cannot be read
+    fn verify(&self, vk_path: &str, proof_path: &str) -> Result<bool, String>;
 }
@@ -0,0 +1,75 @@
+//! Module containing the configuration of the number of threads the provers use
+//!
+//! bellman sizes its thread pool after the number of CPUs the calling thread may run on, which is
+//! not configurable otherwise. Restricting the CPUs of a thread before it generates a proof thus
+//! sets the number of threads of the prover.
+
+/// Restricts the current thread, and the threads it starts afterwards, to `threads` of the CPUs
+/// it may run on
+#[cfg(target_os = "linux")]
+pub fn set_threads(threads: usize) -> Result<(), String> {
+    extern crate libc;
+
+    use std::io;
+    use std::mem;
+
+    let size = mem::size_of::<libc::cpu_set_t>();
+
+    unsafe {
+        let mut available: libc::cpu_set_t = mem::zeroed();
+        if libc::sched_getaffinity(0, size, &mut available) != 0 {
+            return Err(format!(
+                "Could not get the CPUs of the current thread: {}",
+                io::Error::last_os_error()
+            ));
+        }
+
+        let cpus: Vec<usize> = (0..libc::CPU_SETSIZE as usize)
+            .filter(|cpu| libc::CPU_ISSET(*cpu, &available))
+            .collect();
+
+        if threads == 0 || threads > cpus.len() {
+            return Err(format!(
+                "Expected between 1 and {} threads, got {}",
+                cpus.len(),
+                threads
+            ));
+        }
+
+        let mut selected: libc::cpu_set_t = mem::zeroed();
+        for cpu in &cpus[..threads] {
+            libc::CPU_SET(*cpu, &mut selected);
+        }
+
+        if libc::sched_setaffinity(0, size, &selected) != 0 {
+            return Err(format!(
+                "Could not set the CPUs of the current thread: {}",
+                io::Error::last_os_error()
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(not(target_os = "linux"))]
+pub fn set_threads(_: usize) -> Result<(), String> {
+    Err("Setting the number of threads is only supported on Linux".to_string())
+}
+
+#[cfg(test)]
+#[cfg(target_os = "linux")]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    #[test]
+    fn single_thread() {
+        thread::spawn(|| set_threads(1)).join().unwrap().unwrap();
+    }
+
+    #[test]
+    fn no_thread() {
+        assert!(thread::spawn(|| set_threads(0)).join().unwrap().is_err());
+    }
+}
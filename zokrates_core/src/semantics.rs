@@ -6,12 +6,12 @@
 //! @author Thibaut Schaeffer <thibaut@schaeff.fr>
 //! @date 2017
 
-use crate::absy::variable::Variable;
 use crate::absy::Identifier;
 use crate::absy::*;
 use crate::typed_absy::*;
-use crate::types::Signature;
-use std::collections::HashSet;
+use crate::typed_absy::{Parameter, Variable};
+use crate::types::{Signature, StructMember, StructType};
+use std::collections::{HashMap, HashSet};
 use std::fmt;
 use zokrates_field::field::Field;
 
@@ -127,7 +127,12 @@ pub struct FunctionDeclaration {
 pub struct Checker<'ast> {
     scope: HashSet<ScopedVariable<'ast>>,
     functions: HashSet<FunctionDeclaration>,
+    types: HashMap<UserTypeId, Type>,
     level: usize,
+    /// Whether to mark the origin of the checked statements, for profiling
+    profile: bool,
+    /// The function being checked, if its statements are marked
+    marked_function: Option<FunctionIdentifier<'ast>>,
 }
 
 impl<'ast> Checker<'ast> {
@@ -135,7 +140,10 @@ impl<'ast> Checker<'ast> {
         Checker {
             scope: HashSet::new(),
             functions: HashSet::new(),
+            types: HashMap::new(),
             level: 0,
+            profile: false,
+            marked_function: None,
         }
     }
 
@@ -143,6 +151,22 @@ impl<'ast> Checker<'ast> {
         Checker::new().check_program(prog)
     }
 
+    /// Checks `prog`, preceding each statement with a marker of its origin
+    pub fn check_with_markers<T: Field>(
+        prog: Prog<'ast, T>,
+    ) -> Result<TypedProg<'ast, T>, Vec<Error>> {
+        Checker {
+            profile: true,
+            ..Checker::new()
+        }
+        .check_program(prog)
+    }
+
+    // the marker to insert before a statement at `pos`, if statements are marked
+    fn marker<T: Field>(&self, pos: Position) -> Option<TypedStatement<'ast, T>> {
+        self.marked_function.map(|id| TypedStatement::Marker(id, pos))
+    }
+
     fn check_program<T: Field>(
         &mut self,
         prog: Prog<'ast, T>,
@@ -157,30 +181,52 @@ impl<'ast> Checker<'ast> {
         let mut errors = vec![];
         let mut checked_functions = vec![];
 
+        // structs can only refer to structs declared before them
+        for s in prog.structs {
+            match self.check_struct_definition(s) {
+                Ok(()) => {}
+                Err(e) => errors.extend(e),
+            }
+        }
+
+        let mut unresolved_signatures = false;
+
         for func in prog.functions {
             self.enter_scope();
 
-            let dec = FunctionDeclaration {
-                id: func.value.id.to_string(),
-                signature: func.value.signature.clone(),
-            };
+            match self.check_signature(func.value.signature.clone()) {
+                Ok(signature) => {
+                    let dec = FunctionDeclaration {
+                        id: func.value.id.to_string(),
+                        signature: signature.clone(),
+                    };
 
-            match self.check_function(func) {
-                Ok(checked_function) => {
-                    checked_functions.push(checked_function);
+                    match self.check_function(func, signature) {
+                        Ok(checked_function) => {
+                            checked_functions.push(checked_function);
+                        }
+                        Err(e) => {
+                            errors.extend(e);
+                        }
+                    };
+                    self.functions.insert(dec);
                 }
                 Err(e) => {
+                    unresolved_signatures = true;
                     errors.extend(e);
                 }
-            };
-            self.functions.insert(dec);
+            }
+
             self.exit_scope();
         }
 
-        match self.check_single_main() {
-            Ok(()) => {}
-            Err(e) => errors.push(e),
-        };
+        // functions with unresolved signatures are not declared, so main could be missing because of them
+        if !unresolved_signatures {
+            match self.check_single_main() {
+                Ok(()) => {}
+                Err(e) => errors.push(e),
+            };
+        }
 
         if errors.len() > 0 {
             return Err(errors);
@@ -207,11 +253,109 @@ impl<'ast> Checker<'ast> {
         }
     }
 
-    fn check_for_var(&self, var: &VariableNode) -> Result<(), Error> {
-        match var.value.get_type() {
+    fn check_struct_definition(&mut self, s: StructDefinitionNode<'ast>) -> Result<(), Vec<Error>> {
+        let pos = s.pos();
+        let s = s.value;
+
+        let mut errors = vec![];
+        let mut members = vec![];
+        let mut member_ids = HashSet::new();
+
+        for field in s.fields {
+            let field_pos = field.pos();
+            let field = field.value;
+            match self.check_type(field.ty) {
+                Ok(ty) => match member_ids.insert(field.id) {
+                    true => members.push(StructMember::new(field.id.to_string(), ty)),
+                    false => errors.push(Error {
+                        pos: Some(field_pos),
+                        message: format!("Duplicate member {} in struct {}", field.id, s.id),
+                    }),
+                },
+                Err(e) => errors.push(e),
+            }
+        }
+
+        if self.types.contains_key(s.id) {
+            errors.push(Error {
+                pos: Some(pos),
+                message: format!("Duplicate definition for type {}", s.id),
+            });
+        }
+
+        if errors.len() > 0 {
+            return Err(errors);
+        }
+
+        self.types.insert(
+            s.id.to_string(),
+            Type::Struct(StructType::new(s.id.to_string(), members)),
+        );
+
+        Ok(())
+    }
+
+    fn check_type(&self, ty: UnresolvedTypeNode) -> Result<Type, Error> {
+        let pos = ty.pos();
+
+        match ty.value {
+            UnresolvedType::FieldElement => Ok(Type::FieldElement),
+            UnresolvedType::Boolean => Ok(Type::Boolean),
+            UnresolvedType::Uint(bitwidth) => Ok(Type::Uint(bitwidth)),
+            UnresolvedType::Array(box ty, size) => Ok(Type::array(
+                self.check_type(Node::new(pos.0, pos.1, ty))?,
+                size,
+            )),
+            UnresolvedType::User(id) => self.types.get(&id).cloned().ok_or(Error {
+                pos: Some(pos),
+                message: format!("Undefined type {}", id),
+            }),
+        }
+    }
+
+    fn check_signature(&self, signature: UnresolvedSignature) -> Result<Signature, Vec<Error>> {
+        let mut errors = vec![];
+        let mut inputs = vec![];
+        let mut outputs = vec![];
+
+        for t in signature.inputs {
+            match self.check_type(t) {
+                Ok(t) => inputs.push(t),
+                Err(e) => errors.push(e),
+            }
+        }
+
+        for t in signature.outputs {
+            match self.check_type(t) {
+                Ok(t) => outputs.push(t),
+                Err(e) => errors.push(e),
+            }
+        }
+
+        match errors.len() {
+            0 => Ok(Signature::new().inputs(inputs).outputs(outputs)),
+            _ => Err(errors),
+        }
+    }
+
+    fn check_variable(&self, v: VariableNode<'ast>) -> Result<Variable<'ast>, Error> {
+        let ty = self.check_type(Node::new(v.start, v.end, v.value._type))?;
+        Ok(Variable::with_id_and_type(v.value.id.into(), ty))
+    }
+
+    // shifting by at least the bitwidth gives zero, so larger amounts are capped
+    fn shift_amount<T: Field>(by: &T, bitwidth: usize) -> usize {
+        by.to_dec_string()
+            .parse::<usize>()
+            .map(|by| by.min(bitwidth))
+            .unwrap_or(bitwidth)
+    }
+
+    fn check_for_var(&self, var: &Variable<'ast>, pos: (Position, Position)) -> Result<(), Error> {
+        match var.get_type() {
             Type::FieldElement => Ok(()),
             t => Err(Error {
-                pos: Some(var.pos()),
+                pos: Some(pos),
                 message: format!("Variable in for loop cannot have type {}", t),
             }),
         }
@@ -220,23 +364,18 @@ impl<'ast> Checker<'ast> {
     fn check_function<T: Field>(
         &mut self,
         funct_node: FunctionNode<'ast, T>,
+        signature: Signature,
     ) -> Result<TypedFunction<'ast, T>, Vec<Error>> {
         let mut errors = vec![];
         let pos = funct_node.pos();
         let funct = funct_node.value;
 
-        assert_eq!(funct.arguments.len(), funct.signature.inputs.len());
+        assert_eq!(funct.arguments.len(), signature.inputs.len());
 
         let query = FunctionQuery::new(
             funct.id,
-            &funct.signature.inputs,
-            &funct
-                .signature
-                .outputs
-                .clone()
-                .into_iter()
-                .map(|o| Some(o))
-                .collect(),
+            &signature.inputs,
+            &signature.outputs.clone().into_iter().map(|o| Some(o)).collect(),
         );
 
         let candidates = self.find_candidates(&query);
@@ -247,7 +386,7 @@ impl<'ast> Checker<'ast> {
                     pos: Some(pos),
                     message: format!(
                         "Duplicate definition for function {} with signature {}",
-                        funct.id, funct.signature
+                        funct.id, signature
                     ),
                 });
             }
@@ -255,14 +394,26 @@ impl<'ast> Checker<'ast> {
             _ => panic!("duplicate function declaration should have been caught"),
         }
 
-        for arg in &funct.arguments {
-            self.insert_scope(arg.value.id.value.clone());
+        let mut arguments_checked = vec![];
+
+        for (arg, ty) in funct.arguments.into_iter().zip(signature.inputs.iter()) {
+            let v = Variable::with_id_and_type(arg.value.id.value.id.into(), ty.clone());
+            self.insert_scope(v.clone());
+            arguments_checked.push(Parameter {
+                id: v,
+                private: arg.value.private,
+            });
         }
 
         let mut statements_checked = vec![];
 
+        if self.profile {
+            self.marked_function = Some(funct.id);
+        }
+
         for stat in funct.statements.into_iter() {
-            match self.check_statement(stat, &funct.signature.outputs) {
+            statements_checked.extend(self.marker(stat.pos().0));
+            match self.check_statement(stat, &signature.outputs) {
                 Ok(statement) => {
                     statements_checked.push(statement);
                 }
@@ -278,13 +429,9 @@ impl<'ast> Checker<'ast> {
 
         Ok(TypedFunction {
             id: funct.id,
-            arguments: funct
-                .arguments
-                .into_iter()
-                .map(|a| a.value.into())
-                .collect(),
+            arguments: arguments_checked,
             statements: statements_checked,
-            signature: funct.signature,
+            signature,
         })
     }
 
@@ -328,13 +475,16 @@ impl<'ast> Checker<'ast> {
                     }),
                 }
             }
-            Statement::Declaration(var) => match self.insert_scope(var.clone().value) {
-                true => Ok(TypedStatement::Declaration(var.value.into())),
-                false => Err(Error {
-                    pos: Some(pos),
-                    message: format!("Duplicate declaration for variable named {}", var.value.id),
-                }),
-            },
+            Statement::Declaration(var) => {
+                let var = self.check_variable(var)?;
+                match self.insert_scope(var.clone()) {
+                    true => Ok(TypedStatement::Declaration(var)),
+                    false => Err(Error {
+                        pos: Some(pos),
+                        message: format!("Duplicate declaration for variable named {}", var.id),
+                    }),
+                }
+            }
             Statement::Definition(assignee, expr) => {
                 // we create multidef when rhs is a function call to benefit from inference
                 // check rhs is not a function call here
@@ -369,9 +519,9 @@ impl<'ast> Checker<'ast> {
                 let checked_rhs = self.check_expression(rhs)?;
 
                 match (checked_lhs.clone(), checked_rhs.clone()) {
-                    (ref l, ref r) if r.get_type() == l.get_type() => {
-                        Ok(TypedStatement::Condition(checked_lhs, checked_rhs))
-                    }
+                    (ref l, ref r) if r.get_type() == l.get_type() => Ok(
+                        TypedStatement::Condition(checked_lhs, checked_rhs, Some(pos.0)),
+                    ),
                     (e1, e2) => Err(Error {
                         pos: Some(pos),
                         message: format!(
@@ -387,20 +537,24 @@ impl<'ast> Checker<'ast> {
             Statement::For(var, from, to, statements) => {
                 self.enter_scope();
 
-                self.check_for_var(&var)?;
+                let var_pos = var.pos();
+                let var = self.check_variable(var)?;
 
-                self.insert_scope(var.clone().value);
+                self.check_for_var(&var, var_pos)?;
+
+                self.insert_scope(var.clone());
 
                 let mut checked_statements = vec![];
 
                 for stat in statements {
+                    checked_statements.extend(self.marker(stat.pos().0));
                     let checked_stat = self.check_statement(stat, header_return_types)?;
                     checked_statements.push(checked_stat);
                 }
 
                 self.exit_scope();
                 Ok(TypedStatement::For(
-                    var.value.into(),
+                    var,
                     from,
                     to,
                     checked_statements,
@@ -448,7 +602,7 @@ impl<'ast> Checker<'ast> {
 
                                 // we can infer the left hand side to be typed as the return values
                     			let lhs: Vec<_> = var_names.iter().enumerate().map(|(index, name)|
-                    				Variable::new(*name, f.signature.outputs[index].clone())
+                    				Variable::with_id_and_type((*name).into(), f.signature.outputs[index].clone())
                     			).collect();
 
                                 let assignees: Vec<_> = lhs.iter().map(|v| v.clone().into()).collect();
@@ -484,12 +638,10 @@ impl<'ast> Checker<'ast> {
         // check that the assignee is declared
         match assignee.value {
             Assignee::Identifier(variable_name) => match self.get_scope(&variable_name) {
-                Some(var) => Ok(TypedAssignee::Identifier(
-                    crate::typed_absy::Variable::with_id_and_type(
-                        variable_name.into(),
-                        var.id.get_type(),
-                    ),
-                )),
+                Some(var) => Ok(TypedAssignee::Identifier(Variable::with_id_and_type(
+                    variable_name.into(),
+                    var.id.get_type(),
+                ))),
                 None => Err(Error {
                     pos: Some(assignee.pos()),
                     message: format!("Undeclared variable: {:?}", variable_name),
@@ -497,6 +649,20 @@ impl<'ast> Checker<'ast> {
             },
             Assignee::ArrayElement(box assignee, box index) => {
                 let checked_assignee = self.check_assignee(assignee)?;
+
+                match checked_assignee.get_type() {
+                    Type::Array(..) => {}
+                    ty => {
+                        return Err(Error {
+                            pos: Some(pos),
+                            message: format!(
+                                "Cannot access element of {} of non-array type {}",
+                                checked_assignee, ty
+                            ),
+                        })
+                    }
+                };
+
                 let checked_index = match index {
                     RangeOrExpression::Expression(e) => self.check_expression(e)?,
                     r => unimplemented!(
@@ -536,15 +702,12 @@ impl<'ast> Checker<'ast> {
 
                 let checked_expression = self.check_expression(s.value.expression)?;
                 match checked_expression {
-                    TypedExpression::FieldElementArray(e) => {
+                    TypedExpression::Array(e) => {
                         let size = e.size();
                         Ok((0..size)
                             .map(|i| {
-                                FieldElementExpression::Select(
-                                    box e.clone(),
-                                    box FieldElementExpression::Number(T::from(i)),
-                                )
-                                .into()
+                                e.clone()
+                                    .select(FieldElementExpression::Number(T::from(i)))
                             })
                             .collect())
                     }
@@ -552,7 +715,7 @@ impl<'ast> Checker<'ast> {
                         pos: Some(pos),
 
                         message: format!(
-                            "Expected spread operator to apply on field element array, found {}",
+                            "Expected spread operator to apply on array, found {}",
                             e.get_type()
                         ),
                     }),
@@ -578,8 +741,16 @@ impl<'ast> Checker<'ast> {
                         Type::FieldElement => {
                             Ok(FieldElementExpression::Identifier(name.into()).into())
                         }
-                        Type::FieldElementArray(n) => {
-                            Ok(FieldElementArrayExpression::Identifier(n, name.into()).into())
+                        Type::Array(array_type) => Ok(ArrayExpressionInner::Identifier(name.into())
+                            .annotate(*array_type.ty, array_type.size)
+                            .into()),
+                        Type::Uint(bitwidth) => Ok(UExpressionInner::Identifier(name.into())
+                            .annotate(bitwidth)
+                            .into()),
+                        Type::Struct(struct_type) => {
+                            Ok(StructExpressionInner::Identifier(name.into())
+                                .annotate(struct_type)
+                                .into())
                         }
                     },
                     None => Err(Error {
@@ -596,11 +767,19 @@ impl<'ast> Checker<'ast> {
                     (TypedExpression::FieldElement(e1), TypedExpression::FieldElement(e2)) => {
                         Ok(FieldElementExpression::Add(box e1, box e2).into())
                     }
+                    (TypedExpression::Uint(e1), TypedExpression::Uint(e2))
+                        if e1.bitwidth() == e2.bitwidth() =>
+                    {
+                        let bitwidth = e1.bitwidth();
+                        Ok(UExpressionInner::Add(box e1, box e2)
+                            .annotate(bitwidth)
+                            .into())
+                    }
                     (t1, t2) => Err(Error {
                         pos: Some(pos),
 
                         message: format!(
-                            "Expected only field elements, found {:?}, {:?}",
+                            "Expected field elements or unsigned integers of the same width, found {:?}, {:?}",
                             t1.get_type(),
                             t2.get_type()
                         ),
@@ -615,11 +794,19 @@ impl<'ast> Checker<'ast> {
                     (TypedExpression::FieldElement(e1), TypedExpression::FieldElement(e2)) => {
                         Ok(FieldElementExpression::Sub(box e1, box e2).into())
                     }
+                    (TypedExpression::Uint(e1), TypedExpression::Uint(e2))
+                        if e1.bitwidth() == e2.bitwidth() =>
+                    {
+                        let bitwidth = e1.bitwidth();
+                        Ok(UExpressionInner::Sub(box e1, box e2)
+                            .annotate(bitwidth)
+                            .into())
+                    }
                     (t1, t2) => Err(Error {
                         pos: Some(pos),
 
                         message: format!(
-                            "Expected only field elements, found {:?}, {:?}",
+                            "Expected field elements or unsigned integers of the same width, found {:?}, {:?}",
                             t1.get_type(),
                             t2.get_type()
                         ),
@@ -634,11 +821,19 @@ impl<'ast> Checker<'ast> {
                     (TypedExpression::FieldElement(e1), TypedExpression::FieldElement(e2)) => {
                         Ok(FieldElementExpression::Mult(box e1, box e2).into())
                     }
+                    (TypedExpression::Uint(e1), TypedExpression::Uint(e2))
+                        if e1.bitwidth() == e2.bitwidth() =>
+                    {
+                        let bitwidth = e1.bitwidth();
+                        Ok(UExpressionInner::Mult(box e1, box e2)
+                            .annotate(bitwidth)
+                            .into())
+                    }
                     (t1, t2) => Err(Error {
                         pos: Some(pos),
 
                         message: format!(
-                            "Expected only field elements, found {:?}, {:?}",
+                            "Expected field elements or unsigned integers of the same width, found {:?}, {:?}",
                             t1.get_type(),
                             t2.get_type()
                         ),
@@ -697,8 +892,21 @@ impl<'ast> Checker<'ast> {
                                 (TypedExpression::FieldElement(consequence), TypedExpression::FieldElement(alternative)) => {
                                     Ok(FieldElementExpression::IfElse(box condition, box consequence, box alternative).into())
                                 },
-                                (TypedExpression::FieldElementArray(consequence), TypedExpression::FieldElementArray(alternative)) => {
-                                    Ok(FieldElementArrayExpression::IfElse(box condition, box consequence, box alternative).into())
+                                (TypedExpression::Boolean(consequence), TypedExpression::Boolean(alternative)) => {
+                                    Ok(BooleanExpression::IfElse(box condition, box consequence, box alternative).into())
+                                },
+                                (TypedExpression::Array(consequence), TypedExpression::Array(alternative)) => {
+                                    let ty = consequence.inner_type().clone();
+                                    let size = consequence.size();
+                                    Ok(ArrayExpressionInner::IfElse(box condition, box consequence, box alternative).annotate(ty, size).into())
+                                },
+                                (TypedExpression::Uint(consequence), TypedExpression::Uint(alternative)) => {
+                                    let bitwidth = consequence.bitwidth();
+                                    Ok(UExpressionInner::IfElse(box condition, box consequence, box alternative).annotate(bitwidth).into())
+                                },
+                                (TypedExpression::Struct(consequence), TypedExpression::Struct(alternative)) => {
+                                    let ty = consequence.ty().clone();
+                                    Ok(StructExpressionInner::IfElse(box condition, box consequence, box alternative).annotate(ty).into())
                                 },
                                 _ => unimplemented!()
                             }
@@ -718,6 +926,16 @@ impl<'ast> Checker<'ast> {
                 }
             }
             Expression::FieldConstant(n) => Ok(FieldElementExpression::Number(n).into()),
+            Expression::U8Constant(n) => Ok(UExpressionInner::Value(n as u128).annotate(8).into()),
+            Expression::U16Constant(n) => {
+                Ok(UExpressionInner::Value(n as u128).annotate(16).into())
+            }
+            Expression::U32Constant(n) => {
+                Ok(UExpressionInner::Value(n as u128).annotate(32).into())
+            }
+            Expression::U64Constant(n) => {
+                Ok(UExpressionInner::Value(n as u128).annotate(64).into())
+            }
             Expression::FunctionCall(fun_id, arguments) => {
                 // check the arguments
                 let mut arguments_checked = vec![];
@@ -749,12 +967,26 @@ impl<'ast> Checker<'ast> {
                                     arguments_checked,
                                 )
                                 .into()),
-                                Type::FieldElementArray(size) => {
-                                    Ok(FieldElementArrayExpression::FunctionCall(
-                                        size,
+                                Type::Array(ref array_type) => {
+                                    Ok(ArrayExpressionInner::FunctionCall(
                                         f.id.to_string(),
                                         arguments_checked,
                                     )
+                                    .annotate(*array_type.ty.clone(), array_type.size)
+                                    .into())
+                                }
+                                Type::Uint(bitwidth) => Ok(UExpressionInner::FunctionCall(
+                                    f.id.to_string(),
+                                    arguments_checked,
+                                )
+                                .annotate(bitwidth)
+                                .into()),
+                                Type::Struct(ref struct_type) => {
+                                    Ok(StructExpressionInner::FunctionCall(
+                                        f.id.to_string(),
+                                        arguments_checked,
+                                    )
+                                    .annotate(struct_type.clone())
                                     .into())
                                 }
                                 _ => unimplemented!(),
@@ -787,6 +1019,11 @@ impl<'ast> Checker<'ast> {
                     (TypedExpression::FieldElement(e1), TypedExpression::FieldElement(e2)) => {
                         Ok(BooleanExpression::Lt(box e1, box e2).into())
                     }
+                    (TypedExpression::Uint(e1), TypedExpression::Uint(e2))
+                        if e1.bitwidth() == e2.bitwidth() =>
+                    {
+                        Ok(BooleanExpression::UintLt(box e1, box e2).into())
+                    }
                     (e1, e2) => Err(Error {
                         pos: Some(pos),
                         message: format!(
@@ -806,6 +1043,11 @@ impl<'ast> Checker<'ast> {
                     (TypedExpression::FieldElement(e1), TypedExpression::FieldElement(e2)) => {
                         Ok(BooleanExpression::Le(box e1, box e2).into())
                     }
+                    (TypedExpression::Uint(e1), TypedExpression::Uint(e2))
+                        if e1.bitwidth() == e2.bitwidth() =>
+                    {
+                        Ok(BooleanExpression::UintLe(box e1, box e2).into())
+                    }
                     (e1, e2) => Err(Error {
                         pos: Some(pos),
                         message: format!(
@@ -825,6 +1067,11 @@ impl<'ast> Checker<'ast> {
                     (TypedExpression::FieldElement(e1), TypedExpression::FieldElement(e2)) => {
                         Ok(BooleanExpression::Eq(box e1, box e2).into())
                     }
+                    (TypedExpression::Uint(e1), TypedExpression::Uint(e2))
+                        if e1.bitwidth() == e2.bitwidth() =>
+                    {
+                        Ok(BooleanExpression::UintEq(box e1, box e2).into())
+                    }
                     (e1, e2) => Err(Error {
                         pos: Some(pos),
                         message: format!(
@@ -844,6 +1091,11 @@ impl<'ast> Checker<'ast> {
                     (TypedExpression::FieldElement(e1), TypedExpression::FieldElement(e2)) => {
                         Ok(BooleanExpression::Ge(box e1, box e2).into())
                     }
+                    (TypedExpression::Uint(e1), TypedExpression::Uint(e2))
+                        if e1.bitwidth() == e2.bitwidth() =>
+                    {
+                        Ok(BooleanExpression::UintGe(box e1, box e2).into())
+                    }
                     (e1, e2) => Err(Error {
                         pos: Some(pos),
                         message: format!(
@@ -863,6 +1115,11 @@ impl<'ast> Checker<'ast> {
                     (TypedExpression::FieldElement(e1), TypedExpression::FieldElement(e2)) => {
                         Ok(BooleanExpression::Gt(box e1, box e2).into())
                     }
+                    (TypedExpression::Uint(e1), TypedExpression::Uint(e2))
+                        if e1.bitwidth() == e2.bitwidth() =>
+                    {
+                        Ok(BooleanExpression::UintGt(box e1, box e2).into())
+                    }
                     (e1, e2) => Err(Error {
                         pos: Some(pos),
                         message: format!(
@@ -881,8 +1138,9 @@ impl<'ast> Checker<'ast> {
 
                 match index {
                     RangeOrExpression::Range(r) => match array {
-                        TypedExpression::FieldElementArray(array) => {
+                        TypedExpression::Array(array) => {
                             let array_size = array.size();
+                            let inner_type = array.inner_type().clone();
 
                             let from = r
                                 .value
@@ -918,27 +1176,32 @@ impl<'ast> Checker<'ast> {
                                         f, t,
                                     ),
                                 }),
-                                (f, t, _) => Ok(FieldElementArrayExpression::Value(
-                                    t - f,
+                                (f, t, _) => Ok(ArrayExpressionInner::Value(
                                     (f..t)
                                         .map(|i| {
-                                            FieldElementExpression::Select(
-                                                box array.clone(),
-                                                box FieldElementExpression::Number(T::from(i)),
-                                            )
+                                            array
+                                                .clone()
+                                                .select(FieldElementExpression::Number(T::from(i)))
                                         })
                                         .collect(),
                                 )
+                                .annotate(inner_type, t - f)
                                 .into()),
                             }
                         }
-                        _ => panic!(""),
+                        a => Err(Error {
+                            pos: Some(pos),
+                            message: format!(
+                                "Cannot take slice {} of expression of type {}",
+                                r.value,
+                                a.get_type()
+                            ),
+                        }),
                     },
                     RangeOrExpression::Expression(e) => match (array, self.check_expression(e)?) {
-                        (
-                            TypedExpression::FieldElementArray(a),
-                            TypedExpression::FieldElement(i),
-                        ) => Ok(FieldElementExpression::Select(box a, box i).into()),
+                        (TypedExpression::Array(a), TypedExpression::FieldElement(i)) => {
+                            Ok(a.select(i))
+                        }
                         (a, e) => Err(Error {
                             pos: Some(pos),
                             message: format!(
@@ -964,44 +1227,27 @@ impl<'ast> Checker<'ast> {
                 // we infer the type to be the type of the first element
                 let inferred_type = expressions_checked.get(0).unwrap().get_type();
 
-                match inferred_type {
-                    Type::FieldElement => {
-                        // we check all expressions have that same type
-                        let mut unwrapped_expressions = vec![];
-
-                        for e in expressions_checked {
-                            let unwrapped_e = match e {
-                                TypedExpression::FieldElement(e) => Ok(e),
-                                e => Err(Error {
-                                    pos: Some(pos),
-
-                                    message: format!(
-                                        "Expected {} to have type {}, but type is {}",
-                                        e,
-                                        inferred_type,
-                                        e.get_type()
-                                    ),
-                                }),
-                            }?;
-                            unwrapped_expressions.push(unwrapped_e);
-                        }
+                // we check all expressions have that same type
+                for e in &expressions_checked {
+                    if e.get_type() != inferred_type {
+                        return Err(Error {
+                            pos: Some(pos),
 
-                        Ok(FieldElementArrayExpression::Value(
-                            unwrapped_expressions.len(),
-                            unwrapped_expressions,
-                        )
-                        .into())
+                            message: format!(
+                                "Expected {} to have type {}, but type is {}",
+                                e,
+                                inferred_type,
+                                e.get_type()
+                            ),
+                        });
                     }
-                    _ => Err(Error {
-                        pos: Some(pos),
-
-                        message: format!(
-                            "Only arrays of {} are supported, found {}",
-                            Type::FieldElement,
-                            inferred_type
-                        ),
-                    }),
                 }
+
+                let size = expressions_checked.len();
+
+                Ok(ArrayExpressionInner::Value(expressions_checked)
+                    .annotate(inferred_type, size)
+                    .into())
             }
             Expression::And(box e1, box e2) => {
                 let e1_checked = self.check_expression(e1)?;
@@ -1035,10 +1281,161 @@ impl<'ast> Checker<'ast> {
                     }),
                 }
             }
+            Expression::BitAnd(box e1, box e2) => {
+                let e1_checked = self.check_expression(e1)?;
+                let e2_checked = self.check_expression(e2)?;
+                match (e1_checked, e2_checked) {
+                    (TypedExpression::Uint(e1), TypedExpression::Uint(e2))
+                        if e1.bitwidth() == e2.bitwidth() =>
+                    {
+                        let bitwidth = e1.bitwidth();
+                        Ok(UExpressionInner::And(box e1, box e2)
+                            .annotate(bitwidth)
+                            .into())
+                    }
+                    (e1, e2) => Err(Error {
+                        pos: Some(pos),
+
+                        message: format!(
+                            "Cannot apply `&` to {} of type {} and {} of type {}",
+                            e1,
+                            e1.get_type(),
+                            e2,
+                            e2.get_type()
+                        ),
+                    }),
+                }
+            }
+            Expression::BitOr(box e1, box e2) => {
+                let e1_checked = self.check_expression(e1)?;
+                let e2_checked = self.check_expression(e2)?;
+                match (e1_checked, e2_checked) {
+                    (TypedExpression::Uint(e1), TypedExpression::Uint(e2))
+                        if e1.bitwidth() == e2.bitwidth() =>
+                    {
+                        let bitwidth = e1.bitwidth();
+                        Ok(UExpressionInner::Or(box e1, box e2)
+                            .annotate(bitwidth)
+                            .into())
+                    }
+                    (e1, e2) => Err(Error {
+                        pos: Some(pos),
+
+                        message: format!(
+                            "Cannot apply `|` to {} of type {} and {} of type {}",
+                            e1,
+                            e1.get_type(),
+                            e2,
+                            e2.get_type()
+                        ),
+                    }),
+                }
+            }
+            Expression::BitXor(box e1, box e2) => {
+                let e1_checked = self.check_expression(e1)?;
+                let e2_checked = self.check_expression(e2)?;
+                match (e1_checked, e2_checked) {
+                    (TypedExpression::Uint(e1), TypedExpression::Uint(e2))
+                        if e1.bitwidth() == e2.bitwidth() =>
+                    {
+                        let bitwidth = e1.bitwidth();
+                        Ok(UExpressionInner::Xor(box e1, box e2)
+                            .annotate(bitwidth)
+                            .into())
+                    }
+                    (e1, e2) => Err(Error {
+                        pos: Some(pos),
+
+                        message: format!(
+                            "Cannot apply `^` to {} of type {} and {} of type {}",
+                            e1,
+                            e1.get_type(),
+                            e2,
+                            e2.get_type()
+                        ),
+                    }),
+                }
+            }
+            Expression::LeftShift(box e1, box e2) => {
+                let e1_checked = self.check_expression(e1)?;
+                let e2_checked = self.check_expression(e2)?;
+                match (e1_checked, e2_checked) {
+                    (
+                        TypedExpression::Uint(e1),
+                        TypedExpression::FieldElement(FieldElementExpression::Number(by)),
+                    ) => {
+                        let bitwidth = e1.bitwidth();
+                        Ok(
+                            UExpressionInner::LeftShift(box e1, Self::shift_amount(&by, bitwidth))
+                                .annotate(bitwidth)
+                                .into(),
+                        )
+                    }
+                    (TypedExpression::Uint(_), TypedExpression::FieldElement(e2)) => Err(Error {
+                        pos: Some(pos),
+
+                        message: format!(
+                            "Cannot apply `<<` with amount {}, expected a constant",
+                            e2
+                        ),
+                    }),
+                    (e1, e2) => Err(Error {
+                        pos: Some(pos),
+
+                        message: format!(
+                            "Cannot apply `<<` to {} of type {} and {} of type {}, expected an unsigned integer and a field element",
+                            e1,
+                            e1.get_type(),
+                            e2,
+                            e2.get_type()
+                        ),
+                    }),
+                }
+            }
+            Expression::RightShift(box e1, box e2) => {
+                let e1_checked = self.check_expression(e1)?;
+                let e2_checked = self.check_expression(e2)?;
+                match (e1_checked, e2_checked) {
+                    (
+                        TypedExpression::Uint(e1),
+                        TypedExpression::FieldElement(FieldElementExpression::Number(by)),
+                    ) => {
+                        let bitwidth = e1.bitwidth();
+                        Ok(
+                            UExpressionInner::RightShift(box e1, Self::shift_amount(&by, bitwidth))
+                                .annotate(bitwidth)
+                                .into(),
+                        )
+                    }
+                    (TypedExpression::Uint(_), TypedExpression::FieldElement(e2)) => Err(Error {
+                        pos: Some(pos),
+
+                        message: format!(
+                            "Cannot apply `>>` with amount {}, expected a constant",
+                            e2
+                        ),
+                    }),
+                    (e1, e2) => Err(Error {
+                        pos: Some(pos),
+
+                        message: format!(
+                            "Cannot apply `>>` to {} of type {} and {} of type {}, expected an unsigned integer and a field element",
+                            e1,
+                            e1.get_type(),
+                            e2,
+                            e2.get_type()
+                        ),
+                    }),
+                }
+            }
             Expression::Not(box e) => {
                 let e_checked = self.check_expression(e)?;
                 match e_checked {
                     TypedExpression::Boolean(e) => Ok(BooleanExpression::Not(box e).into()),
+                    TypedExpression::Uint(e) => {
+                        let bitwidth = e.bitwidth();
+                        Ok(UExpressionInner::Not(box e).annotate(bitwidth).into())
+                    }
                     e => Err(Error {
                         pos: Some(pos),
 
@@ -1046,12 +1443,123 @@ impl<'ast> Checker<'ast> {
                     }),
                 }
             }
+            Expression::Member(box e, box id) => {
+                let e = self.check_expression(e)?;
+
+                match e {
+                    TypedExpression::Struct(s) => {
+                        let member_type = s
+                            .ty()
+                            .iter()
+                            .find(|m| m.id == id)
+                            .map(|m| *m.ty.clone());
+
+                        match member_type {
+                            Some(Type::FieldElement) => {
+                                Ok(FieldElementExpression::Member(box s, id.to_string()).into())
+                            }
+                            Some(Type::Boolean) => {
+                                Ok(BooleanExpression::Member(box s, id.to_string()).into())
+                            }
+                            Some(Type::Uint(bitwidth)) => {
+                                Ok(UExpressionInner::Member(box s, id.to_string())
+                                    .annotate(bitwidth)
+                                    .into())
+                            }
+                            Some(Type::Array(array_type)) => {
+                                Ok(ArrayExpressionInner::Member(box s, id.to_string())
+                                    .annotate(*array_type.ty, array_type.size)
+                                    .into())
+                            }
+                            Some(Type::Struct(struct_type)) => {
+                                Ok(StructExpressionInner::Member(box s, id.to_string())
+                                    .annotate(struct_type)
+                                    .into())
+                            }
+                            None => Err(Error {
+                                pos: Some(pos),
+                                message: format!("{} doesn't have member {}", s.get_type(), id),
+                            }),
+                        }
+                    }
+                    e => Err(Error {
+                        pos: Some(pos),
+                        message: format!(
+                            "Cannot access member {} on expression of type {}",
+                            id,
+                            e.get_type()
+                        ),
+                    }),
+                }
+            }
+            Expression::InlineStruct(id, inline_members) => {
+                let struct_type = match self.types.get(&id) {
+                    Some(Type::Struct(struct_type)) => Ok(struct_type.clone()),
+                    _ => Err(Error {
+                        pos: Some(pos),
+                        message: format!("Undefined type {}", id),
+                    }),
+                }?;
+
+                if struct_type.members.len() != inline_members.len() {
+                    return Err(Error {
+                        pos: Some(pos),
+                        message: format!(
+                            "Inline struct {} does not match {:?}",
+                            Expression::InlineStruct(id.clone(), inline_members),
+                            Type::Struct(struct_type)
+                        ),
+                    });
+                }
+
+                let mut inline_members: HashMap<_, _> = inline_members.into_iter().collect();
+
+                // members are stored in declaration order, whatever order they are written in
+                let mut result = vec![];
+
+                for member in struct_type.iter() {
+                    match inline_members.remove(member.id.as_str()) {
+                        Some(e) => {
+                            let e = self.check_expression(e)?;
+                            match e.get_type() == *member.ty {
+                                true => result.push(e),
+                                false => {
+                                    return Err(Error {
+                                        pos: Some(pos),
+                                        message: format!(
+                                            "Member {} of struct {} has type {}, found {} of type {}",
+                                            member.id,
+                                            id,
+                                            member.ty,
+                                            e,
+                                            e.get_type()
+                                        ),
+                                    })
+                                }
+                            }
+                        }
+                        None => {
+                            return Err(Error {
+                                pos: Some(pos),
+                                message: format!(
+                                    "Member {} of struct {} not found in inline struct",
+                                    member.id, id
+                                ),
+                            })
+                        }
+                    }
+                }
+
+                Ok(StructExpressionInner::Value(result)
+                    .annotate(struct_type)
+                    .into())
+            }
         }
     }
 
     fn get_scope(&self, variable_name: &Identifier<'ast>) -> Option<&ScopedVariable> {
         self.scope.get(&ScopedVariable {
-            id: Variable::new(*variable_name, Type::FieldElement),
+            id: Variable::field_element((*variable_name).into()),
             level: 0,
         })
     }
@@ -1081,9 +1589,77 @@ impl<'ast> Checker<'ast> {
 
 #[cfg(test)]
 mod tests {
-    // use super::*;
+    use super::*;
     // use absy::parameter::Parameter;
-    // use zokrates_field::field::FieldPrime;
+    use zokrates_field::field::FieldPrime;
+
+    #[test]
+    fn shift_by_constant() {
+        // 0x2a << 3
+        let e: ExpressionNode<FieldPrime> = Expression::LeftShift(
+            box Expression::U8Constant(0x2a).into(),
+            box Expression::FieldConstant(FieldPrime::from(3)).into(),
+        )
+        .into();
+
+        assert_eq!(
+            Checker::new().check_expression(e),
+            Ok(
+                UExpressionInner::LeftShift(box UExpressionInner::Value(0x2a).annotate(8), 3)
+                    .annotate(8)
+                    .into()
+            )
+        );
+
+        // amounts larger than the bitwidth are capped
+        let e: ExpressionNode<FieldPrime> = Expression::RightShift(
+            box Expression::U8Constant(0x2a).into(),
+            box Expression::FieldConstant(FieldPrime::from(300)).into(),
+        )
+        .into();
+
+        assert_eq!(
+            Checker::new().check_expression(e),
+            Ok(
+                UExpressionInner::RightShift(box UExpressionInner::Value(0x2a).annotate(8), 8)
+                    .annotate(8)
+                    .into()
+            )
+        );
+    }
+
+    #[test]
+    fn shift_by_non_constant() {
+        // 0x2a >> a, where a is a field element
+        let mut checker = Checker::new();
+        checker.insert_scope(Variable::field_element("a".into()));
+
+        let e: ExpressionNode<FieldPrime> = Expression::RightShift(
+            box Expression::U8Constant(0x2a).into(),
+            box Expression::Identifier("a").into(),
+        )
+        .into();
+
+        assert_eq!(
+            checker.check_expression(e).unwrap_err().message,
+            "Cannot apply `>>` with amount a, expected a constant"
+        );
+    }
+
+    #[test]
+    fn add_uints_of_different_widths() {
+        // 0x2a + 0x002a, where the operands are u8 and u16
+        let e: ExpressionNode<FieldPrime> = Expression::Add(
+            box Expression::U8Constant(0x2a).into(),
+            box Expression::U16Constant(0x002a).into(),
+        )
+        .into();
+
+        assert_eq!(
+            Checker::new().check_expression(e).unwrap_err().message,
+            "Expected field elements or unsigned integers of the same width, found u8, u16"
+        );
+    }
 
     // pub fn new_with_args(
     //     scope: HashSet<ScopedVariable>,
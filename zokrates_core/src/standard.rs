@@ -35,7 +35,7 @@ impl<T: Field> From<BellmanConstraint<T::BellmanEngine>> for FlatStatement<T> {
         let rhs_b = flat_expression_from_vec(c.b);
         let lhs = flat_expression_from_vec(c.c);
 
-        FlatStatement::Condition(lhs, FlatExpression::Mult(box rhs_a, box rhs_b))
+        FlatStatement::Condition(lhs, FlatExpression::Mult(box rhs_a, box rhs_b), None)
     }
 }
 
@@ -78,10 +78,10 @@ pub fn sha_round<T: Field>() -> FlatFunction<T> {
     // define the signature of the resulting function
     let signature = Signature {
         inputs: vec![
-            Type::FieldElementArray(input_indices.len()),
-            Type::FieldElementArray(current_hash_indices.len()),
+            Type::array(Type::FieldElement, input_indices.len()),
+            Type::array(Type::FieldElement, current_hash_indices.len()),
         ],
-        outputs: vec![Type::FieldElementArray(output_indices.len())],
+        outputs: vec![Type::array(Type::FieldElement, output_indices.len())],
     };
 
     // define parameters to the function based on the variables
@@ -98,6 +98,7 @@ pub fn sha_round<T: Field>() -> FlatFunction<T> {
     let one_binding_statement = FlatStatement::Condition(
         FlatVariable::new(0).into(),
         FlatExpression::Number(T::from(1)),
+        None,
     );
 
     let input_binding_statements =
@@ -106,6 +107,7 @@ pub fn sha_round<T: Field>() -> FlatFunction<T> {
         FlatStatement::Condition(
             FlatVariable::new(cs_index).into(),
             FlatVariable::new(argument_index).into(),
+            None,
         )
     });
 
@@ -161,10 +163,10 @@ mod tests {
             compiled.signature,
             Signature::new()
                 .inputs(vec![
-                    Type::FieldElementArray(512),
-                    Type::FieldElementArray(256)
+                    Type::array(Type::FieldElement, 512),
+                    Type::array(Type::FieldElement, 256)
                 ])
-                .outputs(vec![Type::FieldElementArray(256)])
+                .outputs(vec![Type::array(Type::FieldElement, 256)])
         );
 
         // function should have 768 inputs
@@ -209,20 +211,27 @@ mod tests {
             compiled.statements[1],
             FlatStatement::Condition(
                 FlatVariable::new(0).into(),
-                FlatExpression::Number(FieldPrime::from(1))
+                FlatExpression::Number(FieldPrime::from(1)),
+                None
             )
         );
 
         // bellman input #0: index 1 should equal zokrates input #0: index v_count
         assert_eq!(
             compiled.statements[2],
-            FlatStatement::Condition(FlatVariable::new(1).into(), FlatVariable::new(26936).into())
+            FlatStatement::Condition(
+                FlatVariable::new(1).into(),
+                FlatVariable::new(26936).into(),
+                None
+            )
         );
 
+        let signature = compiled.signature.clone();
         let f = crate::ir::Function::from(compiled);
         let prog = crate::ir::Prog {
             main: f,
             private: vec![true; 768],
+            signature,
         };
 
         let input = (0..512).map(|_| 0).chain((0..256).map(|_| 1)).collect();
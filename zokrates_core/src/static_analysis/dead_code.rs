@@ -1,7 +1,7 @@
 use crate::typed_absy::folder::*;
 use crate::typed_absy::Folder;
 use crate::typed_absy::*;
-use crate::types::{Signature, Type};
+use crate::types::{Signature, StructType, Type};
 use std::collections::HashSet;
 use zokrates_field::field::Field;
 
@@ -79,23 +79,67 @@ impl<'ast, T: Field> Folder<'ast, T> for DeadCode {
         }
     }
 
-    fn fold_field_array_expression(
+    fn fold_array_expression_inner(
         &mut self,
-        e: FieldElementArrayExpression<'ast, T>,
-    ) -> FieldElementArrayExpression<'ast, T> {
+        ty: &Type,
+        size: usize,
+        e: ArrayExpressionInner<'ast, T>,
+    ) -> ArrayExpressionInner<'ast, T> {
         match e {
-            FieldElementArrayExpression::FunctionCall(size, id, exps) => {
+            ArrayExpressionInner::FunctionCall(id, exps) => {
                 let exps: Vec<_> = exps.into_iter().map(|e| self.fold_expression(e)).collect();
 
                 let signature = Signature::new()
                     .inputs(exps.iter().map(|e| e.get_type()).collect())
-                    .outputs(vec![Type::FieldElementArray(size)]);
+                    .outputs(vec![Type::array(ty.clone(), size)]);
 
                 self.called
                     .insert(format!("{}_{}", id, signature.to_slug()));
-                FieldElementArrayExpression::FunctionCall(size, id, exps)
+                ArrayExpressionInner::FunctionCall(id, exps)
             }
-            e => fold_field_array_expression(self, e),
+            e => fold_array_expression_inner(self, ty, size, e),
+        }
+    }
+
+    fn fold_uint_expression_inner(
+        &mut self,
+        bitwidth: usize,
+        e: UExpressionInner<'ast, T>,
+    ) -> UExpressionInner<'ast, T> {
+        match e {
+            UExpressionInner::FunctionCall(id, exps) => {
+                let exps: Vec<_> = exps.into_iter().map(|e| self.fold_expression(e)).collect();
+
+                let signature = Signature::new()
+                    .inputs(exps.iter().map(|e| e.get_type()).collect())
+                    .outputs(vec![Type::Uint(bitwidth)]);
+
+                self.called
+                    .insert(format!("{}_{}", id, signature.to_slug()));
+                UExpressionInner::FunctionCall(id, exps)
+            }
+            e => fold_uint_expression_inner(self, bitwidth, e),
+        }
+    }
+
+    fn fold_struct_expression_inner(
+        &mut self,
+        ty: &StructType,
+        e: StructExpressionInner<'ast, T>,
+    ) -> StructExpressionInner<'ast, T> {
+        match e {
+            StructExpressionInner::FunctionCall(id, exps) => {
+                let exps: Vec<_> = exps.into_iter().map(|e| self.fold_expression(e)).collect();
+
+                let signature = Signature::new()
+                    .inputs(exps.iter().map(|e| e.get_type()).collect())
+                    .outputs(vec![Type::Struct(ty.clone())]);
+
+                self.called
+                    .insert(format!("{}_{}", id, signature.to_slug()));
+                StructExpressionInner::FunctionCall(id, exps)
+            }
+            e => fold_struct_expression_inner(self, ty, e),
         }
     }
 }
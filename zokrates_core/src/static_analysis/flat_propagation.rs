@@ -70,9 +70,10 @@ impl<T: Field> FlatStatement<T> {
                 }
                 e => Some(FlatStatement::Definition(var, e)),
             },
-            FlatStatement::Condition(e1, e2) => Some(FlatStatement::Condition(
+            FlatStatement::Condition(e1, e2, location) => Some(FlatStatement::Condition(
                 e1.propagate(constants),
                 e2.propagate(constants),
+                location,
             )),
             FlatStatement::Directive(d) => Some(FlatStatement::Directive(DirectiveStatement {
                 inputs: d
@@ -82,6 +83,7 @@ impl<T: Field> FlatStatement<T> {
                     .collect(),
                 ..d
             })),
+            FlatStatement::Marker(origin) => Some(FlatStatement::Marker(origin)),
         }
     }
 }
@@ -1,7 +1,7 @@
 use crate::typed_absy::folder::*;
 use crate::typed_absy::Folder;
 use crate::typed_absy::*;
-use crate::types::{Signature, Type};
+use crate::types::{Signature, StructType, Type};
 use std::collections::HashMap;
 use zokrates_field::field::Field;
 
@@ -10,6 +10,8 @@ pub struct Inliner<'ast, T: Field> {
     statements_buffer: Vec<TypedStatement<'ast, T>>,
     context: Vec<(&'ast str, Signature, usize)>,
     call_count: HashMap<String, usize>,
+    /// The last marker encountered, restored after the statements of an inlined call
+    marker: Option<TypedStatement<'ast, T>>,
 }
 
 impl<'ast, T: Field> Inliner<'ast, T> {
@@ -19,6 +21,7 @@ impl<'ast, T: Field> Inliner<'ast, T> {
             statements_buffer: vec![],
             context: vec![],
             call_count: HashMap::new(),
+            marker: None,
         }
     }
 
@@ -39,9 +42,14 @@ impl<'ast, T: Field> Inliner<'ast, T> {
             Some(..) => {
                 // check whether non-array arguments are constant
                 arguments.iter().all(|e| match e {
-                    TypedExpression::FieldElementArray(..) => true,
+                    TypedExpression::Array(..) => true,
+                    TypedExpression::Struct(..) => true,
                     TypedExpression::FieldElement(FieldElementExpression::Number(..)) => true,
                     TypedExpression::Boolean(BooleanExpression::Value(..)) => true,
+                    TypedExpression::Uint(UExpression {
+                        inner: UExpressionInner::Value(..),
+                        ..
+                    }) => true,
                     _ => false,
                 })
             }
@@ -80,6 +88,8 @@ impl<'ast, T: Field> Inliner<'ast, T> {
             .collect();
         self.statements_buffer.append(&mut inputs_bindings);
 
+        let caller_marker = self.marker.clone();
+
         // filter out the return statement and keep it aside
         let (mut statements, ret): (Vec<_>, Vec<_>) = function
             .statements
@@ -94,6 +104,12 @@ impl<'ast, T: Field> Inliner<'ast, T> {
         // add all statements to the buffer
         self.statements_buffer.append(&mut statements);
 
+        // if the inlined statements were marked, the following ones come from the caller again
+        if self.marker != caller_marker {
+            self.marker = caller_marker;
+            self.statements_buffer.extend(self.marker.clone());
+        }
+
         // remove this call from the context
         self.context.pop();
 
@@ -158,6 +174,10 @@ impl<'ast, T: Field> Folder<'ast, T> for Inliner<'ast, T> {
                     }
                 }
             }
+            TypedStatement::Marker(id, pos) => {
+                self.marker = Some(TypedStatement::Marker(id, pos));
+                vec![TypedStatement::Marker(id, pos)]
+            }
             s => fold_statement(self, s),
         };
 
@@ -212,18 +232,20 @@ impl<'ast, T: Field> Folder<'ast, T> for Inliner<'ast, T> {
         }
     }
 
-    // inline calls which return a field element array
-    fn fold_field_array_expression(
+    // inline calls which return an array
+    fn fold_array_expression_inner(
         &mut self,
-        e: FieldElementArrayExpression<'ast, T>,
-    ) -> FieldElementArrayExpression<'ast, T> {
+        ty: &Type,
+        size: usize,
+        e: ArrayExpressionInner<'ast, T>,
+    ) -> ArrayExpressionInner<'ast, T> {
         match e {
-            FieldElementArrayExpression::FunctionCall(size, id, exps) => {
+            ArrayExpressionInner::FunctionCall(id, exps) => {
                 let exps: Vec<_> = exps.into_iter().map(|e| self.fold_expression(e)).collect();
 
                 let passed_signature = Signature::new()
                     .inputs(exps.iter().map(|e| e.get_type()).collect())
-                    .outputs(vec![Type::FieldElementArray(size)]);
+                    .outputs(vec![Type::array(ty.clone(), size)]);
 
                 // find the function
                 let function = self
@@ -235,17 +257,93 @@ impl<'ast, T: Field> Folder<'ast, T> for Inliner<'ast, T> {
                 match self.should_inline(&function, &exps) {
                     true => {
                         let ret = self.inline_call(function.unwrap(), exps);
-                        // unwrap the result to return a field element
+                        // unwrap the result to return an array
                         match ret[0].clone() {
-                            TypedExpression::FieldElementArray(e) => e,
+                            TypedExpression::Array(e) => e.into_inner(),
                             _ => panic!(""),
                         }
                     }
-                    false => FieldElementArrayExpression::FunctionCall(size, id, exps),
+                    false => ArrayExpressionInner::FunctionCall(id, exps),
                 }
             }
             // default
-            e => fold_field_array_expression(self, e),
+            e => fold_array_expression_inner(self, ty, size, e),
+        }
+    }
+
+    // inline calls which return an unsigned integer
+    fn fold_uint_expression_inner(
+        &mut self,
+        bitwidth: usize,
+        e: UExpressionInner<'ast, T>,
+    ) -> UExpressionInner<'ast, T> {
+        match e {
+            UExpressionInner::FunctionCall(id, exps) => {
+                let exps: Vec<_> = exps.into_iter().map(|e| self.fold_expression(e)).collect();
+
+                let passed_signature = Signature::new()
+                    .inputs(exps.iter().map(|e| e.get_type()).collect())
+                    .outputs(vec![Type::Uint(bitwidth)]);
+
+                // find the function
+                let function = self
+                    .functions
+                    .iter()
+                    .find(|f| f.id == id && f.signature == passed_signature)
+                    .cloned();
+
+                match self.should_inline(&function, &exps) {
+                    true => {
+                        let ret = self.inline_call(function.unwrap(), exps);
+                        // unwrap the result to return an unsigned integer
+                        match ret[0].clone() {
+                            TypedExpression::Uint(e) => e.into_inner(),
+                            _ => panic!(""),
+                        }
+                    }
+                    false => UExpressionInner::FunctionCall(id, exps),
+                }
+            }
+            // default
+            e => fold_uint_expression_inner(self, bitwidth, e),
+        }
+    }
+
+    // inline calls which return a struct
+    fn fold_struct_expression_inner(
+        &mut self,
+        ty: &StructType,
+        e: StructExpressionInner<'ast, T>,
+    ) -> StructExpressionInner<'ast, T> {
+        match e {
+            StructExpressionInner::FunctionCall(id, exps) => {
+                let exps: Vec<_> = exps.into_iter().map(|e| self.fold_expression(e)).collect();
+
+                let passed_signature = Signature::new()
+                    .inputs(exps.iter().map(|e| e.get_type()).collect())
+                    .outputs(vec![Type::Struct(ty.clone())]);
+
+                // find the function
+                let function = self
+                    .functions
+                    .iter()
+                    .find(|f| f.id == id && f.signature == passed_signature)
+                    .cloned();
+
+                match self.should_inline(&function, &exps) {
+                    true => {
+                        let ret = self.inline_call(function.unwrap(), exps);
+                        // unwrap the result to return a struct
+                        match ret[0].clone() {
+                            TypedExpression::Struct(e) => e.into_inner(),
+                            _ => panic!(""),
+                        }
+                    }
+                    false => StructExpressionInner::FunctionCall(id, exps),
+                }
+            }
+            // default
+            e => fold_struct_expression_inner(self, ty, e),
         }
     }
 }
@@ -269,19 +367,22 @@ mod tests {
                 ],
                 statements: vec![TypedStatement::Return(vec![
                     FieldElementExpression::Select(
-                        box FieldElementArrayExpression::Identifier(3, Identifier::from("b")),
+                        box ArrayExpressionInner::Identifier(Identifier::from("b"))
+                            .annotate(Type::FieldElement, 3),
                         box FieldElementExpression::Identifier(Identifier::from("a")),
                     )
                     .into(),
                 ])],
                 signature: Signature::new()
-                    .inputs(vec![Type::FieldElement, Type::FieldElementArray(3)])
+                    .inputs(vec![Type::FieldElement, Type::array(Type::FieldElement, 3)])
                     .outputs(vec![Type::FieldElement]),
             };
 
             let arguments = vec![
                 FieldElementExpression::Number(FieldPrime::from(0)).into(),
-                FieldElementArrayExpression::Identifier(3, Identifier::from("random")).into(),
+                ArrayExpressionInner::Identifier(Identifier::from("random"))
+                    .annotate(Type::FieldElement, 3)
+                    .into(),
             ];
 
             let i = Inliner::new();
@@ -299,19 +400,22 @@ mod tests {
                 ],
                 statements: vec![TypedStatement::Return(vec![
                     FieldElementExpression::Select(
-                        box FieldElementArrayExpression::Identifier(3, Identifier::from("b")),
+                        box ArrayExpressionInner::Identifier(Identifier::from("b"))
+                            .annotate(Type::FieldElement, 3),
                         box FieldElementExpression::Identifier(Identifier::from("a")),
                     )
                     .into(),
                 ])],
                 signature: Signature::new()
-                    .inputs(vec![Type::FieldElement, Type::FieldElementArray(3)])
+                    .inputs(vec![Type::FieldElement, Type::array(Type::FieldElement, 3)])
                     .outputs(vec![Type::FieldElement]),
             };
 
             let arguments = vec![
                 FieldElementExpression::Identifier(Identifier::from("notconstant")).into(),
-                FieldElementArrayExpression::Identifier(3, Identifier::from("random")).into(),
+                ArrayExpressionInner::Identifier(Identifier::from("random"))
+                    .annotate(Type::FieldElement, 3)
+                    .into(),
             ];
 
             let i = Inliner::new();
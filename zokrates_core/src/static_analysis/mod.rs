@@ -10,10 +10,10 @@ mod inline;
 mod propagation;
 mod unroll;
 
-use self::dead_code::DeadCode;
-use self::inline::Inliner;
-use self::propagation::Propagator;
-use self::unroll::Unroller;
+pub use self::dead_code::DeadCode;
+pub use self::inline::Inliner;
+pub use self::propagation::Propagator;
+pub use self::unroll::Unroller;
 use crate::flat_absy::FlatProg;
 use crate::typed_absy::TypedProg;
 use zokrates_field::field::Field;
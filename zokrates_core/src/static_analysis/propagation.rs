@@ -6,6 +6,7 @@
 
 use crate::typed_absy::folder::*;
 use crate::typed_absy::*;
+use crate::types::{MemberId, StructType, Type};
 use std::collections::HashMap;
 use zokrates_field::field::Field;
 
@@ -13,6 +14,38 @@ pub struct Propagator<'ast, T: Field> {
     constants: HashMap<TypedAssignee<'ast, T>, TypedExpression<'ast, T>>,
 }
 
+// if `s` is a struct value, return its member `id`
+fn select_member<'ast, T: Field>(
+    s: &StructExpression<'ast, T>,
+    id: &MemberId,
+) -> Option<TypedExpression<'ast, T>> {
+    match s.as_inner() {
+        StructExpressionInner::Value(values) => s
+            .ty()
+            .iter()
+            .position(|m| m.id == *id)
+            .map(|index| values[index].clone()),
+        _ => None,
+    }
+}
+
+// whether `e` is a constant which can be stored in the constants map
+fn is_constant<'ast, T: Field>(e: &TypedExpression<'ast, T>) -> bool {
+    match e {
+        TypedExpression::FieldElement(FieldElementExpression::Number(..)) => true,
+        TypedExpression::Boolean(BooleanExpression::Value(..)) => true,
+        TypedExpression::Uint(UExpression {
+            inner: UExpressionInner::Value(..),
+            ..
+        }) => true,
+        TypedExpression::Array(a) => match a.as_inner() {
+            ArrayExpressionInner::Value(v) => v.iter().all(is_constant),
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
 impl<'ast, T: Field> Propagator<'ast, T> {
     fn new() -> Self {
         Propagator {
@@ -23,6 +56,36 @@ impl<'ast, T: Field> Propagator<'ast, T> {
     pub fn propagate(p: TypedProg<'ast, T>) -> TypedProg<'ast, T> {
         Propagator::new().fold_program(p)
     }
+
+    // if `array` is an array value and `index` is a constant, return the element at `index`
+    // otherwise, return the folded `array` and `index`
+    fn select(
+        &mut self,
+        array: ArrayExpression<'ast, T>,
+        index: FieldElementExpression<'ast, T>,
+    ) -> Result<
+        TypedExpression<'ast, T>,
+        (ArrayExpression<'ast, T>, FieldElementExpression<'ast, T>),
+    > {
+        let array = self.fold_array_expression(array);
+        let index = self.fold_field_expression(index);
+
+        match (array.as_inner(), index) {
+            (ArrayExpressionInner::Value(v), FieldElementExpression::Number(n)) => {
+                let n_as_usize = n.to_dec_string().parse::<usize>().unwrap();
+                if n_as_usize < v.len() {
+                    Ok(v[n_as_usize].clone())
+                } else {
+                    panic!(format!(
+                        "out of bounds index ({} >= {}) found during static analysis",
+                        n_as_usize,
+                        v.len()
+                    ));
+                }
+            }
+            (_, index) => Err((array, index)),
+        }
+    }
 }
 
 impl<'ast, T: Field> Folder<'ast, T> for Propagator<'ast, T> {
@@ -38,22 +101,19 @@ impl<'ast, T: Field> Folder<'ast, T> for Propagator<'ast, T> {
 			// propagation to the defined variable if rhs is a constant
 			TypedStatement::Definition(TypedAssignee::Identifier(var), expr) => {
 				match self.fold_expression(expr) {
-					e @ TypedExpression::Boolean(BooleanExpression::Value(..)) | e @ TypedExpression::FieldElement(FieldElementExpression::Number(..)) => {
+					e @ TypedExpression::Boolean(BooleanExpression::Value(..)) | e @ TypedExpression::FieldElement(FieldElementExpression::Number(..)) | e @ TypedExpression::Uint(UExpression { inner: UExpressionInner::Value(..), .. }) => {
 						self.constants.insert(TypedAssignee::Identifier(var), e);
 						None
 					},
-					TypedExpression::FieldElementArray(FieldElementArrayExpression::Value(size, array)) => {
-						match array.iter().all(|e| match e {
-							FieldElementExpression::Number(..) => true,
-							_ => false
-						}) {
+					e @ TypedExpression::Array(..) => {
+						match is_constant(&e) {
 							true => {
 								// all elements of the array are constants
-								self.constants.insert(TypedAssignee::Identifier(var), FieldElementArrayExpression::Value(size, array).into());
+								self.constants.insert(TypedAssignee::Identifier(var), e);
 								None
 							},
 							false => {
-								Some(TypedStatement::Definition(TypedAssignee::Identifier(var), FieldElementArrayExpression::Value(size, array).into()))
+								Some(TypedStatement::Definition(TypedAssignee::Identifier(var), e))
 							}
 						}
 					},
@@ -70,13 +130,13 @@ impl<'ast, T: Field> Folder<'ast, T> for Propagator<'ast, T> {
 				match (index, expr) {
 					(
 						FieldElementExpression::Number(n),
-						TypedExpression::FieldElement(expr @ FieldElementExpression::Number(..))
-					) => {
+						expr
+					) if is_constant(&expr) => {
 						// a[42] = 33
 						// -> store (a[42] -> 33) in the constants, possibly overwriting the previous entry
 						self.constants.entry(TypedAssignee::Identifier(var)).and_modify(|e| {
 							match *e {
-								TypedExpression::FieldElementArray(FieldElementArrayExpression::Value(size, ref mut v)) => {
+								TypedExpression::Array(ArrayExpression { inner: ArrayExpressionInner::Value(ref mut v), size, .. }) => {
 									let n_as_usize = n.to_dec_string().parse::<usize>().unwrap();
 									if n_as_usize < size {
 										v[n_as_usize] = expr;
@@ -97,11 +157,11 @@ impl<'ast, T: Field> Folder<'ast, T> for Propagator<'ast, T> {
 					}
 				}
 			},
-			TypedStatement::Definition(..) => panic!("multi dimensinal arrays are not supported, this should have been caught during semantic checking"),
+			TypedStatement::Definition(..) => panic!("nested array element definitions are unexpected, they should have been removed during unrolling"),
 			// propagate lhs and rhs for conditions
-			TypedStatement::Condition(e1, e2) => {
+			TypedStatement::Condition(e1, e2, pos) => {
 				// could stop execution here if condition is known to fail
-				Some(TypedStatement::Condition(self.fold_expression(e1), self.fold_expression(e2)))
+				Some(TypedStatement::Condition(self.fold_expression(e1), self.fold_expression(e2), pos))
 			},
 			// we unrolled for loops in the previous step
 			TypedStatement::For(..) => panic!("for loop is unexpected, it should have been unrolled"),
@@ -109,6 +169,7 @@ impl<'ast, T: Field> Folder<'ast, T> for Propagator<'ast, T> {
 				let expression_list = self.fold_expression_list(expression_list);
 				Some(TypedStatement::MultipleDefinition(variables, expression_list))
 			}
+			TypedStatement::Marker(id, pos) => Some(TypedStatement::Marker(id, pos)),
 		};
         match res {
             Some(v) => vec![v],
@@ -201,67 +262,200 @@ impl<'ast, T: Field> Folder<'ast, T> for Propagator<'ast, T> {
                 }
             }
             FieldElementExpression::Select(box array, box index) => {
-                let array = self.fold_field_array_expression(array);
-                let index = self.fold_field_expression(index);
-
-                match (array, index) {
-                    (
-                        FieldElementArrayExpression::Value(size, v),
-                        FieldElementExpression::Number(n),
-                    ) => {
-                        let n_as_usize = n.to_dec_string().parse::<usize>().unwrap();
-                        if n_as_usize < size {
-                            v[n_as_usize].clone()
-                        } else {
-                            panic!(format!(
-                                "out of bounds index ({} >= {}) found during static analysis",
-                                n_as_usize, size
-                            ));
-                        }
-                    }
-                    (
-                        FieldElementArrayExpression::Identifier(size, id),
-                        FieldElementExpression::Number(n),
-                    ) => match self.constants.get(&TypedAssignee::ArrayElement(
-                        box TypedAssignee::Identifier(Variable::field_array(id.clone(), size)),
-                        box FieldElementExpression::Number(n.clone()).into(),
-                    )) {
-                        Some(e) => match e {
-                            TypedExpression::FieldElement(e) => e.clone(),
-                            _ => panic!(""),
-                        },
-                        None => FieldElementExpression::Select(
-                            box FieldElementArrayExpression::Identifier(size, id),
-                            box FieldElementExpression::Number(n),
-                        ),
-                    },
-                    (a, i) => FieldElementExpression::Select(box a, box i),
+                match self.select(array, index) {
+                    Ok(TypedExpression::FieldElement(e)) => e,
+                    Ok(_) => panic!("element should have the type of the expression selecting it"),
+                    Err((a, i)) => FieldElementExpression::Select(box a, box i),
+                }
+            }
+            FieldElementExpression::Member(box s, id) => {
+                let s = self.fold_struct_expression(s);
+                match select_member(&s, &id) {
+                    Some(TypedExpression::FieldElement(e)) => e,
+                    Some(_) => panic!("member should have the type of the expression accessing it"),
+                    None => FieldElementExpression::Member(box s, id),
                 }
             }
             e => fold_field_expression(self, e),
         }
     }
 
-    fn fold_field_array_expression(
+    fn fold_array_expression_inner(
         &mut self,
-        e: FieldElementArrayExpression<'ast, T>,
-    ) -> FieldElementArrayExpression<'ast, T> {
+        ty: &Type,
+        size: usize,
+        e: ArrayExpressionInner<'ast, T>,
+    ) -> ArrayExpressionInner<'ast, T> {
         match e {
-            FieldElementArrayExpression::Identifier(size, id) => {
-                match self
-                    .constants
-                    .get(&TypedAssignee::Identifier(Variable::field_array(
-                        id.clone(),
-                        size,
-                    ))) {
+            ArrayExpressionInner::Identifier(id) => {
+                match self.constants.get(&TypedAssignee::Identifier(Variable::array(
+                    id.clone(),
+                    ty.clone(),
+                    size,
+                ))) {
                     Some(e) => match e {
-                        TypedExpression::FieldElementArray(e) => e.clone(),
+                        TypedExpression::Array(e) => e.as_inner().clone(),
                         _ => panic!("constant stored for an array should be an array"),
                     },
-                    None => FieldElementArrayExpression::Identifier(size, id),
+                    None => ArrayExpressionInner::Identifier(id),
+                }
+            }
+            ArrayExpressionInner::IfElse(box condition, box consequence, box alternative) => {
+                let consequence = self.fold_array_expression(consequence);
+                let alternative = self.fold_array_expression(alternative);
+                match self.fold_boolean_expression(condition) {
+                    BooleanExpression::Value(true) => consequence.into_inner(),
+                    BooleanExpression::Value(false) => alternative.into_inner(),
+                    c => ArrayExpressionInner::IfElse(box c, box consequence, box alternative),
+                }
+            }
+            ArrayExpressionInner::Select(box array, box index) => {
+                match self.select(array, index) {
+                    Ok(TypedExpression::Array(e)) => e.into_inner(),
+                    Ok(_) => panic!("element should have the type of the expression selecting it"),
+                    Err((a, i)) => ArrayExpressionInner::Select(box a, box i),
+                }
+            }
+            ArrayExpressionInner::Member(box s, id) => {
+                let s = self.fold_struct_expression(s);
+                match select_member(&s, &id) {
+                    Some(TypedExpression::Array(e)) => e.into_inner(),
+                    Some(_) => panic!("member should have the type of the expression accessing it"),
+                    None => ArrayExpressionInner::Member(box s, id),
                 }
             }
-            e => fold_field_array_expression(self, e),
+            e => fold_array_expression_inner(self, ty, size, e),
+        }
+    }
+
+    fn fold_uint_expression_inner(
+        &mut self,
+        bitwidth: usize,
+        e: UExpressionInner<'ast, T>,
+    ) -> UExpressionInner<'ast, T> {
+        // all operations wrap around 2**bitwidth
+        let mask = (1u128 << bitwidth) - 1;
+
+        match e {
+            UExpressionInner::Identifier(id) => match self
+                .constants
+                .get(&TypedAssignee::Identifier(Variable::uint(id.clone(), bitwidth)))
+            {
+                Some(e) => match e {
+                    TypedExpression::Uint(e) => e.as_inner().clone(),
+                    _ => panic!("constant stored for an unsigned integer should be an unsigned integer"),
+                },
+                None => UExpressionInner::Identifier(id),
+            },
+            UExpressionInner::Add(box e1, box e2) => match (
+                self.fold_uint_expression(e1).into_inner(),
+                self.fold_uint_expression(e2).into_inner(),
+            ) {
+                (UExpressionInner::Value(v1), UExpressionInner::Value(v2)) => {
+                    UExpressionInner::Value((v1 + v2) & mask)
+                }
+                (e1, e2) => {
+                    UExpressionInner::Add(box e1.annotate(bitwidth), box e2.annotate(bitwidth))
+                }
+            },
+            UExpressionInner::Sub(box e1, box e2) => match (
+                self.fold_uint_expression(e1).into_inner(),
+                self.fold_uint_expression(e2).into_inner(),
+            ) {
+                (UExpressionInner::Value(v1), UExpressionInner::Value(v2)) => {
+                    UExpressionInner::Value(v1.wrapping_sub(v2) & mask)
+                }
+                (e1, e2) => {
+                    UExpressionInner::Sub(box e1.annotate(bitwidth), box e2.annotate(bitwidth))
+                }
+            },
+            UExpressionInner::Mult(box e1, box e2) => match (
+                self.fold_uint_expression(e1).into_inner(),
+                self.fold_uint_expression(e2).into_inner(),
+            ) {
+                (UExpressionInner::Value(v1), UExpressionInner::Value(v2)) => {
+                    UExpressionInner::Value((v1 * v2) & mask)
+                }
+                (e1, e2) => {
+                    UExpressionInner::Mult(box e1.annotate(bitwidth), box e2.annotate(bitwidth))
+                }
+            },
+            UExpressionInner::Xor(box e1, box e2) => match (
+                self.fold_uint_expression(e1).into_inner(),
+                self.fold_uint_expression(e2).into_inner(),
+            ) {
+                (UExpressionInner::Value(v1), UExpressionInner::Value(v2)) => {
+                    UExpressionInner::Value(v1 ^ v2)
+                }
+                (e1, e2) => {
+                    UExpressionInner::Xor(box e1.annotate(bitwidth), box e2.annotate(bitwidth))
+                }
+            },
+            UExpressionInner::And(box e1, box e2) => match (
+                self.fold_uint_expression(e1).into_inner(),
+                self.fold_uint_expression(e2).into_inner(),
+            ) {
+                (UExpressionInner::Value(v1), UExpressionInner::Value(v2)) => {
+                    UExpressionInner::Value(v1 & v2)
+                }
+                (e1, e2) => {
+                    UExpressionInner::And(box e1.annotate(bitwidth), box e2.annotate(bitwidth))
+                }
+            },
+            UExpressionInner::Or(box e1, box e2) => match (
+                self.fold_uint_expression(e1).into_inner(),
+                self.fold_uint_expression(e2).into_inner(),
+            ) {
+                (UExpressionInner::Value(v1), UExpressionInner::Value(v2)) => {
+                    UExpressionInner::Value(v1 | v2)
+                }
+                (e1, e2) => {
+                    UExpressionInner::Or(box e1.annotate(bitwidth), box e2.annotate(bitwidth))
+                }
+            },
+            UExpressionInner::Not(box e) => match self.fold_uint_expression(e).into_inner() {
+                UExpressionInner::Value(v) => UExpressionInner::Value(!v & mask),
+                e => UExpressionInner::Not(box e.annotate(bitwidth)),
+            },
+            UExpressionInner::LeftShift(box e, by) => {
+                match self.fold_uint_expression(e).into_inner() {
+                    UExpressionInner::Value(v) if by < bitwidth => {
+                        UExpressionInner::Value((v << by) & mask)
+                    }
+                    UExpressionInner::Value(_) => UExpressionInner::Value(0),
+                    e => UExpressionInner::LeftShift(box e.annotate(bitwidth), by),
+                }
+            }
+            UExpressionInner::RightShift(box e, by) => {
+                match self.fold_uint_expression(e).into_inner() {
+                    UExpressionInner::Value(v) if by < bitwidth => UExpressionInner::Value(v >> by),
+                    UExpressionInner::Value(_) => UExpressionInner::Value(0),
+                    e => UExpressionInner::RightShift(box e.annotate(bitwidth), by),
+                }
+            }
+            UExpressionInner::IfElse(box condition, box consequence, box alternative) => {
+                let consequence = self.fold_uint_expression(consequence);
+                let alternative = self.fold_uint_expression(alternative);
+                match self.fold_boolean_expression(condition) {
+                    BooleanExpression::Value(true) => consequence.into_inner(),
+                    BooleanExpression::Value(false) => alternative.into_inner(),
+                    c => UExpressionInner::IfElse(box c, box consequence, box alternative),
+                }
+            }
+            UExpressionInner::Select(box array, box index) => match self.select(array, index) {
+                Ok(TypedExpression::Uint(e)) => e.into_inner(),
+                Ok(_) => panic!("element should have the type of the expression selecting it"),
+                Err((a, i)) => UExpressionInner::Select(box a, box i),
+            },
+            UExpressionInner::Member(box s, id) => {
+                let s = self.fold_struct_expression(s);
+                match select_member(&s, &id) {
+                    Some(TypedExpression::Uint(e)) => e.into_inner(),
+                    Some(_) => panic!("member should have the type of the expression accessing it"),
+                    None => UExpressionInner::Member(box s, id),
+                }
+            }
+            e => fold_uint_expression_inner(self, bitwidth, e),
         }
     }
 
@@ -270,6 +464,61 @@ impl<'ast, T: Field> Folder<'ast, T> for Propagator<'ast, T> {
         e: BooleanExpression<'ast, T>,
     ) -> BooleanExpression<'ast, T> {
         match e {
+            BooleanExpression::UintEq(box e1, box e2) => {
+                let e1 = self.fold_uint_expression(e1);
+                let e2 = self.fold_uint_expression(e2);
+
+                match (e1.as_inner(), e2.as_inner()) {
+                    (UExpressionInner::Value(v1), UExpressionInner::Value(v2)) => {
+                        BooleanExpression::Value(v1 == v2)
+                    }
+                    _ => BooleanExpression::UintEq(box e1, box e2),
+                }
+            }
+            BooleanExpression::UintLt(box e1, box e2) => {
+                let e1 = self.fold_uint_expression(e1);
+                let e2 = self.fold_uint_expression(e2);
+
+                match (e1.as_inner(), e2.as_inner()) {
+                    (UExpressionInner::Value(v1), UExpressionInner::Value(v2)) => {
+                        BooleanExpression::Value(v1 < v2)
+                    }
+                    _ => BooleanExpression::UintLt(box e1, box e2),
+                }
+            }
+            BooleanExpression::UintLe(box e1, box e2) => {
+                let e1 = self.fold_uint_expression(e1);
+                let e2 = self.fold_uint_expression(e2);
+
+                match (e1.as_inner(), e2.as_inner()) {
+                    (UExpressionInner::Value(v1), UExpressionInner::Value(v2)) => {
+                        BooleanExpression::Value(v1 <= v2)
+                    }
+                    _ => BooleanExpression::UintLe(box e1, box e2),
+                }
+            }
+            BooleanExpression::UintGt(box e1, box e2) => {
+                let e1 = self.fold_uint_expression(e1);
+                let e2 = self.fold_uint_expression(e2);
+
+                match (e1.as_inner(), e2.as_inner()) {
+                    (UExpressionInner::Value(v1), UExpressionInner::Value(v2)) => {
+                        BooleanExpression::Value(v1 > v2)
+                    }
+                    _ => BooleanExpression::UintGt(box e1, box e2),
+                }
+            }
+            BooleanExpression::UintGe(box e1, box e2) => {
+                let e1 = self.fold_uint_expression(e1);
+                let e2 = self.fold_uint_expression(e2);
+
+                match (e1.as_inner(), e2.as_inner()) {
+                    (UExpressionInner::Value(v1), UExpressionInner::Value(v2)) => {
+                        BooleanExpression::Value(v1 >= v2)
+                    }
+                    _ => BooleanExpression::UintGe(box e1, box e2),
+                }
+            }
             BooleanExpression::Identifier(id) => match self
                 .constants
                 .get(&TypedAssignee::Identifier(Variable::boolean(id.clone())))
@@ -377,12 +626,59 @@ impl<'ast, T: Field> Folder<'ast, T> for Propagator<'ast, T> {
                 let e = self.fold_boolean_expression(e);
                 match e {
                     BooleanExpression::Value(v) => BooleanExpression::Value(!v),
-                    e => e,
+                    e => BooleanExpression::Not(box e),
+                }
+            }
+            BooleanExpression::IfElse(box condition, box consequence, box alternative) => {
+                let consequence = self.fold_boolean_expression(consequence);
+                let alternative = self.fold_boolean_expression(alternative);
+                match self.fold_boolean_expression(condition) {
+                    BooleanExpression::Value(true) => consequence,
+                    BooleanExpression::Value(false) => alternative,
+                    c => BooleanExpression::IfElse(box c, box consequence, box alternative),
+                }
+            }
+            BooleanExpression::Select(box array, box index) => match self.select(array, index) {
+                Ok(TypedExpression::Boolean(e)) => e,
+                Ok(_) => panic!("element should have the type of the expression selecting it"),
+                Err((a, i)) => BooleanExpression::Select(box a, box i),
+            },
+            BooleanExpression::Member(box s, id) => {
+                let s = self.fold_struct_expression(s);
+                match select_member(&s, &id) {
+                    Some(TypedExpression::Boolean(e)) => e,
+                    Some(_) => panic!("member should have the type of the expression accessing it"),
+                    None => BooleanExpression::Member(box s, id),
                 }
             }
             e => fold_boolean_expression(self, e),
         }
     }
+
+    fn fold_struct_expression_inner(
+        &mut self,
+        ty: &StructType,
+        e: StructExpressionInner<'ast, T>,
+    ) -> StructExpressionInner<'ast, T> {
+        match e {
+            StructExpressionInner::Select(box array, box index) => {
+                match self.select(array, index) {
+                    Ok(TypedExpression::Struct(e)) => e.into_inner(),
+                    Ok(_) => panic!("element should have the type of the expression selecting it"),
+                    Err((a, i)) => StructExpressionInner::Select(box a, box i),
+                }
+            }
+            StructExpressionInner::Member(box s, id) => {
+                let s = self.fold_struct_expression(s);
+                match select_member(&s, &id) {
+                    Some(TypedExpression::Struct(e)) => e.into_inner(),
+                    Some(_) => panic!("member should have the type of the expression accessing it"),
+                    None => StructExpressionInner::Member(box s, id),
+                }
+            }
+            e => fold_struct_expression_inner(self, ty, e),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -494,14 +790,12 @@ mod tests {
             #[test]
             fn select() {
                 let e = FieldElementExpression::Select(
-                    box FieldElementArrayExpression::Value(
-                        3,
-                        vec![
-                            FieldElementExpression::Number(FieldPrime::from(1)),
-                            FieldElementExpression::Number(FieldPrime::from(2)),
-                            FieldElementExpression::Number(FieldPrime::from(3)),
-                        ],
-                    ),
+                    box ArrayExpressionInner::Value(vec![
+                        FieldElementExpression::Number(FieldPrime::from(1)).into(),
+                        FieldElementExpression::Number(FieldPrime::from(2)).into(),
+                        FieldElementExpression::Number(FieldPrime::from(3)).into(),
+                    ])
+                    .annotate(Type::FieldElement, 3),
                     box FieldElementExpression::Add(
                         box FieldElementExpression::Number(FieldPrime::from(1)),
                         box FieldElementExpression::Number(FieldPrime::from(1)),
@@ -768,6 +1062,118 @@ mod tests {
                     BooleanExpression::Value(false)
                 );
             }
+
+            #[test]
+            fn not() {
+                let a_bool: Identifier = "a".into();
+
+                assert_eq!(
+                    Propagator::<FieldPrime>::new().fold_boolean_expression(
+                        BooleanExpression::Not(box BooleanExpression::Value(true))
+                    ),
+                    BooleanExpression::Value(false)
+                );
+                // the negation of a non-constant expression is kept
+                assert_eq!(
+                    Propagator::<FieldPrime>::new().fold_boolean_expression(
+                        BooleanExpression::Not(box BooleanExpression::Identifier(a_bool.clone()))
+                    ),
+                    BooleanExpression::Not(box BooleanExpression::Identifier(a_bool))
+                );
+            }
+        }
+
+        #[cfg(test)]
+        mod uint {
+            use super::*;
+
+            #[test]
+            fn add_wraps() {
+                let e: UExpression<FieldPrime> = UExpressionInner::Add(
+                    box UExpressionInner::Value(0xff).annotate(8),
+                    box UExpressionInner::Value(0x02).annotate(8),
+                )
+                .annotate(8);
+
+                assert_eq!(
+                    Propagator::new().fold_uint_expression(e),
+                    UExpressionInner::Value(0x01).annotate(8)
+                );
+            }
+
+            #[test]
+            fn sub_wraps() {
+                let e: UExpression<FieldPrime> = UExpressionInner::Sub(
+                    box UExpressionInner::Value(0x0001).annotate(16),
+                    box UExpressionInner::Value(0x0002).annotate(16),
+                )
+                .annotate(16);
+
+                assert_eq!(
+                    Propagator::new().fold_uint_expression(e),
+                    UExpressionInner::Value(0xffff).annotate(16)
+                );
+            }
+
+            #[test]
+            fn mult_wraps() {
+                let e: UExpression<FieldPrime> = UExpressionInner::Mult(
+                    box UExpressionInner::Value(0xffffffffffffffff).annotate(64),
+                    box UExpressionInner::Value(0x0000000000000002).annotate(64),
+                )
+                .annotate(64);
+
+                assert_eq!(
+                    Propagator::new().fold_uint_expression(e),
+                    UExpressionInner::Value(0xfffffffffffffffe).annotate(64)
+                );
+            }
+
+            #[test]
+            fn not() {
+                let e: UExpression<FieldPrime> =
+                    UExpressionInner::Not(box UExpressionInner::Value(0x0f).annotate(8))
+                        .annotate(8);
+
+                assert_eq!(
+                    Propagator::new().fold_uint_expression(e),
+                    UExpressionInner::Value(0xf0).annotate(8)
+                );
+            }
+
+            #[test]
+            fn shifts() {
+                let e: UExpression<FieldPrime> =
+                    UExpressionInner::LeftShift(box UExpressionInner::Value(0x81).annotate(8), 1)
+                        .annotate(8);
+
+                assert_eq!(
+                    Propagator::new().fold_uint_expression(e),
+                    UExpressionInner::Value(0x02).annotate(8)
+                );
+
+                let e: UExpression<FieldPrime> =
+                    UExpressionInner::RightShift(box UExpressionInner::Value(0x81).annotate(8), 9)
+                        .annotate(8);
+
+                assert_eq!(
+                    Propagator::new().fold_uint_expression(e),
+                    UExpressionInner::Value(0x00).annotate(8)
+                );
+            }
+
+            #[test]
+            fn lt() {
+                let e: BooleanExpression<FieldPrime> = BooleanExpression::UintLt(
+                    box UExpressionInner::Value(0x02).annotate(8),
+                    box UExpressionInner::Value(0x03).annotate(8),
+                );
+
+                assert_eq!(
+                    Propagator::new().fold_boolean_expression(e),
+                    BooleanExpression::Value(true)
+                );
+            }
         }
     }
 
@@ -789,13 +1195,11 @@ mod tests {
                 let declaration = TypedStatement::Declaration(Variable::field_array("a".into(), 2));
                 let definition = TypedStatement::Definition(
                     TypedAssignee::Identifier(Variable::field_array("a".into(), 2)),
-                    FieldElementArrayExpression::Value(
-                        2,
-                        vec![
-                            FieldElementExpression::Number(FieldPrime::from(21)),
-                            FieldElementExpression::Number(FieldPrime::from(22)),
-                        ],
-                    )
+                    ArrayExpressionInner::Value(vec![
+                        FieldElementExpression::Number(FieldPrime::from(21)).into(),
+                        FieldElementExpression::Number(FieldPrime::from(22)).into(),
+                    ])
+                    .annotate(Type::FieldElement, 2)
                     .into(),
                 );
                 let overwrite = TypedStatement::Definition(
@@ -811,13 +1215,11 @@ mod tests {
                 p.fold_statement(declaration);
                 p.fold_statement(definition);
                 let expected_value: TypedExpression<FieldPrime> =
-                    FieldElementArrayExpression::Value(
-                        2,
-                        vec![
-                            FieldElementExpression::Number(FieldPrime::from(21)),
-                            FieldElementExpression::Number(FieldPrime::from(22)),
-                        ],
-                    )
+                    ArrayExpressionInner::Value(vec![
+                        FieldElementExpression::Number(FieldPrime::from(21)).into(),
+                        FieldElementExpression::Number(FieldPrime::from(22)).into(),
+                    ])
+                    .annotate(Type::FieldElement, 2)
                     .into();
 
                 assert_eq!(
@@ -832,13 +1234,11 @@ mod tests {
 
                 p.fold_statement(overwrite);
                 let expected_value: TypedExpression<FieldPrime> =
-                    FieldElementArrayExpression::Value(
-                        2,
-                        vec![
-                            FieldElementExpression::Number(FieldPrime::from(21)),
-                            FieldElementExpression::Number(FieldPrime::from(42)),
-                        ],
-                    )
+                    ArrayExpressionInner::Value(vec![
+                        FieldElementExpression::Number(FieldPrime::from(21)).into(),
+                        FieldElementExpression::Number(FieldPrime::from(42)).into(),
+                    ])
+                    .annotate(Type::FieldElement, 2)
                     .into();
 
                 assert_eq!(
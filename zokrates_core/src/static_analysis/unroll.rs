@@ -45,6 +45,57 @@ impl<'ast> Unroller<'ast> {
     }
 }
 
+/// Turns an assignee `a[i][j]...` into the root variable `a` and the indices `[i, j, ...]`
+fn linear<'ast, T: Field>(
+    a: TypedAssignee<'ast, T>,
+) -> (Variable<'ast>, Vec<FieldElementExpression<'ast, T>>) {
+    match a {
+        TypedAssignee::Identifier(v) => (v, vec![]),
+        TypedAssignee::ArrayElement(box array, box index) => {
+            let (v, mut indices) = linear(array);
+            indices.push(index);
+            (v, indices)
+        }
+    }
+}
+
+/// Builds a copy of `array` where the element at `indices` is replaced by `value`
+fn update<'ast, T: Field>(
+    array: ArrayExpression<'ast, T>,
+    indices: &[FieldElementExpression<'ast, T>],
+    value: TypedExpression<'ast, T>,
+) -> ArrayExpression<'ast, T> {
+    let ty = array.inner_type().clone();
+    let size = array.size();
+    let (index, rest) = indices.split_first().unwrap();
+
+    ArrayExpressionInner::Value(
+        (0..size)
+            .map(|i| {
+                let current = array
+                    .clone()
+                    .select(FieldElementExpression::Number(T::from(i)));
+                let new = match rest.len() {
+                    0 => value.clone(),
+                    _ => match current.clone() {
+                        TypedExpression::Array(inner) => update(inner, rest, value.clone()).into(),
+                        _ => panic!("too many indices for array element assignee"),
+                    },
+                };
+                TypedExpression::if_else(
+                    BooleanExpression::Eq(
+                        box index.clone(),
+                        box FieldElementExpression::Number(T::from(i)),
+                    ),
+                    new,
+                    current,
+                )
+            })
+            .collect(),
+    )
+    .annotate(ty, size)
+}
+
 impl<'ast, T: Field> Folder<'ast, T> for Unroller<'ast> {
     fn fold_statement(&mut self, s: TypedStatement<'ast, T>) -> Vec<TypedStatement<'ast, T>> {
         match s {
@@ -57,57 +108,33 @@ impl<'ast, T: Field> Folder<'ast, T> for Unroller<'ast> {
                     expr,
                 )]
             }
-            TypedStatement::Definition(
-                TypedAssignee::ArrayElement(array @ box TypedAssignee::Identifier(..), box index),
-                expr,
-            ) => {
+            TypedStatement::Definition(assignee @ TypedAssignee::ArrayElement(..), expr) => {
                 let expr = self.fold_expression(expr);
-                let index = self.fold_field_expression(index);
-                let current_array = self.fold_assignee(*array.clone());
 
-                let current_ssa_variable = match current_array {
-                    TypedAssignee::Identifier(v) => v,
-                    _ => panic!("assignee should be an identifier"),
-                };
+                let (original_variable, indices) = linear(assignee);
+                let indices: Vec<_> = indices
+                    .into_iter()
+                    .map(|i| self.fold_field_expression(i))
+                    .collect();
 
-                let original_variable = match *array {
+                let current_ssa_variable = match self.fold_assignee(TypedAssignee::<T>::Identifier(
+                    original_variable.clone(),
+                )) {
                     TypedAssignee::Identifier(v) => v,
                     _ => panic!("assignee should be an identifier"),
                 };
 
-                let array_size = match original_variable.get_type() {
-                    Type::FieldElementArray(size) => size,
-                    _ => panic!("array identifier should be a field element array"),
-                };
-
-                let expr = match expr {
-                    TypedExpression::FieldElement(e) => e,
-                    _ => panic!("right side of array element definition must be a field element"),
+                let current_array = match original_variable.get_type() {
+                    Type::Array(array_type) => ArrayExpressionInner::Identifier(
+                        current_ssa_variable.id,
+                    )
+                    .annotate(*array_type.ty, array_type.size),
+                    _ => panic!("array element assignee should be rooted at an array"),
                 };
 
                 let new_variable = self.issue_next_ssa_variable(original_variable);
 
-                let new_array = FieldElementArrayExpression::Value(
-                    array_size,
-                    (0..array_size)
-                        .map(|i| {
-                            FieldElementExpression::IfElse(
-                                box BooleanExpression::Eq(
-                                    box index.clone(),
-                                    box FieldElementExpression::Number(T::from(i)),
-                                ),
-                                box expr.clone(),
-                                box FieldElementExpression::Select(
-                                    box FieldElementArrayExpression::Identifier(
-                                        array_size,
-                                        current_ssa_variable.id.clone(),
-                                    ),
-                                    box FieldElementExpression::Number(T::from(i)),
-                                ),
-                            )
-                        })
-                        .collect(),
-                );
+                let new_array = update(current_array, &indices, expr);
 
                 vec![TypedStatement::Definition(
                     TypedAssignee::Identifier(new_variable),
@@ -433,13 +460,11 @@ mod tests {
 
             let s = TypedStatement::Definition(
                 TypedAssignee::Identifier(Variable::field_array("a".into(), 2)),
-                FieldElementArrayExpression::Value(
-                    2,
-                    vec![
-                        FieldElementExpression::Number(FieldPrime::from(1)),
-                        FieldElementExpression::Number(FieldPrime::from(1)),
-                    ],
-                )
+                ArrayExpressionInner::Value(vec![
+                    FieldElementExpression::Number(FieldPrime::from(1)).into(),
+                    FieldElementExpression::Number(FieldPrime::from(1)).into(),
+                ])
+                .annotate(Type::FieldElement, 2)
                 .into(),
             );
 
@@ -450,13 +475,11 @@ mod tests {
                         Identifier::from("a").version(0),
                         2
                     )),
-                    FieldElementArrayExpression::Value(
-                        2,
-                        vec![
-                            FieldElementExpression::Number(FieldPrime::from(1)),
-                            FieldElementExpression::Number(FieldPrime::from(1))
-                        ]
-                    )
+                    ArrayExpressionInner::Value(vec![
+                        FieldElementExpression::Number(FieldPrime::from(1)).into(),
+                        FieldElementExpression::Number(FieldPrime::from(1)).into()
+                    ])
+                    .annotate(Type::FieldElement, 2)
                     .into()
                 )]
             );
@@ -476,9 +499,7 @@ mod tests {
                         Identifier::from("a").version(1),
                         2
                     )),
-                    FieldElementArrayExpression::Value(
-                        2,
-                        vec![
+                    ArrayExpressionInner::Value(vec![
                             FieldElementExpression::IfElse(
                                 box BooleanExpression::Eq(
                                     box FieldElementExpression::Number(FieldPrime::from(1)),
@@ -486,13 +507,14 @@ mod tests {
                                 ),
                                 box FieldElementExpression::Number(FieldPrime::from(2)),
                                 box FieldElementExpression::Select(
-                                    box FieldElementArrayExpression::Identifier(
-                                        2,
+                                    box ArrayExpressionInner::Identifier(
                                         Identifier::from("a").version(0)
-                                    ),
+                                    )
+                                    .annotate(Type::FieldElement, 2),
                                     box FieldElementExpression::Number(FieldPrime::from(0))
                                 ),
-                            ),
+                            )
+                            .into(),
                             FieldElementExpression::IfElse(
                                 box BooleanExpression::Eq(
                                     box FieldElementExpression::Number(FieldPrime::from(1)),
@@ -500,15 +522,16 @@ mod tests {
                                 ),
                                 box FieldElementExpression::Number(FieldPrime::from(2)),
                                 box FieldElementExpression::Select(
-                                    box FieldElementArrayExpression::Identifier(
-                                        2,
+                                    box ArrayExpressionInner::Identifier(
                                         Identifier::from("a").version(0)
-                                    ),
+                                    )
+                                    .annotate(Type::FieldElement, 2),
                                     box FieldElementExpression::Number(FieldPrime::from(1))
                                 ),
-                            ),
-                        ]
-                    )
+                            )
+                            .into(),
+                        ])
+                    .annotate(Type::FieldElement, 2)
                     .into()
                 )]
             );
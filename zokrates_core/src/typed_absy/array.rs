@@ -0,0 +1,139 @@
+use crate::typed_absy::*;
+use crate::types::{MemberId, Type};
+use std::fmt;
+use zokrates_field::field::Field;
+
+/// An array expression, annotated with the type of its elements and its size
+#[derive(Clone, PartialEq, Hash, Eq)]
+pub struct ArrayExpression<'ast, T: Field> {
+    pub ty: Type,
+    pub size: usize,
+    pub inner: ArrayExpressionInner<'ast, T>,
+}
+
+#[derive(Clone, PartialEq, Hash, Eq)]
+pub enum ArrayExpressionInner<'ast, T: Field> {
+    Identifier(Identifier<'ast>),
+    Value(Vec<TypedExpression<'ast, T>>),
+    FunctionCall(String, Vec<TypedExpression<'ast, T>>),
+    IfElse(
+        Box<BooleanExpression<'ast, T>>,
+        Box<ArrayExpression<'ast, T>>,
+        Box<ArrayExpression<'ast, T>>,
+    ),
+    Member(Box<StructExpression<'ast, T>>, MemberId),
+    Select(
+        Box<ArrayExpression<'ast, T>>,
+        Box<FieldElementExpression<'ast, T>>,
+    ),
+}
+
+impl<'ast, T: Field> ArrayExpressionInner<'ast, T> {
+    pub fn annotate(self, ty: Type, size: usize) -> ArrayExpression<'ast, T> {
+        ArrayExpression {
+            ty,
+            size,
+            inner: self,
+        }
+    }
+}
+
+impl<'ast, T: Field> ArrayExpression<'ast, T> {
+    pub fn inner_type(&self) -> &Type {
+        &self.ty
+    }
+
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    pub fn as_inner(&self) -> &ArrayExpressionInner<'ast, T> {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> ArrayExpressionInner<'ast, T> {
+        self.inner
+    }
+
+    /// Returns the element of this array at `index`, as an expression of the type of the elements
+    pub fn select(self, index: FieldElementExpression<'ast, T>) -> TypedExpression<'ast, T> {
+        match self.ty.clone() {
+            Type::FieldElement => FieldElementExpression::Select(box self, box index).into(),
+            Type::Boolean => BooleanExpression::Select(box self, box index).into(),
+            Type::Uint(bitwidth) => UExpressionInner::Select(box self, box index)
+                .annotate(bitwidth)
+                .into(),
+            Type::Array(array_type) => ArrayExpressionInner::Select(box self, box index)
+                .annotate(*array_type.ty, array_type.size)
+                .into(),
+            Type::Struct(struct_type) => StructExpressionInner::Select(box self, box index)
+                .annotate(struct_type)
+                .into(),
+        }
+    }
+}
+
+impl<'ast, T: Field> fmt::Display for ArrayExpression<'ast, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.inner {
+            ArrayExpressionInner::Identifier(ref var) => write!(f, "{}", var),
+            ArrayExpressionInner::Value(ref values) => write!(
+                f,
+                "[{}]",
+                values
+                    .iter()
+                    .map(|o| o.to_string())
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ),
+            ArrayExpressionInner::FunctionCall(ref i, ref p) => {
+                r#try!(write!(f, "{}(", i,));
+                for (i, param) in p.iter().enumerate() {
+                    r#try!(write!(f, "{}", param));
+                    if i < p.len() - 1 {
+                        r#try!(write!(f, ", "));
+                    }
+                }
+                write!(f, ")")
+            }
+            ArrayExpressionInner::IfElse(ref condition, ref consequent, ref alternative) => {
+                write!(
+                    f,
+                    "if {} then {} else {} fi",
+                    condition, consequent, alternative
+                )
+            }
+            ArrayExpressionInner::Member(ref struc, ref id) => write!(f, "{}.{}", struc, id),
+            ArrayExpressionInner::Select(ref array, ref index) => {
+                write!(f, "{}[{}]", array, index)
+            }
+        }
+    }
+}
+
+impl<'ast, T: Field> fmt::Debug for ArrayExpression<'ast, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.inner {
+            ArrayExpressionInner::Identifier(ref var) => write!(f, "{:?}", var),
+            ArrayExpressionInner::Value(ref values) => write!(f, "{:?}", values),
+            ArrayExpressionInner::FunctionCall(ref i, ref p) => {
+                r#try!(write!(f, "FunctionCall({:?}, (", i));
+                r#try!(f.debug_list().entries(p.iter()).finish());
+                write!(f, ")")
+            }
+            ArrayExpressionInner::IfElse(ref condition, ref consequent, ref alternative) => {
+                write!(
+                    f,
+                    "IfElse({:?}, {:?}, {:?})",
+                    condition, consequent, alternative
+                )
+            }
+            ArrayExpressionInner::Member(ref struc, ref id) => {
+                write!(f, "Member({:?}, {:?})", struc, id)
+            }
+            ArrayExpressionInner::Select(ref array, ref index) => {
+                write!(f, "Select({:?}, {:?})", array, index)
+            }
+        }
+    }
+}
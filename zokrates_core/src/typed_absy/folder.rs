@@ -1,6 +1,7 @@
 // Generic walk through a typed AST. Not mutating in place
 
 use crate::typed_absy::*;
+use crate::types::{StructType, Type};
 use zokrates_field::field::Field;
 
 pub trait Folder<'ast, T: Field>: Sized {
@@ -48,7 +49,9 @@ pub trait Folder<'ast, T: Field>: Sized {
         match e {
             TypedExpression::FieldElement(e) => self.fold_field_expression(e).into(),
             TypedExpression::Boolean(e) => self.fold_boolean_expression(e).into(),
-            TypedExpression::FieldElementArray(e) => self.fold_field_array_expression(e).into(),
+            TypedExpression::Array(e) => self.fold_array_expression(e).into(),
+            TypedExpression::Uint(e) => self.fold_uint_expression(e).into(),
+            TypedExpression::Struct(e) => self.fold_struct_expression(e).into(),
         }
     }
 
@@ -82,11 +85,39 @@ pub trait Folder<'ast, T: Field>: Sized {
     ) -> BooleanExpression<'ast, T> {
         fold_boolean_expression(self, e)
     }
-    fn fold_field_array_expression(
+    fn fold_array_expression(&mut self, e: ArrayExpression<'ast, T>) -> ArrayExpression<'ast, T> {
+        fold_array_expression(self, e)
+    }
+    fn fold_array_expression_inner(
         &mut self,
-        e: FieldElementArrayExpression<'ast, T>,
-    ) -> FieldElementArrayExpression<'ast, T> {
-        fold_field_array_expression(self, e)
+        ty: &Type,
+        size: usize,
+        e: ArrayExpressionInner<'ast, T>,
+    ) -> ArrayExpressionInner<'ast, T> {
+        fold_array_expression_inner(self, ty, size, e)
+    }
+    fn fold_uint_expression(&mut self, e: UExpression<'ast, T>) -> UExpression<'ast, T> {
+        fold_uint_expression(self, e)
+    }
+    fn fold_uint_expression_inner(
+        &mut self,
+        bitwidth: usize,
+        e: UExpressionInner<'ast, T>,
+    ) -> UExpressionInner<'ast, T> {
+        fold_uint_expression_inner(self, bitwidth, e)
+    }
+    fn fold_struct_expression(
+        &mut self,
+        e: StructExpression<'ast, T>,
+    ) -> StructExpression<'ast, T> {
+        fold_struct_expression(self, e)
+    }
+    fn fold_struct_expression_inner(
+        &mut self,
+        ty: &StructType,
+        e: StructExpressionInner<'ast, T>,
+    ) -> StructExpressionInner<'ast, T> {
+        fold_struct_expression_inner(self, ty, e)
     }
 }
 
@@ -119,8 +150,8 @@ pub fn fold_statement<'ast, T: Field, F: Folder<'ast, T>>(
             TypedStatement::Definition(f.fold_assignee(a), f.fold_expression(e))
         }
         TypedStatement::Declaration(v) => TypedStatement::Declaration(f.fold_variable(v)),
-        TypedStatement::Condition(left, right) => {
-            TypedStatement::Condition(f.fold_expression(left), f.fold_expression(right))
+        TypedStatement::Condition(left, right, pos) => {
+            TypedStatement::Condition(f.fold_expression(left), f.fold_expression(right), pos)
         }
         TypedStatement::For(v, from, to, statements) => TypedStatement::For(
             f.fold_variable(v),
@@ -135,36 +166,95 @@ pub fn fold_statement<'ast, T: Field, F: Folder<'ast, T>>(
             variables.into_iter().map(|v| f.fold_variable(v)).collect(),
             f.fold_expression_list(elist),
         ),
+        TypedStatement::Marker(id, pos) => TypedStatement::Marker(id, pos),
     };
     vec![res]
 }
 
-pub fn fold_field_array_expression<'ast, T: Field, F: Folder<'ast, T>>(
+pub fn fold_array_expression<'ast, T: Field, F: Folder<'ast, T>>(
+    f: &mut F,
+    e: ArrayExpression<'ast, T>,
+) -> ArrayExpression<'ast, T> {
+    ArrayExpression {
+        inner: f.fold_array_expression_inner(&e.ty, e.size, e.inner),
+        ..e
+    }
+}
+
+pub fn fold_array_expression_inner<'ast, T: Field, F: Folder<'ast, T>>(
     f: &mut F,
-    e: FieldElementArrayExpression<'ast, T>,
-) -> FieldElementArrayExpression<'ast, T> {
+    _: &Type,
+    _: usize,
+    e: ArrayExpressionInner<'ast, T>,
+) -> ArrayExpressionInner<'ast, T> {
     match e {
-        FieldElementArrayExpression::Identifier(size, id) => {
-            FieldElementArrayExpression::Identifier(size, f.fold_name(id))
+        ArrayExpressionInner::Identifier(id) => ArrayExpressionInner::Identifier(f.fold_name(id)),
+        ArrayExpressionInner::Value(exprs) => {
+            ArrayExpressionInner::Value(exprs.into_iter().map(|e| f.fold_expression(e)).collect())
         }
-        FieldElementArrayExpression::Value(size, exprs) => FieldElementArrayExpression::Value(
-            size,
-            exprs
-                .into_iter()
-                .map(|e| f.fold_field_expression(e))
-                .collect(),
-        ),
-        FieldElementArrayExpression::FunctionCall(size, id, exps) => {
+        ArrayExpressionInner::FunctionCall(id, exps) => {
+            let exps = exps.into_iter().map(|e| f.fold_expression(e)).collect();
+            ArrayExpressionInner::FunctionCall(id, exps)
+        }
+        ArrayExpressionInner::IfElse(box condition, box consequence, box alternative) => {
+            ArrayExpressionInner::IfElse(
+                box f.fold_boolean_expression(condition),
+                box f.fold_array_expression(consequence),
+                box f.fold_array_expression(alternative),
+            )
+        }
+        ArrayExpressionInner::Member(box s, id) => {
+            let s = f.fold_struct_expression(s);
+            ArrayExpressionInner::Member(box s, id)
+        }
+        ArrayExpressionInner::Select(box array, box index) => {
+            let array = f.fold_array_expression(array);
+            let index = f.fold_field_expression(index);
+            ArrayExpressionInner::Select(box array, box index)
+        }
+    }
+}
+
+pub fn fold_struct_expression<'ast, T: Field, F: Folder<'ast, T>>(
+    f: &mut F,
+    e: StructExpression<'ast, T>,
+) -> StructExpression<'ast, T> {
+    StructExpression {
+        inner: f.fold_struct_expression_inner(&e.ty, e.inner),
+        ..e
+    }
+}
+
+pub fn fold_struct_expression_inner<'ast, T: Field, F: Folder<'ast, T>>(
+    f: &mut F,
+    _: &StructType,
+    e: StructExpressionInner<'ast, T>,
+) -> StructExpressionInner<'ast, T> {
+    match e {
+        StructExpressionInner::Identifier(id) => StructExpressionInner::Identifier(f.fold_name(id)),
+        StructExpressionInner::Value(exprs) => {
+            StructExpressionInner::Value(exprs.into_iter().map(|e| f.fold_expression(e)).collect())
+        }
+        StructExpressionInner::FunctionCall(id, exps) => {
             let exps = exps.into_iter().map(|e| f.fold_expression(e)).collect();
-            FieldElementArrayExpression::FunctionCall(size, id, exps)
+            StructExpressionInner::FunctionCall(id, exps)
         }
-        FieldElementArrayExpression::IfElse(box condition, box consequence, box alternative) => {
-            FieldElementArrayExpression::IfElse(
+        StructExpressionInner::IfElse(box condition, box consequence, box alternative) => {
+            StructExpressionInner::IfElse(
                 box f.fold_boolean_expression(condition),
-                box f.fold_field_array_expression(consequence),
-                box f.fold_field_array_expression(alternative),
+                box f.fold_struct_expression(consequence),
+                box f.fold_struct_expression(alternative),
             )
         }
+        StructExpressionInner::Member(box s, id) => {
+            let s = f.fold_struct_expression(s);
+            StructExpressionInner::Member(box s, id)
+        }
+        StructExpressionInner::Select(box array, box index) => {
+            let array = f.fold_array_expression(array);
+            let index = f.fold_field_expression(index);
+            StructExpressionInner::Select(box array, box index)
+        }
     }
 }
 
@@ -213,10 +303,14 @@ pub fn fold_field_expression<'ast, T: Field, F: Folder<'ast, T>>(
             FieldElementExpression::FunctionCall(id, exps)
         }
         FieldElementExpression::Select(box array, box index) => {
-            let array = f.fold_field_array_expression(array);
+            let array = f.fold_array_expression(array);
             let index = f.fold_field_expression(index);
             FieldElementExpression::Select(box array, box index)
         }
+        FieldElementExpression::Member(box s, id) => {
+            let s = f.fold_struct_expression(s);
+            FieldElementExpression::Member(box s, id)
+        }
     }
 }
 
@@ -266,6 +360,128 @@ pub fn fold_boolean_expression<'ast, T: Field, F: Folder<'ast, T>>(
             let e = f.fold_boolean_expression(e);
             BooleanExpression::Not(box e)
         }
+        BooleanExpression::UintEq(box e1, box e2) => {
+            let e1 = f.fold_uint_expression(e1);
+            let e2 = f.fold_uint_expression(e2);
+            BooleanExpression::UintEq(box e1, box e2)
+        }
+        BooleanExpression::UintLt(box e1, box e2) => {
+            let e1 = f.fold_uint_expression(e1);
+            let e2 = f.fold_uint_expression(e2);
+            BooleanExpression::UintLt(box e1, box e2)
+        }
+        BooleanExpression::UintLe(box e1, box e2) => {
+            let e1 = f.fold_uint_expression(e1);
+            let e2 = f.fold_uint_expression(e2);
+            BooleanExpression::UintLe(box e1, box e2)
+        }
+        BooleanExpression::UintGt(box e1, box e2) => {
+            let e1 = f.fold_uint_expression(e1);
+            let e2 = f.fold_uint_expression(e2);
+            BooleanExpression::UintGt(box e1, box e2)
+        }
+        BooleanExpression::UintGe(box e1, box e2) => {
+            let e1 = f.fold_uint_expression(e1);
+            let e2 = f.fold_uint_expression(e2);
+            BooleanExpression::UintGe(box e1, box e2)
+        }
+        BooleanExpression::IfElse(box cond, box cons, box alt) => {
+            let cond = f.fold_boolean_expression(cond);
+            let cons = f.fold_boolean_expression(cons);
+            let alt = f.fold_boolean_expression(alt);
+            BooleanExpression::IfElse(box cond, box cons, box alt)
+        }
+        BooleanExpression::Member(box s, id) => {
+            let s = f.fold_struct_expression(s);
+            BooleanExpression::Member(box s, id)
+        }
+        BooleanExpression::Select(box array, box index) => {
+            let array = f.fold_array_expression(array);
+            let index = f.fold_field_expression(index);
+            BooleanExpression::Select(box array, box index)
+        }
+    }
+}
+
+pub fn fold_uint_expression<'ast, T: Field, F: Folder<'ast, T>>(
+    f: &mut F,
+    e: UExpression<'ast, T>,
+) -> UExpression<'ast, T> {
+    UExpression {
+        inner: f.fold_uint_expression_inner(e.bitwidth, e.inner),
+        ..e
+    }
+}
+
+pub fn fold_uint_expression_inner<'ast, T: Field, F: Folder<'ast, T>>(
+    f: &mut F,
+    _: usize,
+    e: UExpressionInner<'ast, T>,
+) -> UExpressionInner<'ast, T> {
+    match e {
+        UExpressionInner::Value(v) => UExpressionInner::Value(v),
+        UExpressionInner::Identifier(id) => UExpressionInner::Identifier(f.fold_name(id)),
+        UExpressionInner::Add(box left, box right) => {
+            let left = f.fold_uint_expression(left);
+            let right = f.fold_uint_expression(right);
+            UExpressionInner::Add(box left, box right)
+        }
+        UExpressionInner::Sub(box left, box right) => {
+            let left = f.fold_uint_expression(left);
+            let right = f.fold_uint_expression(right);
+            UExpressionInner::Sub(box left, box right)
+        }
+        UExpressionInner::Mult(box left, box right) => {
+            let left = f.fold_uint_expression(left);
+            let right = f.fold_uint_expression(right);
+            UExpressionInner::Mult(box left, box right)
+        }
+        UExpressionInner::Xor(box left, box right) => {
+            let left = f.fold_uint_expression(left);
+            let right = f.fold_uint_expression(right);
+            UExpressionInner::Xor(box left, box right)
+        }
+        UExpressionInner::And(box left, box right) => {
+            let left = f.fold_uint_expression(left);
+            let right = f.fold_uint_expression(right);
+            UExpressionInner::And(box left, box right)
+        }
+        UExpressionInner::Or(box left, box right) => {
+            let left = f.fold_uint_expression(left);
+            let right = f.fold_uint_expression(right);
+            UExpressionInner::Or(box left, box right)
+        }
+        UExpressionInner::Not(box e) => {
+            let e = f.fold_uint_expression(e);
+            UExpressionInner::Not(box e)
+        }
+        UExpressionInner::LeftShift(box e, by) => {
+            let e = f.fold_uint_expression(e);
+            UExpressionInner::LeftShift(box e, by)
+        }
+        UExpressionInner::RightShift(box e, by) => {
+            let e = f.fold_uint_expression(e);
+            UExpressionInner::RightShift(box e, by)
+        }
+        UExpressionInner::IfElse(box cond, box cons, box alt) => {
+            let cond = f.fold_boolean_expression(cond);
+            let cons = f.fold_uint_expression(cons);
+            let alt = f.fold_uint_expression(alt);
+            UExpressionInner::IfElse(box cond, box cons, box alt)
+        }
+        UExpressionInner::FunctionCall(id, exps) => {
+            let exps = exps.into_iter().map(|e| f.fold_expression(e)).collect();
+            UExpressionInner::FunctionCall(id, exps)
+        }
+        UExpressionInner::Member(box s, id) => {
+            let s = f.fold_struct_expression(s);
+            UExpressionInner::Member(box s, id)
+        }
+        UExpressionInner::Select(box array, box index) => {
+            let array = f.fold_array_expression(array);
+            let index = f.fold_field_expression(index);
+            UExpressionInner::Select(box array, box index)
+        }
     }
 }
 
@@ -0,0 +1,923 @@
+//! Module containing an interpreter for typed programs
+//!
+//! Programs are evaluated on values rather than compiled to constraints, which makes running them
+//! much faster. The semantics are the ones of the compiled program: both branches of a conditional
+//! expression are evaluated, arithmetic on unsigned integers wraps around, and comparisons of field
+//! elements only hold for elements which fit in the bitwidth of the field minus two bits.
+
+use crate::ir;
+use crate::parser::Position;
+use crate::typed_absy::*;
+use crate::types::{MemberId, Signature, Type};
+use num::{One, Zero};
+use num_bigint::BigUint;
+use std::collections::HashMap;
+use std::fmt;
+use zokrates_field::field::{Field, Pow};
+
+pub type ExecutionResult<T> = Result<Vec<T>, Error>;
+
+impl<'ast, T: Field> TypedProg<'ast, T> {
+    /// Executes `main` on its inputs and returns its outputs, both flattened to field elements in
+    /// the same way as for the compiled program
+    pub fn execute<U: Into<T> + Clone>(&self, inputs: &Vec<U>) -> ExecutionResult<T> {
+        let main = self
+            .functions
+            .iter()
+            .find(|f| f.id == "main")
+            .expect("a program should have a main function after semantic checking");
+
+        let expected = main
+            .arguments
+            .iter()
+            .map(|p| p.id.get_type().get_primitive_count())
+            .sum();
+        if inputs.len() != expected {
+            return Err(Error::WrongInputCount {
+                expected,
+                received: inputs.len(),
+            });
+        }
+
+        let mut inputs = inputs.iter().map(|i| i.clone().into());
+        let arguments = main
+            .arguments
+            .iter()
+            .map(|p| Value::decode(&p.id.get_type(), &mut inputs))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let outputs = Interpreter { program: self }.call_typed(main, arguments)?;
+
+        Ok(outputs.into_iter().flat_map(|v| v.encode()).collect())
+    }
+}
+
+#[derive(Clone, PartialEq)]
+enum Value<T: Field> {
+    FieldElement(T),
+    Boolean(bool),
+    Uint(u128),
+    Array(Vec<Value<T>>),
+    // the values of the members, in declaration order
+    Struct(Vec<Value<T>>),
+}
+
+impl<T: Field> Value<T> {
+    /// Reads a value of type `ty` from field elements, checking that they are in range
+    fn decode<I: Iterator<Item = T>>(ty: &Type, inputs: &mut I) -> Result<Value<T>, Error> {
+        match ty {
+            Type::FieldElement => Ok(Value::FieldElement(inputs.next().unwrap())),
+            Type::Boolean => {
+                let v = inputs.next().unwrap();
+                if v == T::zero() {
+                    Ok(Value::Boolean(false))
+                } else if v == T::one() {
+                    Ok(Value::Boolean(true))
+                } else {
+                    Err(Error::InvalidInput {
+                        value: v.to_dec_string(),
+                        ty: ty.clone(),
+                    })
+                }
+            }
+            Type::Uint(bitwidth) => {
+                let v = inputs.next().unwrap();
+                match v.to_dec_string().parse() {
+                    Ok(v) if v <= max_uint(*bitwidth) => Ok(Value::Uint(v)),
+                    _ => Err(Error::InvalidInput {
+                        value: v.to_dec_string(),
+                        ty: ty.clone(),
+                    }),
+                }
+            }
+            Type::Array(array_type) => (0..array_type.size)
+                .map(|_| Value::decode(&array_type.ty, inputs))
+                .collect::<Result<_, _>>()
+                .map(Value::Array),
+            Type::Struct(struct_type) => struct_type
+                .iter()
+                .map(|m| Value::decode(&m.ty, inputs))
+                .collect::<Result<_, _>>()
+                .map(Value::Struct),
+        }
+    }
+
+    /// Returns the field elements this value is represented by
+    fn encode(self) -> Vec<T> {
+        match self {
+            Value::FieldElement(v) => vec![v],
+            Value::Boolean(b) => vec![if b { T::one() } else { T::zero() }],
+            Value::Uint(v) => vec![T::try_from_dec_str(&v.to_string()).unwrap()],
+            Value::Array(values) | Value::Struct(values) => {
+                values.into_iter().flat_map(|v| v.encode()).collect()
+            }
+        }
+    }
+
+    fn field_element(self) -> T {
+        match self {
+            Value::FieldElement(v) => v,
+            _ => panic!("expected a field element after semantic checking"),
+        }
+    }
+
+    fn boolean(self) -> bool {
+        match self {
+            Value::Boolean(b) => b,
+            _ => panic!("expected a boolean after semantic checking"),
+        }
+    }
+
+    fn uint(self) -> u128 {
+        match self {
+            Value::Uint(v) => v,
+            _ => panic!("expected an unsigned integer after semantic checking"),
+        }
+    }
+
+    fn array(self) -> Vec<Value<T>> {
+        match self {
+            Value::Array(values) => values,
+            _ => panic!("expected an array after semantic checking"),
+        }
+    }
+
+    fn members(self) -> Vec<Value<T>> {
+        match self {
+            Value::Struct(values) => values,
+            _ => panic!("expected a struct after semantic checking"),
+        }
+    }
+}
+
+impl<T: Field> fmt::Display for Value<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Value::FieldElement(v) => write!(f, "{}", v),
+            Value::Boolean(b) => write!(f, "{}", b),
+            Value::Uint(v) => write!(f, "{}", v),
+            Value::Array(values) | Value::Struct(values) => write!(
+                f,
+                "[{}]",
+                values
+                    .iter()
+                    .map(|v| v.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        }
+    }
+}
+
+fn to_biguint<T: Field>(value: &T) -> BigUint {
+    BigUint::from_bytes_le(&value.into_byte_vector())
+}
+
+fn max_uint(bitwidth: usize) -> u128 {
+    u128::max_value() >> (128 - bitwidth)
+}
+
+type Frame<'ast, T> = HashMap<Identifier<'ast>, Value<T>>;
+
+struct Interpreter<'a, 'ast, T: Field> {
+    program: &'a TypedProg<'ast, T>,
+}
+
+impl<'a, 'ast, T: Field> Interpreter<'a, 'ast, T> {
+    fn call(
+        &self,
+        id: &str,
+        arguments: &Vec<TypedExpression<'ast, T>>,
+        outputs: Vec<Type>,
+        frame: &Frame<'ast, T>,
+    ) -> Result<Vec<Value<T>>, Error> {
+        let signature = Signature::new()
+            .inputs(arguments.iter().map(|a| a.get_type()).collect())
+            .outputs(outputs);
+
+        let arguments = arguments
+            .iter()
+            .map(|a| self.expression(a, frame))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        match self
+            .program
+            .functions
+            .iter()
+            .find(|f| f.id == id && f.signature == signature)
+        {
+            Some(function) => self.call_typed(function, arguments),
+            None => self.call_imported(id, signature, arguments),
+        }
+    }
+
+    fn call_typed(
+        &self,
+        function: &TypedFunction<'ast, T>,
+        arguments: Vec<Value<T>>,
+    ) -> Result<Vec<Value<T>>, Error> {
+        let mut frame = function
+            .arguments
+            .iter()
+            .map(|p| p.id.id.clone())
+            .zip(arguments)
+            .collect();
+
+        Ok(self
+            .statements(&function.statements, &mut frame)?
+            .expect("a function should return after semantic checking"))
+    }
+
+    /// Imported functions are already compiled, so we execute them as programs
+    fn call_imported(
+        &self,
+        id: &str,
+        signature: Signature,
+        arguments: Vec<Value<T>>,
+    ) -> Result<Vec<Value<T>>, Error> {
+        let function = self
+            .program
+            .imported_functions
+            .iter()
+            .find(|f| f.id == id && f.signature == signature)
+            .expect("a called function should exist after semantic checking");
+
+        let program = ir::Prog {
+            main: ir::Function::from(function.clone()),
+            private: vec![true; function.arguments.len()],
+            signature: signature.clone(),
+        };
+
+        let inputs: Vec<T> = arguments.into_iter().flat_map(|a| a.encode()).collect();
+        let mut outputs = program
+            .execute(&inputs)
+            .map_err(Error::Imported)?
+            .return_values()
+            .into_iter();
+
+        signature
+            .outputs
+            .iter()
+            .map(|ty| Value::decode(ty, &mut outputs))
+            .collect()
+    }
+
+    /// Executes statements, returning the values of the return statement if one is reached
+    fn statements(
+        &self,
+        statements: &Vec<TypedStatement<'ast, T>>,
+        frame: &mut Frame<'ast, T>,
+    ) -> Result<Option<Vec<Value<T>>>, Error> {
+        for statement in statements {
+            match statement {
+                TypedStatement::Return(expressions) => {
+                    return expressions
+                        .iter()
+                        .map(|e| self.expression(e, frame))
+                        .collect::<Result<_, _>>()
+                        .map(Some);
+                }
+                TypedStatement::Definition(assignee, expression) => {
+                    let value = self.expression(expression, frame)?;
+                    self.assign(assignee, value, frame)?;
+                }
+                TypedStatement::Declaration(..) | TypedStatement::Marker(..) => {}
+                TypedStatement::Condition(left, right, position) => {
+                    let left = self.expression(left, frame)?;
+                    let right = self.expression(right, frame)?;
+                    if left != right {
+                        return Err(Error::UnsatisfiedAssertion {
+                            left: left.to_string(),
+                            right: right.to_string(),
+                            position: *position,
+                        });
+                    }
+                }
+                TypedStatement::For(variable, from, to, body) => {
+                    let mut i = from.clone();
+                    while to_biguint(&i) < to_biguint(to) {
+                        frame.insert(variable.id.clone(), Value::FieldElement(i.clone()));
+                        if let Some(values) = self.statements(body, frame)? {
+                            return Ok(Some(values));
+                        }
+                        i = i + T::one();
+                    }
+                }
+                TypedStatement::MultipleDefinition(variables, rhs) => match rhs {
+                    TypedExpressionList::FunctionCall(id, arguments, types) => {
+                        let values = self.call(id, arguments, types.clone(), frame)?;
+                        for (variable, value) in variables.iter().zip(values) {
+                            frame.insert(variable.id.clone(), value);
+                        }
+                    }
+                },
+            }
+        }
+
+        Ok(None)
+    }
+
+    fn assign(
+        &self,
+        assignee: &TypedAssignee<'ast, T>,
+        value: Value<T>,
+        frame: &mut Frame<'ast, T>,
+    ) -> Result<(), Error> {
+        match assignee {
+            TypedAssignee::Identifier(variable) => {
+                frame.insert(variable.id.clone(), value);
+                Ok(())
+            }
+            TypedAssignee::ArrayElement(array, index) => {
+                let mut values = self.assignee(array, frame)?.array();
+                let index = self.index(self.field_element(index, frame)?, values.len())?;
+                values[index] = value;
+                self.assign(array, Value::Array(values), frame)
+            }
+        }
+    }
+
+    fn assignee(
+        &self,
+        assignee: &TypedAssignee<'ast, T>,
+        frame: &Frame<'ast, T>,
+    ) -> Result<Value<T>, Error> {
+        match assignee {
+            TypedAssignee::Identifier(variable) => Ok(self.identifier(&variable.id, frame)),
+            TypedAssignee::ArrayElement(array, index) => {
+                let values = self.assignee(array, frame)?.array();
+                let index = self.index(self.field_element(index, frame)?, values.len())?;
+                Ok(values.into_iter().nth(index).unwrap())
+            }
+        }
+    }
+
+    fn identifier(&self, id: &Identifier<'ast>, frame: &Frame<'ast, T>) -> Value<T> {
+        frame
+            .get(id)
+            .cloned()
+            .expect("a variable should be defined before it is used after semantic checking")
+    }
+
+    /// Checks that `index` is within an array of size `size`, like the compiled program does
+    fn index(&self, index: T, size: usize) -> Result<usize, Error> {
+        match index.to_dec_string().parse() {
+            Ok(i) if i < size => Ok(i),
+            _ => Err(Error::OutOfBounds {
+                index: index.to_dec_string(),
+                size,
+            }),
+        }
+    }
+
+    fn select(
+        &self,
+        array: &ArrayExpression<'ast, T>,
+        index: &FieldElementExpression<'ast, T>,
+        frame: &Frame<'ast, T>,
+    ) -> Result<Value<T>, Error> {
+        let values = self.array(array, frame)?;
+        let index = self.index(self.field_element(index, frame)?, values.len())?;
+        Ok(values.into_iter().nth(index).unwrap())
+    }
+
+    fn member(
+        &self,
+        s: &StructExpression<'ast, T>,
+        id: &MemberId,
+        frame: &Frame<'ast, T>,
+    ) -> Result<Value<T>, Error> {
+        let index = s
+            .ty()
+            .iter()
+            .position(|m| m.id == *id)
+            .expect("a struct should have the accessed member after semantic checking");
+        Ok(self.structure(s, frame)?.into_iter().nth(index).unwrap())
+    }
+
+    /// Evaluates both branches, so that errors in either of them are reported like in the compiled
+    /// program
+    fn if_else<E, F: Fn(&E) -> Result<Value<T>, Error>>(
+        &self,
+        condition: &BooleanExpression<'ast, T>,
+        consequence: &E,
+        alternative: &E,
+        evaluate: F,
+        frame: &Frame<'ast, T>,
+    ) -> Result<Value<T>, Error> {
+        let condition = self.boolean(condition, frame)?;
+        let consequence = evaluate(consequence)?;
+        let alternative = evaluate(alternative)?;
+        Ok(if condition { consequence } else { alternative })
+    }
+
+    fn single_output(
+        &self,
+        id: &str,
+        arguments: &Vec<TypedExpression<'ast, T>>,
+        ty: Type,
+        frame: &Frame<'ast, T>,
+    ) -> Result<Value<T>, Error> {
+        Ok(self
+            .call(id, arguments, vec![ty], frame)?
+            .into_iter()
+            .next()
+            .unwrap())
+    }
+
+    fn expression(
+        &self,
+        e: &TypedExpression<'ast, T>,
+        frame: &Frame<'ast, T>,
+    ) -> Result<Value<T>, Error> {
+        match e {
+            TypedExpression::FieldElement(e) => {
+                self.field_element(e, frame).map(Value::FieldElement)
+            }
+            TypedExpression::Boolean(e) => self.boolean(e, frame).map(Value::Boolean),
+            TypedExpression::Uint(e) => self.uint(e, frame).map(Value::Uint),
+            TypedExpression::Array(e) => self.array(e, frame).map(Value::Array),
+            TypedExpression::Struct(e) => self.structure(e, frame).map(Value::Struct),
+        }
+    }
+
+    fn field_element(
+        &self,
+        e: &FieldElementExpression<'ast, T>,
+        frame: &Frame<'ast, T>,
+    ) -> Result<T, Error> {
+        match e {
+            FieldElementExpression::Number(n) => Ok(n.clone()),
+            FieldElementExpression::Identifier(id) => {
+                Ok(self.identifier(id, frame).field_element())
+            }
+            FieldElementExpression::Add(left, right) => {
+                Ok(self.field_element(left, frame)? + self.field_element(right, frame)?)
+            }
+            FieldElementExpression::Sub(left, right) => {
+                Ok(self.field_element(left, frame)? - self.field_element(right, frame)?)
+            }
+            FieldElementExpression::Mult(left, right) => {
+                Ok(self.field_element(left, frame)? * self.field_element(right, frame)?)
+            }
+            FieldElementExpression::Div(left, right) => {
+                let left = self.field_element(left, frame)?;
+                let right = self.field_element(right, frame)?;
+                match right == T::zero() {
+                    true => Err(Error::DivisionByZero),
+                    false => Ok(left / right),
+                }
+            }
+            FieldElementExpression::Pow(base, exponent) => Ok(self
+                .field_element(base, frame)?
+                .pow(self.field_element(exponent, frame)?)),
+            FieldElementExpression::IfElse(condition, consequence, alternative) => self
+                .if_else(
+                    condition,
+                    consequence,
+                    alternative,
+                    |e| self.field_element(e, frame).map(Value::FieldElement),
+                    frame,
+                )
+                .map(Value::field_element),
+            FieldElementExpression::FunctionCall(id, arguments) => self
+                .single_output(id, arguments, Type::FieldElement, frame)
+                .map(Value::field_element),
+            FieldElementExpression::Select(array, index) => {
+                self.select(array, index, frame).map(Value::field_element)
+            }
+            FieldElementExpression::Member(s, id) => {
+                self.member(s, id, frame).map(Value::field_element)
+            }
+        }
+    }
+
+    /// Compares two field elements, which like in the compiled program must fit in the bitwidth
+    /// of the field minus two bits
+    fn lt(&self, left: T, right: T) -> Result<bool, Error> {
+        let bits = T::get_required_bits() - 2;
+        let bound = BigUint::one() << bits;
+
+        let left = to_biguint(&left);
+        let right = to_biguint(&right);
+
+        for value in vec![&left, &right] {
+            if *value >= bound {
+                return Err(Error::ComparisonOutOfRange {
+                    value: value.to_string(),
+                    bits,
+                });
+            }
+        }
+
+        Ok(left < right)
+    }
+
+    fn boolean(
+        &self,
+        e: &BooleanExpression<'ast, T>,
+        frame: &Frame<'ast, T>,
+    ) -> Result<bool, Error> {
+        match e {
+            BooleanExpression::Identifier(id) => Ok(self.identifier(id, frame).boolean()),
+            BooleanExpression::Value(b) => Ok(*b),
+            BooleanExpression::Lt(left, right) => self.lt(
+                self.field_element(left, frame)?,
+                self.field_element(right, frame)?,
+            ),
+            BooleanExpression::Le(left, right) => self
+                .lt(
+                    self.field_element(right, frame)?,
+                    self.field_element(left, frame)?,
+                )
+                .map(|gt| !gt),
+            BooleanExpression::Ge(left, right) => self
+                .lt(
+                    self.field_element(left, frame)?,
+                    self.field_element(right, frame)?,
+                )
+                .map(|lt| !lt),
+            BooleanExpression::Gt(left, right) => self.lt(
+                self.field_element(right, frame)?,
+                self.field_element(left, frame)?,
+            ),
+            BooleanExpression::Eq(left, right) => {
+                Ok(self.field_element(left, frame)? == self.field_element(right, frame)?)
+            }
+            BooleanExpression::Or(left, right) => {
+                let left = self.boolean(left, frame)?;
+                let right = self.boolean(right, frame)?;
+                Ok(left || right)
+            }
+            BooleanExpression::And(left, right) => {
+                let left = self.boolean(left, frame)?;
+                let right = self.boolean(right, frame)?;
+                Ok(left && right)
+            }
+            BooleanExpression::Not(e) => Ok(!self.boolean(e, frame)?),
+            BooleanExpression::UintLt(left, right) => {
+                Ok(self.uint(left, frame)? < self.uint(right, frame)?)
+            }
+            BooleanExpression::UintLe(left, right) => {
+                Ok(self.uint(left, frame)? <= self.uint(right, frame)?)
+            }
+            BooleanExpression::UintEq(left, right) => {
+                Ok(self.uint(left, frame)? == self.uint(right, frame)?)
+            }
+            BooleanExpression::UintGe(left, right) => {
+                Ok(self.uint(left, frame)? >= self.uint(right, frame)?)
+            }
+            BooleanExpression::UintGt(left, right) => {
+                Ok(self.uint(left, frame)? > self.uint(right, frame)?)
+            }
+            BooleanExpression::IfElse(condition, consequence, alternative) => self
+                .if_else(
+                    condition,
+                    consequence,
+                    alternative,
+                    |e| self.boolean(e, frame).map(Value::Boolean),
+                    frame,
+                )
+                .map(Value::boolean),
+            BooleanExpression::Member(s, id) => self.member(s, id, frame).map(Value::boolean),
+            BooleanExpression::Select(array, index) => {
+                self.select(array, index, frame).map(Value::boolean)
+            }
+        }
+    }
+
+    fn uint(&self, e: &UExpression<'ast, T>, frame: &Frame<'ast, T>) -> Result<u128, Error> {
+        let bitwidth = e.bitwidth();
+        let max = max_uint(bitwidth);
+
+        match e.as_inner() {
+            UExpressionInner::Identifier(id) => Ok(self.identifier(id, frame).uint()),
+            UExpressionInner::Value(v) => Ok(*v),
+            UExpressionInner::Add(left, right) => Ok(self
+                .uint(left, frame)?
+                .wrapping_add(self.uint(right, frame)?)
+                & max),
+            UExpressionInner::Sub(left, right) => Ok(self
+                .uint(left, frame)?
+                .wrapping_sub(self.uint(right, frame)?)
+                & max),
+            UExpressionInner::Mult(left, right) => Ok(self
+                .uint(left, frame)?
+                .wrapping_mul(self.uint(right, frame)?)
+                & max),
+            UExpressionInner::Xor(left, right) => {
+                Ok(self.uint(left, frame)? ^ self.uint(right, frame)?)
+            }
+            UExpressionInner::And(left, right) => {
+                Ok(self.uint(left, frame)? & self.uint(right, frame)?)
+            }
+            UExpressionInner::Or(left, right) => {
+                Ok(self.uint(left, frame)? | self.uint(right, frame)?)
+            }
+            UExpressionInner::Not(e) => Ok(!self.uint(e, frame)? & max),
+            UExpressionInner::LeftShift(e, by) => {
+                let e = self.uint(e, frame)?;
+                Ok(if *by < bitwidth { (e << by) & max } else { 0 })
+            }
+            UExpressionInner::RightShift(e, by) => {
+                let e = self.uint(e, frame)?;
+                Ok(if *by < bitwidth { e >> by } else { 0 })
+            }
+            UExpressionInner::IfElse(condition, consequence, alternative) => self
+                .if_else(
+                    condition,
+                    consequence,
+                    alternative,
+                    |e| self.uint(e, frame).map(Value::Uint),
+                    frame,
+                )
+                .map(Value::uint),
+            UExpressionInner::FunctionCall(id, arguments) => self
+                .single_output(id, arguments, Type::Uint(bitwidth), frame)
+                .map(Value::uint),
+            UExpressionInner::Member(s, id) => self.member(s, id, frame).map(Value::uint),
+            UExpressionInner::Select(array, index) => {
+                self.select(array, index, frame).map(Value::uint)
+            }
+        }
+    }
+
+    /// Returns the amount to shift by, or `None` if every bit is shifted out
+    fn array(
+        &self,
+        e: &ArrayExpression<'ast, T>,
+        frame: &Frame<'ast, T>,
+    ) -> Result<Vec<Value<T>>, Error> {
+        match e.as_inner() {
+            ArrayExpressionInner::Identifier(id) => Ok(self.identifier(id, frame).array()),
+            ArrayExpressionInner::Value(values) => {
+                values.iter().map(|v| self.expression(v, frame)).collect()
+            }
+            ArrayExpressionInner::FunctionCall(id, arguments) => self
+                .single_output(id, arguments, e.get_type(), frame)
+                .map(Value::array),
+            ArrayExpressionInner::IfElse(condition, consequence, alternative) => self
+                .if_else(
+                    condition,
+                    consequence,
+                    alternative,
+                    |e| self.array(e, frame).map(Value::Array),
+                    frame,
+                )
+                .map(Value::array),
+            ArrayExpressionInner::Member(s, id) => self.member(s, id, frame).map(Value::array),
+            ArrayExpressionInner::Select(array, index) => {
+                self.select(array, index, frame).map(Value::array)
+            }
+        }
+    }
+
+    fn structure(
+        &self,
+        e: &StructExpression<'ast, T>,
+        frame: &Frame<'ast, T>,
+    ) -> Result<Vec<Value<T>>, Error> {
+        match e.as_inner() {
+            StructExpressionInner::Identifier(id) => Ok(self.identifier(id, frame).members()),
+            StructExpressionInner::Value(values) => {
+                values.iter().map(|v| self.expression(v, frame)).collect()
+            }
+            StructExpressionInner::FunctionCall(id, arguments) => self
+                .single_output(id, arguments, e.get_type(), frame)
+                .map(Value::members),
+            StructExpressionInner::IfElse(condition, consequence, alternative) => self
+                .if_else(
+                    condition,
+                    consequence,
+                    alternative,
+                    |e| self.structure(e, frame).map(Value::Struct),
+                    frame,
+                )
+                .map(Value::members),
+            StructExpressionInner::Member(s, id) => self.member(s, id, frame).map(Value::members),
+            StructExpressionInner::Select(array, index) => {
+                self.select(array, index, frame).map(Value::members)
+            }
+        }
+    }
+}
+
+#[derive(PartialEq)]
+pub enum Error {
+    UnsatisfiedAssertion {
+        left: String,
+        right: String,
+        position: Option<Position>,
+    },
+    DivisionByZero,
+    OutOfBounds {
+        index: String,
+        size: usize,
+    },
+    ComparisonOutOfRange {
+        value: String,
+        bits: usize,
+    },
+    InvalidInput {
+        value: String,
+        ty: Type,
+    },
+    WrongInputCount {
+        expected: usize,
+        received: usize,
+    },
+    Imported(ir::Error),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Error::UnsatisfiedAssertion {
+                ref left,
+                ref right,
+                ref position,
+            } => match position {
+                Some(position) => {
+                    write!(f, "Expected {} to equal {} at {}", left, right, position)
+                }
+                None => write!(f, "Expected {} to equal {}", left, right),
+            },
+            Error::DivisionByZero => write!(f, "Division by zero"),
+            Error::OutOfBounds { ref index, size } => write!(
+                f,
+                "Index {} is out of bounds for an array of size {}",
+                index, size
+            ),
+            Error::ComparisonOutOfRange { ref value, bits } => write!(
+                f,
+                "Cannot compare {}, only field elements which fit in {} bits can be compared",
+                value, bits
+            ),
+            Error::InvalidInput { ref value, ref ty } => {
+                write!(f, "Input {} is not a valid {}", value, ty)
+            }
+            Error::WrongInputCount { expected, received } => write!(
+                f,
+                "Program takes {} input{} but was passed {} value{}",
+                expected,
+                if expected == 1 { "" } else { "s" },
+                received,
+                if received == 1 { "" } else { "s" }
+            ),
+            Error::Imported(ref e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl fmt::Debug for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::compile::{check, compile, CompilationArtifacts};
+    use std::io::BufReader;
+    use zokrates_field::field::FieldPrime;
+
+    fn run(source: &str, inputs: Vec<u32>) -> ExecutionResult<FieldPrime> {
+        let typed: TypedProg<FieldPrime> = check(source, None, None).unwrap();
+        typed.execute(&inputs.into_iter().map(FieldPrime::from).collect::<Vec<_>>())
+    }
+
+    #[test]
+    fn arithmetic_and_calls() {
+        let source = r#"
+def square(field a) -> (field):
+	return a * a
+
+def main(field a, field b) -> (field, field):
+	field c = square(a) + b
+	for field i in 0..3 do
+		c = c + i
+	endfor
+	return c, a / b
+"#;
+
+        assert_eq!(
+            run(source, vec![3, 2]),
+            Ok(vec![
+                FieldPrime::from(14),
+                FieldPrime::from(3) / FieldPrime::from(2)
+            ])
+        );
+        assert_eq!(run(source, vec![3, 0]), Err(Error::DivisionByZero));
+        assert_eq!(
+            run(source, vec![3]),
+            Err(Error::WrongInputCount {
+                expected: 2,
+                received: 1
+            })
+        );
+    }
+
+    #[test]
+    fn assertion_position() {
+        let source = r#"
+def main(field a) -> (field):
+	field b = a + 1
+	b == 2
+	return b
+"#;
+
+        assert_eq!(run(source, vec![1]), Ok(vec![FieldPrime::from(2)]));
+        assert_eq!(
+            run(source, vec![2]),
+            Err(Error::UnsatisfiedAssertion {
+                left: String::from("3"),
+                right: String::from("2"),
+                position: Some(Position { line: 4, col: 2 }),
+            })
+        );
+    }
+
+    #[test]
+    fn arrays_and_structs() {
+        let source = r#"
+struct Point {
+	field x
+	field y
+}
+
+def main(Point[2] points, field i) -> (Point, bool):
+	field[2] xs = [points[0].x, points[1].x]
+	xs[i] = 42
+	return Point { x: xs[0], y: xs[1] }, points[i].y < points[1 - i].y
+"#;
+
+        assert_eq!(
+            run(source, vec![1, 2, 3, 4, 1]),
+            Ok(vec![
+                FieldPrime::from(1),
+                FieldPrime::from(42),
+                FieldPrime::from(0)
+            ])
+        );
+        assert_eq!(
+            run(source, vec![1, 2, 3, 4, 2]),
+            Err(Error::OutOfBounds {
+                index: String::from("2"),
+                size: 2
+            })
+        );
+    }
+
+    #[test]
+    fn uints() {
+        let source = r#"
+def main(u8 a, bool b) -> (u8):
+	return if b then a + 0x01 else (a << 4) ^ (a >> 1) fi
+"#;
+
+        assert_eq!(run(source, vec![255, 1]), Ok(vec![FieldPrime::from(0)]));
+        assert_eq!(run(source, vec![0x0f, 0]), Ok(vec![FieldPrime::from(0xf7)]));
+        assert_eq!(
+            run(source, vec![256, 1]),
+            Err(Error::InvalidInput {
+                value: String::from("256"),
+                ty: Type::Uint(8)
+            })
+        );
+        assert_eq!(
+            run(source, vec![1, 2]),
+            Err(Error::InvalidInput {
+                value: String::from("2"),
+                ty: Type::Boolean
+            })
+        );
+    }
+
+    #[test]
+    fn agrees_with_compiled_program() {
+        let source = r#"
+def main(field a, private field b) -> (field, bool):
+	field c = if a < b then b - a else a - b fi
+	return c * c, a == b
+"#;
+
+        let compiled: CompilationArtifacts<FieldPrime> =
+            compile(&mut BufReader::new(source.as_bytes()), None, None).unwrap();
+
+        for inputs in vec![vec![3, 5], vec![5, 3], vec![4, 4]] {
+            let expected = compiled
+                .prog()
+                .execute(
+                    &inputs
+                        .iter()
+                        .cloned()
+                        .map(FieldPrime::from)
+                        .collect::<Vec<_>>(),
+                )
+                .unwrap()
+                .return_values();
+            assert_eq!(run(source, inputs), Ok(expected));
+        }
+    }
+}
@@ -5,17 +5,27 @@
 //! @author Jacob Eberhardt <jacob.eberhardt@tu-berlin.de>
 //! @date 2017
 
+mod array;
 pub mod folder;
+mod interpreter;
 mod parameter;
+mod structs;
+mod uint;
 mod variable;
 
+pub use crate::typed_absy::array::{ArrayExpression, ArrayExpressionInner};
+pub use crate::typed_absy::interpreter::{Error, ExecutionResult};
 pub use crate::typed_absy::parameter::Parameter;
+pub use crate::typed_absy::structs::{StructExpression, StructExpressionInner};
+pub use crate::typed_absy::uint::{UExpression, UExpressionInner};
 pub use crate::typed_absy::variable::Variable;
+use crate::abi::{Abi, AbiInput};
 use crate::types::Signature;
 
 use crate::flat_absy::*;
 use crate::imports::Import;
-use crate::types::Type;
+use crate::parser::Position;
+use crate::types::{MemberId, Type};
 use std::fmt;
 use zokrates_field::field::Field;
 
@@ -62,6 +72,30 @@ pub struct TypedProg<'ast, T: Field> {
     pub imported_functions: Vec<FlatFunction<T>>,
 }
 
+impl<'ast, T: Field> TypedProg<'ast, T> {
+    /// Returns the ABI of the `main` function of this program
+    pub fn abi(&self) -> Abi {
+        let main = self
+            .functions
+            .iter()
+            .find(|f| f.id == "main")
+            .expect("a program should have a main function after semantic checking");
+
+        Abi {
+            inputs: main
+                .arguments
+                .iter()
+                .map(|p| AbiInput {
+                    name: p.id.id.id.to_string(),
+                    public: !p.private,
+                    ty: p.id.get_type(),
+                })
+                .collect(),
+            outputs: main.signature.outputs.clone(),
+        }
+    }
+}
+
 impl<'ast, T: Field> fmt::Display for TypedProg<'ast, T> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let mut res = vec![];
@@ -181,7 +215,7 @@ impl<'ast, T: Field> Typed for TypedAssignee<'ast, T> {
             TypedAssignee::ArrayElement(ref a, _) => {
                 let a_type = a.get_type();
                 match a_type {
-                    Type::FieldElementArray(_) => Type::FieldElement,
+                    Type::Array(t) => *t.ty,
                     _ => panic!("array element has to take array"),
                 }
             }
@@ -209,9 +243,17 @@ pub enum TypedStatement<'ast, T: Field> {
     Return(Vec<TypedExpression<'ast, T>>),
     Definition(TypedAssignee<'ast, T>, TypedExpression<'ast, T>),
     Declaration(Variable<'ast>),
-    Condition(TypedExpression<'ast, T>, TypedExpression<'ast, T>),
+    /// An equality check, with the position of the assertion in the source if there is one
+    Condition(
+        TypedExpression<'ast, T>,
+        TypedExpression<'ast, T>,
+        Option<Position>,
+    ),
     For(Variable<'ast>, T, T, Vec<TypedStatement<'ast, T>>),
     MultipleDefinition(Vec<Variable<'ast>>, TypedExpressionList<'ast, T>),
+    /// Marks the statements which follow as originating from the statement of a function at a
+    /// position. Only inserted when compiling for profiling
+    Marker(FunctionIdentifier<'ast>, Position),
 }
 
 impl<'ast, T: Field> fmt::Debug for TypedStatement<'ast, T> {
@@ -231,8 +273,8 @@ impl<'ast, T: Field> fmt::Debug for TypedStatement<'ast, T> {
             TypedStatement::Definition(ref lhs, ref rhs) => {
                 write!(f, "Definition({:?}, {:?})", lhs, rhs)
             }
-            TypedStatement::Condition(ref lhs, ref rhs) => {
-                write!(f, "Condition({:?}, {:?})", lhs, rhs)
+            TypedStatement::Condition(ref lhs, ref rhs, ref pos) => {
+                write!(f, "Condition({:?}, {:?}, {:?})", lhs, rhs, pos)
             }
             TypedStatement::For(ref var, ref start, ref stop, ref list) => {
                 r#try!(write!(f, "for {:?} in {:?}..{:?} do\n", var, start, stop));
@@ -244,6 +286,7 @@ impl<'ast, T: Field> fmt::Debug for TypedStatement<'ast, T> {
             TypedStatement::MultipleDefinition(ref lhs, ref rhs) => {
                 write!(f, "MultipleDefinition({:?}, {:?})", lhs, rhs)
             }
+            TypedStatement::Marker(ref id, ref pos) => write!(f, "Marker({}, {:?})", id, pos),
         }
     }
 }
@@ -263,7 +306,7 @@ impl<'ast, T: Field> fmt::Display for TypedStatement<'ast, T> {
             }
             TypedStatement::Declaration(ref var) => write!(f, "{}", var),
             TypedStatement::Definition(ref lhs, ref rhs) => write!(f, "{} = {}", lhs, rhs),
-            TypedStatement::Condition(ref lhs, ref rhs) => write!(f, "{} == {}", lhs, rhs),
+            TypedStatement::Condition(ref lhs, ref rhs, _) => write!(f, "{} == {}", lhs, rhs),
             TypedStatement::For(ref var, ref start, ref stop, ref list) => {
                 r#try!(write!(f, "for {} in {}..{} do\n", var, start, stop));
                 for l in list {
@@ -280,6 +323,7 @@ impl<'ast, T: Field> fmt::Display for TypedStatement<'ast, T> {
                 }
                 write!(f, " = {}", rhs)
             }
+            TypedStatement::Marker(ref id, ref pos) => write!(f, "// {} {}", id, pos),
         }
     }
 }
@@ -292,7 +336,9 @@ pub trait Typed {
 pub enum TypedExpression<'ast, T: Field> {
     Boolean(BooleanExpression<'ast, T>),
     FieldElement(FieldElementExpression<'ast, T>),
-    FieldElementArray(FieldElementArrayExpression<'ast, T>),
+    Array(ArrayExpression<'ast, T>),
+    Uint(UExpression<'ast, T>),
+    Struct(StructExpression<'ast, T>),
 }
 
 impl<'ast, T: Field> From<BooleanExpression<'ast, T>> for TypedExpression<'ast, T> {
@@ -307,9 +353,62 @@ impl<'ast, T: Field> From<FieldElementExpression<'ast, T>> for TypedExpression<'
     }
 }
 
-impl<'ast, T: Field> From<FieldElementArrayExpression<'ast, T>> for TypedExpression<'ast, T> {
-    fn from(e: FieldElementArrayExpression<'ast, T>) -> TypedExpression<T> {
-        TypedExpression::FieldElementArray(e)
+impl<'ast, T: Field> From<ArrayExpression<'ast, T>> for TypedExpression<'ast, T> {
+    fn from(e: ArrayExpression<'ast, T>) -> TypedExpression<T> {
+        TypedExpression::Array(e)
+    }
+}
+
+impl<'ast, T: Field> From<UExpression<'ast, T>> for TypedExpression<'ast, T> {
+    fn from(e: UExpression<'ast, T>) -> TypedExpression<T> {
+        TypedExpression::Uint(e)
+    }
+}
+
+impl<'ast, T: Field> From<StructExpression<'ast, T>> for TypedExpression<'ast, T> {
+    fn from(e: StructExpression<'ast, T>) -> TypedExpression<T> {
+        TypedExpression::Struct(e)
+    }
+}
+
+impl<'ast, T: Field> TypedExpression<'ast, T> {
+    /// Builds `if condition then consequence else alternative fi`, where both branches have the same type
+    pub fn if_else(
+        condition: BooleanExpression<'ast, T>,
+        consequence: TypedExpression<'ast, T>,
+        alternative: TypedExpression<'ast, T>,
+    ) -> TypedExpression<'ast, T> {
+        match (consequence, alternative) {
+            (TypedExpression::FieldElement(c), TypedExpression::FieldElement(a)) => {
+                FieldElementExpression::IfElse(box condition, box c, box a).into()
+            }
+            (TypedExpression::Boolean(c), TypedExpression::Boolean(a)) => {
+                BooleanExpression::IfElse(box condition, box c, box a).into()
+            }
+            (TypedExpression::Uint(c), TypedExpression::Uint(a)) => {
+                let bitwidth = c.bitwidth();
+                UExpressionInner::IfElse(box condition, box c, box a)
+                    .annotate(bitwidth)
+                    .into()
+            }
+            (TypedExpression::Array(c), TypedExpression::Array(a)) => {
+                let (ty, size) = (c.inner_type().clone(), c.size());
+                ArrayExpressionInner::IfElse(box condition, box c, box a)
+                    .annotate(ty, size)
+                    .into()
+            }
+            (TypedExpression::Struct(c), TypedExpression::Struct(a)) => {
+                let ty = c.ty().clone();
+                StructExpressionInner::IfElse(box condition, box c, box a)
+                    .annotate(ty)
+                    .into()
+            }
+            (c, a) => panic!(
+                "branches of a conditional expression should have the same type, found {} and {}",
+                c.get_type(),
+                a.get_type()
+            ),
+        }
     }
 }
 
@@ -318,7 +417,9 @@ impl<'ast, T: Field> fmt::Display for TypedExpression<'ast, T> {
         match *self {
             TypedExpression::Boolean(ref e) => write!(f, "{}", e),
             TypedExpression::FieldElement(ref e) => write!(f, "{}", e),
-            TypedExpression::FieldElementArray(ref e) => write!(f, "{}", e),
+            TypedExpression::Array(ref e) => write!(f, "{}", e),
+            TypedExpression::Uint(ref e) => write!(f, "{}", e),
+            TypedExpression::Struct(ref e) => write!(f, "{}", e),
         }
     }
 }
@@ -328,7 +429,9 @@ impl<'ast, T: Field> fmt::Debug for TypedExpression<'ast, T> {
         match *self {
             TypedExpression::Boolean(ref e) => write!(f, "{:?}", e),
             TypedExpression::FieldElement(ref e) => write!(f, "{:?}", e),
-            TypedExpression::FieldElementArray(ref e) => write!(f, "{:?}", e),
+            TypedExpression::Array(ref e) => write!(f, "{:?}", e),
+            TypedExpression::Uint(ref e) => write!(f, "{:?}", e),
+            TypedExpression::Struct(ref e) => write!(f, "{:?}", e),
         }
     }
 }
@@ -338,19 +441,28 @@ impl<'ast, T: Field> Typed for TypedExpression<'ast, T> {
         match *self {
             TypedExpression::Boolean(_) => Type::Boolean,
             TypedExpression::FieldElement(_) => Type::FieldElement,
-            TypedExpression::FieldElementArray(ref e) => e.get_type(),
+            TypedExpression::Array(ref e) => e.get_type(),
+            TypedExpression::Uint(ref e) => e.get_type(),
+            TypedExpression::Struct(ref e) => e.get_type(),
         }
     }
 }
 
-impl<'ast, T: Field> Typed for FieldElementArrayExpression<'ast, T> {
+impl<'ast, T: Field> Typed for StructExpression<'ast, T> {
     fn get_type(&self) -> Type {
-        match *self {
-            FieldElementArrayExpression::Identifier(n, _) => Type::FieldElementArray(n),
-            FieldElementArrayExpression::Value(n, _) => Type::FieldElementArray(n),
-            FieldElementArrayExpression::FunctionCall(n, _, _) => Type::FieldElementArray(n),
-            FieldElementArrayExpression::IfElse(_, ref consequence, _) => consequence.get_type(),
-        }
+        Type::Struct(self.ty.clone())
+    }
+}
+
+impl<'ast, T: Field> Typed for UExpression<'ast, T> {
+    fn get_type(&self) -> Type {
+        Type::Uint(self.bitwidth)
+    }
+}
+
+impl<'ast, T: Field> Typed for ArrayExpression<'ast, T> {
+    fn get_type(&self) -> Type {
+        Type::array(self.ty.clone(), self.size)
     }
 }
 
@@ -402,9 +514,10 @@ pub enum FieldElementExpression<'ast, T: Field> {
     ),
     FunctionCall(String, Vec<TypedExpression<'ast, T>>),
     Select(
-        Box<FieldElementArrayExpression<'ast, T>>,
+        Box<ArrayExpression<'ast, T>>,
         Box<FieldElementExpression<'ast, T>>,
     ),
+    Member(Box<StructExpression<'ast, T>>, MemberId),
 }
 
 #[derive(Clone, PartialEq, Hash, Eq)]
@@ -440,30 +553,21 @@ pub enum BooleanExpression<'ast, T: Field> {
         Box<BooleanExpression<'ast, T>>,
     ),
     Not(Box<BooleanExpression<'ast, T>>),
-}
-
-// for now we store the array size in the variants
-#[derive(Clone, PartialEq, Hash, Eq)]
-pub enum FieldElementArrayExpression<'ast, T: Field> {
-    Identifier(usize, Identifier<'ast>),
-    Value(usize, Vec<FieldElementExpression<'ast, T>>),
-    FunctionCall(usize, String, Vec<TypedExpression<'ast, T>>),
+    UintLt(Box<UExpression<'ast, T>>, Box<UExpression<'ast, T>>),
+    UintLe(Box<UExpression<'ast, T>>, Box<UExpression<'ast, T>>),
+    UintEq(Box<UExpression<'ast, T>>, Box<UExpression<'ast, T>>),
+    UintGe(Box<UExpression<'ast, T>>, Box<UExpression<'ast, T>>),
+    UintGt(Box<UExpression<'ast, T>>, Box<UExpression<'ast, T>>),
     IfElse(
         Box<BooleanExpression<'ast, T>>,
-        Box<FieldElementArrayExpression<'ast, T>>,
-        Box<FieldElementArrayExpression<'ast, T>>,
+        Box<BooleanExpression<'ast, T>>,
+        Box<BooleanExpression<'ast, T>>,
+    ),
+    Member(Box<StructExpression<'ast, T>>, MemberId),
+    Select(
+        Box<ArrayExpression<'ast, T>>,
+        Box<FieldElementExpression<'ast, T>>,
     ),
-}
-
-impl<'ast, T: Field> FieldElementArrayExpression<'ast, T> {
-    pub fn size(&self) -> usize {
-        match *self {
-            FieldElementArrayExpression::Identifier(s, _)
-            | FieldElementArrayExpression::Value(s, _)
-            | FieldElementArrayExpression::FunctionCall(s, ..) => s,
-            FieldElementArrayExpression::IfElse(_, ref consequence, _) => consequence.size(),
-        }
-    }
 }
 
 impl<'ast, T: Field> fmt::Display for FieldElementExpression<'ast, T> {
@@ -494,6 +598,7 @@ impl<'ast, T: Field> fmt::Display for FieldElementExpression<'ast, T> {
                 write!(f, ")")
             }
             FieldElementExpression::Select(ref id, ref index) => write!(f, "{}[{}]", id, index),
+            FieldElementExpression::Member(ref struc, ref id) => write!(f, "{}.{}", struc, id),
         }
     }
 }
@@ -511,40 +616,18 @@ impl<'ast, T: Field> fmt::Display for BooleanExpression<'ast, T> {
             BooleanExpression::And(ref lhs, ref rhs) => write!(f, "{} && {}", lhs, rhs),
             BooleanExpression::Not(ref exp) => write!(f, "!{}", exp),
             BooleanExpression::Value(b) => write!(f, "{}", b),
-        }
-    }
-}
-
-impl<'ast, T: Field> fmt::Display for FieldElementArrayExpression<'ast, T> {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match *self {
-            FieldElementArrayExpression::Identifier(_, ref var) => write!(f, "{}", var),
-            FieldElementArrayExpression::Value(_, ref values) => write!(
+            BooleanExpression::UintLt(ref lhs, ref rhs) => write!(f, "{} < {}", lhs, rhs),
+            BooleanExpression::UintLe(ref lhs, ref rhs) => write!(f, "{} <= {}", lhs, rhs),
+            BooleanExpression::UintEq(ref lhs, ref rhs) => write!(f, "{} == {}", lhs, rhs),
+            BooleanExpression::UintGe(ref lhs, ref rhs) => write!(f, "{} >= {}", lhs, rhs),
+            BooleanExpression::UintGt(ref lhs, ref rhs) => write!(f, "{} > {}", lhs, rhs),
+            BooleanExpression::IfElse(ref condition, ref consequent, ref alternative) => write!(
                 f,
-                "[{}]",
-                values
-                    .iter()
-                    .map(|o| o.to_string())
-                    .collect::<Vec<String>>()
-                    .join(", ")
+                "if {} then {} else {} fi",
+                condition, consequent, alternative
             ),
-            FieldElementArrayExpression::FunctionCall(_, ref i, ref p) => {
-                r#try!(write!(f, "{}(", i,));
-                for (i, param) in p.iter().enumerate() {
-                    r#try!(write!(f, "{}", param));
-                    if i < p.len() - 1 {
-                        r#try!(write!(f, ", "));
-                    }
-                }
-                write!(f, ")")
-            }
-            FieldElementArrayExpression::IfElse(ref condition, ref consequent, ref alternative) => {
-                write!(
-                    f,
-                    "if {} then {} else {} fi",
-                    condition, consequent, alternative
-                )
-            }
+            BooleanExpression::Member(ref struc, ref id) => write!(f, "{}.{}", struc, id),
+            BooleanExpression::Select(ref array, ref index) => write!(f, "{}[{}]", array, index),
         }
     }
 }
@@ -582,26 +665,8 @@ impl<'ast, T: Field> fmt::Debug for FieldElementExpression<'ast, T> {
             FieldElementExpression::Select(ref id, ref index) => {
                 write!(f, "Select({:?}, {:?})", id, index)
             }
-        }
-    }
-}
-
-impl<'ast, T: Field> fmt::Debug for FieldElementArrayExpression<'ast, T> {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match *self {
-            FieldElementArrayExpression::Identifier(_, ref var) => write!(f, "{:?}", var),
-            FieldElementArrayExpression::Value(_, ref values) => write!(f, "{:?}", values),
-            FieldElementArrayExpression::FunctionCall(_, ref i, ref p) => {
-                r#try!(write!(f, "FunctionCall({:?}, (", i));
-                r#try!(f.debug_list().entries(p.iter()).finish());
-                write!(f, ")")
-            }
-            FieldElementArrayExpression::IfElse(ref condition, ref consequent, ref alternative) => {
-                write!(
-                    f,
-                    "IfElse({:?}, {:?}, {:?})",
-                    condition, consequent, alternative
-                )
+            FieldElementExpression::Member(ref struc, ref id) => {
+                write!(f, "Member({:?}, {:?})", struc, id)
             }
         }
     }
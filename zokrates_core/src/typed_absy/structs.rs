@@ -0,0 +1,115 @@
+use crate::typed_absy::*;
+use crate::types::{MemberId, StructType};
+use std::fmt;
+use zokrates_field::field::Field;
+
+/// A struct expression, annotated with its type
+#[derive(Clone, PartialEq, Hash, Eq)]
+pub struct StructExpression<'ast, T: Field> {
+    pub ty: StructType,
+    pub inner: StructExpressionInner<'ast, T>,
+}
+
+#[derive(Clone, PartialEq, Hash, Eq)]
+pub enum StructExpressionInner<'ast, T: Field> {
+    Identifier(Identifier<'ast>),
+    // the values of the members, in declaration order
+    Value(Vec<TypedExpression<'ast, T>>),
+    FunctionCall(String, Vec<TypedExpression<'ast, T>>),
+    IfElse(
+        Box<BooleanExpression<'ast, T>>,
+        Box<StructExpression<'ast, T>>,
+        Box<StructExpression<'ast, T>>,
+    ),
+    Member(Box<StructExpression<'ast, T>>, MemberId),
+    Select(
+        Box<ArrayExpression<'ast, T>>,
+        Box<FieldElementExpression<'ast, T>>,
+    ),
+}
+
+impl<'ast, T: Field> StructExpressionInner<'ast, T> {
+    pub fn annotate(self, ty: StructType) -> StructExpression<'ast, T> {
+        StructExpression { ty, inner: self }
+    }
+}
+
+impl<'ast, T: Field> StructExpression<'ast, T> {
+    pub fn ty(&self) -> &StructType {
+        &self.ty
+    }
+
+    pub fn as_inner(&self) -> &StructExpressionInner<'ast, T> {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> StructExpressionInner<'ast, T> {
+        self.inner
+    }
+}
+
+impl<'ast, T: Field> fmt::Display for StructExpression<'ast, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.inner {
+            StructExpressionInner::Identifier(ref var) => write!(f, "{}", var),
+            StructExpressionInner::Value(ref values) => write!(
+                f,
+                "{{{}}}",
+                self.ty
+                    .iter()
+                    .zip(values.iter())
+                    .map(|(member, value)| format!("{}: {}", member.id, value))
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            ),
+            StructExpressionInner::FunctionCall(ref i, ref p) => {
+                r#try!(write!(f, "{}(", i,));
+                for (i, param) in p.iter().enumerate() {
+                    r#try!(write!(f, "{}", param));
+                    if i < p.len() - 1 {
+                        r#try!(write!(f, ", "));
+                    }
+                }
+                write!(f, ")")
+            }
+            StructExpressionInner::IfElse(ref condition, ref consequent, ref alternative) => {
+                write!(
+                    f,
+                    "if {} then {} else {} fi",
+                    condition, consequent, alternative
+                )
+            }
+            StructExpressionInner::Member(ref struc, ref id) => write!(f, "{}.{}", struc, id),
+            StructExpressionInner::Select(ref array, ref index) => {
+                write!(f, "{}[{}]", array, index)
+            }
+        }
+    }
+}
+
+impl<'ast, T: Field> fmt::Debug for StructExpression<'ast, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.inner {
+            StructExpressionInner::Identifier(ref var) => write!(f, "{:?}", var),
+            StructExpressionInner::Value(ref values) => write!(f, "{:?}", values),
+            StructExpressionInner::FunctionCall(ref i, ref p) => {
+                r#try!(write!(f, "FunctionCall({:?}, (", i));
+                r#try!(f.debug_list().entries(p.iter()).finish());
+                write!(f, ")")
+            }
+            StructExpressionInner::IfElse(ref condition, ref consequent, ref alternative) => {
+                write!(
+                    f,
+                    "IfElse({:?}, {:?}, {:?})",
+                    condition, consequent, alternative
+                )
+            }
+            StructExpressionInner::Member(ref struc, ref id) => {
+                write!(f, "Member({:?}, {:?})", struc, id)
+            }
+            StructExpressionInner::Select(ref array, ref index) => {
+                write!(f, "Select({:?}, {:?})", array, index)
+            }
+        }
+    }
+}
@@ -0,0 +1,136 @@
+use crate::typed_absy::*;
+use crate::types::MemberId;
+use std::fmt;
+use zokrates_field::field::Field;
+
+/// An unsigned integer expression, annotated with its bitwidth
+#[derive(Clone, PartialEq, Hash, Eq)]
+pub struct UExpression<'ast, T: Field> {
+    pub bitwidth: usize,
+    pub inner: UExpressionInner<'ast, T>,
+}
+
+#[derive(Clone, PartialEq, Hash, Eq)]
+pub enum UExpressionInner<'ast, T: Field> {
+    Identifier(Identifier<'ast>),
+    Value(u128),
+    Add(Box<UExpression<'ast, T>>, Box<UExpression<'ast, T>>),
+    Sub(Box<UExpression<'ast, T>>, Box<UExpression<'ast, T>>),
+    Mult(Box<UExpression<'ast, T>>, Box<UExpression<'ast, T>>),
+    Xor(Box<UExpression<'ast, T>>, Box<UExpression<'ast, T>>),
+    And(Box<UExpression<'ast, T>>, Box<UExpression<'ast, T>>),
+    Or(Box<UExpression<'ast, T>>, Box<UExpression<'ast, T>>),
+    Not(Box<UExpression<'ast, T>>),
+    // shifts are by a constant amount, at most the bitwidth
+    LeftShift(Box<UExpression<'ast, T>>, usize),
+    RightShift(Box<UExpression<'ast, T>>, usize),
+    IfElse(
+        Box<BooleanExpression<'ast, T>>,
+        Box<UExpression<'ast, T>>,
+        Box<UExpression<'ast, T>>,
+    ),
+    FunctionCall(String, Vec<TypedExpression<'ast, T>>),
+    Member(Box<StructExpression<'ast, T>>, MemberId),
+    Select(
+        Box<ArrayExpression<'ast, T>>,
+        Box<FieldElementExpression<'ast, T>>,
+    ),
+}
+
+impl<'ast, T: Field> UExpressionInner<'ast, T> {
+    pub fn annotate(self, bitwidth: usize) -> UExpression<'ast, T> {
+        UExpression {
+            bitwidth,
+            inner: self,
+        }
+    }
+}
+
+impl<'ast, T: Field> UExpression<'ast, T> {
+    pub fn bitwidth(&self) -> usize {
+        self.bitwidth
+    }
+
+    pub fn as_inner(&self) -> &UExpressionInner<'ast, T> {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> UExpressionInner<'ast, T> {
+        self.inner
+    }
+}
+
+impl<'ast, T: Field> fmt::Display for UExpression<'ast, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.inner {
+            UExpressionInner::Value(ref v) => {
+                write!(f, "{:#0width$x}", v, width = 2 + self.bitwidth / 4)
+            }
+            UExpressionInner::Identifier(ref var) => write!(f, "{}", var),
+            UExpressionInner::Add(ref lhs, ref rhs) => write!(f, "({} + {})", lhs, rhs),
+            UExpressionInner::Sub(ref lhs, ref rhs) => write!(f, "({} - {})", lhs, rhs),
+            UExpressionInner::Mult(ref lhs, ref rhs) => write!(f, "({} * {})", lhs, rhs),
+            UExpressionInner::Xor(ref lhs, ref rhs) => write!(f, "({} ^ {})", lhs, rhs),
+            UExpressionInner::And(ref lhs, ref rhs) => write!(f, "({} & {})", lhs, rhs),
+            UExpressionInner::Or(ref lhs, ref rhs) => write!(f, "({} | {})", lhs, rhs),
+            UExpressionInner::Not(ref e) => write!(f, "!{}", e),
+            UExpressionInner::LeftShift(ref e, ref by) => write!(f, "({} << {})", e, by),
+            UExpressionInner::RightShift(ref e, ref by) => write!(f, "({} >> {})", e, by),
+            UExpressionInner::IfElse(ref condition, ref consequent, ref alternative) => write!(
+                f,
+                "if {} then {} else {} fi",
+                condition, consequent, alternative
+            ),
+            UExpressionInner::FunctionCall(ref i, ref p) => {
+                r#try!(write!(f, "{}(", i,));
+                for (i, param) in p.iter().enumerate() {
+                    r#try!(write!(f, "{}", param));
+                    if i < p.len() - 1 {
+                        r#try!(write!(f, ", "));
+                    }
+                }
+                write!(f, ")")
+            }
+            UExpressionInner::Member(ref struc, ref id) => write!(f, "{}.{}", struc, id),
+            UExpressionInner::Select(ref array, ref index) => write!(f, "{}[{}]", array, index),
+        }
+    }
+}
+
+impl<'ast, T: Field> fmt::Debug for UExpression<'ast, T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.inner {
+            UExpressionInner::Value(ref v) => write!(f, "U{}({})", self.bitwidth, v),
+            UExpressionInner::Identifier(ref var) => write!(f, "Ide({})", var),
+            UExpressionInner::Add(ref lhs, ref rhs) => write!(f, "Add({:?}, {:?})", lhs, rhs),
+            UExpressionInner::Sub(ref lhs, ref rhs) => write!(f, "Sub({:?}, {:?})", lhs, rhs),
+            UExpressionInner::Mult(ref lhs, ref rhs) => write!(f, "Mult({:?}, {:?})", lhs, rhs),
+            UExpressionInner::Xor(ref lhs, ref rhs) => write!(f, "Xor({:?}, {:?})", lhs, rhs),
+            UExpressionInner::And(ref lhs, ref rhs) => write!(f, "And({:?}, {:?})", lhs, rhs),
+            UExpressionInner::Or(ref lhs, ref rhs) => write!(f, "Or({:?}, {:?})", lhs, rhs),
+            UExpressionInner::Not(ref e) => write!(f, "Not({:?})", e),
+            UExpressionInner::LeftShift(ref e, ref by) => {
+                write!(f, "LeftShift({:?}, {:?})", e, by)
+            }
+            UExpressionInner::RightShift(ref e, ref by) => {
+                write!(f, "RightShift({:?}, {:?})", e, by)
+            }
+            UExpressionInner::IfElse(ref condition, ref consequent, ref alternative) => write!(
+                f,
+                "IfElse({:?}, {:?}, {:?})",
+                condition, consequent, alternative
+            ),
+            UExpressionInner::FunctionCall(ref i, ref p) => {
+                r#try!(write!(f, "FunctionCall({:?}, (", i));
+                r#try!(f.debug_list().entries(p.iter()).finish());
+                write!(f, ")")
+            }
+            UExpressionInner::Member(ref struc, ref id) => {
+                write!(f, "Member({:?}, {:?})", struc, id)
+            }
+            UExpressionInner::Select(ref array, ref index) => {
+                write!(f, "Select({:?}, {:?})", array, index)
+            }
+        }
+    }
+}
@@ -1,4 +1,3 @@
-use crate::absy;
 use crate::typed_absy::Identifier;
 use crate::types::Type;
 use std::fmt;
@@ -18,8 +17,17 @@ impl<'ast> Variable<'ast> {
         Self::with_id_and_type(id, Type::Boolean)
     }
 
+    pub fn uint(id: Identifier<'ast>, bitwidth: usize) -> Variable<'ast> {
+        Self::with_id_and_type(id, Type::Uint(bitwidth))
+    }
+
+    #[cfg(test)]
     pub fn field_array(id: Identifier<'ast>, size: usize) -> Variable<'ast> {
-        Self::with_id_and_type(id, Type::FieldElementArray(size))
+        Self::array(id, Type::FieldElement, size)
+    }
+
+    pub fn array(id: Identifier<'ast>, ty: Type, size: usize) -> Variable<'ast> {
+        Self::with_id_and_type(id, Type::array(ty, size))
     }
 
     pub fn with_id_and_type(id: Identifier<'ast>, _type: Type) -> Variable<'ast> {
@@ -42,16 +50,3 @@ impl<'ast> fmt::Debug for Variable<'ast> {
         write!(f, "Variable(type: {:?}, id: {:?})", self._type, self.id,)
     }
 }
-
-impl<'ast> From<absy::Variable<'ast>> for Variable<'ast> {
-    fn from(v: absy::Variable) -> Variable {
-        Variable::with_id_and_type(
-            Identifier {
-                id: v.id,
-                version: 0,
-                stack: vec![],
-            },
-            v._type,
-        )
-    }
-}
@@ -41,11 +41,11 @@ pub fn split<T: Field>() -> FlatProg<T> {
         .map(|index| use_variable(&mut layout, format!("o{}", index), &mut counter))
         .collect();
 
-    let helper = Helper::bits();
+    let helper = Helper::bits::<T>();
 
     let signature = Signature {
         inputs: vec![Type::FieldElement],
-        outputs: vec![Type::FieldElementArray(nbits)],
+        outputs: vec![Type::array(Type::FieldElement, nbits)],
     };
 
     let outputs = directive_outputs
@@ -62,6 +62,7 @@ pub fn split<T: Field>() -> FlatProg<T> {
             FlatStatement::Condition(
                 bit.clone(),
                 FlatExpression::Mult(box bit.clone(), box bit.clone()),
+                None,
             )
         })
         .collect();
@@ -85,6 +86,7 @@ pub fn split<T: Field>() -> FlatProg<T> {
             box FlatExpression::Identifier(FlatVariable::new(0)),
             box FlatExpression::Number(T::from(1)),
         ),
+        None,
     ));
 
     statements.insert(
@@ -220,7 +222,7 @@ mod tests {
                     (0..FieldPrime::get_required_bits())
                         .map(|i| FlatVariable::new(i + 1))
                         .collect(),
-                    Helper::bits(),
+                    Helper::bits::<FieldPrime>(),
                     vec![FlatVariable::new(0)]
                 ))
             );
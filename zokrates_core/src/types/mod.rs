@@ -1,14 +1,86 @@
 pub use crate::types::signature::Signature;
 use std::fmt;
+use std::hash::{Hash, Hasher};
 
 pub mod conversions;
 mod signature;
 
+pub type MemberId = String;
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct StructMember {
+    pub id: MemberId,
+    pub ty: Box<Type>,
+}
+
+impl StructMember {
+    pub fn new(id: MemberId, ty: Type) -> Self {
+        StructMember { id, ty: box ty }
+    }
+}
+
+/// A struct type, identified by its name: two structs with the same members are different types
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StructType {
+    pub id: String,
+    pub members: Vec<StructMember>,
+}
+
+impl StructType {
+    pub fn new(id: String, members: Vec<StructMember>) -> Self {
+        StructType { id, members }
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<StructMember> {
+        self.members.iter()
+    }
+}
+
+impl PartialEq for StructType {
+    fn eq(&self, other: &StructType) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Eq for StructType {}
+
+impl Hash for StructType {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.id.hash(state);
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ArrayType {
+    pub size: usize,
+    pub ty: Box<Type>,
+}
+
+impl ArrayType {
+    pub fn new(ty: Type, size: usize) -> Self {
+        ArrayType { size, ty: box ty }
+    }
+
+    // the innermost element type and the sizes of each dimension, outermost first
+    fn dimensions(&self) -> (&Type, Vec<usize>) {
+        match *self.ty {
+            Type::Array(ref inner) => {
+                let (ty, mut dimensions) = inner.dimensions();
+                dimensions.insert(0, self.size);
+                (ty, dimensions)
+            }
+            ref ty => (ty, vec![self.size]),
+        }
+    }
+}
+
 #[derive(Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum Type {
     FieldElement,
     Boolean,
-    FieldElementArray(usize),
+    Array(ArrayType),
+    Uint(usize),
+    Struct(StructType),
 }
 
 impl fmt::Display for Type {
@@ -16,7 +88,16 @@ impl fmt::Display for Type {
         match *self {
             Type::FieldElement => write!(f, "field"),
             Type::Boolean => write!(f, "bool"),
-            Type::FieldElementArray(size) => write!(f, "{}[{}]", Type::FieldElement, size),
+            Type::Array(ref array_type) => {
+                let (ty, dimensions) = array_type.dimensions();
+                r#try!(write!(f, "{}", ty));
+                for size in dimensions {
+                    r#try!(write!(f, "[{}]", size));
+                }
+                Ok(())
+            }
+            Type::Uint(bitwidth) => write!(f, "u{}", bitwidth),
+            Type::Struct(ref struct_type) => write!(f, "{}", struct_type.id),
         }
     }
 }
@@ -26,18 +107,37 @@ impl fmt::Debug for Type {
         match *self {
             Type::FieldElement => write!(f, "field"),
             Type::Boolean => write!(f, "bool"),
-            Type::FieldElementArray(size) => write!(f, "{}[{}]", Type::FieldElement, size),
+            Type::Array(..) => write!(f, "{}", self),
+            Type::Uint(bitwidth) => write!(f, "u{}", bitwidth),
+            Type::Struct(ref struct_type) => write!(
+                f,
+                "{} {{{}}}",
+                struct_type.id,
+                struct_type
+                    .iter()
+                    .map(|m| format!("{}: {}", m.id, m.ty))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
         }
     }
 }
 
 impl Type {
+    pub fn array(ty: Type, size: usize) -> Self {
+        Type::Array(ArrayType::new(ty, size))
+    }
+
     // the number of field elements the type maps to
     pub fn get_primitive_count(&self) -> usize {
         match self {
             Type::FieldElement => 1,
             Type::Boolean => 1,
-            Type::FieldElementArray(size) => size * Type::FieldElement.get_primitive_count(),
+            Type::Array(array_type) => array_type.size * array_type.ty.get_primitive_count(),
+            Type::Uint(_) => 1,
+            Type::Struct(ref struct_type) => {
+                struct_type.iter().map(|m| m.ty.get_primitive_count()).sum()
+            }
         }
     }
 
@@ -45,7 +145,15 @@ impl Type {
         match *self {
             Type::FieldElement => String::from("f"),
             Type::Boolean => String::from("b"),
-            Type::FieldElementArray(size) => format!("{}[{}]", Type::FieldElement.to_slug(), size), // TODO differentiate types?
+            Type::Array(ref array_type) => {
+                let (ty, dimensions) = array_type.dimensions();
+                dimensions
+                    .into_iter()
+                    .fold(ty.to_slug(), |acc, size| format!("{}[{}]", acc, size))
+            }
+            Type::Uint(bitwidth) => format!("u{}", bitwidth),
+            // struct types are compared by name, so their slug is their name
+            Type::Struct(ref struct_type) => format!("{{{}}}", struct_type.id),
         }
     }
 }
@@ -56,8 +164,53 @@ mod tests {
 
     #[test]
     fn array() {
-        let t = Type::FieldElementArray(42);
+        let t = Type::array(Type::FieldElement, 42);
         assert_eq!(t.get_primitive_count(), 42);
         assert_eq!(t.to_slug(), "f[42]");
     }
+
+    #[test]
+    fn multidimensional_array() {
+        // bool[2][3] is an array of 2 arrays of 3 booleans
+        let t = Type::array(Type::array(Type::Boolean, 3), 2);
+        assert_eq!(t.get_primitive_count(), 6);
+        assert_eq!(t.to_slug(), "b[2][3]");
+        assert_eq!(t.to_string(), "bool[2][3]");
+    }
+
+    #[test]
+    fn uint() {
+        let t = Type::Uint(32);
+        assert_eq!(t.get_primitive_count(), 1);
+        assert_eq!(t.to_slug(), "u32");
+    }
+
+    #[test]
+    fn struct_type() {
+        let t = Type::Struct(StructType::new(
+            "Order".into(),
+            vec![
+                StructMember::new("price".into(), Type::FieldElement),
+                StructMember::new("side".into(), Type::Boolean),
+                StructMember::new("path".into(), Type::array(Type::FieldElement, 3)),
+            ],
+        ));
+        assert_eq!(t.get_primitive_count(), 5);
+        assert_eq!(t.to_slug(), "{Order}");
+        assert_eq!(t.to_string(), "Order");
+        assert_eq!(
+            format!("{:?}", t),
+            "Order {price: field, side: bool, path: field[3]}"
+        );
+    }
+
+    #[test]
+    fn struct_types_are_compared_by_name() {
+        let members = vec![StructMember::new("x".into(), Type::FieldElement)];
+        let foo = Type::Struct(StructType::new("Foo".into(), members.clone()));
+        let bar = Type::Struct(StructType::new("Bar".into(), members));
+
+        assert_ne!(foo, bar);
+        assert_eq!(foo, Type::Struct(StructType::new("Foo".into(), vec![])));
+    }
 }
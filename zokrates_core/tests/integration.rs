@@ -12,6 +12,10 @@ zokrates_test! {
     assert_one,
     array_if,
     fact_up_to_4,
+    lt,
     split,
     spread_slice,
+    uint_ops,
+    structs,
+    multidim_arrays,
 }
@@ -1,7 +1,6 @@
 extern crate serde_json;
 extern crate zokrates_field;
 
-use std::io;
 use zokrates_core::compile::{compile as generic_compile, CompileErrors};
 use zokrates_core::ir;
 use zokrates_field::field::{Field, FieldPrime};
@@ -82,7 +81,8 @@ pub fn read_file(path: &str) -> String {
 }
 
 pub fn compile(code: &str) -> Result<ir::Prog<FieldPrime>, CompileErrors> {
-    generic_compile::<FieldPrime, &[u8], &[u8], io::Error>(&mut code.as_bytes(), None, None)
+    generic_compile::<FieldPrime, &[u8]>(&mut code.as_bytes(), None, None)
+        .map(|artifacts| artifacts.prog().clone())
 }
 
 macro_rules! zokrates_test {
@@ -0,0 +1,86 @@
+//! Benchmarks of the Montgomery implementation of the BN128 scalar field against the reference
+//! `BigInt` implementation. Run with
+//! `cargo bench -p zokrates_field --features bench`.
+
+#![feature(test)]
+
+extern crate test;
+
+use test::{black_box, Bencher};
+use zokrates_field::bigint::FieldPrime as BigIntFieldPrime;
+use zokrates_field::field::{Field, FieldPrime, Pow};
+
+const A: &str = "12717674712096337777352654721552646000065650461901806515903699665717959876900";
+const B: &str = "6042471409729479866150380306128222617399890671095126975526159292198160466142";
+
+macro_rules! field_benches {
+    ($module:ident, $field:ty) => {
+        mod $module {
+            use super::*;
+
+            fn operands() -> ($field, $field) {
+                (
+                    <$field>::try_from_dec_str(A).unwrap(),
+                    <$field>::try_from_dec_str(B).unwrap(),
+                )
+            }
+
+            #[bench]
+            fn add(b: &mut Bencher) {
+                let (x, y) = operands();
+                b.iter(|| black_box(x.clone()) + black_box(&y));
+            }
+
+            #[bench]
+            fn sub(b: &mut Bencher) {
+                let (x, y) = operands();
+                b.iter(|| black_box(x.clone()) - black_box(&y));
+            }
+
+            #[bench]
+            fn mul(b: &mut Bencher) {
+                let (x, y) = operands();
+                b.iter(|| black_box(x.clone()) * black_box(&y));
+            }
+
+            #[bench]
+            fn inverse_mul(b: &mut Bencher) {
+                let (x, _) = operands();
+                b.iter(|| black_box(&x).inverse_mul());
+            }
+
+            #[bench]
+            fn pow(b: &mut Bencher) {
+                let (x, _) = operands();
+                b.iter(|| black_box(x.clone()).pow(black_box(255usize)));
+            }
+
+            // a sequence of operations similar to the ones performed when computing a witness
+            #[bench]
+            fn multiply_accumulate(b: &mut Bencher) {
+                let (x, y) = operands();
+                b.iter(|| {
+                    let mut acc = <$field>::from(0);
+                    for _ in 0..100 {
+                        acc = acc * black_box(&x) + black_box(&y);
+                    }
+                    acc
+                });
+            }
+
+            #[bench]
+            fn try_from_dec_str(b: &mut Bencher) {
+                b.iter(|| <$field>::try_from_dec_str(black_box(A)).unwrap());
+            }
+
+            #[bench]
+            fn to_dec_string(b: &mut Bencher) {
+                let (x, _) = operands();
+                b.iter(|| black_box(&x).to_dec_string());
+            }
+        }
+    };
+}
+
+field_benches!(montgomery, FieldPrime);
+field_benches!(bigint, BigIntFieldPrime);
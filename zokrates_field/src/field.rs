@@ -1,33 +1,26 @@
-#![allow(deprecated)] // TODO remove when lazy_static is warning-free
-
 //
 // @file field.rs
 // @author Dennis Kuhnert <dennis.kuhnert@campus.tu-berlin.de>
 // @author Jacob Eberhardt <jacob.eberhardt@tu-berlin.de>
 // @date 2017
 
-use lazy_static::lazy_static;
-use num_bigint::{BigInt, BigUint, Sign, ToBigInt};
+use ff::{Field as FfField, PrimeField, PrimeFieldRepr};
+use num_bigint::{BigInt, BigUint, Sign};
 use num_integer::Integer;
 use num_traits::{One, Zero};
-use pairing::bn256::Bn256;
+use pairing::bls12_381::{Bls12, Fr as Bls12Fr, FrRepr as Bls12FrRepr};
+use pairing::bn256::{Bn256, Fr, FrRepr};
 use pairing::ff::ScalarEngine;
 use pairing::Engine;
+use serde::de::Error;
+use serde::{Deserializer, Serializer};
 use serde_derive::{Deserialize, Serialize};
 use std::convert::From;
 use std::fmt;
 use std::fmt::{Debug, Display};
-use std::hash::Hash;
+use std::hash::{Hash, Hasher};
 use std::ops::{Add, Div, Mul, Sub};
 
-lazy_static! {
-    static ref P: BigInt = BigInt::parse_bytes(
-        b"21888242871839275222246405745257275088548364400416034343698204186575808495617",
-        10
-    )
-    .unwrap();
-}
-
 pub trait Pow<RHS> {
     type Output;
     fn pow(self, _: RHS) -> Self::Output;
@@ -62,14 +55,12 @@ pub trait Field:
     type BellmanEngine: Engine;
 
     fn from_bellman(e: <Self::BellmanEngine as ScalarEngine>::Fr) -> Self {
-        use ff::{PrimeField, PrimeFieldRepr};
         let mut res: Vec<u8> = vec![];
         e.into_repr().write_le(&mut res).unwrap();
         Self::from_byte_vector(res)
     }
 
     fn into_bellman(self) -> <Self::BellmanEngine as ScalarEngine>::Fr {
-        use ff::PrimeField;
         let s = self.to_dec_string();
         <Self::BellmanEngine as ScalarEngine>::Fr::from_str(&s).unwrap()
     }
@@ -95,283 +86,323 @@ pub trait Field:
     fn to_compact_dec_string(&self) -> String;
 }
 
-#[derive(PartialEq, PartialOrd, Clone, Eq, Ord, Hash, Serialize, Deserialize)]
-pub struct FieldPrime {
+// the serialized form of elements is kept identical to the one of the previous `BigInt` based
+// implementation, so that compiled programs and witnesses remain readable
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "FieldPrime")]
+struct SerializedFieldPrime {
     value: BigInt,
 }
 
-impl Field for FieldPrime {
-    type BellmanEngine = Bn256;
+// implements `Field` for the scalar field of a bellman engine, stored in Montgomery form
+macro_rules! prime_field {
+    ($(#[$attr:meta])* $name:ident, $engine:ident, $fr:ident, $repr:ident) => {
+    $(#[$attr])*
+    #[derive(PartialOrd, Clone, Eq, Ord)]
+    pub struct $name {
+        value: $fr,
+    }
 
-    fn into_byte_vector(&self) -> Vec<u8> {
-        match self.value.to_biguint() {
-            Option::Some(val) => val.to_bytes_le(),
-            Option::None => panic!("Should never happen."),
+    impl $name {
+        fn modulus() -> BigUint {
+            let mut bytes = vec![];
+            $fr::char().write_le(&mut bytes).unwrap();
+            BigUint::from_bytes_le(&bytes)
         }
-    }
 
-    fn from_byte_vector(bytes: Vec<u8>) -> Self {
-        let uval = BigUint::from_bytes_le(bytes.as_slice());
-        FieldPrime {
-            value: BigInt::from_biguint(Sign::Plus, uval),
+        fn to_biguint(&self) -> BigUint {
+            let mut bytes = vec![];
+            self.value.into_repr().write_le(&mut bytes).unwrap();
+            BigUint::from_bytes_le(&bytes)
         }
-    }
 
-    fn to_dec_string(&self) -> String {
-        self.value.to_str_radix(10)
-    }
+        // `value` must be smaller than the modulus
+        fn from_biguint(value: &BigUint) -> $name {
+            let mut bytes = value.to_bytes_le();
+            bytes.resize(32, 0);
+            let mut repr = $repr::default();
+            repr.read_le(&bytes[..]).unwrap();
+            $name {
+                value: $fr::from_repr(repr).unwrap(),
+            }
+        }
 
-    fn inverse_mul(&self) -> FieldPrime {
-        let (b, s, _) = extended_euclid(&self.value, &*P);
-        assert_eq!(b, BigInt::one());
-        FieldPrime {
-            value: &s - s.div_floor(&*P) * &*P,
+        fn from_u64(num: u64) -> $name {
+            $name {
+                value: $fr::from_repr($repr::from(num)).unwrap(),
+            }
         }
-    }
-    fn min_value() -> FieldPrime {
-        FieldPrime {
-            value: ToBigInt::to_bigint(&0).unwrap(),
+
+        fn from_i64(num: i64) -> $name {
+            match num < 0 {
+                true => $name::zero() - $name::from_u64(-num as u64),
+                false => $name::from_u64(num as u64),
+            }
         }
     }
-    fn max_value() -> FieldPrime {
-        FieldPrime {
-            value: &*P - ToBigInt::to_bigint(&1).unwrap(),
+
+    impl Field for $name {
+        type BellmanEngine = $engine;
+
+        fn from_bellman(e: $fr) -> Self {
+            $name { value: e }
+        }
+
+        fn into_bellman(self) -> $fr {
+            self.value
+        }
+
+        fn into_byte_vector(&self) -> Vec<u8> {
+            self.to_biguint().to_bytes_le()
+        }
+
+        fn from_byte_vector(bytes: Vec<u8>) -> Self {
+            $name::from_biguint(&BigUint::from_bytes_le(bytes.as_slice()))
+        }
+
+        fn to_dec_string(&self) -> String {
+            self.to_biguint().to_str_radix(10)
+        }
+
+        fn inverse_mul(&self) -> $name {
+            $name {
+                value: self
+                    .value
+                    .inverse()
+                    .expect("zero has no multiplicative inverse"),
+            }
+        }
+        fn min_value() -> $name {
+            $name::zero()
+        }
+        fn max_value() -> $name {
+            $name::zero() - $name::one()
+        }
+        fn get_required_bits() -> usize {
+            $fr::NUM_BITS as usize
+        }
+        fn try_from_dec_str<'a>(s: &'a str) -> Result<Self, ()> {
+            let x = BigInt::parse_bytes(s.as_bytes(), 10).ok_or(())?;
+            let p = BigInt::from_biguint(Sign::Plus, $name::modulus());
+            let x = &x - x.div_floor(&p) * &p;
+            Ok($name::from_biguint(&x.to_biguint().unwrap()))
+        }
+        fn to_compact_dec_string(&self) -> String {
+            // values up to (p-1)/2 included are represented as positive, values between (p+1)/2 and p-1 as represented as negative by subtracting p
+            let value = self.to_biguint();
+            if value <= $name::max_value().to_biguint() / BigUint::from(2u32) {
+                value.to_str_radix(10)
+            } else {
+                format!(
+                    "(-{})",
+                    ($name::zero() - self).to_biguint().to_str_radix(10)
+                )
+            }
         }
     }
-    fn get_required_bits() -> usize {
-        (*P).bits()
+
+    impl Default for $name {
+        fn default() -> Self {
+            $name::zero()
+        }
     }
-    fn try_from_dec_str<'a>(s: &'a str) -> Result<Self, ()> {
-        let x = BigInt::parse_bytes(s.as_bytes(), 10).ok_or(())?;
-        Ok(FieldPrime {
-            value: &x - x.div_floor(&*P) * &*P,
-        })
+
+    impl PartialEq for $name {
+        fn eq(&self, other: &$name) -> bool {
+            self.value == other.value
+        }
     }
-    fn to_compact_dec_string(&self) -> String {
-        // values up to (p-1)/2 included are represented as positive, values between (p+1)/2 and p-1 as represented as negative by subtracting p
-        if self.value <= FieldPrime::max_value().value / 2 {
-            format!("{}", self.value.to_str_radix(10))
-        } else {
-            format!(
-                "({})",
-                (&self.value - (FieldPrime::max_value().value + BigInt::one())).to_str_radix(10)
-            )
+
+    impl Hash for $name {
+        fn hash<H: Hasher>(&self, state: &mut H) {
+            self.value.into_repr().as_ref().hash(state)
         }
     }
-}
 
-impl Default for FieldPrime {
-    fn default() -> Self {
-        FieldPrime {
-            value: BigInt::default(),
+    impl serde::Serialize for $name {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let serialized = SerializedFieldPrime {
+                value: BigInt::from_biguint(Sign::Plus, self.to_biguint()),
+            };
+            serde::Serialize::serialize(&serialized, serializer)
         }
     }
-}
 
-impl Display for FieldPrime {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self.value.to_str_radix(10))
+    impl<'de> serde::Deserialize<'de> for $name {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let serialized: SerializedFieldPrime = serde::Deserialize::deserialize(deserializer)?;
+            let value = serialized.value;
+            match value.to_biguint() {
+                Some(ref v) if v < &$name::modulus() => Ok($name::from_biguint(v)),
+                _ => Err(D::Error::custom(format!(
+                    "{} is not a field element",
+                    value
+                ))),
+            }
+        }
     }
-}
 
-impl Debug for FieldPrime {
-    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}", self.value.to_str_radix(10))
+    impl Display for $name {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "{}", self.to_dec_string())
+        }
     }
-}
 
-impl From<i32> for FieldPrime {
-    fn from(num: i32) -> Self {
-        let x = ToBigInt::to_bigint(&num).unwrap();
-        FieldPrime {
-            value: &x - x.div_floor(&*P) * &*P,
+    impl Debug for $name {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "{}", self.to_dec_string())
         }
     }
-}
 
-impl From<u32> for FieldPrime {
-    fn from(num: u32) -> Self {
-        let x = ToBigInt::to_bigint(&num).unwrap();
-        FieldPrime {
-            value: &x - x.div_floor(&*P) * &*P,
+    impl From<i32> for $name {
+        fn from(num: i32) -> Self {
+            $name::from_i64(num as i64)
         }
     }
-}
 
-impl From<usize> for FieldPrime {
-    fn from(num: usize) -> Self {
-        let x = ToBigInt::to_bigint(&num).unwrap();
-        FieldPrime {
-            value: &x - x.div_floor(&*P) * &*P,
+    impl From<u32> for $name {
+        fn from(num: u32) -> Self {
+            $name::from_u64(num as u64)
         }
     }
-}
 
-impl Zero for FieldPrime {
-    fn zero() -> FieldPrime {
-        FieldPrime {
-            value: ToBigInt::to_bigint(&0).unwrap(),
+    impl From<usize> for $name {
+        fn from(num: usize) -> Self {
+            $name::from_u64(num as u64)
         }
     }
-    fn is_zero(&self) -> bool {
-        self.value == ToBigInt::to_bigint(&0).unwrap()
+
+    impl Zero for $name {
+        fn zero() -> $name {
+            $name {
+                value: FfField::zero(),
+            }
+        }
+        fn is_zero(&self) -> bool {
+            self.value.is_zero()
+        }
     }
-}
 
-impl One for FieldPrime {
-    fn one() -> FieldPrime {
-        FieldPrime {
-            value: ToBigInt::to_bigint(&1).unwrap(),
+    impl One for $name {
+        fn one() -> $name {
+            $name {
+                value: FfField::one(),
+            }
         }
     }
-}
 
-impl Add<FieldPrime> for FieldPrime {
-    type Output = FieldPrime;
+    impl Add<$name> for $name {
+        type Output = $name;
 
-    fn add(self, other: FieldPrime) -> FieldPrime {
-        FieldPrime {
-            value: (self.value + other.value) % &*P,
+        fn add(self, other: $name) -> $name {
+            self + &other
         }
     }
-}
 
-impl<'a> Add<&'a FieldPrime> for FieldPrime {
-    type Output = FieldPrime;
+    impl<'a> Add<&'a $name> for $name {
+        type Output = $name;
 
-    fn add(self, other: &FieldPrime) -> FieldPrime {
-        FieldPrime {
-            value: (self.value + other.value.clone()) % &*P,
+        fn add(mut self, other: &$name) -> $name {
+            self.value.add_assign(&other.value);
+            self
         }
     }
-}
 
-impl Sub<FieldPrime> for FieldPrime {
-    type Output = FieldPrime;
+    impl Sub<$name> for $name {
+        type Output = $name;
 
-    fn sub(self, other: FieldPrime) -> FieldPrime {
-        let x = self.value - other.value;
-        FieldPrime {
-            value: &x - x.div_floor(&*P) * &*P,
+        fn sub(self, other: $name) -> $name {
+            self - &other
         }
     }
-}
 
-impl<'a> Sub<&'a FieldPrime> for FieldPrime {
-    type Output = FieldPrime;
+    impl<'a> Sub<&'a $name> for $name {
+        type Output = $name;
 
-    fn sub(self, other: &FieldPrime) -> FieldPrime {
-        let x = self.value - other.value.clone();
-        FieldPrime {
-            value: &x - x.div_floor(&*P) * &*P,
+        fn sub(mut self, other: &$name) -> $name {
+            self.value.sub_assign(&other.value);
+            self
         }
     }
-}
 
-impl Mul<FieldPrime> for FieldPrime {
-    type Output = FieldPrime;
+    impl Mul<$name> for $name {
+        type Output = $name;
 
-    fn mul(self, other: FieldPrime) -> FieldPrime {
-        FieldPrime {
-            value: (self.value * other.value) % &*P,
+        fn mul(self, other: $name) -> $name {
+            self * &other
         }
     }
-}
 
-impl<'a> Mul<&'a FieldPrime> for FieldPrime {
-    type Output = FieldPrime;
+    impl<'a> Mul<&'a $name> for $name {
+        type Output = $name;
 
-    fn mul(self, other: &FieldPrime) -> FieldPrime {
-        FieldPrime {
-            value: (self.value * other.value.clone()) % &*P,
+        fn mul(mut self, other: &$name) -> $name {
+            self.value.mul_assign(&other.value);
+            self
         }
     }
-}
 
-impl Div<FieldPrime> for FieldPrime {
-    type Output = FieldPrime;
+    impl Div<$name> for $name {
+        type Output = $name;
 
-    fn div(self, other: FieldPrime) -> FieldPrime {
-        self * other.inverse_mul()
+        fn div(self, other: $name) -> $name {
+            self * other.inverse_mul()
+        }
     }
-}
 
-impl<'a> Div<&'a FieldPrime> for FieldPrime {
-    type Output = FieldPrime;
+    impl<'a> Div<&'a $name> for $name {
+        type Output = $name;
 
-    fn div(self, other: &FieldPrime) -> FieldPrime {
-        self / other.clone()
+        fn div(self, other: &$name) -> $name {
+            self / other.clone()
+        }
     }
-}
 
-impl Pow<usize> for FieldPrime {
-    type Output = FieldPrime;
+    impl Pow<usize> for $name {
+        type Output = $name;
 
-    fn pow(self, exp: usize) -> FieldPrime {
-        let mut res = FieldPrime::from(1);
-        for _ in 0..exp {
-            res = res * &self;
+        fn pow(self, exp: usize) -> $name {
+            $name {
+                value: self.value.pow([exp as u64]),
+            }
         }
-        res
     }
-}
 
-impl Pow<FieldPrime> for FieldPrime {
-    type Output = FieldPrime;
+    impl Pow<$name> for $name {
+        type Output = $name;
 
-    fn pow(self, exp: FieldPrime) -> FieldPrime {
-        let mut res = FieldPrime::one();
-        let mut current = FieldPrime::zero();
-        loop {
-            if current >= exp {
-                return res;
-            }
-            res = res * &self;
-            current = current + FieldPrime::one();
+        fn pow(self, exp: $name) -> $name {
+            self.pow(&exp)
         }
     }
-}
 
-impl<'a> Pow<&'a FieldPrime> for FieldPrime {
-    type Output = FieldPrime;
+    impl<'a> Pow<&'a $name> for $name {
+        type Output = $name;
 
-    fn pow(self, exp: &'a FieldPrime) -> FieldPrime {
-        let mut res = FieldPrime::one();
-        let mut current = FieldPrime::zero();
-        loop {
-            if &current >= exp {
-                return res;
+        fn pow(self, exp: &'a $name) -> $name {
+            $name {
+                value: self.value.pow(exp.value.into_repr()),
             }
-            res = res * &self;
-            current = current + FieldPrime::one();
         }
     }
+    };
 }
 
-/// Calculates the gcd using an iterative implementation of the extended euclidian algorithm.
-/// Returning `(d, s, t)` so that `d = s * a + t * b`
-///
-/// # Arguments
-/// * `a` - First number as `BigInt`
-/// * `b` - Second number as `BigInt`
-fn extended_euclid(a: &BigInt, b: &BigInt) -> (BigInt, BigInt, BigInt) {
-    let (mut s, mut old_s) = (BigInt::zero(), BigInt::one());
-    let (mut t, mut old_t) = (BigInt::one(), BigInt::zero());
-    let (mut r, mut old_r) = (b.clone(), a.clone());
-    while !&r.is_zero() {
-        let quotient = &old_r / &r;
-        let tmp_r = old_r.clone();
-        old_r = r.clone();
-        r = &tmp_r - &quotient * &r;
-        let tmp_s = old_s.clone();
-        old_s = s.clone();
-        s = &tmp_s - &quotient * &s;
-        let tmp_t = old_t.clone();
-        old_t = t.clone();
-        t = &tmp_t - &quotient * &t;
-    }
-    return (old_r, old_s, old_t);
-}
+prime_field!(
+    /// An element of the BN128 scalar field, stored in Montgomery form on four 64-bit limbs
+    FieldPrime,
+    Bn256,
+    Fr,
+    FrRepr
+);
+
+prime_field!(
+    /// An element of the BLS12-381 scalar field, stored in Montgomery form on four 64-bit limbs
+    Bls12Field,
+    Bls12,
+    Bls12Fr,
+    Bls12FrRepr
+);
 
 #[cfg(test)]
 mod tests {
@@ -383,6 +414,12 @@ mod tests {
         }
     }
 
+    impl FieldPrime {
+        fn to_bigint(&self) -> BigInt {
+            BigInt::from_biguint(Sign::Plus, self.to_biguint())
+        }
+    }
+
     #[cfg(test)]
     mod field_prime {
         use super::*;
@@ -392,15 +429,17 @@ mod tests {
         fn positive_number() {
             assert_eq!(
                 "1234245612".parse::<BigInt>().unwrap(),
-                FieldPrime::from("1234245612").value
+                FieldPrime::from("1234245612").to_bigint()
             );
         }
 
         #[test]
         fn negative_number() {
             assert_eq!(
-                P.checked_sub(&"12".parse::<BigInt>().unwrap()).unwrap(),
-                FieldPrime::from("-12").value
+                BigInt::from_biguint(Sign::Plus, FieldPrime::modulus())
+                    .checked_sub(&"12".parse::<BigInt>().unwrap())
+                    .unwrap(),
+                FieldPrime::from("-12").to_bigint()
             );
         }
 
@@ -408,11 +447,11 @@ mod tests {
         fn addition() {
             assert_eq!(
                 "65484493".parse::<BigInt>().unwrap(),
-                (FieldPrime::from("65416358") + FieldPrime::from("68135")).value
+                (FieldPrime::from("65416358") + FieldPrime::from("68135")).to_bigint()
             );
             assert_eq!(
                 "65484493".parse::<BigInt>().unwrap(),
-                (FieldPrime::from("65416358") + &FieldPrime::from("68135")).value
+                (FieldPrime::from("65416358") + &FieldPrime::from("68135")).to_bigint()
             );
         }
 
@@ -420,11 +459,11 @@ mod tests {
         fn addition_negative_small() {
             assert_eq!(
                 "3".parse::<BigInt>().unwrap(),
-                (FieldPrime::from("5") + FieldPrime::from("-2")).value
+                (FieldPrime::from("5") + FieldPrime::from("-2")).to_bigint()
             );
             assert_eq!(
                 "3".parse::<BigInt>().unwrap(),
-                (FieldPrime::from("5") + &FieldPrime::from("-2")).value
+                (FieldPrime::from("5") + &FieldPrime::from("-2")).to_bigint()
             );
         }
 
@@ -432,11 +471,11 @@ mod tests {
         fn addition_negative() {
             assert_eq!(
                 "65348223".parse::<BigInt>().unwrap(),
-                (FieldPrime::from("65416358") + FieldPrime::from("-68135")).value
+                (FieldPrime::from("65416358") + FieldPrime::from("-68135")).to_bigint()
             );
             assert_eq!(
                 "65348223".parse::<BigInt>().unwrap(),
-                (FieldPrime::from("65416358") + &FieldPrime::from("-68135")).value
+                (FieldPrime::from("65416358") + &FieldPrime::from("-68135")).to_bigint()
             );
         }
 
@@ -444,11 +483,11 @@ mod tests {
         fn subtraction() {
             assert_eq!(
                 "65348223".parse::<BigInt>().unwrap(),
-                (FieldPrime::from("65416358") - FieldPrime::from("68135")).value
+                (FieldPrime::from("65416358") - FieldPrime::from("68135")).to_bigint()
             );
             assert_eq!(
                 "65348223".parse::<BigInt>().unwrap(),
-                (FieldPrime::from("65416358") - &FieldPrime::from("68135")).value
+                (FieldPrime::from("65416358") - &FieldPrime::from("68135")).to_bigint()
             );
         }
 
@@ -456,11 +495,11 @@ mod tests {
         fn subtraction_negative() {
             assert_eq!(
                 "65484493".parse::<BigInt>().unwrap(),
-                (FieldPrime::from("65416358") - FieldPrime::from("-68135")).value
+                (FieldPrime::from("65416358") - FieldPrime::from("-68135")).to_bigint()
             );
             assert_eq!(
                 "65484493".parse::<BigInt>().unwrap(),
-                (FieldPrime::from("65416358") - &FieldPrime::from("-68135")).value
+                (FieldPrime::from("65416358") - &FieldPrime::from("-68135")).to_bigint()
             );
         }
 
@@ -470,13 +509,13 @@ mod tests {
                 "21888242871839275222246405745257275088548364400416034343698204186575743147394"
                     .parse::<BigInt>()
                     .unwrap(),
-                (FieldPrime::from("68135") - FieldPrime::from("65416358")).value
+                (FieldPrime::from("68135") - FieldPrime::from("65416358")).to_bigint()
             );
             assert_eq!(
                 "21888242871839275222246405745257275088548364400416034343698204186575743147394"
                     .parse::<BigInt>()
                     .unwrap(),
-                (FieldPrime::from("68135") - &FieldPrime::from("65416358")).value
+                (FieldPrime::from("68135") - &FieldPrime::from("65416358")).to_bigint()
             );
         }
 
@@ -484,11 +523,11 @@ mod tests {
         fn multiplication() {
             assert_eq!(
                 "13472".parse::<BigInt>().unwrap(),
-                (FieldPrime::from("32") * FieldPrime::from("421")).value
+                (FieldPrime::from("32") * FieldPrime::from("421")).to_bigint()
             );
             assert_eq!(
                 "13472".parse::<BigInt>().unwrap(),
-                (FieldPrime::from("32") * &FieldPrime::from("421")).value
+                (FieldPrime::from("32") * &FieldPrime::from("421")).to_bigint()
             );
         }
 
@@ -498,13 +537,13 @@ mod tests {
                 "21888242871839275222246405745257275088548364400416034343698204186575808014369"
                     .parse::<BigInt>()
                     .unwrap(),
-                (FieldPrime::from("54") * FieldPrime::from("-8912")).value
+                (FieldPrime::from("54") * FieldPrime::from("-8912")).to_bigint()
             );
             assert_eq!(
                 "21888242871839275222246405745257275088548364400416034343698204186575808014369"
                     .parse::<BigInt>()
                     .unwrap(),
-                (FieldPrime::from("54") * &FieldPrime::from("-8912")).value
+                (FieldPrime::from("54") * &FieldPrime::from("-8912")).to_bigint()
             );
         }
 
@@ -512,11 +551,11 @@ mod tests {
         fn multiplication_two_negative() {
             assert_eq!(
                 "648".parse::<BigInt>().unwrap(),
-                (FieldPrime::from("-54") * FieldPrime::from("-12")).value
+                (FieldPrime::from("-54") * FieldPrime::from("-12")).to_bigint()
             );
             assert_eq!(
                 "648".parse::<BigInt>().unwrap(),
-                (FieldPrime::from("-54") * &FieldPrime::from("-12")).value
+                (FieldPrime::from("-54") * &FieldPrime::from("-12")).to_bigint()
             );
         }
 
@@ -529,7 +568,7 @@ mod tests {
                 (FieldPrime::from(
                     "21888242871839225222246405785257275088694311157297823662689037894645225727"
                 ) * FieldPrime::from("218882428715392752222464057432572755886923"))
-                .value
+                .to_bigint()
             );
             assert_eq!(
                 "6042471409729479866150380306128222617399890671095126975526159292198160466142"
@@ -538,7 +577,7 @@ mod tests {
                 (FieldPrime::from(
                     "21888242871839225222246405785257275088694311157297823662689037894645225727"
                 ) * &FieldPrime::from("218882428715392752222464057432572755886923"))
-                    .value
+                    .to_bigint()
             );
         }
 
@@ -570,11 +609,11 @@ mod tests {
         fn pow_small() {
             assert_eq!(
                 "8".parse::<BigInt>().unwrap(),
-                (FieldPrime::from("2").pow(FieldPrime::from("3"))).value
+                (FieldPrime::from("2").pow(FieldPrime::from("3"))).to_bigint()
             );
             assert_eq!(
                 "8".parse::<BigInt>().unwrap(),
-                (FieldPrime::from("2").pow(&FieldPrime::from("3"))).value
+                (FieldPrime::from("2").pow(&FieldPrime::from("3"))).to_bigint()
             );
         }
 
@@ -582,7 +621,7 @@ mod tests {
         fn pow_usize() {
             assert_eq!(
                 "614787626176508399616".parse::<BigInt>().unwrap(),
-                (FieldPrime::from("54").pow(12)).value
+                (FieldPrime::from("54").pow(12)).to_bigint()
             );
         }
 
@@ -590,11 +629,11 @@ mod tests {
         fn pow() {
             assert_eq!(
                 "614787626176508399616".parse::<BigInt>().unwrap(),
-                (FieldPrime::from("54").pow(FieldPrime::from("12"))).value
+                (FieldPrime::from("54").pow(FieldPrime::from("12"))).to_bigint()
             );
             assert_eq!(
                 "614787626176508399616".parse::<BigInt>().unwrap(),
-                (FieldPrime::from("54").pow(&FieldPrime::from("12"))).value
+                (FieldPrime::from("54").pow(&FieldPrime::from("12"))).to_bigint()
             );
         }
 
@@ -604,13 +643,13 @@ mod tests {
                 "21888242871839275222246405745257275088548364400416034343686819230535502784513"
                     .parse::<BigInt>()
                     .unwrap(),
-                (FieldPrime::from("-54").pow(FieldPrime::from("11"))).value
+                (FieldPrime::from("-54").pow(FieldPrime::from("11"))).to_bigint()
             );
             assert_eq!(
                 "21888242871839275222246405745257275088548364400416034343686819230535502784513"
                     .parse::<BigInt>()
                     .unwrap(),
-                (FieldPrime::from("-54").pow(&FieldPrime::from("11"))).value
+                (FieldPrime::from("-54").pow(&FieldPrime::from("11"))).to_bigint()
             );
         }
 
@@ -666,72 +705,6 @@ mod tests {
         }
     }
 
-    #[test]
-    fn bigint_assertions() {
-        let x = BigInt::parse_bytes(b"65", 10).unwrap();
-        assert_eq!(&x + &x, BigInt::parse_bytes(b"130", 10).unwrap());
-        assert_eq!(
-            "1".parse::<BigInt>().unwrap(),
-            "3".parse::<BigInt>()
-                .unwrap()
-                .div_floor(&"2".parse::<BigInt>().unwrap())
-        );
-        assert_eq!(
-            "-2".parse::<BigInt>().unwrap(),
-            "-3".parse::<BigInt>()
-                .unwrap()
-                .div_floor(&"2".parse::<BigInt>().unwrap())
-        );
-    }
-
-    #[test]
-    fn test_extended_euclid() {
-        assert_eq!(
-            (
-                ToBigInt::to_bigint(&1).unwrap(),
-                ToBigInt::to_bigint(&-9).unwrap(),
-                ToBigInt::to_bigint(&47).unwrap()
-            ),
-            extended_euclid(
-                &ToBigInt::to_bigint(&120).unwrap(),
-                &ToBigInt::to_bigint(&23).unwrap()
-            )
-        );
-        assert_eq!(
-            (
-                ToBigInt::to_bigint(&2).unwrap(),
-                ToBigInt::to_bigint(&2).unwrap(),
-                ToBigInt::to_bigint(&-11).unwrap()
-            ),
-            extended_euclid(
-                &ToBigInt::to_bigint(&122).unwrap(),
-                &ToBigInt::to_bigint(&22).unwrap()
-            )
-        );
-        assert_eq!(
-            (
-                ToBigInt::to_bigint(&2).unwrap(),
-                ToBigInt::to_bigint(&-9).unwrap(),
-                ToBigInt::to_bigint(&47).unwrap()
-            ),
-            extended_euclid(
-                &ToBigInt::to_bigint(&240).unwrap(),
-                &ToBigInt::to_bigint(&46).unwrap()
-            )
-        );
-        let (b, s, _) = extended_euclid(&ToBigInt::to_bigint(&253).unwrap(), &*P);
-        assert_eq!(b, BigInt::one());
-        let s_field = FieldPrime {
-            value: &s - s.div_floor(&*P) * &*P,
-        };
-        assert_eq!(
-            FieldPrime::from(
-                "12717674712096337777352654721552646000065650461901806515903699665717959876900"
-            ),
-            s_field
-        );
-    }
-
     mod bellman {
         use super::*;
 
@@ -801,4 +774,137 @@ mod tests {
         }
     }
 
+    mod bls12 {
+        use super::*;
+        use bincode::{deserialize, serialize, Infinite};
+
+        extern crate rand;
+        use rand::{thread_rng, Rng};
+
+        #[test]
+        fn modulus() {
+            assert_eq!(Bls12Field::get_required_bits(), 255);
+            assert_eq!(
+                Bls12Field::max_value().to_dec_string(),
+                "52435875175126190479447740508185965837690552500527637822603658699938581184512"
+            );
+            assert_eq!(
+                Bls12Field::try_from_dec_str(
+                    "52435875175126190479447740508185965837690552500527637822603658699938581184515"
+                )
+                .unwrap(),
+                Bls12Field::from(2)
+            );
+            assert_eq!(
+                Bls12Field::max_value() + Bls12Field::one(),
+                Bls12Field::zero()
+            );
+        }
+
+        #[test]
+        fn arithmetic() {
+            assert_eq!(
+                Bls12Field::from(54) * Bls12Field::from(-8912),
+                Bls12Field::from(-481248)
+            );
+            assert_eq!(
+                Bls12Field::from(48) / Bls12Field::from(12),
+                Bls12Field::from(4)
+            );
+            assert_eq!(Bls12Field::from(-1).to_compact_dec_string(), "(-1)");
+        }
+
+        #[test]
+        fn bellman() {
+            let rng = &mut thread_rng();
+            for _ in 0..100 {
+                let a: Bls12Fr = rng.gen();
+                assert_eq!(Bls12Field::from_bellman(a).into_bellman(), a);
+            }
+        }
+
+        #[test]
+        fn serde_ser_deser() {
+            let serialized = &serialize(&Bls12Field::from(-11), Infinite).unwrap();
+            let deserialized: Bls12Field = deserialize(serialized).unwrap();
+            assert_eq!(Bls12Field::from(-11), deserialized);
+        }
+    }
+
+    mod bigint {
+        use super::*;
+        use crate::bigint::FieldPrime as BigIntFieldPrime;
+        use bincode::{serialize, Infinite};
+
+        extern crate rand;
+        use pairing::bn256::Fr;
+        use rand::{thread_rng, Rng};
+
+        // a random element of both implementations, with the same value
+        fn random() -> (FieldPrime, BigIntFieldPrime) {
+            let a = FieldPrime::from_bellman(thread_rng().gen::<Fr>());
+            let b = BigIntFieldPrime::try_from_dec_str(&a.to_dec_string()).unwrap();
+            (a, b)
+        }
+
+        #[test]
+        fn arithmetic() {
+            for _ in 0..100 {
+                let (a, a_ref) = random();
+                let (b, b_ref) = random();
+
+                let sum = a.clone() + &b;
+                let difference = a.clone() - &b;
+                let product = a.clone() * &b;
+                let quotient = a.clone() / &b;
+                let power = a.clone().pow(17);
+
+                assert_eq!(
+                    sum.to_dec_string(),
+                    (a_ref.clone() + &b_ref).to_dec_string()
+                );
+                assert_eq!(
+                    difference.to_dec_string(),
+                    (a_ref.clone() - &b_ref).to_dec_string()
+                );
+                assert_eq!(
+                    product.to_dec_string(),
+                    (a_ref.clone() * &b_ref).to_dec_string()
+                );
+                assert_eq!(
+                    quotient.to_dec_string(),
+                    (a_ref.clone() / &b_ref).to_dec_string()
+                );
+                assert_eq!(power.to_dec_string(), a_ref.pow(17).to_dec_string());
+            }
+        }
+
+        #[test]
+        fn representation() {
+            for _ in 0..100 {
+                let (a, a_ref) = random();
+
+                assert_eq!(a.to_compact_dec_string(), a_ref.to_compact_dec_string());
+                assert_eq!(a.into_byte_vector(), a_ref.into_byte_vector());
+                assert_eq!(
+                    serialize(&a, Infinite).unwrap(),
+                    serialize(&a_ref, Infinite).unwrap()
+                );
+                assert_eq!(
+                    serde_json::to_string(&a).unwrap(),
+                    serde_json::to_string(&a_ref).unwrap()
+                );
+            }
+        }
+
+        #[test]
+        fn ordering() {
+            for _ in 0..100 {
+                let (a, a_ref) = random();
+                let (b, b_ref) = random();
+
+                assert_eq!(a.cmp(&b), a_ref.cmp(&b_ref));
+            }
+        }
+    }
 }
@@ -1,4 +1,6 @@
 extern crate ff_ce as ff;
 extern crate pairing_ce as pairing;
 
+#[cfg(any(test, feature = "bench"))]
+pub mod bigint;
 pub mod field;
@@ -1,7 +1,11 @@
+extern crate zokrates_core;
+
+use std::collections::BTreeMap;
 use std::fs::File;
 use std::io;
-use std::io::BufReader;
+use std::io::{BufReader, Read};
 use std::path::{Component, PathBuf};
+use zokrates_core::imports::{Error, Resolver};
 
 const ZOKRATES_HOME: &str = &"ZOKRATES_HOME";
 
@@ -9,37 +13,101 @@ pub fn resolve(
     location: &Option<String>,
     source: &String,
 ) -> Result<(BufReader<File>, String, String), io::Error> {
-    // the fs resolver has to be provided a location, as it supports relative paths
-    match location {
-        Some(location) => resolve_with_location(location, source),
-        None => Err(io::Error::new(io::ErrorKind::Other, "No location provided")),
-    }
+    FileSystemResolver::new().open(location, source)
 }
 
-fn resolve_with_location(
-    location: &String,
-    source: &String,
-) -> Result<(BufReader<File>, String, String), io::Error> {
-    let source = PathBuf::from(source);
+/// Resolves imports from the file system
+///
+/// Paths starting with `./` or `../` are interpreted relative to the importing file. Paths
+/// starting with the name of a library, such as `utils/hash.code` for a library `utils`, are
+/// interpreted relative to the directory of the library. Other paths are interpreted relative to
+/// the standard library, at `$ZOKRATES_HOME` unless another directory is set.
+#[derive(Default)]
+pub struct FileSystemResolver {
+    home: Option<PathBuf>,
+    libraries: BTreeMap<String, PathBuf>,
+}
 
-    // paths starting with `./` or `../` are interpreted relative to the current file
-    // other paths `abc/def.code` are interpreted relative to $ZOKRATES_HOME
-    let base = match source.components().next() {
-        Some(Component::CurDir) | Some(Component::ParentDir) => PathBuf::from(location),
-        _ => PathBuf::from(
-            std::env::var(ZOKRATES_HOME).expect("$ZOKRATES_HOME is not set, please set it"),
-        ),
-    };
+impl FileSystemResolver {
+    pub fn new() -> FileSystemResolver {
+        FileSystemResolver::default()
+    }
 
-    let path = base.join(PathBuf::from(source));
+    /// Sets the directory of the standard library instead of `$ZOKRATES_HOME`
+    pub fn home<P: Into<PathBuf>>(mut self, home: P) -> Self {
+        self.home = Some(home.into());
+        self
+    }
 
-    if path.is_dir() {
-        return Err(io::Error::new(io::ErrorKind::Other, "Not a file"));
+    /// Adds a library, so that `name/path/to/file.code` is read from `directory/path/to/file.code`
+    pub fn library<S: Into<String>, P: Into<PathBuf>>(mut self, name: S, directory: P) -> Self {
+        self.libraries.insert(name.into(), directory.into());
+        self
     }
 
-    let (next_location, alias) = generate_next_parameters(&path)?;
+    fn open(
+        &self,
+        location: &Option<String>,
+        source: &String,
+    ) -> Result<(BufReader<File>, String, String), io::Error> {
+        // the fs resolver has to be provided a location, as it supports relative paths
+        match location {
+            Some(location) => self.open_with_location(location, source),
+            None => Err(io::Error::new(io::ErrorKind::Other, "No location provided")),
+        }
+    }
 
-    File::open(path).and_then(|f| Ok((BufReader::new(f), next_location, alias)))
+    fn open_with_location(
+        &self,
+        location: &String,
+        source: &String,
+    ) -> Result<(BufReader<File>, String, String), io::Error> {
+        let source = PathBuf::from(source);
+
+        let path = match source.components().next() {
+            // paths starting with `./` or `../` are interpreted relative to the current file
+            Some(Component::CurDir) | Some(Component::ParentDir) => {
+                PathBuf::from(location).join(&source)
+            }
+            Some(Component::Normal(name))
+                if self.libraries.contains_key(&*name.to_string_lossy()) =>
+            {
+                self.libraries[&*name.to_string_lossy()].join(source.strip_prefix(name).unwrap())
+            }
+            // other paths `abc/def.code` are interpreted relative to the standard library
+            _ => self
+                .home
+                .clone()
+                .unwrap_or_else(|| {
+                    PathBuf::from(
+                        std::env::var(ZOKRATES_HOME)
+                            .expect("$ZOKRATES_HOME is not set, please set it"),
+                    )
+                })
+                .join(&source),
+        };
+
+        if path.is_dir() {
+            return Err(io::Error::new(io::ErrorKind::Other, "Not a file"));
+        }
+
+        let (next_location, alias) = generate_next_parameters(&path)?;
+
+        File::open(path).and_then(|f| Ok((BufReader::new(f), next_location, alias)))
+    }
+}
+
+impl Resolver for FileSystemResolver {
+    fn resolve(
+        &self,
+        current_location: &Option<String>,
+        import_location: &String,
+    ) -> Result<(String, String, String), Error> {
+        let (mut reader, location, alias) = self.open(current_location, import_location)?;
+        let mut source = String::new();
+        reader.read_to_string(&mut source)?;
+        Ok((source, location, alias))
+    }
 }
 
 fn generate_next_parameters(path: &PathBuf) -> Result<(String, String), io::Error> {
@@ -264,4 +332,45 @@ mod tests {
             &"bar.code".to_string(),
         );
     }
+
+    #[test]
+    fn resolve_library() {
+        use std::io::Write;
+
+        // create a library folder with a code file in a subfolder
+        let library_folder = tempfile::tempdir().unwrap();
+        std::fs::create_dir(library_folder.path().join("hashes")).unwrap();
+        let file_path = library_folder.path().join("hashes").join("bar.code");
+        let mut file = File::create(file_path).unwrap();
+        writeln!(file, "<library code>").unwrap();
+
+        let resolver = FileSystemResolver::new()
+            .home("/path/to/stdlib")
+            .library("utils", library_folder.path());
+
+        let (source, location, alias) = resolver
+            .resolve(
+                &Some("/path/to/source".to_string()),
+                &"utils/hashes/bar.code".to_string(),
+            )
+            .unwrap();
+        assert_eq!(source, "<library code>\n".to_string());
+        assert_eq!(
+            location,
+            library_folder
+                .path()
+                .join("hashes")
+                .to_string_lossy()
+                .to_string()
+        );
+        assert_eq!(alias, "bar".to_string());
+
+        // other paths are still resolved from the standard library
+        assert!(resolver
+            .resolve(
+                &Some("/path/to/source".to_string()),
+                &"other/bar.code".to_string(),
+            )
+            .is_err());
+    }
 }
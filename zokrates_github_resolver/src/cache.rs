@@ -0,0 +1,183 @@
+//! Local storage of the files imported from GitHub, so that builds are reproducible and can run
+//! offline.
+//!
+//! The cache is a directory holding each downloaded file under the hash of its content. The
+//! lockfile records, for each import of a project, the commit its branch resolved to and the hash
+//! of the imported file, so that later builds import the same content. A branch is resolved once,
+//! and all the files imported from it are pinned to the same commit.
+
+use blake2_rfc_bellman_edition::blake2b::Blake2b;
+use serde_derive::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::env;
+use std::fs::{self, File};
+use std::io::{self, BufReader, Write};
+use std::path::{Path, PathBuf};
+use tempfile::NamedTempFile;
+
+/// Name of the lockfile of a project
+pub const LOCKFILE: &str = "zokrates.lock";
+
+/// Environment variable overriding the default cache directory
+const ZOKRATES_CACHE: &str = "ZOKRATES_CACHE";
+
+/// Returns the hash of `content` identifying it in the cache, as a hex string
+pub fn hash(content: &[u8]) -> String {
+    let mut hasher = Blake2b::new(32);
+    hasher.update(content);
+    hasher
+        .finalize()
+        .as_bytes()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// A directory of files, each one stored under the hash of its content
+pub struct Cache {
+    directory: PathBuf,
+}
+
+impl Cache {
+    pub fn new<P: Into<PathBuf>>(directory: P) -> Cache {
+        Cache {
+            directory: directory.into(),
+        }
+    }
+
+    /// Returns the cache at `$ZOKRATES_CACHE`, or at `~/.zokrates/cache` if it is not set
+    pub fn default_directory() -> Option<PathBuf> {
+        env::var_os(ZOKRATES_CACHE)
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".zokrates/cache")))
+    }
+
+    /// Returns the file whose content hashes to `hash`, if it is in the cache and was not
+    /// altered since it was stored
+    pub fn get(&self, hash: &str) -> Option<Vec<u8>> {
+        fs::read(self.directory.join(hash))
+            .ok()
+            .filter(|content| self::hash(content) == hash)
+    }
+
+    /// Stores `content` and returns its hash
+    pub fn insert(&self, content: &[u8]) -> io::Result<String> {
+        let hash = hash(content);
+        let path = self.directory.join(&hash);
+
+        if !path.exists() {
+            fs::create_dir_all(&self.directory)?;
+            // write to a temporary file first so that the cache never holds a partial file
+            let mut file = NamedTempFile::new_in(&self.directory)?;
+            file.write_all(content)?;
+            file.persist(&path).map_err(|e| e.error)?;
+        }
+
+        Ok(hash)
+    }
+}
+
+/// The commit and the content of an import, as recorded in the lockfile
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Locked {
+    pub commit: String,
+    pub hash: String,
+}
+
+/// The resolved GitHub imports of a project, keyed by import path
+pub struct Lockfile {
+    path: PathBuf,
+    imports: BTreeMap<String, Locked>,
+}
+
+impl Lockfile {
+    /// Reads the lockfile at `path`, which is empty if the file does not exist yet
+    pub fn read<P: Into<PathBuf>>(path: P) -> io::Result<Lockfile> {
+        let path = path.into();
+
+        let imports = match File::open(&path) {
+            Ok(file) => serde_json::from_reader(BufReader::new(file)).map_err(|e| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("Invalid lockfile {}: {}", path.display(), e),
+                )
+            })?,
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => BTreeMap::new(),
+            Err(e) => return Err(e),
+        };
+
+        Ok(Lockfile { path, imports })
+    }
+
+    pub fn get(&self, import: &str) -> Option<&Locked> {
+        self.imports.get(import)
+    }
+
+    /// Returns the commit the imports starting with `prefix`, the repository and the branch of an
+    /// import, are pinned to, so that all the files of a branch are imported from the same commit
+    pub fn commit(&self, prefix: &str) -> Option<&str> {
+        self.imports
+            .range(prefix.to_string()..)
+            .take_while(|(import, _)| import.starts_with(prefix))
+            .map(|(_, locked)| locked.commit.as_str())
+            .next()
+    }
+
+    /// Records `import` and writes the lockfile
+    pub fn insert(&mut self, import: &str, locked: Locked) -> io::Result<()> {
+        self.imports.insert(import.to_string(), locked);
+
+        let file = File::create(&self.path)?;
+        serde_json::to_writer_pretty(file, &self.imports)
+            .map_err(|e| io::Error::new(io::ErrorKind::Other, e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cache() {
+        let directory = tempfile::tempdir().unwrap();
+        let cache = Cache::new(directory.path().join("cache"));
+
+        let hash = cache.insert(b"def main() -> (field): return 1").unwrap();
+        assert_eq!(hash, self::hash(b"def main() -> (field): return 1"));
+        assert_eq!(
+            cache.get(&hash),
+            Some(b"def main() -> (field): return 1".to_vec())
+        );
+
+        // altered files are ignored
+        fs::write(directory.path().join("cache").join(&hash), "altered").unwrap();
+        assert_eq!(cache.get(&hash), None);
+    }
+
+    #[test]
+    fn lockfile() {
+        let directory = tempfile::tempdir().unwrap();
+        let path = directory.path().join(LOCKFILE);
+        let locked = Locked {
+            commit: String::from("0123456789abcdef0123456789abcdef01234567"),
+            hash: hash(b"content"),
+        };
+
+        let mut lockfile = Lockfile::read(&path).unwrap();
+        assert_eq!(lockfile.get("github.com/owner/repo/master/foo.code"), None);
+        lockfile
+            .insert("github.com/owner/repo/master/foo.code", locked.clone())
+            .unwrap();
+
+        let lockfile = Lockfile::read(&path).unwrap();
+        assert_eq!(
+            lockfile.get("github.com/owner/repo/master/foo.code"),
+            Some(&locked)
+        );
+        assert_eq!(
+            lockfile.commit("github.com/owner/repo/master/"),
+            Some(locked.commit.as_str())
+        );
+        assert_eq!(lockfile.commit("github.com/owner/repo/develop/"), None);
+    }
+}
@@ -16,12 +16,22 @@
 //! repository's `master` branch by downloading from URL:
 //! https://raw.githubusercontent.com/Zokrates/ZoKrates/master/zokrates_cli/examples/merkleTree/sha256PathProof3.code
 //!
+//! To make builds reproducible, a `Lockfile` records the commit each branch resolved to and the
+//! hash of each imported file, and a `Cache` keeps the imported files so that they are downloaded
+//! once and can be imported offline.
+//!
+
+mod cache;
+
+pub use crate::cache::{Cache, Locked, Lockfile, LOCKFILE};
 
+use crate::cache::hash;
 use reqwest;
-use std::fs::File;
-use std::io::{self, copy, BufReader};
-use std::path::PathBuf;
-use tempfile::NamedTempFile;
+use reqwest::header::{ACCEPT, USER_AGENT};
+use std::cell::RefCell;
+use std::io::{self, copy};
+use std::path::Path;
+use zokrates_core::imports::{Error, Resolver};
 
 #[cfg(test)]
 use mockito::{self, Mock};
@@ -33,35 +43,155 @@ const GITHUB_IMPORT_PREFIX: &str = "github.com/";
 #[cfg(not(test))]
 const GITHUB_URL_BASE: &str = "https://raw.githubusercontent.com";
 
-/// Resolves import from the Github.
-/// This importer needs to be provided with location since relative paths could be used inside the
+/// GitHub API URL base, used to resolve branches to commits
+#[cfg(not(test))]
+const GITHUB_API_BASE: &str = "https://api.github.com";
+
+/// Resolves imports from GitHub.
+/// This resolver needs to be provided with location since relative paths could be used inside the
 /// files that are imported from github.
-pub fn resolve(
-    location: &Option<String>,
-    path: &String,
-) -> Result<(BufReader<File>, String, String), io::Error> {
-    if let Some(location) = location {
-        let (root, repo, branch, path) = parse_input_path(&path)?;
+///
+/// By default, each import downloads the file from its branch. With a lockfile, an import is
+/// pinned to the commit its branch pointed to when it was first resolved, and to the hash of the
+/// file, so that later builds import the same content. All the files of a branch are pinned to
+/// the same commit. With a cache, each file is only downloaded once, and imports recorded in the
+/// lockfile can be resolved offline.
+#[derive(Default)]
+pub struct GithubResolver {
+    cache: Option<Cache>,
+    lockfile: Option<RefCell<Lockfile>>,
+    offline: bool,
+}
+
+impl GithubResolver {
+    pub fn new() -> GithubResolver {
+        GithubResolver::default()
+    }
+
+    pub fn cache(mut self, cache: Cache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    pub fn lockfile(mut self, lockfile: Lockfile) -> Self {
+        self.lockfile = Some(RefCell::new(lockfile));
+        self
+    }
+
+    /// Resolves imports from the cache only, failing for imports which are not in the lockfile
+    pub fn offline(mut self, offline: bool) -> Self {
+        self.offline = offline;
+        self
+    }
+
+    /// Returns the content of the file imported by `import`
+    fn fetch(&self, import: &str) -> Result<Vec<u8>, io::Error> {
+        let (root, repo, branch, path) = parse_input_path(import)?;
 
         #[cfg(not(test))]
-        let url = GITHUB_URL_BASE;
+        let (url, api) = (GITHUB_URL_BASE, GITHUB_API_BASE);
         #[cfg(test)]
-        let url = mockito::server_url();
+        let (url, api) = (mockito::server_url(), mockito::server_url());
+
+        let locked = self
+            .lockfile
+            .as_ref()
+            .and_then(|lockfile| lockfile.borrow().get(import).cloned());
+
+        if let Some(locked) = locked {
+            if let Some(content) = self.cache.as_ref().and_then(|c| c.get(&locked.hash)) {
+                return Ok(content);
+            }
 
-        let pb = download_from_github(&url, &root, &repo, &branch, &path)?;
-        let file = File::open(&pb)?;
-        let br = BufReader::new(file);
+            if self.offline {
+                return Err(io::Error::new(
+                    io::ErrorKind::Other,
+                    format!(
+                        "{} is not in the cache, it cannot be imported offline",
+                        import
+                    ),
+                ));
+            }
 
-        let alias = PathBuf::from(path.clone())
-            .as_path()
+            let content = download_from_github(&url, &root, &repo, &locked.commit, &path)?;
+
+            if hash(&content) != locked.hash {
+                return Err(io::Error::new(
+                    io::ErrorKind::Other,
+                    format!(
+                        "{} at commit {} does not match the hash in the lockfile",
+                        import, locked.commit
+                    ),
+                ));
+            }
+
+            self.store(&content)?;
+            return Ok(content);
+        }
+
+        if self.offline {
+            return Err(io::Error::new(
+                io::ErrorKind::Other,
+                format!(
+                    "{} is not in the lockfile, it cannot be imported offline",
+                    import
+                ),
+            ));
+        }
+
+        match self.lockfile {
+            Some(ref lockfile) => {
+                // files of a branch which was already resolved are imported from the same commit
+                let prefix = format!("{}{}/{}/{}/", GITHUB_IMPORT_PREFIX, root, repo, branch);
+                let commit = lockfile.borrow().commit(&prefix).map(String::from);
+                let commit = match commit {
+                    Some(commit) => commit,
+                    None => resolve_commit(&api, &root, &repo, &branch)?,
+                };
+                let content = download_from_github(&url, &root, &repo, &commit, &path)?;
+                let hash = self.store(&content)?;
+                lockfile
+                    .borrow_mut()
+                    .insert(import, Locked { commit, hash })?;
+                Ok(content)
+            }
+            None => {
+                let content = download_from_github(&url, &root, &repo, &branch, &path)?;
+                self.store(&content)?;
+                Ok(content)
+            }
+        }
+    }
+
+    /// Stores `content` in the cache if there is one, and returns its hash
+    fn store(&self, content: &[u8]) -> Result<String, io::Error> {
+        match self.cache {
+            Some(ref cache) => cache.insert(content),
+            None => Ok(hash(content)),
+        }
+    }
+}
+
+impl Resolver for GithubResolver {
+    fn resolve(
+        &self,
+        current_location: &Option<String>,
+        import_location: &String,
+    ) -> Result<(String, String, String), Error> {
+        let location = current_location
+            .as_ref()
+            .ok_or_else(|| Error::new("No location provided"))?;
+
+        let source = String::from_utf8(self.fetch(import_location)?)
+            .map_err(|_| Error::new(format!("{} is not valid UTF-8", import_location)))?;
+
+        let alias = Path::new(import_location)
             .file_stem()
             .unwrap()
             .to_string_lossy()
             .to_string();
 
-        Ok((br, location.to_owned(), alias))
-    } else {
-        Err(io::Error::new(io::ErrorKind::Other, "No location provided"))
+        Ok((source, location.to_owned(), alias))
     }
 }
 
@@ -118,7 +248,7 @@ fn download_from_github(
     repo: &str,
     branch: &str,
     path: &str,
-) -> Result<PathBuf, io::Error> {
+) -> Result<Vec<u8>, io::Error> {
     let url = format!(
         "{github}/{root}/{repo}/{branch}/{path}",
         github = github,
@@ -131,7 +261,7 @@ fn download_from_github(
     download_url(&url)
 }
 
-fn download_url(url: &str) -> Result<PathBuf, io::Error> {
+fn download_url(url: &str) -> Result<Vec<u8>, io::Error> {
     let mut response = reqwest::get(url).map_err(|e| {
         io::Error::new(
             io::ErrorKind::Other,
@@ -146,10 +276,54 @@ fn download_url(url: &str) -> Result<PathBuf, io::Error> {
         ));
     }
 
-    let (mut dest, pb) = NamedTempFile::new()?.keep()?;
-    copy(&mut response, &mut dest)?;
+    let mut content = vec![];
+    copy(&mut response, &mut content)?;
 
-    Ok(pb)
+    Ok(content)
+}
+
+/// Resolves a branch of a repository to the hash of the commit it points to
+fn resolve_commit(
+    github_api: &str,
+    root: &str,
+    repo: &str,
+    branch: &str,
+) -> Result<String, io::Error> {
+    let url = format!(
+        "{github_api}/repos/{root}/{repo}/commits/{branch}",
+        github_api = github_api,
+        root = root,
+        repo = repo,
+        branch = branch
+    );
+
+    let error = |e: String| {
+        io::Error::new(
+            io::ErrorKind::Other,
+            format!("Unable to resolve {}/{}/{}: {}", root, repo, branch, e),
+        )
+    };
+
+    // this media type makes github return the hash of the commit only
+    let mut response = reqwest::Client::new()
+        .get(&url)
+        .header(ACCEPT, "application/vnd.github.v3.sha")
+        .header(USER_AGENT, "zokrates")
+        .send()
+        .map_err(|e| error(e.to_string()))?;
+
+    if !response.status().is_success() {
+        return Err(error(response.status().to_string()));
+    }
+
+    let commit = response.text().map_err(|e| error(e.to_string()))?;
+    let commit = commit.trim();
+
+    if commit.len() != 40 || !commit.chars().all(|c| c.is_ascii_hexdigit()) {
+        return Err(error(format!("unexpected commit hash {}", commit)));
+    }
+
+    Ok(commit.to_string())
 }
 
 #[cfg(test)]
@@ -177,6 +351,30 @@ mod tests {
         (m1, m2)
     }
 
+    const COMMIT: &str = "0123456789abcdef0123456789abcdef01234567";
+
+    /// Initializes mocks resolving the `master` branch to `COMMIT` and serving `foo.code` at it
+    fn init_commit_mock() -> (Mock, Mock) {
+        let m1 = mockito::mock("GET", "/repos/Zokrates/ZoKrates/commits/master")
+            .with_status(200)
+            .with_body(COMMIT)
+            .create();
+
+        let m2 = mockito::mock(
+            "GET",
+            format!(
+                "/Zokrates/ZoKrates/{}/zokrates_cli/examples/imports/foo.code",
+                COMMIT
+            )
+            .as_str(),
+        )
+        .with_status(200)
+        .with_body_from_file("./static/foo.code")
+        .create();
+
+        (m1, m2)
+    }
+
     #[test]
     pub fn import_simple() {
         let res = parse_input_path(
@@ -209,7 +407,7 @@ mod tests {
     #[test]
     pub fn resolve_ok() {
         let (_m0, _m1) = init_github_mock();
-        let res = resolve(
+        let res = GithubResolver::new().resolve(
             &Some("".to_string()),
             &String::from(
                 "github.com/Zokrates/ZoKrates/master/zokrates_cli/examples/imports/foo.code",
@@ -221,7 +419,8 @@ mod tests {
     #[test]
     pub fn resolve_err() {
         let (_m0, _m1) = init_github_mock();
-        assert!(resolve(
+        assert!(GithubResolver::new()
+            .resolve(
             &Some("".to_string()),
             &String::from(
                 "github.com/Zokrates/ZoKrates/master/zokrates_cli/examples/imports/notfound.code"
@@ -229,4 +428,121 @@ mod tests {
         )
         .is_err());
     }
+
+    #[test]
+    pub fn lockfile_and_cache() {
+        let (_m0, _m1) = init_commit_mock();
+        let directory = tempfile::tempdir().unwrap();
+        let lockfile_path = directory.path().join(LOCKFILE);
+        let cache = || Cache::new(directory.path().join("cache"));
+        let import = String::from(
+            "github.com/Zokrates/ZoKrates/master/zokrates_cli/examples/imports/foo.code",
+        );
+        let content = std::fs::read_to_string("./static/foo.code").unwrap();
+
+        // the import is pinned to the commit of the branch
+        let resolver = GithubResolver::new()
+            .cache(cache())
+            .lockfile(Lockfile::read(&lockfile_path).unwrap());
+        let (source, _, alias) = resolver.resolve(&Some("".to_string()), &import).unwrap();
+        assert_eq!(source, content);
+        assert_eq!(alias, "foo");
+
+        let locked = Lockfile::read(&lockfile_path)
+            .unwrap()
+            .get(&import)
+            .cloned()
+            .unwrap();
+        assert_eq!(locked.commit, COMMIT);
+        assert_eq!(locked.hash, hash(content.as_bytes()));
+
+        // once locked and cached, the import resolves offline
+        let resolver = GithubResolver::new()
+            .cache(cache())
+            .lockfile(Lockfile::read(&lockfile_path).unwrap())
+            .offline(true);
+        assert_eq!(
+            resolver.resolve(&Some("".to_string()), &import).unwrap().0,
+            content
+        );
+
+        // imports which are not in the lockfile do not
+        let resolver = GithubResolver::new()
+            .cache(cache())
+            .lockfile(Lockfile::read(directory.path().join("other.lock")).unwrap())
+            .offline(true);
+        assert!(resolver.resolve(&Some("".to_string()), &import).is_err());
+    }
+
+    #[test]
+    pub fn lockfile_same_commit() {
+        // another branch than in the other tests, whose mocks would also match
+        let m0 = mockito::mock("GET", "/repos/Zokrates/ZoKrates/commits/develop")
+            .with_status(200)
+            .with_body(COMMIT)
+            .expect(1)
+            .create();
+        let _m1 = ["foo.code", "bar.code"]
+            .iter()
+            .map(|file| {
+                mockito::mock(
+                    "GET",
+                    format!(
+                        "/Zokrates/ZoKrates/{}/zokrates_cli/examples/imports/{}",
+                        COMMIT, file
+                    )
+                    .as_str(),
+                )
+                .with_status(200)
+                .with_body_from_file("./static/foo.code")
+                .create()
+            })
+            .collect::<Vec<_>>();
+        let directory = tempfile::tempdir().unwrap();
+        let lockfile_path = directory.path().join(LOCKFILE);
+        let imports = ["foo.code", "bar.code"]
+            .iter()
+            .map(|file| {
+                format!(
+                    "github.com/Zokrates/ZoKrates/develop/zokrates_cli/examples/imports/{}",
+                    file
+                )
+            })
+            .collect::<Vec<_>>();
+
+        // the branch is resolved once, and both files are pinned to its commit
+        let resolver = GithubResolver::new().lockfile(Lockfile::read(&lockfile_path).unwrap());
+        for import in &imports {
+            resolver.resolve(&Some("".to_string()), import).unwrap();
+        }
+        m0.assert();
+
+        let lockfile = Lockfile::read(&lockfile_path).unwrap();
+        for import in &imports {
+            assert_eq!(lockfile.get(import).unwrap().commit, COMMIT);
+        }
+    }
+
+    #[test]
+    pub fn lockfile_mismatch() {
+        let (_m0, _m1) = init_commit_mock();
+        let directory = tempfile::tempdir().unwrap();
+        let import = String::from(
+            "github.com/Zokrates/ZoKrates/master/zokrates_cli/examples/imports/foo.code",
+        );
+
+        let mut lockfile = Lockfile::read(directory.path().join(LOCKFILE)).unwrap();
+        lockfile
+            .insert(
+                &import,
+                Locked {
+                    commit: COMMIT.to_string(),
+                    hash: hash(b"other content"),
+                },
+            )
+            .unwrap();
+
+        let resolver = GithubResolver::new().lockfile(lockfile);
+        assert!(resolver.resolve(&Some("".to_string()), &import).is_err());
+    }
 }
@@ -113,6 +113,27 @@ mod tests {
             };
         }
 
+        #[test]
+        fn parse_from_import() {
+            parses_to! {
+                parser: ZoKratesParser,
+                input: "from \"./utils\" import hash, compress as c\n",
+                rule: Rule::from_import_directive,
+                tokens: [
+                    from_import_directive(0, 42, [
+                        import_source(6, 13),
+                        import_symbol(22, 26, [
+                            identifier(22, 26)
+                        ]),
+                        import_symbol(28, 41, [
+                            identifier(28, 36),
+                            identifier(40, 41)
+                        ])
+                    ])
+                ]
+            };
+        }
+
         #[test]
         fn parse_invalid_identifier() {
             fails_with! {
@@ -144,5 +165,49 @@ mod tests {
             let parse = ZoKratesParser::parse(Rule::iteration_statement, input);
             assert!(parse.is_ok());
         }
+
+        #[test]
+        fn parse_hex_literal() {
+            parses_to! {
+                parser: ZoKratesParser,
+                input: "0x0000002a",
+                rule: Rule::constant,
+                tokens: [
+                    constant(0, 10, [
+                        hex_literal(0, 10, [
+                            hex_number(2, 10, [
+                                hex_number_32(2, 10)
+                            ])
+                        ])
+                    ])
+                ]
+            };
+        }
+
+        #[test]
+        fn parse_call_access() {
+            let parse = |e: &str| {
+                let source = format!("def main() -> (field):\n  return {}\n", e);
+                ZoKratesParser::parse(Rule::file, &source).map(|_| ())
+            };
+
+            assert!(parse("f(1)[0].a").is_ok());
+            // only identifiers are callable
+            assert!(parse("a[0](1)").is_err());
+            assert!(parse("s.f(1)").is_err());
+            assert!(parse("f(1)(2)").is_err());
+        }
+
+        #[test]
+        fn parse_invalid_identifier_because_uint_keyword() {
+            fails_with! {
+                parser: ZoKratesParser,
+                input: "u32",
+                rule: Rule::identifier,
+                positives: vec![Rule::identifier],
+                negatives: vec![],
+                pos: 0
+            };
+        }
     }
 }
@@ -9,12 +9,14 @@ extern crate lazy_static;
 
 pub use ast::{
     Access, ArrayAccess, ArrayInitializerExpression, ArrayType, AssertionStatement, Assignee,
-    AssignmentStatement, BasicType, BinaryExpression, BinaryOperator, CallAccess,
-    ConstantExpression, DefinitionStatement, Expression, File, FromExpression, Function,
-    IdentifierExpression, ImportDirective, ImportSource, InlineArrayExpression, IterationStatement,
+    AssignmentStatement, BasicOrStructType, BasicType, BinaryExpression, BinaryOperator,
+    CallAccess, ConstantExpression, DefinitionStatement, Expression, File, FromExpression,
+    FromImportDirective, Function, HexLiteralExpression, HexNumberExpression, IdentifierExpression,
+    ImportDirective, ImportSource, ImportSymbol, InlineArrayExpression, InlineStructExpression,
+    InlineStructMember, IterationStatement, MainImportDirective, MemberAccess,
     MultiAssignmentStatement, Parameter, PostfixExpression, Range, RangeOrExpression,
-    ReturnStatement, Span, Spread, SpreadOrExpression, Statement, TernaryExpression, ToExpression,
-    Type, UnaryExpression, UnaryOperator, Visibility,
+    ReturnStatement, Span, Spread, SpreadOrExpression, Statement, StructDefinition, StructField,
+    StructType, TernaryExpression, ToExpression, Type, UnaryExpression, UnaryOperator, Visibility,
 };
 
 mod ast {
@@ -34,7 +36,6 @@ mod ast {
     fn build_precedence_climber() -> PrecClimber<Rule> {
         PrecClimber::new(vec![
             Operator::new(Rule::op_inclusive_or, Assoc::Left),
-            Operator::new(Rule::op_exclusive_or, Assoc::Left),
             Operator::new(Rule::op_and, Assoc::Left),
             Operator::new(Rule::op_equal, Assoc::Left)
                 | Operator::new(Rule::op_not_equal, Assoc::Left),
@@ -42,6 +43,11 @@ mod ast {
                 | Operator::new(Rule::op_gte, Assoc::Left)
                 | Operator::new(Rule::op_lt, Assoc::Left)
                 | Operator::new(Rule::op_gt, Assoc::Left),
+            Operator::new(Rule::op_bit_or, Assoc::Left),
+            Operator::new(Rule::op_exclusive_or, Assoc::Left),
+            Operator::new(Rule::op_bit_and, Assoc::Left),
+            Operator::new(Rule::op_left_shift, Assoc::Left)
+                | Operator::new(Rule::op_right_shift, Assoc::Left),
             Operator::new(Rule::op_add, Assoc::Left) | Operator::new(Rule::op_sub, Assoc::Left),
             Operator::new(Rule::op_mul, Assoc::Left) | Operator::new(Rule::op_div, Assoc::Left),
             Operator::new(Rule::op_pow, Assoc::Left),
@@ -75,6 +81,10 @@ mod ast {
             Rule::op_inclusive_or => Expression::binary(BinaryOperator::Or, lhs, rhs, span),
             Rule::op_exclusive_or => Expression::binary(BinaryOperator::Xor, lhs, rhs, span),
             Rule::op_and => Expression::binary(BinaryOperator::And, lhs, rhs, span),
+            Rule::op_bit_and => Expression::binary(BinaryOperator::BitAnd, lhs, rhs, span),
+            Rule::op_bit_or => Expression::binary(BinaryOperator::BitOr, lhs, rhs, span),
+            Rule::op_left_shift => Expression::binary(BinaryOperator::LeftShift, lhs, rhs, span),
+            Rule::op_right_shift => Expression::binary(BinaryOperator::RightShift, lhs, rhs, span),
             _ => unreachable!(),
         })
     }
@@ -121,6 +131,9 @@ mod ast {
                     Rule::postfix_expression => Expression::Postfix(
                         PostfixExpression::from_pest(&mut pair.into_inner()).unwrap(),
                     ),
+                    Rule::inline_struct_expression => Expression::InlineStruct(
+                        InlineStructExpression::from_pest(&mut pair.into_inner()).unwrap(),
+                    ),
                     Rule::inline_array_expression => Expression::InlineArray(
                         InlineArrayExpression::from_pest(&mut pair.into_inner()).unwrap(),
                     ),
@@ -141,7 +154,7 @@ mod ast {
                             span
                         })
                     },
-                    r => unreachable!("`term` should contain one of [`expression`, `conditional_expression`, `primary_expression`, `postfix_expression`, `inline_array_expression`, `unary_expression`, `array_initializer_expression`], found {:#?}", r)
+                    r => unreachable!("`term` should contain one of [`expression`, `conditional_expression`, `primary_expression`, `postfix_expression`, `inline_struct_expression`, `inline_array_expression`, `unary_expression`, `array_initializer_expression`], found {:#?}", r)
                 }
             }
             r => unreachable!(
@@ -155,12 +168,31 @@ mod ast {
     #[pest_ast(rule(Rule::file))]
     pub struct File<'ast> {
         pub imports: Vec<ImportDirective<'ast>>,
+        pub structs: Vec<StructDefinition<'ast>>,
         pub functions: Vec<Function<'ast>>,
         pub eoi: EOI,
         #[pest_ast(outer())]
         pub span: Span<'ast>,
     }
 
+    #[derive(Debug, FromPest, PartialEq, Clone)]
+    #[pest_ast(rule(Rule::ty_struct_definition))]
+    pub struct StructDefinition<'ast> {
+        pub id: IdentifierExpression<'ast>,
+        pub fields: Vec<StructField<'ast>>,
+        #[pest_ast(outer())]
+        pub span: Span<'ast>,
+    }
+
+    #[derive(Debug, FromPest, PartialEq, Clone)]
+    #[pest_ast(rule(Rule::struct_field))]
+    pub struct StructField<'ast> {
+        pub ty: Type<'ast>,
+        pub id: IdentifierExpression<'ast>,
+        #[pest_ast(outer())]
+        pub span: Span<'ast>,
+    }
+
     #[derive(Debug, FromPest, PartialEq, Clone)]
     #[pest_ast(rule(Rule::function_definition))]
     pub struct Function<'ast> {
@@ -174,13 +206,38 @@ mod ast {
 
     #[derive(Debug, FromPest, PartialEq, Clone)]
     #[pest_ast(rule(Rule::import_directive))]
-    pub struct ImportDirective<'ast> {
+    pub enum ImportDirective<'ast> {
+        Main(MainImportDirective<'ast>),
+        From(FromImportDirective<'ast>),
+    }
+
+    #[derive(Debug, FromPest, PartialEq, Clone)]
+    #[pest_ast(rule(Rule::main_import_directive))]
+    pub struct MainImportDirective<'ast> {
         pub source: ImportSource<'ast>,
         pub alias: Option<IdentifierExpression<'ast>>,
         #[pest_ast(outer())]
         pub span: Span<'ast>,
     }
 
+    #[derive(Debug, FromPest, PartialEq, Clone)]
+    #[pest_ast(rule(Rule::from_import_directive))]
+    pub struct FromImportDirective<'ast> {
+        pub source: ImportSource<'ast>,
+        pub symbols: Vec<ImportSymbol<'ast>>,
+        #[pest_ast(outer())]
+        pub span: Span<'ast>,
+    }
+
+    #[derive(Debug, FromPest, PartialEq, Clone)]
+    #[pest_ast(rule(Rule::import_symbol))]
+    pub struct ImportSymbol<'ast> {
+        pub id: IdentifierExpression<'ast>,
+        pub alias: Option<IdentifierExpression<'ast>>,
+        #[pest_ast(outer())]
+        pub span: Span<'ast>,
+    }
+
     #[derive(Debug, FromPest, PartialEq, Clone)]
     #[pest_ast(rule(Rule::import_source))]
     pub struct ImportSource<'ast> {
@@ -195,6 +252,7 @@ mod ast {
     pub enum Type<'ast> {
         Basic(BasicType<'ast>),
         Array(ArrayType<'ast>),
+        Struct(StructType<'ast>),
     }
 
     #[derive(Debug, FromPest, PartialEq, Clone)]
@@ -202,6 +260,10 @@ mod ast {
     pub enum BasicType<'ast> {
         Field(FieldType),
         Boolean(BooleanType<'ast>),
+        U8(U8Type<'ast>),
+        U16(U16Type<'ast>),
+        U32(U32Type<'ast>),
+        U64(U64Type<'ast>),
     }
 
     #[derive(Debug, FromPest, PartialEq, Clone)]
@@ -211,8 +273,23 @@ mod ast {
     #[derive(Debug, FromPest, PartialEq, Clone)]
     #[pest_ast(rule(Rule::ty_array))]
     pub struct ArrayType<'ast> {
-        pub ty: BasicType<'ast>,
-        pub size: Expression<'ast>,
+        pub ty: BasicOrStructType<'ast>,
+        pub dimensions: Vec<Expression<'ast>>,
+        #[pest_ast(outer())]
+        pub span: Span<'ast>,
+    }
+
+    #[derive(Debug, FromPest, PartialEq, Clone)]
+    #[pest_ast(rule(Rule::ty_basic_or_struct))]
+    pub enum BasicOrStructType<'ast> {
+        Struct(StructType<'ast>),
+        Basic(BasicType<'ast>),
+    }
+
+    #[derive(Debug, FromPest, PartialEq, Clone)]
+    #[pest_ast(rule(Rule::ty_struct))]
+    pub struct StructType<'ast> {
+        pub id: IdentifierExpression<'ast>,
         #[pest_ast(outer())]
         pub span: Span<'ast>,
     }
@@ -224,6 +301,34 @@ mod ast {
         pub span: Span<'ast>,
     }
 
+    #[derive(Debug, FromPest, PartialEq, Clone)]
+    #[pest_ast(rule(Rule::ty_u8))]
+    pub struct U8Type<'ast> {
+        #[pest_ast(outer())]
+        pub span: Span<'ast>,
+    }
+
+    #[derive(Debug, FromPest, PartialEq, Clone)]
+    #[pest_ast(rule(Rule::ty_u16))]
+    pub struct U16Type<'ast> {
+        #[pest_ast(outer())]
+        pub span: Span<'ast>,
+    }
+
+    #[derive(Debug, FromPest, PartialEq, Clone)]
+    #[pest_ast(rule(Rule::ty_u32))]
+    pub struct U32Type<'ast> {
+        #[pest_ast(outer())]
+        pub span: Span<'ast>,
+    }
+
+    #[derive(Debug, FromPest, PartialEq, Clone)]
+    #[pest_ast(rule(Rule::ty_u64))]
+    pub struct U64Type<'ast> {
+        #[pest_ast(outer())]
+        pub span: Span<'ast>,
+    }
+
     #[derive(Debug, FromPest, PartialEq, Clone)]
     #[pest_ast(rule(Rule::parameter))]
     pub struct Parameter<'ast> {
@@ -333,6 +438,10 @@ mod ast {
         Lte,
         Gte,
         Pow,
+        BitAnd,
+        BitOr,
+        LeftShift,
+        RightShift,
     }
 
     #[derive(Debug, PartialEq, FromPest, Clone)]
@@ -356,6 +465,7 @@ mod ast {
         Identifier(IdentifierExpression<'ast>),
         Constant(ConstantExpression<'ast>),
         InlineArray(InlineArrayExpression<'ast>),
+        InlineStruct(InlineStructExpression<'ast>),
         ArrayInitializer(ArrayInitializerExpression<'ast>),
         Unary(UnaryExpression<'ast>),
     }
@@ -403,6 +513,7 @@ mod ast {
     #[pest_ast(rule(Rule::postfix_expression))]
     pub struct PostfixExpression<'ast> {
         pub id: IdentifierExpression<'ast>,
+        pub call: Option<CallAccess<'ast>>,
         pub access: Vec<Access<'ast>>,
         #[pest_ast(outer())]
         pub span: Span<'ast>,
@@ -425,6 +536,24 @@ mod ast {
         pub span: Span<'ast>,
     }
 
+    #[derive(Debug, FromPest, PartialEq, Clone)]
+    #[pest_ast(rule(Rule::inline_struct_expression))]
+    pub struct InlineStructExpression<'ast> {
+        pub ty: IdentifierExpression<'ast>,
+        pub members: Vec<InlineStructMember<'ast>>,
+        #[pest_ast(outer())]
+        pub span: Span<'ast>,
+    }
+
+    #[derive(Debug, FromPest, PartialEq, Clone)]
+    #[pest_ast(rule(Rule::inline_struct_member))]
+    pub struct InlineStructMember<'ast> {
+        pub id: IdentifierExpression<'ast>,
+        pub expression: Expression<'ast>,
+        #[pest_ast(outer())]
+        pub span: Span<'ast>,
+    }
+
     #[derive(Debug, FromPest, PartialEq, Clone)]
     #[pest_ast(rule(Rule::array_initializer_expression))]
     pub struct ArrayInitializerExpression<'ast> {
@@ -446,8 +575,8 @@ mod ast {
     #[derive(Debug, FromPest, PartialEq, Clone)]
     #[pest_ast(rule(Rule::access))]
     pub enum Access<'ast> {
-        Call(CallAccess<'ast>),
         Select(ArrayAccess<'ast>),
+        Member(MemberAccess<'ast>),
     }
 
     #[derive(Debug, FromPest, PartialEq, Clone)]
@@ -466,6 +595,14 @@ mod ast {
         pub span: Span<'ast>,
     }
 
+    #[derive(Debug, FromPest, PartialEq, Clone)]
+    #[pest_ast(rule(Rule::member_access))]
+    pub struct MemberAccess<'ast> {
+        pub id: IdentifierExpression<'ast>,
+        #[pest_ast(outer())]
+        pub span: Span<'ast>,
+    }
+
     #[derive(Debug, PartialEq, Clone)]
     pub struct BinaryExpression<'ast> {
         pub op: BinaryOperator,
@@ -521,6 +658,7 @@ mod ast {
                 Expression::Ternary(t) => &t.span,
                 Expression::Postfix(p) => &p.span,
                 Expression::InlineArray(a) => &a.span,
+                Expression::InlineStruct(s) => &s.span,
                 Expression::ArrayInitializer(a) => &a.span,
                 Expression::Unary(u) => &u.span,
             }
@@ -555,6 +693,7 @@ mod ast {
     pub enum ConstantExpression<'ast> {
         DecimalNumber(DecimalNumberExpression<'ast>),
         BooleanLiteral(BooleanLiteralExpression<'ast>),
+        HexLiteral(HexLiteralExpression<'ast>),
     }
 
     impl<'ast> ConstantExpression<'ast> {
@@ -562,10 +701,64 @@ mod ast {
             match self {
                 ConstantExpression::DecimalNumber(n) => &n.span,
                 ConstantExpression::BooleanLiteral(c) => &c.span,
+                ConstantExpression::HexLiteral(h) => &h.span,
             }
         }
     }
 
+    #[derive(Debug, FromPest, PartialEq, Clone)]
+    #[pest_ast(rule(Rule::hex_literal))]
+    pub struct HexLiteralExpression<'ast> {
+        pub value: HexNumberExpression<'ast>,
+        #[pest_ast(outer())]
+        pub span: Span<'ast>,
+    }
+
+    #[derive(Debug, FromPest, PartialEq, Clone)]
+    #[pest_ast(rule(Rule::hex_number))]
+    pub enum HexNumberExpression<'ast> {
+        U8(U8NumberExpression<'ast>),
+        U16(U16NumberExpression<'ast>),
+        U32(U32NumberExpression<'ast>),
+        U64(U64NumberExpression<'ast>),
+    }
+
+    #[derive(Debug, FromPest, PartialEq, Clone)]
+    #[pest_ast(rule(Rule::hex_number_8))]
+    pub struct U8NumberExpression<'ast> {
+        #[pest_ast(outer(with(span_into_str)))]
+        pub value: String,
+        #[pest_ast(outer())]
+        pub span: Span<'ast>,
+    }
+
+    #[derive(Debug, FromPest, PartialEq, Clone)]
+    #[pest_ast(rule(Rule::hex_number_16))]
+    pub struct U16NumberExpression<'ast> {
+        #[pest_ast(outer(with(span_into_str)))]
+        pub value: String,
+        #[pest_ast(outer())]
+        pub span: Span<'ast>,
+    }
+
+    #[derive(Debug, FromPest, PartialEq, Clone)]
+    #[pest_ast(rule(Rule::hex_number_32))]
+    pub struct U32NumberExpression<'ast> {
+        #[pest_ast(outer(with(span_into_str)))]
+        pub value: String,
+        #[pest_ast(outer())]
+        pub span: Span<'ast>,
+    }
+
+    #[derive(Debug, FromPest, PartialEq, Clone)]
+    #[pest_ast(rule(Rule::hex_number_64))]
+    pub struct U64NumberExpression<'ast> {
+        #[pest_ast(outer(with(span_into_str)))]
+        pub value: String,
+        #[pest_ast(outer())]
+        pub span: Span<'ast>,
+    }
+
     #[derive(Debug, FromPest, PartialEq, Clone)]
     #[pest_ast(rule(Rule::decimal_number))]
     pub struct DecimalNumberExpression<'ast> {
@@ -727,14 +920,15 @@ mod tests {
                     })],
                     span: Span::new(&source, 29, source.len()).unwrap(),
                 }],
-                imports: vec![ImportDirective {
+                imports: vec![ImportDirective::Main(MainImportDirective {
                     source: ImportSource {
                         value: String::from("foo"),
                         span: Span::new(&source, 8, 11).unwrap()
                     },
                     alias: None,
                     span: Span::new(&source, 0, 29).unwrap()
-                }],
+                })],
+                structs: vec![],
                 eoi: EOI {},
                 span: Span::new(&source, 0, 65).unwrap()
             })
@@ -794,14 +988,15 @@ mod tests {
                     })],
                     span: Span::new(&source, 29, 74).unwrap(),
                 }],
-                imports: vec![ImportDirective {
+                imports: vec![ImportDirective::Main(MainImportDirective {
                     source: ImportSource {
                         value: String::from("foo"),
                         span: Span::new(&source, 8, 11).unwrap()
                     },
                     alias: None,
                     span: Span::new(&source, 0, 29).unwrap()
-                }],
+                })],
+                structs: vec![],
                 eoi: EOI {},
                 span: Span::new(&source, 0, 74).unwrap()
             })
@@ -849,14 +1044,15 @@ mod tests {
                     })],
                     span: Span::new(&source, 29, 81).unwrap(),
                 }],
-                imports: vec![ImportDirective {
+                imports: vec![ImportDirective::Main(MainImportDirective {
                     source: ImportSource {
                         value: String::from("foo"),
                         span: Span::new(&source, 8, 11).unwrap()
                     },
                     alias: None,
                     span: Span::new(&source, 0, 29).unwrap()
-                }],
+                })],
+                structs: vec![],
                 eoi: EOI {},
                 span: Span::new(&source, 0, 81).unwrap()
             })
@@ -889,6 +1085,7 @@ mod tests {
                     span: Span::new(&source, 0, 34).unwrap(),
                 }],
                 imports: vec![],
+                structs: vec![],
                 eoi: EOI {},
                 span: Span::new(&source, 0, 34).unwrap()
             })
@@ -960,12 +1157,105 @@ mod tests {
                     span: Span::new(&source, 0, 50).unwrap(),
                 }],
                 imports: vec![],
+                structs: vec![],
                 eoi: EOI {},
                 span: Span::new(&source, 0, 50).unwrap()
             })
         );
     }
 
+    #[test]
+    fn from_import() {
+        let source = r#"from "./utils" import hash, compress as c
+def main() -> (field): return 1
+"#;
+        assert_eq!(
+            generate_ast(&source).unwrap().imports,
+            vec![ImportDirective::From(FromImportDirective {
+                source: ImportSource {
+                    value: String::from("./utils"),
+                    span: Span::new(&source, 6, 13).unwrap()
+                },
+                symbols: vec![
+                    ImportSymbol {
+                        id: IdentifierExpression {
+                            value: String::from("hash"),
+                            span: Span::new(&source, 22, 26).unwrap()
+                        },
+                        alias: None,
+                        span: Span::new(&source, 22, 26).unwrap()
+                    },
+                    ImportSymbol {
+                        id: IdentifierExpression {
+                            value: String::from("compress"),
+                            span: Span::new(&source, 28, 36).unwrap()
+                        },
+                        alias: Some(IdentifierExpression {
+                            value: String::from("c"),
+                            span: Span::new(&source, 40, 41).unwrap()
+                        }),
+                        span: Span::new(&source, 28, 41).unwrap()
+                    }
+                ],
+                span: Span::new(&source, 0, 42).unwrap()
+            })]
+        );
+    }
+
+    // renders `e` with explicit parentheses around binary expressions
+    fn parenthesize(e: &Expression) -> String {
+        match e {
+            Expression::Binary(b) => format!(
+                "({} {:?} {})",
+                parenthesize(&b.left),
+                b.op,
+                parenthesize(&b.right)
+            ),
+            e => e.span().as_str().to_string(),
+        }
+    }
+
+    #[test]
+    fn xor_precedence() {
+        // `^` binds tighter than the comparisons, `&&` and `||`, and looser than `&`
+        for (expression, expected) in &[
+            ("a ^ b && c", "((a Xor b) And c)"),
+            ("a || b ^ c", "(a Or (b Xor c))"),
+            ("a ^ b == c", "((a Xor b) Eq c)"),
+            ("a < b ^ c", "(a Lt (b Xor c))"),
+            ("a | b ^ c & d", "(a BitOr (b Xor (c BitAnd d)))"),
+        ] {
+            let source = format!("def main() -> (bool): return {}\n", expression);
+            let file = generate_ast(&source).unwrap();
+
+            match &file.functions[0].statements[0] {
+                Statement::Return(r) => assert_eq!(parenthesize(&r.expressions[0]), *expected),
+                _ => unreachable!(),
+            }
+        }
+    }
+
+    #[test]
+    fn call_then_accesses() {
+        let source = "def main() -> (field): return f(1)[0].a\n";
+        let file = generate_ast(&source).unwrap();
+
+        match &file.functions[0].statements[0] {
+            Statement::Return(r) => match &r.expressions[0] {
+                Expression::Postfix(p) => {
+                    assert_eq!(p.id.span.as_str(), "f");
+                    assert_eq!(p.call.as_ref().unwrap().span.as_str(), "(1)");
+                    assert_eq!(p.access.len(), 2);
+                }
+                e => panic!("expected a postfix expression, found {:?}", e),
+            },
+            _ => unreachable!(),
+        }
+
+        // only identifiers are callable
+        assert!(generate_ast("def main() -> (field): return a[0](1)\n").is_err());
+    }
+
     #[test]
     fn playground() {
         let source = r#"import "heyman" as yo
@@ -0,0 +1,85 @@
+#[macro_use]
+extern crate serde_derive;
+
+mod utils;
+
+use std::fs::File;
+use std::io::BufReader;
+use std::path::PathBuf;
+use zokrates_core::compile::compile_aux;
+use zokrates_core::ir;
+use zokrates_core::optimizer::{LinearOptimizer, RedefinitionOptimizer, TautologyOptimizer};
+use zokrates_field::field::{Field, FieldPrime};
+use zokrates_fs_resolver::FileSystemResolver;
+
+/// Compiles the program of a test with and without linear constraint elimination, checks that both
+/// programs pass the test, and returns their constraint counts
+fn constraint_counts(test_path: &str) -> (usize, usize) {
+    let t: utils::Tests = serde_json::from_reader(BufReader::new(
+        File::open(&PathBuf::from(test_path)).unwrap(),
+    ))
+    .unwrap();
+
+    let mut code_reader = BufReader::new(File::open(&t.entry_point).unwrap());
+
+    let flattened = compile_aux::<FieldPrime, _>(
+        &mut code_reader,
+        Some(
+            t.entry_point
+                .parent()
+                .unwrap()
+                .to_str()
+                .unwrap()
+                .to_string(),
+        ),
+        Some(&FileSystemResolver::new()),
+        false,
+    )
+    .unwrap();
+
+    let without =
+        TautologyOptimizer::optimize(RedefinitionOptimizer::optimize(ir::Prog::from(flattened)));
+    let with = TautologyOptimizer::optimize(LinearOptimizer::optimize(without.clone()));
+
+    for test in t.tests.into_iter() {
+        let input: Vec<_> = test
+            .input
+            .values
+            .iter()
+            .map(|v| FieldPrime::try_from_dec_str(v).unwrap())
+            .collect();
+
+        let output = with.execute(&input);
+        assert_eq!(
+            output.as_ref().ok().map(|w| w.return_values()),
+            without.execute(&input).ok().map(|w| w.return_values())
+        );
+        utils::compare(output, test.output).unwrap();
+    }
+
+    (without.constraint_count(), with.constraint_count())
+}
+
+#[test]
+#[ignore]
+fn eliminate_linear_constraints_sha256() {
+    let (before, after) = constraint_counts("./tests/bench/hashes/sha256/512bit.json");
+    assert!(
+        after < before,
+        "{} constraints before elimination, {} after",
+        before,
+        after
+    );
+}
+
+#[test]
+#[ignore]
+fn eliminate_linear_constraints_pedersen() {
+    let (before, after) = constraint_counts("./tests/bench/hashes/pedersen/512bit.json");
+    assert!(
+        after < before,
+        "{} constraints before elimination, {} after",
+        before,
+        after
+    );
+}
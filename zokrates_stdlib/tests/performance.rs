@@ -0,0 +1,149 @@
+#[macro_use]
+extern crate serde_derive;
+
+mod utils;
+
+use std::env;
+use std::fs::File;
+use std::io::BufReader;
+use std::path::PathBuf;
+use std::thread;
+use std::time::{Duration, Instant};
+use zokrates_core::compile::compile_aux;
+use zokrates_core::ir;
+use zokrates_core::optimizer::Optimize;
+use zokrates_core::proof_system::{set_threads, ProofSystem, G16};
+use zokrates_field::field::{Field, FieldPrime};
+use zokrates_fs_resolver::FileSystemResolver;
+
+/// Compiles the program of a test and returns it along with its first test case
+fn compile(test_path: &str) -> (ir::Prog<FieldPrime>, utils::Test) {
+    let t: utils::Tests = serde_json::from_reader(BufReader::new(
+        File::open(&PathBuf::from(test_path)).unwrap(),
+    ))
+    .unwrap();
+
+    let mut code_reader = BufReader::new(File::open(&t.entry_point).unwrap());
+
+    let flattened = compile_aux::<FieldPrime, _>(
+        &mut code_reader,
+        Some(
+            t.entry_point
+                .parent()
+                .unwrap()
+                .to_str()
+                .unwrap()
+                .to_string(),
+        ),
+        Some(&FileSystemResolver::new()),
+        false,
+    )
+    .unwrap();
+
+    (
+        ir::Prog::from(flattened).optimize(),
+        t.tests.into_iter().next().unwrap(),
+    )
+}
+
+fn inputs(test: &utils::Test) -> Vec<FieldPrime> {
+    test.input
+        .values
+        .iter()
+        .map(|v| FieldPrime::try_from_dec_str(v).unwrap())
+        .collect()
+}
+
+// the average duration of `runs` calls to `f`
+fn time<F: FnMut()>(runs: u32, mut f: F) -> Duration {
+    let start = Instant::now();
+    for _ in 0..runs {
+        f();
+    }
+    start.elapsed() / runs
+}
+
+// reports absolute times only: the map-based witness it replaced is gone, so there is no baseline
+// to compare against in the same build
+#[test]
+#[ignore]
+fn bench_witness_sha256() {
+    for test_path in &[
+        "./tests/bench/hashes/sha256/512bit.json",
+        "./tests/bench/hashes/sha256/512bitPadded.json",
+    ] {
+        let (program, test) = compile(test_path);
+        let inputs = inputs(&test);
+
+        let duration = time(10, || {
+            program.execute(&inputs).unwrap();
+        });
+
+        utils::compare(program.execute(&inputs), test.output).unwrap();
+
+        println!(
+            "{}: {} constraints, witness computed in {:?}",
+            test_path,
+            program.constraint_count(),
+            duration
+        );
+    }
+}
+
+#[test]
+#[ignore]
+fn bench_prove_sha256() {
+    let (program, test) = compile("./tests/bench/hashes/sha256/512bit.json");
+    let witness = program.execute(&inputs(&test)).unwrap();
+
+    let dir = env::temp_dir();
+    let pk_path = dir.join("bench_prove_sha256.pk");
+    let vk_path = dir.join("bench_prove_sha256.vk");
+    let proof_path = dir.join("bench_prove_sha256.json");
+
+    G16 {}
+        .setup(
+            program.clone(),
+            pk_path.to_str().unwrap(),
+            vk_path.to_str().unwrap(),
+        )
+        .unwrap();
+
+    // each proof is generated in its own thread, as restricting the threads applies to the
+    // current thread and the threads it starts
+    let prove = |threads: Option<usize>| {
+        let program = program.clone();
+        let witness = witness.clone();
+        let pk_path = pk_path.clone();
+        let proof_path = proof_path.clone();
+
+        thread::spawn(move || {
+            if let Some(threads) = threads {
+                set_threads(threads).unwrap();
+            }
+            let start = Instant::now();
+            G16 {}
+                .generate_proof(
+                    program,
+                    witness,
+                    pk_path.to_str().unwrap(),
+                    proof_path.to_str().unwrap(),
+                )
+                .unwrap();
+            start.elapsed()
+        })
+        .join()
+        .unwrap()
+    };
+
+    let single = prove(Some(1));
+    let all = prove(None);
+
+    println!(
+        "{} constraints, proof generated in {:?} with 1 thread and {:?} with all CPUs ({:.2}x)",
+        program.constraint_count(),
+        single,
+        all,
+        single.as_secs_f64() / all.as_secs_f64()
+    );
+}